@@ -0,0 +1,71 @@
+//! Compares the borrow-based `Network::apply` against the previous
+//! implementation, which cloned the input for every neuron and recursively
+//! allocated a sub-vector at every step of the combinator chain, and against
+//! bit-parallel evaluation via `Network::apply_batch`.
+//!
+//! Run with `cargo bench --bench apply`.
+
+use bnl::batch::BitBatch;
+use bnl::{compute_boolean, Combinator, Network};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+fn legacy_zip(left: bool, remaining: Vec<bool>, combinators: &[Combinator]) -> bool {
+    match remaining.len() {
+        0 => panic!("This shouldn't happen!"),
+        1 => compute_boolean(left, remaining[0], combinators[0]),
+        _ => compute_boolean(left, legacy_zip(remaining[0], remaining[1..].to_vec(), &combinators[1..]), combinators[0])
+    }
+}
+
+fn legacy_apply(network: &Network, input: Vec<bool>) -> Vec<bool> {
+    let mut res: Vec<bool> = input;
+    for layer in &network.layers {
+        res = layer.neurons.iter().map(|n| {
+            let input = res.clone();
+            let acc = legacy_zip(input[0], input[1..].to_vec(), &n.input_combinators);
+            compute_boolean(acc, n.bias, n.result_combinator)
+        }).collect();
+    }
+    res
+}
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    f();
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn main() {
+    use rand::Rng;
+    let mut rng = rand::thread_rng();
+    for &(width, ref layers) in &[(16, vec![16, 16]), (64, vec![128, 128, 64]), (256, vec![256, 64])] {
+        let network = Network::new(width, layers.clone());
+        let inputs: Vec<Vec<bool>> = (0..1000).map(|_| (0..width).map(|_| rng.gen()).collect()).collect();
+        for input in &inputs {
+            assert_eq!(network.apply(input), legacy_apply(&network, input.clone()));
+        }
+        let legacy = time(|| for input in &inputs {
+            black_box(legacy_apply(&network, input.clone()));
+        });
+        let current = time(|| for input in &inputs {
+            black_box(network.apply(input));
+        });
+        let packed = BitBatch::from_samples(&inputs);
+        assert_eq!(network.apply_batch(&packed).to_samples(), inputs.iter().map(|i| network.apply(i)).collect::<Vec<_>>());
+        let batch = time(|| {
+            black_box(network.apply_batch(&packed));
+        });
+        println!(
+            "{:>3} inputs, layers {:<14} legacy {:>9.2?}  borrowed {:>9.2?} ({:>5.1}x)  batch {:>9.2?} ({:>6.1}x)",
+            width,
+            format!("{:?}", layers),
+            legacy / inputs.len() as u32,
+            current / inputs.len() as u32,
+            legacy.as_secs_f64() / current.as_secs_f64(),
+            batch / inputs.len() as u32,
+            legacy.as_secs_f64() / batch.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! Compares mutating a whole population via `mutate::apply_batch`, which
+//! mutates the neurons of its networks in place, against the cost of first
+//! round-tripping every network through a `flat::FlatNetwork` (via
+//! `FlatNetwork::new` and `FlatNetwork::write_to`), as `apply_batch` once did
+//! to mutate their flat parameters.
+//!
+//! Run with `cargo bench --bench batch_mutate`.
+
+use bnl::flat::FlatNetwork;
+use bnl::mutate::{apply_batch, BatchOps};
+use bnl::Network;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    f();
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    for &(width, ref layers, size) in &[(16, vec![16, 16], 256), (64, vec![128, 128, 64], 64), (256, vec![256, 64], 32)] {
+        let mut population: Vec<Network> = (0..size).map(|_| Network::new(width, layers.clone())).collect();
+        for &rate in &[0.001, 0.01] {
+            let ops = BatchOps {
+                bias_rate: rate,
+                combinator_rate: rate,
+                wiring_rate: rate
+            };
+            let round_trip = time(|| {
+                for network in population.iter_mut() {
+                    FlatNetwork::new(network).write_to(black_box(network));
+                }
+                apply_batch(black_box(&mut population), ops, &mut rng);
+            });
+            let in_place = time(|| apply_batch(black_box(&mut population), ops, &mut rng));
+            println!(
+                "{:>3} inputs, layers {:<14} x{:<4} rate {:<6} round trip {:>9.2?}  in place {:>9.2?} ({:>5.1}x)",
+                width,
+                format!("{:?}", layers),
+                size,
+                rate,
+                round_trip,
+                in_place,
+                round_trip.as_secs_f64() / in_place.as_secs_f64()
+            );
+        }
+    }
+}
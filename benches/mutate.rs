@@ -0,0 +1,66 @@
+//! Compares mutating a whole population via `mutate::apply_batch`, which skips
+//! directly to the parameters it changes, against walking the nested layers
+//! and neurons of the population while drawing once per parameter, and
+//! against mutating each network on its own via
+//! `mutate::replace_parameters`.
+//!
+//! Run with `cargo bench --bench mutate`.
+
+use bnl::mutate::{apply_batch, replace_parameters, BatchOps};
+use bnl::Network;
+use rand::Rng;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+fn legacy_apply_batch<R: Rng + ?Sized>(population: &mut [Network], rate: f64, rng: &mut R) {
+    for neuron in population.iter_mut().flat_map(|n| n.layers.iter_mut()).flat_map(|l| l.neurons.iter_mut()) {
+        if rng.gen_bool(rate) {
+            neuron.bias = !neuron.bias;
+        }
+        for c in neuron.input_combinators.iter_mut() {
+            if rng.gen_bool(rate) {
+                *c = rng.gen();
+            }
+        }
+        if rng.gen_bool(rate) {
+            neuron.result_combinator = rng.gen();
+        }
+    }
+}
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    f();
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    for &(width, ref layers, size) in &[(16, vec![16, 16], 256), (64, vec![128, 128, 64], 64), (256, vec![256, 64], 32)] {
+        let mut population: Vec<Network> = (0..size).map(|_| Network::new(width, layers.clone())).collect();
+        for &rate in &[0.001, 0.01] {
+            let ops = BatchOps {
+                bias_rate: rate,
+                combinator_rate: rate,
+                wiring_rate: rate
+            };
+            let legacy = time(|| legacy_apply_batch(black_box(&mut population), rate, &mut rng));
+            let individual = time(|| for network in population.iter_mut() {
+                black_box(replace_parameters(network, rate, &mut rng));
+            });
+            let batch = time(|| apply_batch(black_box(&mut population), ops, &mut rng));
+            println!(
+                "{:>3} inputs, layers {:<14} x{:<4} rate {:<6} legacy {:>9.2?}  individual {:>9.2?}  batch {:>9.2?} ({:>5.1}x)",
+                width,
+                format!("{:?}", layers),
+                size,
+                rate,
+                legacy,
+                individual,
+                batch,
+                legacy.as_secs_f64() / batch.as_secs_f64()
+            );
+        }
+    }
+}
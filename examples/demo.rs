@@ -0,0 +1,11 @@
+//! Applies a randomized network to a fixed input and prints the result.
+
+use bnl::Network;
+
+fn main() {
+    let n = Network::new(6, vec![6, 7, 6]);
+    let input = vec![true, false, true, true, false, true];
+
+    println!("n = {:?}\n", n);
+    println!("Result = {:?}", n.apply(&input));
+}
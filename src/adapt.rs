@@ -0,0 +1,77 @@
+//! Contains `Network::adapt_to`, which maps the parameters of a network onto
+//! a differently shaped topology, so that networks (or whole populations)
+//! can be warm-started when a progressive search changes their topology.
+//!
+//! Neurons are matched by position, as in `Network::diff`: neuron `j` of
+//! layer `i` of the adapted network is a copy of neuron `j` of layer `i` of
+//! the original network if it has one, resized to read the (possibly
+//! different) width of its new input, and a new randomized neuron otherwise.
+//! Resizing drops inputs from or appends ignored inputs to the end of the
+//! chain of a neuron (or, if it is sparsely wired, wraps its indices around
+//! the new width), as `mutate::delete_layers` does.
+
+use crate::mutate::resize_arity;
+use crate::network::{Layer, Network, Neuron};
+use crate::population::Population;
+use rand::Rng;
+
+/// Implements topology adaptation on `bnl` networks.
+impl Network {
+    /// Creates a new network of the same input width as this network and of
+    /// the specified layer lengths, copying the neurons of this network which
+    /// fit into it and randomizing the rest.
+    ///
+    /// Panics if any of the layer lengths is zero.
+    pub fn adapt_to(&self, layer_lengths: Vec<usize>) -> Network {
+        self.adapt_to_with_rng(layer_lengths, &mut crate::random::rng())
+    }
+
+    /// Adapts this network to the specified layer lengths as with
+    /// `Network::adapt_to`, drawing the parameters of new neurons from the
+    /// specified generator.
+    pub fn adapt_to_with_rng<R: Rng + ?Sized>(&self, layer_lengths: Vec<usize>, rng: &mut R) -> Network {
+        if layer_lengths.contains(&0) {
+            panic!("Cannot adapt a network to a topology with an empty layer!");
+        }
+        let mut width = self.input_width();
+        let mut layers: Vec<Layer> = Vec::with_capacity(layer_lengths.len());
+        for (i, &len) in layer_lengths.iter().enumerate() {
+            let old: &[Neuron] = self.layers.get(i).map_or(&[], |l| &l.neurons);
+            let neurons = (0..len).map(|j| match old.get(j) {
+                Some(n) => {
+                    let mut n = n.clone();
+                    resize_arity(&mut n, width);
+                    n
+                },
+                None => Neuron::new_with_rng(width, rng)
+            }).collect();
+            layers.push(Layer { neurons });
+            width = len;
+        }
+        Network {
+            layers
+        }
+    }
+}
+
+/// Implements topology adaptation on populations.
+impl Population {
+    /// Adapts every network of this population to the specified layer lengths
+    /// (see `Network::adapt_to`).
+    ///
+    /// Panics if any of the layer lengths is zero.
+    pub fn adapt_to(&mut self, layer_lengths: Vec<usize>) {
+        self.adapt_to_with_rng(layer_lengths, &mut crate::random::rng())
+    }
+
+    /// Adapts every network of this population to the specified layer lengths
+    /// as with `Population::adapt_to`, drawing the parameters of new neurons
+    /// from the specified generator.
+    pub fn adapt_to_with_rng<R: Rng + ?Sized>(&mut self, layer_lengths: Vec<usize>, rng: &mut R) {
+        let networks: Vec<Network> = self.networks().iter().map(|n| n.adapt_to_with_rng(layer_lengths.clone(), rng)).collect();
+        self.genomes.clear();
+        for n in &networks {
+            self.genomes.push(n);
+        }
+    }
+}
@@ -0,0 +1,514 @@
+//! Contains analysis passes for inspecting the behavior of `bnl` networks.
+
+use crate::network::{compute_boolean, reads_left, reads_right, Network};
+pub use crate::bits::hamming_distance;
+use crate::truth::MAX_TABLE_WIDTH;
+
+/// The largest input width for which `classify` determines whether an output
+/// is a threshold function.
+pub const MAX_THRESHOLD_WIDTH: usize = 8;
+
+/// Represents a group of samples which produce the same (or nearly the same)
+/// activation pattern in a hidden layer of a network.
+#[derive(Clone,Debug)]
+pub struct ActivationCluster {
+    /// The activation pattern of the first sample assigned to this cluster.
+    pub pattern: Vec<bool>,
+
+    /// The indices of the samples assigned to this cluster.
+    pub samples: Vec<usize>
+}
+
+/// Represents a rough per-gate energy cost for some hardware platform.
+#[derive(Clone,Debug,PartialEq)]
+pub struct EnergyProfile {
+    /// A human-readable name for the platform.
+    pub name: &'static str,
+
+    /// The approximate energy consumed per two-input gate evaluation, in
+    /// picojoules.
+    pub picojoules_per_gate: f64
+}
+
+/// Implements custom methods and preset values for energy profiles.
+impl EnergyProfile {
+    /// A dedicated ASIC on a modern process, where a gate toggle costs on the
+    /// order of a femtojoule.
+    pub const ASIC: EnergyProfile = EnergyProfile {
+        name: "asic",
+        picojoules_per_gate: 0.001
+    };
+
+    /// An FPGA, where each gate is realized through LUT and routing fabric.
+    pub const FPGA: EnergyProfile = EnergyProfile {
+        name: "fpga",
+        picojoules_per_gate: 1.0
+    };
+
+    /// A low-power microcontroller evaluating each gate in software with a few
+    /// instructions.
+    pub const MICROCONTROLLER: EnergyProfile = EnergyProfile {
+        name: "microcontroller",
+        picojoules_per_gate: 100.0
+    };
+}
+
+/// Represents the classes of boolean functions an output of a network belongs
+/// to, as determined by `classify`.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct FunctionClass {
+    /// Whether the output is linear (affine over GF(2)), i.e. the XOR of a
+    /// constant and some of the inputs.
+    pub linear: bool,
+
+    /// Whether the output is monotone, never changing from `true` to `false`
+    /// when an input changes from `false` to `true`.
+    pub monotone: bool,
+
+    /// Whether the output is symmetric, depending only on the number of `true`
+    /// inputs.
+    pub symmetric: bool,
+
+    /// Whether the output is a threshold function, `true` exactly when a
+    /// weighted sum of the inputs reaches some threshold, or `None` if the
+    /// input width exceeds `MAX_THRESHOLD_WIDTH`.
+    pub threshold: Option<bool>
+}
+
+/// Represents the number of gate evaluations performed by a single inference
+/// of a network.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct OpCount {
+    /// The total number of gates (input and result combinators) evaluated by
+    /// `Network::apply`.
+    pub gates: usize,
+
+    /// The number of gates whose output can actually influence the output of
+    /// their neuron, which is what remains after pruning dead gates.
+    pub live_gates: usize,
+
+    /// The total number of neurons in the network.
+    pub neurons: usize
+}
+
+/// Implements custom methods for operation counts.
+impl OpCount {
+    /// Estimates the energy consumed by one inference (counting only live
+    /// gates) on the specified platform, in picojoules.
+    pub fn energy(&self, profile: &EnergyProfile) -> f64 {
+        self.live_gates as f64 * profile.picojoules_per_gate
+    }
+}
+
+/// Groups the specified input samples by the activation pattern they produce
+/// in the layer at the specified index.
+///
+/// Samples whose activations are within `max_distance` bits (by Hamming
+/// distance) of an existing cluster's pattern are assigned to that cluster,
+/// so a `max_distance` of zero groups only identical activation vectors. The
+/// resulting clusters are sorted from largest to smallest.
+pub fn activation_clusters(network: &Network, inputs: &[Vec<bool>], layer: usize, max_distance: usize) -> Vec<ActivationCluster> {
+    let mut clusters: Vec<ActivationCluster> = Vec::new();
+    for (i, input) in inputs.iter().enumerate() {
+        let act = network.activations(input, layer);
+        match clusters.iter_mut().find(|c| hamming_distance(&c.pattern, &act) <= max_distance) {
+            Some(c) => c.samples.push(i),
+            None => clusters.push(ActivationCluster {
+                pattern: act,
+                samples: vec![i]
+            })
+        }
+    }
+    clusters.sort_by_key(|c| std::cmp::Reverse(c.samples.len()));
+    clusters
+}
+
+/// Determines which classes of boolean functions the output at the specified
+/// index of the specified network belongs to, exactly, from its truth table.
+///
+/// Threshold functions are recognized by checking that the output is unate
+/// (monotone in each input, up to negating it) and solving the linear program
+/// for its weights in exact rational arithmetic, which is only attempted for
+/// up to `MAX_THRESHOLD_WIDTH` inputs.
+///
+/// Panics if the index is not an output of the network, or if its input
+/// width exceeds `truth::MAX_TABLE_WIDTH`.
+pub fn classify(network: &Network, output: usize) -> FunctionClass {
+    if output >= network.output_width() {
+        panic!("Cannot classify output {} of a network with {} outputs!", output, network.output_width());
+    }
+    let width = network.input_width();
+    if width > MAX_TABLE_WIDTH {
+        panic!("Cannot classify the outputs of a network with {} inputs!", width);
+    }
+    let table = network.truth_table_packed(MAX_TABLE_WIDTH).unwrap();
+    let t = |x: usize| (table.signals[output][x / 64] >> (x % 64)) & 1 == 1;
+    let rows = 1usize << width;
+    let constant = t(0);
+    let coefficients = (0..width).filter(|&i| t(1 << i) != constant).fold(0, |acc, i| acc | (1 << i));
+    let mut by_count: Vec<Option<bool>> = vec![None; width + 1];
+    let (mut linear, mut symmetric) = (true, true);
+    let mut positive = vec![true; width];
+    let mut negative = vec![true; width];
+    for x in 0..rows {
+        linear &= t(x) == (constant ^ ((x & coefficients).count_ones() % 2 == 1));
+        symmetric &= *by_count[x.count_ones() as usize].get_or_insert(t(x)) == t(x);
+        for i in (0..width).filter(|&i| x & (1 << i) == 0) {
+            let (low, high) = (t(x), t(x | (1 << i)));
+            positive[i] &= !low || high;
+            negative[i] &= low || !high;
+        }
+    }
+    let monotone = positive.iter().all(|&p| p);
+    let threshold = if width > MAX_THRESHOLD_WIDTH {
+        None
+    } else if (0..width).any(|i| !positive[i] && !negative[i]) {
+        Some(false)
+    } else {
+        let flips = (0..width).filter(|&i| !positive[i]).fold(0, |acc, i| acc | (1 << i));
+        Some(is_threshold(width, |x| t(x ^ flips)))
+    };
+    FunctionClass {
+        linear,
+        monotone,
+        symmetric,
+        threshold
+    }
+}
+
+/// Determines which bits of the network's input never affect its output over
+/// the specified collection of input samples.
+///
+/// An input bit is considered "dead" if flipping it leaves the network's
+/// output unchanged for every sample. The returned indices are in ascending
+/// order.
+pub fn dead_inputs(network: &Network, inputs: &[Vec<bool>]) -> Vec<usize> {
+    let width = match inputs.first() {
+        Some(i) => i.len(),
+        None => return Vec::new()
+    };
+    let outputs: Vec<Vec<bool>> = inputs.iter().map(|i| network.apply(i)).collect();
+    (0..width).filter(|&bit| {
+        inputs.iter().zip(outputs.iter()).all(|(input, output)| {
+            let mut flipped = input.clone();
+            flipped[bit] = !flipped[bit];
+            network.apply(&flipped) == *output
+        })
+    }).collect()
+}
+
+/// Estimates the complexity of the function mapping inputs to the specified
+/// output bit of a dataset, as the number of leaves of a greedily grown
+/// decision tree which fits the samples exactly (as far as contradictions in
+/// the data allow).
+///
+/// Splits are chosen to minimize the number of misclassified samples of each
+/// branch, so functions such as parity (where no single split helps) are
+/// correctly found to be maximally complex.
+pub fn decision_tree_leaves(dataset: &[(Vec<bool>, Vec<bool>)], output: usize) -> usize {
+    fn grow(dataset: &[(Vec<bool>, Vec<bool>)], output: usize, samples: Vec<usize>, features: Vec<usize>) -> usize {
+        let ones = samples.iter().filter(|&&s| dataset[s].1[output]).count();
+        if ones == 0 || ones == samples.len() {
+            return 1;
+        }
+        let split = features.iter()
+            .map(|&f| {
+                let (t, e): (Vec<usize>, Vec<usize>) = samples.iter().partition(|&&s| dataset[s].0[f]);
+                let errors = |side: &Vec<usize>| {
+                    let o = side.iter().filter(|&&s| dataset[s].1[output]).count();
+                    o.min(side.len() - o)
+                };
+                (errors(&t) + errors(&e), f, t, e)
+            })
+            .filter(|(_, _, t, e)| !t.is_empty() && !e.is_empty())
+            .min_by_key(|(errors, f, _, _)| (*errors, *f));
+        match split {
+            Some((_, f, t, e)) => {
+                let rest: Vec<usize> = features.into_iter().filter(|&x| x != f).collect();
+                grow(dataset, output, t, rest.clone()) + grow(dataset, output, e, rest)
+            },
+            None => 1
+        }
+    }
+    let width = dataset.first().map_or(0, |(i, _)| i.len());
+    grow(dataset, output, (0..dataset.len()).collect(), (0..width).collect())
+}
+
+/// Finds pairs of neurons within the same layer which compute identical
+/// functions over the specified collection of network input samples.
+///
+/// Each result is a tuple of `(layer, first_neuron, second_neuron)` where
+/// `first_neuron < second_neuron`. Neurons are compared over the signals that
+/// the samples produce at their layer, so two neurons agreeing on every
+/// sample are reported even if some other input would tell them apart: the
+/// result is exact only when the samples enumerate every input (as
+/// `probe_inputs` does for narrow networks). Duplicates may be merged with
+/// `merge_duplicate_neurons`.
+pub fn duplicate_neurons(network: &Network, inputs: &[Vec<bool>]) -> Vec<(usize, usize, usize)> {
+    let mut duplicates: Vec<(usize, usize, usize)> = Vec::new();
+    let mut signals: Vec<Vec<bool>> = inputs.to_vec();
+    for (l, layer) in network.layers.iter().enumerate() {
+        let columns: Vec<Vec<bool>> = layer.neurons.iter()
+            .map(|n| signals.iter().map(|s| n.apply(s)).collect())
+            .collect();
+        for i in 0..columns.len() {
+            for j in (i + 1)..columns.len() {
+                if columns[i] == columns[j] {
+                    duplicates.push((l, i, j));
+                }
+            }
+        }
+        signals = signals.into_iter().map(|s| layer.apply(&s)).collect();
+    }
+    duplicates
+}
+
+/// Determines whether the specified monotone boolean function of the specified
+/// number of inputs is a threshold function, by checking whether some
+/// non-negative weights and threshold separate its minimal true points from
+/// its maximal false points with a margin of one.
+fn is_threshold<F: Fn(usize) -> bool>(width: usize, f: F) -> bool {
+    let rows = 1usize << width;
+    if f(0) || !f(rows - 1) {
+        return true;
+    }
+    let mut constraints: Vec<(Vec<Ratio>, Ratio)> = Vec::new();
+    for x in 0..rows {
+        let bit = |i: usize| Ratio::from((x >> i) & 1);
+        let minimal = f(x) && (0..width).all(|i| x & (1 << i) == 0 || !f(x & !(1 << i)));
+        let maximal = !f(x) && (0..width).all(|i| x & (1 << i) != 0 || f(x | (1 << i)));
+        if minimal {
+            let mut row: Vec<Ratio> = (0..width).map(bit).collect();
+            row.push(Ratio::from(0).sub(Ratio::from(1)));
+            constraints.push((row, Ratio::from(1)));
+        } else if maximal {
+            let mut row: Vec<Ratio> = (0..width).map(|i| Ratio::from(0).sub(bit(i))).collect();
+            row.push(Ratio::from(1));
+            constraints.push((row, Ratio::from(1)));
+        }
+    }
+    is_feasible(&constraints)
+}
+
+/// Determines whether the system of constraints `a . v >= b` (each given as
+/// `(a, b)` with `b` non-negative) has a non-negative solution `v`, by phase
+/// one of the simplex method with Bland's rule.
+fn is_feasible(constraints: &[(Vec<Ratio>, Ratio)]) -> bool {
+    let m = constraints.len();
+    let vars = constraints.first().map_or(0, |(a, _)| a.len());
+    let columns = vars + 2 * m;
+    let zero = Ratio::from(0);
+    let mut tableau: Vec<Vec<Ratio>> = constraints.iter().enumerate().map(|(r, (a, b))| {
+        let mut row = a.clone();
+        row.extend((0..m).map(|s| if s == r { zero.sub(Ratio::from(1)) } else { zero }));
+        row.extend((0..m).map(|s| Ratio::from((s == r) as usize)));
+        row.push(*b);
+        row
+    }).collect();
+    let mut objective: Vec<Ratio> = (0..=columns)
+        .map(|j| if (vars + m..columns).contains(&j) { zero } else { tableau.iter().fold(zero, |acc, row| acc.sub(row[j])) })
+        .collect();
+    let mut basis: Vec<usize> = (vars + m..columns).collect();
+    while let Some(entering) = (0..columns).find(|&j| objective[j].num < 0) {
+        let leaving = (0..m)
+            .filter(|&r| tableau[r][entering].num > 0)
+            .min_by(|&a, &b| {
+                let (ra, rb) = (tableau[a][columns].div(tableau[a][entering]), tableau[b][columns].div(tableau[b][entering]));
+                ra.sub(rb).num.cmp(&0).then(basis[a].cmp(&basis[b]))
+            });
+        let leaving = match leaving {
+            Some(r) => r,
+            None => break
+        };
+        let pivot = tableau[leaving][entering];
+        tableau[leaving].iter_mut().for_each(|v| *v = v.div(pivot));
+        let pivot_row = tableau[leaving].clone();
+        for (r, row) in tableau.iter_mut().enumerate() {
+            let factor = row[entering];
+            if r != leaving && factor.num != 0 {
+                row.iter_mut().zip(pivot_row.iter()).for_each(|(v, &p)| *v = v.sub(factor.mul(p)));
+            }
+        }
+        let factor = objective[entering];
+        objective.iter_mut().zip(pivot_row.iter()).for_each(|(v, &p)| *v = v.sub(factor.mul(p)));
+        basis[leaving] = entering;
+    }
+    objective[columns].num == 0
+}
+
+/// Returns a copy of the specified network in which each hidden neuron found
+/// to duplicate an earlier neuron of its layer (see `duplicate_neurons`) is
+/// removed, the neurons of the following layer reading the earlier neuron in
+/// its place, along with the number of neurons removed.
+///
+/// Consumers of a removed neuron are rewired sparsely, so that they read the
+/// same number of signals as before. The copy computes the same outputs as
+/// the network for every one of the specified samples, and for every input
+/// if they enumerate all of them. Output neurons, locked neurons and neurons
+/// feeding locked neurons are never removed.
+pub fn merge_duplicate_neurons(network: &Network, inputs: &[Vec<bool>]) -> (Network, usize) {
+    let mut res = network.clone();
+    let mut removed = 0;
+    let mut signals: Vec<Vec<bool>> = inputs.to_vec();
+    for l in 0..res.layers.len() {
+        if l + 1 < res.layers.len() && !res.layers[l + 1].neurons.iter().any(|n| n.locked) {
+            let columns: Vec<Vec<bool>> = res.layers[l].neurons.iter()
+                .map(|n| signals.iter().map(|s| n.apply(s)).collect())
+                .collect();
+            let mut j = columns.len();
+            while j > 1 {
+                j -= 1;
+                let i = match (0..j).find(|&i| columns[i] == columns[j]) {
+                    Some(i) if !res.layers[l].neurons[j].locked => i,
+                    _ => continue
+                };
+                res.layers[l].neurons.remove(j);
+                for n in res.layers[l + 1].neurons.iter_mut() {
+                    let wiring: Vec<usize> = (0..n.fan_in())
+                        .map(|k| match n.input_index(k) {
+                            x if x == j => i,
+                            x if x > j => x - 1,
+                            x => x
+                        })
+                        .collect();
+                    n.arity -= 1;
+                    n.inputs = Some(wiring);
+                }
+                removed += 1;
+            }
+        }
+        signals = signals.into_iter().map(|s| res.layers[l].apply(&s)).collect();
+    }
+    (res, removed)
+}
+
+/// Counts the gate evaluations performed by a single inference of the
+/// specified network.
+///
+/// A gate is live if its output can reach the output of its neuron: every
+/// combinator it feeds within the input chain (in the order given by the
+/// neuron's reduction) must read the side it feeds, and the result combinator
+/// must not be constant given the neuron's bias.
+pub fn op_count(network: &Network) -> OpCount {
+    let mut count = OpCount {
+        gates: 0,
+        live_gates: 0,
+        neurons: 0
+    };
+    for n in network.layers.iter().flat_map(|l| l.neurons.iter()) {
+        count.neurons += 1;
+        count.gates += n.input_combinators.len() + 1;
+        let r = n.result_combinator;
+        if compute_boolean(false, n.bias, r) == compute_boolean(true, n.bias, r) {
+            continue;
+        }
+        let leaves = vec![0; n.fan_in()];
+        count.live_gates += 1 + n.reduction.reduce(leaves, &n.input_combinators, |l, r, c| {
+            1 + if reads_left(c) { l } else { 0 } + if reads_right(c) { r } else { 0 }
+        });
+    }
+    count
+}
+
+/// Produces a collection of input samples of the specified width suitable for
+/// probing the behavior of a network.
+///
+/// If the full input space contains at most `max_samples` vectors it is
+/// enumerated exhaustively (with bit `i` of the sample index mapped to input
+/// `i`), otherwise `max_samples` random vectors are generated.
+pub fn probe_inputs(width: usize, max_samples: usize) -> Vec<Vec<bool>> {
+    probe_inputs_with_rng(width, max_samples, &mut crate::random::rng())
+}
+
+/// Produces probe inputs as with `probe_inputs`, drawing any random vectors
+/// from the specified generator.
+pub fn probe_inputs_with_rng<R: rand::Rng + ?Sized>(width: usize, max_samples: usize, rng: &mut R) -> Vec<Vec<bool>> {
+    if width < usize::BITS as usize && (1usize << width) <= max_samples {
+        (0..(1usize << width))
+            .map(|x| (0..width).map(|i| (x >> i) & 1 == 1).collect())
+            .collect()
+    } else {
+        (0..max_samples)
+            .map(|_| (0..width).map(|_| rng.gen()).collect())
+            .collect()
+    }
+}
+
+/// Proposes a reasonable vector of layer lengths (as accepted by
+/// `Network::new`) for learning the specified dataset.
+///
+/// The estimate is driven by the most complex output bit (see
+/// `decision_tree_leaves`): functions needing at most twice as many leaves as
+/// there are inputs get a single hidden layer about as wide as that
+/// complexity, while more complex ones get two hidden layers. Hidden
+/// widths are kept between the output width and twice the input width, and
+/// never exceed the number of samples.
+pub fn suggest_topology(dataset: &[(Vec<bool>, Vec<bool>)]) -> Vec<usize> {
+    let (input_width, output_width) = match dataset.first() {
+        Some((i, e)) => (i.len(), e.len()),
+        None => return Vec::new()
+    };
+    let leaves = (0..output_width).map(|o| decision_tree_leaves(dataset, o)).max().unwrap_or(1);
+    let upper = (2 * input_width).max(output_width).min(dataset.len().max(output_width));
+    let hidden = leaves.max(output_width).min(upper).max(1);
+    if leaves <= 2 * input_width {
+        vec![hidden, output_width]
+    } else {
+        vec![hidden, (hidden + output_width) / 2, output_width]
+    }
+}
+
+/// Represents an exact rational number, kept in lowest terms with a positive
+/// denominator.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+struct Ratio {
+    /// The denominator of this number.
+    den: i128,
+
+    /// The numerator of this number.
+    num: i128
+}
+
+/// Implements exact arithmetic on rational numbers.
+impl Ratio {
+    /// Divides this number by another, non-zero number.
+    fn div(self, other: Ratio) -> Ratio {
+        Ratio::new(self.num * other.den, self.den * other.num)
+    }
+
+    /// Multiplies this number by another.
+    fn mul(self, other: Ratio) -> Ratio {
+        Ratio::new(self.num * other.num, self.den * other.den)
+    }
+
+    /// Creates the number with the specified numerator and non-zero
+    /// denominator, in lowest terms.
+    fn new(num: i128, den: i128) -> Ratio {
+        let (mut a, mut b) = (num.abs(), den.abs());
+        while b != 0 {
+            let r = a % b;
+            a = b;
+            b = r;
+        }
+        let g = a.max(1) * den.signum();
+        Ratio {
+            den: den / g,
+            num: num / g
+        }
+    }
+
+    /// Subtracts another number from this number.
+    fn sub(self, other: Ratio) -> Ratio {
+        Ratio::new(self.num * other.den - other.num * self.den, self.den * other.den)
+    }
+}
+
+/// Allows rational numbers to be created from whole numbers.
+impl From<usize> for Ratio {
+    fn from(n: usize) -> Ratio {
+        Ratio {
+            den: 1,
+            num: n as i128
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//! Contains the `NetworkArena` type, which stores many `bnl` networks in a
+//! single set of contiguous arrays, as the genetic trainer of the
+//! `population` module does with its generations.
+//!
+//! Each network, or genome, stored in an arena is referred to by its index,
+//! and its parameters live in the arrays of one `flat::FlatNetwork` holding
+//! the layers of every genome in turn. Genomes may be evaluated bit-parallel,
+//! mutated and recombined directly in the arena, so that evolution neither
+//! allocates a nested `Network` per offspring nor chases pointers through one
+//! when scoring it. Clearing an arena keeps its arrays allocated, so that a
+//! pair of arenas alternating between the current and the next generation
+//! stops allocating once they have grown to the size of a generation.
+//!
+//! Genomes may be converted back into networks via `NetworkArena::get`, which
+//! yields exactly the network they were pushed as.
+
+use crate::batch::{apply_flat_neuron, BitBatch, BitDataset};
+use crate::combinator::Combinator;
+use crate::flat::FlatNetwork;
+use crate::network::{Layer, Network, Neuron};
+use rand::Rng;
+use std::ops::Range;
+
+/// Represents a collection of networks whose parameters are stored
+/// contiguously, each referred to by its index.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct NetworkArena {
+    /// The parameters of every genome, as a flat network holding the layers
+    /// of each genome in turn. Its input width is unused.
+    flat: FlatNetwork,
+
+    /// The genomes of this arena, in order.
+    genomes: Vec<Genome>
+}
+
+/// Represents the location of a single genome within a `NetworkArena`.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+struct Genome {
+    /// The index of the first layer of the genome within the flat network of
+    /// its arena.
+    first_layer: usize,
+
+    /// The width of the input vectors accepted by the genome.
+    input_width: usize
+}
+
+/// Implements custom methods for network arenas.
+impl NetworkArena {
+    /// "Applies" the specified genome to every sample of the specified batch
+    /// at once, as with `Network::apply_batch`.
+    pub fn apply_batch(&self, genome: usize, input: &BitBatch) -> BitBatch {
+        let layers = self.layers(genome);
+        if input.is_empty() {
+            let width = layers.last().map_or(input.width(), |l| self.flat.layer_neurons(l).len());
+            return BitBatch {
+                len: 0,
+                signals: vec![Vec::new(); width]
+            };
+        }
+        let mut signals = input.signals.clone();
+        for l in layers {
+            signals = self.flat.layer_neurons(l).map(|n| apply_flat_neuron(&self.flat, n, &signals)).collect();
+        }
+        BitBatch {
+            len: input.len,
+            signals
+        }
+    }
+
+    /// Removes every genome from this arena, keeping its arrays allocated for
+    /// the genomes pushed next.
+    pub fn clear(&mut self) {
+        let flat = &mut self.flat;
+        flat.biases.clear();
+        flat.combinator_offsets.truncate(1);
+        flat.input_combinators.clear();
+        flat.input_indices.clear();
+        flat.layer_offsets.truncate(1);
+        flat.locked.clear();
+        flat.reductions.clear();
+        flat.result_combinators.clear();
+        flat.sparse.clear();
+        self.genomes.clear();
+    }
+
+    /// Computes the total error of the specified genome on the specified
+    /// packed dataset, as with `BitDataset::error`.
+    pub fn error(&self, genome: usize, dataset: &BitDataset) -> usize {
+        #[cfg(feature = "profiling")]
+        let _timer = crate::profile::timer(crate::profile::Phase::Evaluate);
+        self.apply_batch(genome, &dataset.inputs).hamming_distance(&dataset.expected)
+    }
+
+    /// Converts the specified genome back into a network.
+    ///
+    /// Panics if there is no such genome.
+    pub fn get(&self, genome: usize) -> Network {
+        let mut width = self.genome(genome).input_width;
+        let layers = self.layers(genome).map(|l| {
+            let neurons: Vec<Neuron> = self.flat.layer_neurons(l).map(|n| Neuron {
+                arity: width,
+                bias: self.flat.biases[n],
+                input_combinators: self.flat.neuron_combinators(n).to_vec(),
+                inputs: if self.flat.sparse[n] { Some(self.flat.neuron_inputs(n).to_vec()) } else { None },
+                locked: self.flat.locked[n],
+                reduction: self.flat.reductions[n],
+                result_combinator: self.flat.result_combinators[n]
+            }).collect();
+            width = neurons.len();
+            Layer {
+                neurons
+            }
+        }).collect();
+        Network {
+            layers
+        }
+    }
+
+    /// Returns the input width of the specified genome.
+    ///
+    /// Panics if there is no such genome.
+    pub fn input_width(&self, genome: usize) -> usize {
+        self.genome(genome).input_width
+    }
+
+    /// Determines whether this arena holds no genomes.
+    pub fn is_empty(&self) -> bool {
+        self.genomes.is_empty()
+    }
+
+    /// Returns the number of genomes in this arena.
+    pub fn len(&self) -> usize {
+        self.genomes.len()
+    }
+
+    /// Creates a new empty arena.
+    pub fn new() -> Self {
+        NetworkArena {
+            flat: FlatNetwork {
+                biases: Vec::new(),
+                combinator_offsets: vec![0],
+                input_combinators: Vec::new(),
+                input_indices: Vec::new(),
+                input_width: 0,
+                layer_offsets: vec![0],
+                locked: Vec::new(),
+                reductions: Vec::new(),
+                result_combinators: Vec::new(),
+                sparse: Vec::new()
+            },
+            genomes: Vec::new()
+        }
+    }
+
+    /// Returns the number of neurons in each layer of the specified genome.
+    ///
+    /// Panics if there is no such genome.
+    pub fn shape(&self, genome: usize) -> Vec<usize> {
+        self.layers(genome).map(|l| self.flat.layer_neurons(l).len()).collect()
+    }
+
+    /// Appends the specified network to this arena as a new genome, returning
+    /// its index.
+    pub fn push(&mut self, network: &Network) -> usize {
+        self.begin(network.input_width());
+        for layer in &network.layers {
+            for n in &layer.neurons {
+                let flat = &mut self.flat;
+                flat.biases.push(n.bias);
+                flat.input_combinators.extend_from_slice(&n.input_combinators);
+                flat.input_indices.extend((0..n.fan_in()).map(|k| n.input_index(k)));
+                flat.combinator_offsets.push(flat.input_combinators.len());
+                flat.locked.push(n.locked);
+                flat.reductions.push(n.reduction);
+                flat.result_combinators.push(n.result_combinator);
+                flat.sparse.push(n.inputs.is_some());
+            }
+            self.end_layer();
+        }
+        self.genomes.len() - 1
+    }
+
+    /// Appends a copy of the specified genome of another arena to this arena,
+    /// returning its index.
+    ///
+    /// Panics if there is no such genome.
+    pub fn push_from(&mut self, other: &NetworkArena, genome: usize) -> usize {
+        self.begin(other.input_width(genome));
+        for l in other.layers(genome) {
+            for n in other.flat.layer_neurons(l) {
+                self.push_neuron(other, n);
+            }
+            self.end_layer();
+        }
+        self.genomes.len() - 1
+    }
+
+    /// Appends a child of the specified genomes of another arena to this
+    /// arena, taking each whole layer from one of the two parents at random
+    /// as with `crossover::layer_crossover_with_rng`, and returns its index.
+    ///
+    /// Panics if the parents are of different shapes.
+    pub fn push_layer_crossover_with_rng<R: Rng + ?Sized>(&mut self, parents: &NetworkArena, a: usize, b: usize, rng: &mut R) -> usize {
+        parents.check_shapes(a, b);
+        self.begin(parents.input_width(a));
+        for (la, lb) in parents.layers(a).zip(parents.layers(b)) {
+            let l = if rng.gen() { lb } else { la };
+            for n in parents.flat.layer_neurons(l) {
+                self.push_neuron(parents, n);
+            }
+            self.end_layer();
+        }
+        self.genomes.len() - 1
+    }
+
+    /// Appends a child of the specified genomes of another arena to this
+    /// arena, taking each neuron from the neuron at the same position of one
+    /// of the two parents at random as with
+    /// `crossover::uniform_crossover_with_rng`, and returns its index.
+    ///
+    /// Panics if the parents are of different shapes.
+    pub fn push_uniform_crossover_with_rng<R: Rng + ?Sized>(&mut self, parents: &NetworkArena, a: usize, b: usize, rng: &mut R) -> usize {
+        parents.check_shapes(a, b);
+        self.begin(parents.input_width(a));
+        for (la, lb) in parents.layers(a).zip(parents.layers(b)) {
+            for (na, nb) in parents.flat.layer_neurons(la).zip(parents.flat.layer_neurons(lb)) {
+                self.push_neuron(parents, if rng.gen() { nb } else { na });
+            }
+            self.end_layer();
+        }
+        self.genomes.len() - 1
+    }
+
+    /// Replaces each individual parameter of each unlocked neuron of the
+    /// specified genome by a random value with the specified probability, in
+    /// place, as with `mutate::replace_parameters_from` (drawing the same
+    /// random choices in the same order), and returns the number of
+    /// parameters replaced.
+    ///
+    /// Panics if the pool is empty or there is no such genome.
+    pub fn replace_parameters_from<R: Rng + ?Sized>(&mut self, genome: usize, rate: f64, combinators: &[Combinator], rng: &mut R) -> usize {
+        #[cfg(feature = "profiling")]
+        let _timer = crate::profile::timer(crate::profile::Phase::Mutate);
+        if combinators.is_empty() {
+            panic!("Cannot replace combinators from an empty pool!");
+        }
+        let layers = self.layers(genome);
+        let flat = &mut self.flat;
+        let mut res = 0;
+        for n in flat.layer_offsets[layers.start]..flat.layer_offsets[layers.end] {
+            if flat.locked[n] {
+                continue;
+            }
+            if rng.gen_bool(rate) {
+                flat.biases[n] = rng.gen();
+                res += 1;
+            }
+            if rng.gen_bool(rate) {
+                flat.result_combinators[n] = combinators[rng.gen_range(0, combinators.len())];
+                res += 1;
+            }
+            for c in flat.input_combinators[flat.combinator_offsets[n]..flat.combinator_offsets[n + 1]].iter_mut() {
+                if rng.gen_bool(rate) {
+                    *c = combinators[rng.gen_range(0, combinators.len())];
+                    res += 1;
+                }
+            }
+        }
+        res
+    }
+
+    /// Converts every genome of this arena back into a network, in order.
+    pub fn to_networks(&self) -> Vec<Network> {
+        (0..self.len()).map(|g| self.get(g)).collect()
+    }
+
+    /// Starts a new genome of the specified input width.
+    fn begin(&mut self, input_width: usize) {
+        self.genomes.push(Genome {
+            first_layer: self.flat.num_layers(),
+            input_width
+        });
+    }
+
+    /// Panics if the specified genomes are of different shapes.
+    fn check_shapes(&self, a: usize, b: usize) {
+        if self.input_width(a) != self.input_width(b) || self.shape(a) != self.shape(b) {
+            panic!("Cannot recombine genomes of different shapes!");
+        }
+    }
+
+    /// Ends the layer being pushed.
+    fn end_layer(&mut self) {
+        self.flat.layer_offsets.push(self.flat.biases.len());
+    }
+
+    /// Returns the location of the specified genome.
+    fn genome(&self, genome: usize) -> Genome {
+        match self.genomes.get(genome) {
+            Some(&g) => g,
+            None => panic!("Cannot find genome {} of an arena of {} genomes!", genome, self.genomes.len())
+        }
+    }
+
+    /// Returns the range of the indices of the layers of the specified genome
+    /// within the flat network of this arena.
+    fn layers(&self, genome: usize) -> Range<usize> {
+        let start = self.genome(genome).first_layer;
+        let end = self.genomes.get(genome + 1).map_or(self.flat.num_layers(), |g| g.first_layer);
+        start..end
+    }
+
+    /// Appends a copy of the neuron at the specified flat index of another
+    /// arena to the layer being pushed.
+    fn push_neuron(&mut self, other: &NetworkArena, n: usize) {
+        let (flat, source) = (&mut self.flat, &other.flat);
+        flat.biases.push(source.biases[n]);
+        flat.input_combinators.extend_from_slice(source.neuron_combinators(n));
+        flat.input_indices.extend_from_slice(source.neuron_inputs(n));
+        flat.combinator_offsets.push(flat.input_combinators.len());
+        flat.locked.push(source.locked[n]);
+        flat.reductions.push(source.reductions[n]);
+        flat.result_combinators.push(source.result_combinators[n]);
+        flat.sparse.push(source.sparse[n]);
+    }
+}
+
+/// Provides an empty arena.
+impl Default for NetworkArena {
+    fn default() -> Self {
+        NetworkArena::new()
+    }
+}
@@ -0,0 +1,110 @@
+//! Contains `Network::balance`, which lowers the depth of a `bnl` network
+//! (the length of its critical path, see `Network::depth`) without changing
+//! the function it computes, for hardware targets whose clock rate is bounded
+//! by the depth of the circuits exported to them.
+//!
+//! Every combinator, including the result combinator of each neuron, counts
+//! as one level of logic, so a neuron reducing `n` signals as a fold (see
+//! `Reduction`) has a depth of `n`, which reducing them as a balanced tree
+//! lowers to `ceil(log2(n)) + 1`. This only preserves what the neuron
+//! computes where its input combinator chain can be reassociated: where every
+//! combinator of the chain is the same associative combinator (`AND`, `OR`,
+//! `XOR`, `XNOR`, `LEFT`, `RIGHT`, `TRUE` or `FALSE`), or where every
+//! combinator is `XOR` or `XNOR`, whose result depends only on how many of
+//! them are `XNOR`.
+//!
+//! The pass is critical-path-aware: each such neuron is rebalanced only if
+//! that makes its output available earlier given when each of its inputs
+//! arrives, so a fold which already combines its latest input last is left
+//! alone. Locked neurons are left untouched.
+
+use crate::combinator::Combinator;
+use crate::network::{Network, Neuron, Reduction};
+
+/// The combinators `c` for which `c(c(x, y), z) = c(x, c(y, z))`.
+pub const ASSOCIATIVE: [Combinator; 8] = [
+    Combinator::False, Combinator::And, Combinator::Left, Combinator::Right,
+    Combinator::Xor, Combinator::Or, Combinator::Xnor, Combinator::True
+];
+
+/// Represents the outcome of a call to `Network::balance`.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct BalanceReport {
+    /// The number of neurons of each layer which were rebalanced into trees.
+    pub balanced: Vec<usize>,
+
+    /// The depth of the network after balancing.
+    pub depth_after: usize,
+
+    /// The depth of the network before balancing.
+    pub depth_before: usize
+}
+
+/// Implements custom methods for balancing reports.
+impl BalanceReport {
+    /// Determines whether balancing left the network unchanged.
+    pub fn is_empty(&self) -> bool {
+        self.balanced.iter().all(|&n| n == 0)
+    }
+}
+
+/// Implements depth reduction on `bnl` networks.
+impl Network {
+    /// Returns a copy of this network computing the same function with every
+    /// unlocked neuron whose input chain can be reassociated reducing its
+    /// inputs as a tree wherever that shortens the critical path through it,
+    /// along with a report of the neurons rebalanced.
+    pub fn balance(&self) -> (Network, BalanceReport) {
+        let mut network = self.clone();
+        let mut balanced = vec![0; self.layers.len()];
+        let mut arrivals: Vec<usize> = vec![0; self.input_width()];
+        for (i, layer) in network.layers.iter_mut().enumerate() {
+            let mut next: Vec<usize> = Vec::with_capacity(layer.neurons.len());
+            for n in layer.neurons.iter_mut() {
+                let mut level = arrival(n, n.reduction, &arrivals);
+                if !n.locked && n.reduction != Reduction::Tree && can_reassociate(&n.input_combinators) {
+                    let tree = arrival(n, Reduction::Tree, &arrivals);
+                    if tree < level {
+                        n.reduction = Reduction::Tree;
+                        balanced[i] += 1;
+                        level = tree;
+                    }
+                }
+                next.push(level);
+            }
+            arrivals = next;
+        }
+        let report = BalanceReport {
+            balanced,
+            depth_after: network.depth(),
+            depth_before: self.depth()
+        };
+        (network, report)
+    }
+
+    /// Computes the depth of this network: the largest number of combinators
+    /// (counting the result combinator of each neuron) on any path from an
+    /// input to an output.
+    pub fn depth(&self) -> usize {
+        let mut arrivals: Vec<usize> = vec![0; self.input_width()];
+        for l in &self.layers {
+            arrivals = l.neurons.iter().map(|n| arrival(n, n.reduction, &arrivals)).collect();
+        }
+        arrivals.into_iter().max().unwrap_or(0)
+    }
+}
+
+/// Computes the level at which the output of the specified neuron is
+/// available when it reduces its input with the specified reduction, given
+/// the levels at which the signals of its input are available.
+fn arrival(neuron: &Neuron, reduction: Reduction, arrivals: &[usize]) -> usize {
+    let signals: Vec<usize> = (0..neuron.fan_in()).map(|k| arrivals[neuron.input_index(k)]).collect();
+    reduction.reduce(signals, &neuron.input_combinators, |l, r, _| l.max(r) + 1) + 1
+}
+
+/// Determines whether the specified input combinator chain computes the same
+/// function under every reduction.
+fn can_reassociate(combinators: &[Combinator]) -> bool {
+    let uniform = combinators.windows(2).all(|w| w[0] == w[1]) && combinators.first().is_none_or(|c| ASSOCIATIVE.contains(c));
+    uniform || combinators.iter().all(|&c| c == Combinator::Xor || c == Combinator::Xnor)
+}
@@ -0,0 +1,259 @@
+//! Contains a bit-parallel evaluation mode for `bnl` networks.
+//!
+//! A `BitBatch` stores many samples transposed and packed into `u64` words,
+//! one bit per sample, so that a single bitwise operation applies a
+//! combinator to 64 samples at once. This is well suited to evaluating the
+//! same network over a large dataset, such as when computing its error
+//! during training. The evaluation kernels are generic over the `Word` into
+//! which samples are packed (see the `word` module), so that
+//! `Network::apply_words` may evaluate wider words such as `u128`.
+
+use crate::combinator::Combinator;
+use crate::flat::FlatNetwork;
+use crate::linear::LinearLayer;
+use crate::network::{Layer, Network, Neuron, Reduction};
+use crate::word::{pack, unpack, Word};
+
+/// Represents a batch of equally wide boolean vectors packed for bit-parallel
+/// evaluation.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct BitBatch {
+    /// The number of samples in this batch.
+    pub len: usize,
+
+    /// The packed values of each signal, where bit `j` of word `w` of signal
+    /// `i` is signal `i` of sample `64 * w + j`. Bits beyond the last sample
+    /// are unspecified.
+    pub signals: Vec<Vec<u64>>
+}
+
+/// Implements custom methods for bit batches.
+impl BitBatch {
+    /// Packs the specified samples into a new batch.
+    ///
+    /// Panics if the samples are not all of the same width.
+    pub fn from_samples(samples: &[Vec<bool>]) -> Self {
+        let width = samples.first().map_or(0, |s| s.len());
+        BitBatch {
+            len: samples.len(),
+            signals: pack(samples, width)
+        }
+    }
+
+    /// Counts the positions at which this batch and another batch of the same
+    /// length differ, summed over every sample.
+    ///
+    /// Panics if the batches are of different lengths. Signals present in only
+    /// one of the batches count as differing for every sample.
+    pub fn hamming_distance(&self, other: &BitBatch) -> usize {
+        if self.len != other.len {
+            panic!("Cannot compare batches of {} and {} samples!", self.len, other.len);
+        }
+        let mask = self.last_word_mask();
+        let mut res: usize = self.signals.iter().zip(other.signals.iter()).map(|(a, b)| {
+            a.iter().zip(b.iter()).enumerate().map(|(w, (x, y))| {
+                let diff = x ^ y;
+                let diff = if w + 1 == a.len() { diff & mask } else { diff };
+                diff.count_ones() as usize
+            }).sum::<usize>()
+        }).sum();
+        res += (self.width() as isize - other.width() as isize).unsigned_abs() * self.len;
+        res
+    }
+
+    /// Determines whether this batch contains no samples.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a mask of the bits of the last word of each signal which
+    /// correspond to samples.
+    fn last_word_mask(&self) -> u64 {
+        match self.len % 64 {
+            0 => !0,
+            r => (1 << r) - 1
+        }
+    }
+
+    /// Counts the positions at which each sample of this batch differs from
+    /// the corresponding sample of another batch of the same length (see
+    /// `BitBatch::hamming_distance`).
+    ///
+    /// Panics if the batches are of different lengths.
+    pub fn sample_distances(&self, other: &BitBatch) -> Vec<usize> {
+        if self.len != other.len {
+            panic!("Cannot compare batches of {} and {} samples!", self.len, other.len);
+        }
+        let missing = (self.width() as isize - other.width() as isize).unsigned_abs();
+        let mut res = vec![missing; self.len];
+        for (a, b) in self.signals.iter().zip(other.signals.iter()) {
+            for (w, (x, y)) in a.iter().zip(b.iter()).enumerate() {
+                let mut diff = x ^ y;
+                while diff != 0 {
+                    let j = 64 * w + diff.trailing_zeros() as usize;
+                    if j < self.len {
+                        res[j] += 1;
+                    }
+                    diff &= diff - 1;
+                }
+            }
+        }
+        res
+    }
+
+    /// Unpacks this batch back into its samples.
+    pub fn to_samples(&self) -> Vec<Vec<bool>> {
+        unpack(&self.signals, self.len)
+    }
+
+    /// Returns the width of the samples in this batch.
+    pub fn width(&self) -> usize {
+        self.signals.len()
+    }
+}
+
+/// Represents a dataset of `(input, expected)` samples packed for bit-parallel
+/// evaluation.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct BitDataset {
+    /// The packed expected outputs of the samples.
+    pub expected: BitBatch,
+
+    /// The packed inputs of the samples.
+    pub inputs: BitBatch
+}
+
+/// Implements custom methods for packed datasets.
+impl BitDataset {
+    /// Computes the total error of the specified network on this dataset, as
+    /// the sum of the Hamming distances between its outputs and the expected
+    /// outputs (see `train::error`).
+    pub fn error(&self, network: &Network) -> usize {
+        #[cfg(feature = "profiling")]
+        let _timer = crate::profile::timer(crate::profile::Phase::Evaluate);
+        network.apply_batch(&self.inputs).hamming_distance(&self.expected)
+    }
+
+    /// Packs the specified samples into a new dataset.
+    pub fn from_samples(samples: &[(Vec<bool>, Vec<bool>)]) -> Self {
+        let inputs: Vec<Vec<bool>> = samples.iter().map(|(i, _)| i.clone()).collect();
+        let expected: Vec<Vec<bool>> = samples.iter().map(|(_, e)| e.clone()).collect();
+        BitDataset {
+            expected: BitBatch::from_samples(&expected),
+            inputs: BitBatch::from_samples(&inputs)
+        }
+    }
+
+    /// Determines whether this dataset contains no samples.
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// Returns the number of samples in this dataset.
+    pub fn len(&self) -> usize {
+        self.inputs.len
+    }
+
+    /// Computes the error of the specified network on each sample of this
+    /// dataset, in order (see `BitDataset::error`).
+    pub fn sample_errors(&self, network: &Network) -> Vec<usize> {
+        #[cfg(feature = "profiling")]
+        let _timer = crate::profile::timer(crate::profile::Phase::Evaluate);
+        network.apply_batch(&self.inputs).sample_distances(&self.expected)
+    }
+}
+
+/// Implements bit-parallel evaluation on `bnl` networks.
+impl Network {
+    /// "Applies" this network to every sample of the specified batch at once,
+    /// producing the batch of their outputs. Layers of XOR neurons are
+    /// evaluated as matrix products (see the `linear` module).
+    pub fn apply_batch(&self, input: &BitBatch) -> BitBatch {
+        if input.is_empty() {
+            return BitBatch {
+                len: 0,
+                signals: vec![Vec::new(); self.output_width()]
+            };
+        }
+        BitBatch {
+            len: input.len,
+            signals: self.apply_words(&input.signals)
+        }
+    }
+
+    /// "Applies" this network to packed signals of any word width (see the
+    /// `word` module), producing the packed signals of its outputs.
+    pub fn apply_words<W: Word>(&self, signals: &[Vec<W>]) -> Vec<Vec<W>> {
+        let mut signals: Vec<Vec<W>> = signals.to_vec();
+        for layer in &self.layers {
+            signals = apply_layer(layer, &signals);
+        }
+        signals
+    }
+}
+
+/// Applies the specified layer to packed signals, as a matrix product if its
+/// neurons are linear (see the `linear` module).
+pub(crate) fn apply_layer<W: Word>(layer: &Layer, signals: &[Vec<W>]) -> Vec<Vec<W>> {
+    match LinearLayer::from_layer(layer) {
+        Some(linear) => linear.apply_packed(signals),
+        None => layer.neurons.iter().map(|n| apply_neuron(n, signals)).collect()
+    }
+}
+
+/// Applies the neuron at the specified flat index of the specified flat
+/// network to packed signals, as `apply_neuron` does to a nested neuron.
+pub(crate) fn apply_flat_neuron<W: Word>(flat: &FlatNetwork, neuron: usize, signals: &[Vec<W>]) -> Vec<W> {
+    let words = signals.first().map_or(0, |s| s.len());
+    let bias = W::splat(flat.biases[neuron]);
+    let combinators = flat.neuron_combinators(neuron);
+    let selected: Vec<&Vec<W>> = flat.neuron_inputs(neuron).iter().map(|&i| &signals[i]).collect();
+    let (last, rest) = selected.split_last().expect("Cannot apply a neuron to an empty input!");
+    (0..words).map(|w| {
+        let acc = match flat.reductions[neuron] {
+            Reduction::RightFold => rest.iter().zip(combinators.iter()).rev()
+                .fold(last[w], |acc, (s, &c)| compute_word(s[w], acc, c)),
+            reduction => reduction.reduce(selected.iter().map(|s| s[w]).collect(), combinators, compute_word)
+        };
+        compute_word(acc, bias, flat.result_combinators[neuron])
+    }).collect()
+}
+
+/// Applies the specified neuron to packed signals.
+pub(crate) fn apply_neuron<W: Word>(neuron: &Neuron, signals: &[Vec<W>]) -> Vec<W> {
+    let words = signals.first().map_or(0, |s| s.len());
+    let bias = W::splat(neuron.bias);
+    let selected: Vec<&Vec<W>> = (0..neuron.fan_in()).map(|k| &signals[neuron.input_index(k)]).collect();
+    let (last, rest) = selected.split_last().expect("Cannot apply a neuron to an empty input!");
+    (0..words).map(|w| {
+        let acc = match neuron.reduction {
+            Reduction::RightFold => rest.iter().zip(neuron.input_combinators.iter()).rev()
+                .fold(last[w], |acc, (s, &c)| compute_word(s[w], acc, c)),
+            reduction => reduction.reduce(selected.iter().map(|s| s[w]).collect(), &neuron.input_combinators, compute_word)
+        };
+        compute_word(acc, bias, neuron.result_combinator)
+    }).collect()
+}
+
+/// Computes the result of the specified boolean combinator on each of the
+/// pairs of bits of two words.
+pub fn compute_word<W: Word>(left: W, right: W, combinator: Combinator) -> W {
+    match combinator {
+        Combinator::False       => W::ZERO,
+        Combinator::And         => left & right,
+        Combinator::AndNotRight => left & !right,
+        Combinator::Left        => left,
+        Combinator::AndNotLeft  => !left & right,
+        Combinator::Right       => right,
+        Combinator::Xor         => left ^ right,
+        Combinator::Or          => left | right,
+        Combinator::Nor         => !(left | right),
+        Combinator::Xnor        => !(left ^ right),
+        Combinator::NotRight    => !right,
+        Combinator::ImpliedBy   => left | !right,
+        Combinator::NotLeft     => !left,
+        Combinator::Implies     => !left | right,
+        Combinator::Nand        => !(left & right),
+        Combinator::True        => !W::ZERO
+    }
+}
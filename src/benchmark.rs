@@ -0,0 +1,203 @@
+//! Contains a benchmark suite of standard boolean learning tasks, run with
+//! fixed budgets and seeds so that changes to the training algorithms can be
+//! compared consistently across versions of the crate.
+//!
+//! Every task is learned by evolving a population of `POPULATION_SIZE`
+//! networks, shaped by `analysis::suggest_topology`, for `GENERATIONS`
+//! generations with the default `EvolveOptions`, once for each of `SEEDS`.
+//! Runs draw from `random::Xoshiro256` generators (as in the `repro`
+//! module), so the errors of a suite are identical on every platform for a
+//! given version of the crate, and only its timings vary.
+
+use crate::analysis::suggest_topology;
+use crate::population::{EvolveOptions, Population};
+use crate::random::Xoshiro256;
+use rand::SeedableRng;
+use std::fmt;
+use std::time::Instant;
+
+/// The number of generations evolved by each run.
+pub const GENERATIONS: usize = 200;
+
+/// The number of networks in the population of each run.
+pub const POPULATION_SIZE: usize = 32;
+
+/// The seeds of the generators of the runs of each task.
+pub const SEEDS: [u64; 3] = [0x626e_6c01, 0x626e_6c02, 0x626e_6c03];
+
+/// Represents the outcome of running the suite (see `run`).
+#[derive(Clone,Debug)]
+pub struct SuiteReport {
+    /// The result of each task, in the order in which the tasks were run.
+    pub results: Vec<TaskResult>
+}
+
+/// Implements custom methods for suite reports.
+impl SuiteReport {
+    /// Returns the score of the suite: the mean bit accuracy over every task,
+    /// which is one if every run of every task learned it exactly.
+    pub fn score(&self) -> f64 {
+        if self.results.is_empty() {
+            return 0.0;
+        }
+        self.results.iter().map(TaskResult::bit_accuracy).sum::<f64>() / self.results.len() as f64
+    }
+}
+
+/// Formats suite reports as a table with one row per task, followed by the
+/// score of the suite.
+impl fmt::Display for SuiteReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<14} {:>8} {:>8} {:>10} {:>8} {:>12} {:>9}", "task", "inputs", "bits", "best", "solved", "evaluations", "seconds")?;
+        for r in &self.results {
+            writeln!(
+                f, "{:<14} {:>8} {:>8} {:>10} {:>8} {:>12} {:>9.2}",
+                r.task.to_string(), r.task.input_width(), r.bits, r.errors.iter().min().unwrap_or(&0),
+                format!("{}/{}", r.solved(), r.errors.len()), r.evaluations, r.seconds
+            )?;
+        }
+        writeln!(f, "score: {:.4}", self.score())
+    }
+}
+
+/// Represents a standard boolean learning task.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Task {
+    /// The sum of two numbers of the given number of bits each, as one more
+    /// bit than each of them.
+    Adder(usize),
+
+    /// Whether more than half of the given number of inputs are true.
+    Majority(usize),
+
+    /// The data input selected by the given number of address inputs, which
+    /// come first, out of two to the power of that many data inputs.
+    Multiplexer(usize),
+
+    /// Whether an odd number of the given number of inputs are true.
+    Parity(usize)
+}
+
+/// Implements custom methods for tasks.
+impl Task {
+    /// Returns every sample of this task, with inputs enumerated in order of
+    /// their value, where bit `i` of the value is input `i`.
+    pub fn dataset(&self) -> Vec<(Vec<bool>, Vec<bool>)> {
+        let width = self.input_width();
+        (0..(1usize << width)).map(|x| {
+            let input: Vec<bool> = (0..width).map(|b| (x >> b) & 1 == 1).collect();
+            let expected = match *self {
+                Task::Adder(n) => {
+                    let sum = (x & ((1 << n) - 1)) + (x >> n);
+                    (0..=n).map(|b| (sum >> b) & 1 == 1).collect()
+                },
+                Task::Majority(n) => vec![2 * x.count_ones() as usize > n],
+                Task::Multiplexer(n) => vec![input[n + (x & ((1 << n) - 1))]],
+                Task::Parity(_) => vec![x.count_ones() % 2 == 1]
+            };
+            (input, expected)
+        }).collect()
+    }
+
+    /// Returns the width of the inputs of this task.
+    pub fn input_width(&self) -> usize {
+        match *self {
+            Task::Adder(n) => 2 * n,
+            Task::Majority(n) | Task::Parity(n) => n,
+            Task::Multiplexer(n) => n + (1 << n)
+        }
+    }
+
+    /// Returns the standard battery of tasks: parity of four to ten inputs,
+    /// the 6- and 11-input multiplexers, the 2- and 3-bit adders and majority
+    /// of five, seven and nine inputs.
+    pub fn standard() -> Vec<Task> {
+        let mut res: Vec<Task> = (4..=10).map(Task::Parity).collect();
+        res.extend(&[Task::Multiplexer(2), Task::Multiplexer(3)]);
+        res.extend(&[Task::Adder(2), Task::Adder(3)]);
+        res.extend(&[Task::Majority(5), Task::Majority(7), Task::Majority(9)]);
+        res
+    }
+}
+
+/// Formats tasks by their name and size, such as `parity-4`, `mux-6`,
+/// `adder-2` or `majority-5`.
+impl fmt::Display for Task {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Task::Adder(n) => write!(f, "adder-{}", n),
+            Task::Majority(n) => write!(f, "majority-{}", n),
+            Task::Multiplexer(_) => write!(f, "mux-{}", self.input_width()),
+            Task::Parity(n) => write!(f, "parity-{}", n)
+        }
+    }
+}
+
+/// Represents the outcome of the runs of a single task.
+#[derive(Clone,Debug)]
+pub struct TaskResult {
+    /// The number of output bits over every sample of the task.
+    pub bits: usize,
+
+    /// The error of the best network of each run, in the order of `SEEDS`.
+    pub errors: Vec<usize>,
+
+    /// The total number of networks evaluated over every run.
+    pub evaluations: usize,
+
+    /// The total wall-clock time of every run, in seconds.
+    pub seconds: f64,
+
+    /// The task.
+    pub task: Task
+}
+
+/// Implements custom methods for task results.
+impl TaskResult {
+    /// Returns the fraction of output bits reproduced by the best networks,
+    /// averaged over every run.
+    pub fn bit_accuracy(&self) -> f64 {
+        if self.bits == 0 || self.errors.is_empty() {
+            return 0.0;
+        }
+        let mean = self.errors.iter().sum::<usize>() as f64 / self.errors.len() as f64;
+        1.0 - mean / self.bits as f64
+    }
+
+    /// Returns the number of runs whose best network reproduces the task
+    /// exactly.
+    pub fn solved(&self) -> usize {
+        self.errors.iter().filter(|&&e| e == 0).count()
+    }
+}
+
+/// Runs every specified task once per seed of `SEEDS`, returning their
+/// results.
+pub fn run(tasks: &[Task]) -> SuiteReport {
+    SuiteReport {
+        results: tasks.iter().map(|&t| run_task(t)).collect()
+    }
+}
+
+/// Runs the specified task once per seed of `SEEDS`.
+pub fn run_task(task: Task) -> TaskResult {
+    let dataset = task.dataset();
+    let layers = suggest_topology(&dataset);
+    let start = Instant::now();
+    let mut result = TaskResult {
+        bits: dataset.iter().map(|(_, e)| e.len()).sum(),
+        errors: Vec::with_capacity(SEEDS.len()),
+        evaluations: 0,
+        seconds: 0.0,
+        task
+    };
+    for &seed in SEEDS.iter() {
+        let mut rng = Xoshiro256::seed_from_u64(seed);
+        let mut population = Population::new_with_rng(POPULATION_SIZE, task.input_width(), layers.clone(), EvolveOptions::default(), &mut rng);
+        let report = population.evolve_with_rng(&dataset, GENERATIONS, &mut rng);
+        result.errors.push(report.best_error);
+        result.evaluations += report.evaluations;
+    }
+    result.seconds = start.elapsed().as_secs_f64();
+    result
+}
@@ -0,0 +1,141 @@
+//! Contains width-carrying wrappers around the boolean vectors consumed and
+//! produced by `bnl` networks, along with `parse_bits` for reading them from
+//! text.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Represents an input vector of boolean values to be fed into a network.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct InputVec {
+    /// The underlying boolean values.
+    bits: Vec<bool>
+}
+
+/// Implements custom methods on input vectors.
+impl InputVec {
+    /// Returns the underlying boolean values of this vector as a slice.
+    pub fn as_slice(&self) -> &[bool] {
+        &self.bits
+    }
+
+    /// Consumes this vector, returning the underlying boolean values.
+    pub fn into_inner(self) -> Vec<bool> {
+        self.bits
+    }
+
+    /// Creates a new input vector from the specified boolean values.
+    pub fn new(bits: Vec<bool>) -> Self {
+        InputVec {
+            bits
+        }
+    }
+
+    /// Returns the width (number of boolean values) of this vector.
+    pub fn width(&self) -> usize {
+        self.bits.len()
+    }
+}
+
+/// Allows input vectors to be created from plain vectors of boolean values.
+impl From<Vec<bool>> for InputVec {
+    fn from(bits: Vec<bool>) -> Self {
+        InputVec::new(bits)
+    }
+}
+
+/// Allows the output of one network to be used as the input of another.
+impl From<OutputVec> for InputVec {
+    fn from(output: OutputVec) -> Self {
+        InputVec::new(output.into_inner())
+    }
+}
+
+/// Represents an output vector of boolean values produced by a network.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct OutputVec {
+    /// The underlying boolean values.
+    bits: Vec<bool>
+}
+
+/// Implements custom methods on output vectors.
+impl OutputVec {
+    /// Returns the underlying boolean values of this vector as a slice.
+    pub fn as_slice(&self) -> &[bool] {
+        &self.bits
+    }
+
+    /// Consumes this vector, returning the underlying boolean values.
+    pub fn into_inner(self) -> Vec<bool> {
+        self.bits
+    }
+
+    /// Creates a new output vector from the specified boolean values.
+    pub fn new(bits: Vec<bool>) -> Self {
+        OutputVec {
+            bits
+        }
+    }
+
+    /// Returns the width (number of boolean values) of this vector.
+    pub fn width(&self) -> usize {
+        self.bits.len()
+    }
+}
+
+/// Formats the specified boolean values as a single run of `0`/`1` digits, as
+/// accepted by `parse_bits`.
+pub fn format_bits(bits: &[bool]) -> String {
+    bits.iter().map(|&b| if b { '1' } else { '0' }).collect()
+}
+
+/// Computes the number of positions at which two boolean vectors differ.
+/// Any difference in length also counts towards the distance.
+pub fn hamming_distance(left: &[bool], right: &[bool]) -> usize {
+    let differing = left.iter().zip(right.iter()).filter(|(l, r)| l != r).count();
+    differing + left.len().max(right.len()) - left.len().min(right.len())
+}
+
+/// Parses a vector of boolean values from a flexibly formatted string.
+///
+/// If `hex_width` is specified, the string is read as a hexadecimal number
+/// (optionally prefixed by `0x`) and converted into that many bits, most
+/// significant first, so that `"0x2d"` with a width of `6` is `101101`.
+/// Otherwise the string is either a single run of `0`/`1` digits (`"101101"`)
+/// or a sequence of values separated by commas and/or whitespace, each of
+/// which is `0`, `1`, `t`, `f`, `true` or `false` in any case (`"1,0,1"`,
+/// `"t f t"`).
+pub fn parse_bits(s: &str, hex_width: Option<usize>) -> Result<Vec<bool>, String> {
+    let s = s.trim();
+    if let Some(width) = hex_width {
+        let digits = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+        let mut bits: Vec<bool> = Vec::new();
+        for c in digits.chars() {
+            let d = c.to_digit(16).ok_or_else(|| format!("{:?} is not a hexadecimal digit", c))?;
+            bits.extend((0..4).rev().map(|i| (d >> i) & 1 == 1));
+        }
+        if bits.len() > width {
+            let excess = bits.len() - width;
+            if bits[..excess].iter().any(|&b| b) {
+                return Err(format!("{} does not fit in {} bits", s, width));
+            }
+            bits.drain(..excess);
+        }
+        let mut res: Vec<bool> = vec![false; width - bits.len()];
+        res.extend(bits);
+        return Ok(res);
+    }
+    if !s.is_empty() && s.chars().all(|c| c == '0' || c == '1') {
+        return Ok(s.chars().map(|c| c == '1').collect());
+    }
+    s.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|t| !t.is_empty())
+        .map(|t| match t.to_ascii_lowercase().as_str() {
+            "0" | "f" | "false" => Ok(false),
+            "1" | "t" | "true" => Ok(true),
+            _ => Err(format!("{:?} is not a boolean value", t))
+        })
+        .collect()
+}
@@ -0,0 +1,159 @@
+//! Contains the `NetworkBuilder` type, for constructing `bnl` networks by hand
+//! (such as reference circuits to compare trained networks against) rather
+//! than at random.
+//!
+//! Each layer is described by a closure adding its neurons to a
+//! `LayerBuilder`, which knows the width of the layer's input:
+//!
+//! ```
+//! use bnl::builder::NetworkBuilder;
+//! use bnl::Combinator::*;
+//!
+//! // The parity of three inputs.
+//! let parity = NetworkBuilder::new(3)
+//!     .layer(|l| l.neuron(&[Xor, Xor], false, Left))
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(parity.apply(&[true, false, true]), vec![false]);
+//! ```
+//!
+//! Hand-crafted layers may be mixed with random ones initialized from an
+//! `InitProfile` (see `NetworkBuilder::random_layer`).
+
+use crate::combinator::Combinator;
+use crate::network::{InitProfile, Layer, Network, Neuron, Reduction};
+use rand::Rng;
+
+/// Represents a layer of a `NetworkBuilder` under construction.
+#[derive(Clone,Debug)]
+pub struct LayerBuilder {
+    /// The width of the input of this layer.
+    input_len: usize,
+
+    /// The neurons added to this layer so far.
+    neurons: Vec<Neuron>
+}
+
+/// Implements custom methods for layer builders.
+impl LayerBuilder {
+    /// Adds a neuron which always outputs the specified value (see
+    /// `Neuron::constant`).
+    pub fn constant(mut self, value: bool) -> Self {
+        self.neurons.push(Neuron::constant(self.input_len, value));
+        self
+    }
+
+    /// Returns the width of the input of this layer, which is one more than the
+    /// number of input combinators each of its neurons must have.
+    pub fn input_len(&self) -> usize {
+        self.input_len
+    }
+
+    /// Locks the neuron added last (see `Neuron::locked`), so that training
+    /// leaves it untouched.
+    ///
+    /// Panics if no neuron has been added yet.
+    pub fn locked(mut self) -> Self {
+        self.neurons.last_mut().expect("Cannot lock a neuron before adding one!").locked = true;
+        self
+    }
+
+    /// Adds a neuron with the specified input combinator chain, bias and result
+    /// combinator, reading the whole input of this layer.
+    ///
+    /// The length of the chain is checked by `NetworkBuilder::build`.
+    pub fn neuron(mut self, input_combinators: &[Combinator], bias: bool, result_combinator: Combinator) -> Self {
+        self.neurons.push(Neuron {
+            arity: self.input_len,
+            bias,
+            input_combinators: input_combinators.to_vec(),
+            inputs: None,
+            locked: false,
+            reduction: Reduction::RightFold,
+            result_combinator
+        });
+        self
+    }
+
+    /// Adds the specified neuron as-is.
+    pub fn push(mut self, neuron: Neuron) -> Self {
+        self.neurons.push(neuron);
+        self
+    }
+
+    /// Adds a neuron which outputs the input value at the specified index (see
+    /// `Neuron::selector`).
+    pub fn selector(mut self, index: usize) -> Self {
+        self.neurons.push(Neuron::selector(self.input_len, index));
+        self
+    }
+}
+
+/// Represents a `bnl` network under construction.
+#[derive(Clone,Debug)]
+pub struct NetworkBuilder {
+    /// The width of the input of the network.
+    input_len: usize,
+
+    /// The layers added so far.
+    layers: Vec<Layer>
+}
+
+/// Implements custom methods for network builders.
+impl NetworkBuilder {
+    /// Finishes building the network, checking that every layer contains at
+    /// least one neuron and that every neuron's input combinator chain matches
+    /// the width of its layer's input.
+    pub fn build(self) -> Result<Network, String> {
+        let mut width = self.input_len;
+        for (i, l) in self.layers.iter().enumerate() {
+            if l.neurons.is_empty() {
+                return Err(format!("layer {}: layer contains no neurons", i));
+            }
+            l.validate(width).map_err(|e| format!("layer {}: {}", i, e))?;
+            width = l.neurons.len();
+        }
+        Ok(Network {
+            layers: self.layers
+        })
+    }
+
+    /// Appends a layer whose neurons are added by the specified closure.
+    pub fn layer<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(LayerBuilder) -> LayerBuilder
+    {
+        let input_len = self.layers.last().map_or(self.input_len, |l| l.neurons.len());
+        let layer = f(LayerBuilder {
+            input_len,
+            neurons: Vec::new()
+        });
+        self.layers.push(Layer {
+            neurons: layer.neurons
+        });
+        self
+    }
+
+    /// Creates a new builder of a network reading inputs of the specified
+    /// width.
+    pub fn new(input_len: usize) -> Self {
+        NetworkBuilder {
+            input_len,
+            layers: Vec::new()
+        }
+    }
+
+    /// Appends a layer of the specified number of neurons, randomly
+    /// initialized according to the specified profile.
+    pub fn random_layer(self, num_neurons: usize, profile: &InitProfile) -> Self {
+        self.random_layer_with_rng(num_neurons, profile, &mut crate::random::rng())
+    }
+
+    /// Appends a random layer as with `NetworkBuilder::random_layer`, drawing
+    /// its parameters from the specified generator.
+    pub fn random_layer_with_rng<R: Rng + ?Sized>(mut self, num_neurons: usize, profile: &InitProfile, rng: &mut R) -> Self {
+        let input_len = self.layers.last().map_or(self.input_len, |l| l.neurons.len());
+        self.layers.push(profile.layer_with_rng(input_len, num_neurons, rng));
+        self
+    }
+}
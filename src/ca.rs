@@ -0,0 +1,115 @@
+//! Contains routines for using `bnl` neurons and networks as the local update
+//! rules of cellular automata over boolean lines and grids.
+//!
+//! All automata wrap around at their edges (toroidal boundary conditions).
+
+use crate::network::{Network, Neuron};
+
+/// Represents the shape of the neighborhood read by the rule of a
+/// two-dimensional cellular automaton.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Neighborhood {
+    /// Every cell within the given Chebyshev distance, including the cell
+    /// itself, so a radius of one reads `9` cells.
+    Moore(usize),
+
+    /// Every cell within the given Manhattan distance, including the cell
+    /// itself, so a radius of one reads `5` cells.
+    VonNeumann(usize)
+}
+
+/// Implements custom methods for neighborhoods.
+impl Neighborhood {
+    /// Returns the `(row, column)` offsets of the cells in this neighborhood,
+    /// in the row-major order in which they are passed to the update rule.
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        let (radius, manhattan) = match self {
+            Neighborhood::Moore(r) => (*r as isize, false),
+            Neighborhood::VonNeumann(r) => (*r as isize, true)
+        };
+        let mut res: Vec<(isize, isize)> = Vec::new();
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                if !manhattan || dy.abs() + dx.abs() <= radius {
+                    res.push((dy, dx));
+                }
+            }
+        }
+        res
+    }
+}
+
+/// Represents anything which may act as the local update rule of a cellular
+/// automaton, mapping the states of a neighborhood to the next state of the
+/// cell at its center.
+pub trait Rule {
+    /// Computes the next state of a cell from the current states of its
+    /// neighborhood.
+    fn next(&self, neighborhood: &[bool]) -> bool;
+}
+
+/// Allows a network to act as an update rule, using its first output.
+impl Rule for Network {
+    fn next(&self, neighborhood: &[bool]) -> bool {
+        self.apply(neighborhood)[0]
+    }
+}
+
+/// Allows a single neuron to act as an update rule.
+impl Rule for Neuron {
+    fn next(&self, neighborhood: &[bool]) -> bool {
+        self.apply(neighborhood)
+    }
+}
+
+/// Runs a two-dimensional cellular automaton for the specified number of
+/// steps, returning every generation of the grid (starting with the initial
+/// grid).
+pub fn run_grid<R: Rule>(rule: &R, grid: Vec<Vec<bool>>, neighborhood: Neighborhood, steps: usize) -> Vec<Vec<Vec<bool>>> {
+    let mut history: Vec<Vec<Vec<bool>>> = vec![grid];
+    for _i in 0..steps {
+        let next = step_grid(rule, history.last().unwrap(), neighborhood);
+        history.push(next);
+    }
+    history
+}
+
+/// Runs a one-dimensional cellular automaton for the specified number of
+/// steps, returning every generation of the line (starting with the initial
+/// line).
+pub fn run_line<R: Rule>(rule: &R, cells: Vec<bool>, radius: usize, steps: usize) -> Vec<Vec<bool>> {
+    let mut history: Vec<Vec<bool>> = vec![cells];
+    for _i in 0..steps {
+        let next = step_line(rule, history.last().unwrap(), radius);
+        history.push(next);
+    }
+    history
+}
+
+/// Advances a two-dimensional cellular automaton by a single step.
+pub fn step_grid<R: Rule>(rule: &R, grid: &[Vec<bool>], neighborhood: Neighborhood) -> Vec<Vec<bool>> {
+    let rows = grid.len() as isize;
+    let offsets = neighborhood.offsets();
+    (0..rows).map(|y| {
+        let cols = grid[y as usize].len() as isize;
+        (0..cols).map(|x| {
+            let n: Vec<bool> = offsets.iter()
+                .map(|(dy, dx)| grid[(y + dy).rem_euclid(rows) as usize][(x + dx).rem_euclid(cols) as usize])
+                .collect();
+            rule.next(&n)
+        }).collect()
+    }).collect()
+}
+
+/// Advances a one-dimensional cellular automaton by a single step, where each
+/// cell reads the `2 * radius + 1` cells centered on it (from left to right).
+pub fn step_line<R: Rule>(rule: &R, cells: &[bool], radius: usize) -> Vec<bool> {
+    let len = cells.len() as isize;
+    let radius = radius as isize;
+    (0..len).map(|i| {
+        let n: Vec<bool> = (-radius..=radius)
+            .map(|d| cells[(i + d).rem_euclid(len) as usize])
+            .collect();
+        rule.next(&n)
+    }).collect()
+}
@@ -0,0 +1,198 @@
+//! Contains a persistent cache of network errors, shared between runs within
+//! an experiment directory.
+//!
+//! Each entry maps a hash of a network's structure and a hash of a dataset to
+//! the error of that network on that dataset, so repeated runs (such as the
+//! runs of a hyperparameter sweep) need not re-score identical networks.
+//! Entries are stored one per line in the file `eval-cache.txt` as three
+//! hexadecimal numbers (network hash, dataset hash, error), and new entries
+//! are appended by `EvalCache::flush`, so several runs may share one file.
+//!
+//! The hashes are 64-bit FNV-1a hashes, which are stable across platforms and
+//! compiler versions but not collision-free; a collision yields the error of a
+//! different network.
+
+use crate::network::{Network, Reduction};
+use crate::random::Map;
+use crate::train::error;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// The name of the cache file within an experiment directory.
+pub const CACHE_FILE: &str = "eval-cache.txt";
+
+/// Represents a persistent cache of network errors.
+#[derive(Clone,Debug)]
+pub struct EvalCache {
+    /// The cached errors, keyed by network hash and then dataset hash.
+    pub entries: Map<(u64, u64), usize>,
+
+    /// The number of lookups answered from the cache.
+    pub hits: usize,
+
+    /// The number of lookups which required evaluating the network.
+    pub misses: usize,
+
+    /// The path of the cache file.
+    pub path: PathBuf,
+
+    /// The entries added since the cache was opened or last flushed.
+    pending: Vec<(u64, u64, usize)>
+}
+
+/// Implements custom methods for evaluation caches.
+impl EvalCache {
+    /// Returns the error of the specified network on the specified dataset,
+    /// whose hash (see `dataset_hash`) must also be specified, evaluating the
+    /// network and caching the result if it is not already cached.
+    pub fn error(&mut self, network: &Network, dataset: &[(Vec<bool>, Vec<bool>)], dataset_hash: u64) -> usize {
+        match self.get(network, dataset_hash) {
+            Some(e) => e,
+            None => {
+                let e = error(network, dataset);
+                self.insert(network, dataset_hash, e);
+                e
+            }
+        }
+    }
+
+    /// Appends every entry added since the cache was opened or last flushed to
+    /// the cache file.
+    pub fn flush(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        let mut text = String::new();
+        for (n, d, e) in &self.pending {
+            text.push_str(&format!("{:016x} {:016x} {:x}\n", n, d, e));
+        }
+        file.write_all(text.as_bytes())?;
+        self.pending.clear();
+        Ok(())
+    }
+
+    /// Returns the cached error of the specified network on the dataset with
+    /// the specified hash, if any, counting the lookup as a hit or a miss.
+    pub fn get(&mut self, network: &Network, dataset_hash: u64) -> Option<usize> {
+        let res = self.entries.get(&(network_hash(network), dataset_hash)).copied();
+        if res.is_some() {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        res
+    }
+
+    /// Caches the error of the specified network on the dataset with the
+    /// specified hash, to be written to the cache file by the next `flush`.
+    pub fn insert(&mut self, network: &Network, dataset_hash: u64, error: usize) {
+        let key = (network_hash(network), dataset_hash);
+        if self.entries.insert(key, error) != Some(error) {
+            self.pending.push((key.0, key.1, error));
+        }
+    }
+
+    /// Opens the cache of the specified experiment directory, creating the
+    /// directory if it does not exist and loading any existing entries.
+    pub fn open<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let path = dir.as_ref().join(CACHE_FILE);
+        let mut entries: Map<(u64, u64), usize> = Map::new();
+        if path.exists() {
+            for (i, line) in fs::read_to_string(&path)?.lines().enumerate() {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let parsed = match fields.as_slice() {
+                    [n, d, e] => u64::from_str_radix(n, 16).ok()
+                        .zip(u64::from_str_radix(d, 16).ok())
+                        .zip(usize::from_str_radix(e, 16).ok()),
+                    _ => None
+                };
+                match parsed {
+                    Some(((n, d), e)) => {
+                        entries.insert((n, d), e);
+                    },
+                    None => return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{}:{}: malformed cache entry", path.display(), i + 1)
+                    ))
+                }
+            }
+        }
+        Ok(EvalCache {
+            entries,
+            hits: 0,
+            misses: 0,
+            path,
+            pending: Vec::new()
+        })
+    }
+}
+
+/// Computes a stable hash of the concatenation of the specified byte strings.
+pub(crate) fn bytes_hash(chunks: &[&[u8]]) -> u64 {
+    let mut h = Fnv::new();
+    chunks.iter().flat_map(|c| c.iter()).for_each(|&b| h.write_u8(b));
+    h.0
+}
+
+/// Computes a stable hash of the specified dataset.
+pub fn dataset_hash(dataset: &[(Vec<bool>, Vec<bool>)]) -> u64 {
+    let mut h = Fnv::new();
+    h.write_usize(dataset.len());
+    for (input, expected) in dataset {
+        h.write_usize(input.len());
+        input.iter().for_each(|&b| h.write_u8(b as u8));
+        h.write_usize(expected.len());
+        expected.iter().for_each(|&b| h.write_u8(b as u8));
+    }
+    h.0
+}
+
+/// Computes a stable hash of the structure of the specified network.
+pub fn network_hash(network: &Network) -> u64 {
+    let mut h = Fnv::new();
+    h.write_usize(network.layers.len());
+    for layer in &network.layers {
+        h.write_usize(layer.neurons.len());
+        for n in &layer.neurons {
+            h.write_usize(n.arity);
+            h.write_u8(n.bias as u8);
+            h.write_usize(n.input_combinators.len());
+            n.input_combinators.iter().for_each(|&c| h.write_u8(u8::from(c)));
+            if let Some(inputs) = &n.inputs {
+                inputs.iter().for_each(|&i| h.write_usize(i));
+            }
+            if n.reduction != Reduction::RightFold {
+                h.write_u8(n.reduction as u8);
+            }
+            h.write_u8(u8::from(n.result_combinator));
+        }
+    }
+    h.0
+}
+
+/// Represents the state of a 64-bit FNV-1a hash.
+struct Fnv(u64);
+
+/// Implements custom methods for FNV-1a hashes.
+impl Fnv {
+    /// Creates a new hash in its initial state.
+    fn new() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+
+    /// Feeds a single byte into this hash.
+    fn write_u8(&mut self, byte: u8) {
+        self.0 ^= byte as u64;
+        self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+
+    /// Feeds a length or count into this hash, as eight little-endian bytes.
+    fn write_usize(&mut self, value: usize) {
+        for byte in (value as u64).to_le_bytes().iter() {
+            self.write_u8(*byte);
+        }
+    }
+}
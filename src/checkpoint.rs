@@ -0,0 +1,199 @@
+//! Contains the `Trainer` type, for running `Network::train` as a resumable
+//! job which periodically saves its complete state to disk.
+//!
+//! A checkpoint holds the current network, the training options, the report
+//! so far and the state of the trainer's random number generator (a
+//! `random::Xoshiro256`), so that a run resumed from it via
+//! `Trainer::resume` continues exactly as the interrupted run would have,
+//! finishing with the same network and report. The dataset itself is not
+//! saved, and must be passed again when resuming; its hash (see
+//! `Dataset::hash`) is saved and checked, so that resuming a run on a
+//! different dataset fails with an `Error::DatasetMismatch` rather than
+//! silently continuing on it.
+//!
+//! ```no_run
+//! use bnl::checkpoint::Trainer;
+//!
+//! let dataset = vec![(vec![true, false], vec![true])];
+//! let network = bnl::Network::new(2, vec![3, 1]);
+//! let mut trainer = Trainer::new(network, Default::default(), 42).with_checkpoints("run.bin", 1000);
+//! let report = trainer.run(&dataset).unwrap();
+//!
+//! // ...or, after the process was killed:
+//! let report = Trainer::resume("run.bin").unwrap().run(&dataset).unwrap();
+//! ```
+
+use crate::batch::BitDataset;
+use crate::cache::dataset_hash;
+use crate::error::Error;
+use crate::network::Network;
+use crate::persist::{corrupt_model, deserialize, invalid_data, Format};
+use crate::random::Xoshiro256;
+use crate::train::{climb, Batches, TrainOptions, TrainReport};
+use rand::SeedableRng;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Represents a resumable hill-climbing run (see `Network::train`).
+#[derive(Clone,Debug,serde::Serialize,serde::Deserialize)]
+pub struct Trainer {
+    /// The file checkpoints are written to and the number of iterations
+    /// between them, if checkpointing is enabled.
+    checkpoints: Option<(PathBuf, usize)>,
+
+    /// The hash of the dataset being trained on (see `cache::dataset_hash`),
+    /// once training has started.
+    dataset_hash: Option<u64>,
+
+    /// The network being trained.
+    network: Network,
+
+    /// The options controlling training.
+    options: TrainOptions,
+
+    /// The outcome of training so far, once training has started.
+    report: Option<TrainReport>,
+
+    /// The generator random choices are drawn from.
+    rng: Xoshiro256
+}
+
+/// Implements custom methods for resumable trainers.
+impl Trainer {
+    /// Saves the complete state of this trainer to the specified file, in the
+    /// format given by its extension (see `persist::Format::from_path`).
+    ///
+    /// The state is first written to a temporary file alongside it, which
+    /// then replaces the file, so that an interrupted write never leaves a
+    /// corrupt checkpoint behind.
+    pub fn checkpoint<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        #[cfg(feature = "profiling")]
+        let _timer = crate::profile::timer(crate::profile::Phase::Serialize);
+        let path = path.as_ref();
+        let bytes = match Format::from_path(path) {
+            Format::Bincode => bincode::serialize(self).map_err(invalid_data)?,
+            Format::Json => serde_json::to_vec_pretty(self).map_err(invalid_data)?
+        };
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, bytes)?;
+        fs::rename(&temporary, path)
+    }
+
+    /// Returns the hash of the dataset being trained on (see `Dataset::hash`),
+    /// or `None` if training has not started.
+    pub fn dataset_hash(&self) -> Option<u64> {
+        self.dataset_hash
+    }
+
+    /// Determines whether this trainer has finished, having run for every
+    /// iteration of its options or reproduced every sample.
+    pub fn is_finished(&self) -> bool {
+        self.report.as_ref().is_some_and(|r| r.iterations >= self.options.iterations || r.final_error == 0)
+    }
+
+    /// Returns the network being trained.
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+
+    /// Creates a new trainer of the specified network with the specified
+    /// options, whose random choices are drawn from a generator seeded with
+    /// the specified seed.
+    ///
+    /// Running it to completion trains the network exactly as
+    /// `Network::train_with_rng` would with `Xoshiro256::seed_from_u64(seed)`.
+    pub fn new(network: Network, options: TrainOptions, seed: u64) -> Self {
+        Trainer {
+            checkpoints: None,
+            dataset_hash: None,
+            network,
+            options,
+            report: None,
+            rng: Xoshiro256::seed_from_u64(seed)
+        }
+    }
+
+    /// Returns the outcome of training so far, or `None` if training has not
+    /// started.
+    pub fn report(&self) -> Option<&TrainReport> {
+        self.report.as_ref()
+    }
+
+    /// Loads a trainer from the checkpoint at the specified file, in the format
+    /// given by its extension (see `persist::Format::from_path`), which
+    /// continues checkpointing as it did before.
+    pub fn resume<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let bytes = fs::read(&path)?;
+        let trainer: Trainer = deserialize(&bytes, Format::from_path(&path))?;
+        trainer.network.validate().map_err(|e| corrupt_model(None, e))?;
+        Ok(trainer)
+    }
+
+    /// Trains the network on the specified dataset until this trainer has
+    /// finished (see `Trainer::is_finished`), returning the final report.
+    ///
+    /// Fails as with `Trainer::run_for` if training was started on a
+    /// different dataset.
+    pub fn run(&mut self, dataset: &[(Vec<bool>, Vec<bool>)]) -> io::Result<TrainReport> {
+        self.run_for(dataset, usize::MAX)?;
+        Ok(self.report.clone().unwrap())
+    }
+
+    /// Trains the network on the specified dataset for at most the specified
+    /// number of further iterations, writing a checkpoint (if enabled) each
+    /// time the total number of iterations reaches a multiple of the
+    /// checkpoint interval and once training has finished. Returns whether
+    /// training has finished.
+    ///
+    /// Fails with an I/O error of kind `InvalidInput` wrapping an
+    /// `Error::DatasetMismatch`, leaving this trainer untouched, if training
+    /// was started on a different dataset.
+    pub fn run_for(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], iterations: usize) -> io::Result<bool> {
+        let hash = dataset_hash(dataset);
+        if let Some(expected) = self.dataset_hash.filter(|&h| h != hash) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, Error::DatasetMismatch { expected, found: hash }));
+        }
+        self.dataset_hash = Some(hash);
+        let packed = BitDataset::from_samples(dataset);
+        let mut report = match self.report.take() {
+            Some(r) => r,
+            None => {
+                let initial = packed.error(&self.network);
+                TrainReport {
+                    final_error: initial,
+                    history: vec![initial],
+                    initial_error: initial,
+                    iterations: 0
+                }
+            }
+        };
+        let batches = Batches::new(dataset.to_vec(), self.options.balanced_batch);
+        for _i in 0..iterations {
+            if report.iterations >= self.options.iterations || report.final_error == 0 {
+                break;
+            }
+            climb(&mut self.network, &packed, batches.as_ref(), &self.options, &mut report, &mut self.rng);
+            if let Some((path, interval)) = &self.checkpoints {
+                if *interval > 0 && report.iterations.is_multiple_of(*interval) {
+                    self.report = Some(report.clone());
+                    self.checkpoint(path)?;
+                }
+            }
+        }
+        self.report = Some(report);
+        let finished = self.is_finished();
+        if let (true, Some((path, _))) = (finished, &self.checkpoints) {
+            self.checkpoint(path)?;
+        }
+        Ok(finished)
+    }
+
+    /// Enables checkpointing of this trainer to the specified file every
+    /// specified number of iterations.
+    pub fn with_checkpoints<P: AsRef<Path>>(mut self, path: P, interval: usize) -> Self {
+        self.checkpoints = Some((path.as_ref().to_path_buf(), interval));
+        self
+    }
+}
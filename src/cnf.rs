@@ -0,0 +1,112 @@
+//! Contains routines for encoding `bnl` networks as boolean formulas in
+//! conjunctive normal form (CNF), for analysis with off-the-shelf SAT
+//! solvers.
+//!
+//! The encoding is a Tseitin transformation: every input of the network,
+//! every gate of every neuron (each of its input combinators, and its result
+//! combinator) and a constant `true` are given a variable, and each gate
+//! contributes the clauses relating its output variable to its input
+//! variables. The satisfying assignments of the formula are then exactly the
+//! evaluations of the network, one for each input vector.
+//!
+//! The formula is printed in the DIMACS format by its `Display`
+//! implementation, listing the variables of the inputs and outputs in comment
+//! lines. Constraining the outputs (see `Cnf::with_outputs`) turns it into the
+//! question of whether some input produces them, whose solutions are the
+//! inputs which do.
+
+use crate::combinator::Combinator;
+use crate::network::{compute_boolean, Network};
+use std::fmt;
+
+/// Represents a formula in conjunctive normal form relating the inputs of a
+/// network to its outputs.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct Cnf {
+    /// The clauses of the formula, each a disjunction of literals, where the
+    /// literal `v` is variable `v` and `-v` is its negation.
+    pub clauses: Vec<Vec<i64>>,
+
+    /// The variable of each input of the network.
+    pub inputs: Vec<i64>,
+
+    /// The number of variables, numbered from one.
+    pub num_vars: usize,
+
+    /// The variable of each output of the network.
+    pub outputs: Vec<i64>
+}
+
+/// Implements custom methods for CNF formulas.
+impl Cnf {
+    /// Creates a copy of this formula additionally requiring each output of
+    /// the network to take the specified value.
+    ///
+    /// Panics if the number of values does not match the number of outputs.
+    pub fn with_outputs(&self, values: &[bool]) -> Cnf {
+        if values.len() != self.outputs.len() {
+            panic!("Cannot constrain {} outputs with {} values!", self.outputs.len(), values.len());
+        }
+        let mut res = self.clone();
+        res.clauses.extend(self.outputs.iter().zip(values.iter()).map(|(&o, &v)| vec![if v { o } else { -o }]));
+        res
+    }
+}
+
+/// Writes CNF formulas in the DIMACS format.
+impl fmt::Display for Cnf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names = |vars: &[i64]| vars.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(" ");
+        writeln!(f, "c inputs {}", names(&self.inputs))?;
+        writeln!(f, "c outputs {}", names(&self.outputs))?;
+        writeln!(f, "p cnf {} {}", self.num_vars, self.clauses.len())?;
+        for clause in &self.clauses {
+            writeln!(f, "{} 0", names(clause))?;
+        }
+        Ok(())
+    }
+}
+
+/// Implements CNF encoding on `bnl` networks.
+impl Network {
+    /// Encodes the relation between the inputs and outputs of this network as
+    /// a CNF formula via the Tseitin transformation.
+    pub fn to_cnf(&self) -> Cnf {
+        let width = self.input_width() as i64;
+        let constant = width + 1;
+        let mut res = Cnf {
+            clauses: vec![vec![constant]],
+            inputs: (1..=width).collect(),
+            num_vars: constant as usize,
+            outputs: Vec::new()
+        };
+        let mut signals: Vec<i64> = res.inputs.clone();
+        for layer in &self.layers {
+            let mut next: Vec<i64> = Vec::with_capacity(layer.neurons.len());
+            for n in &layer.neurons {
+                let mut gate = |left: i64, right: i64, combinator: Combinator| {
+                    res.num_vars += 1;
+                    let output = res.num_vars as i64;
+                    encode_gate(&mut res.clauses, left, right, output, combinator);
+                    output
+                };
+                let selected: Vec<i64> = (0..n.fan_in()).map(|k| signals[n.input_index(k)]).collect();
+                let acc = n.reduction.reduce(selected, &n.input_combinators, &mut gate);
+                next.push(gate(acc, if n.bias { constant } else { -constant }, n.result_combinator));
+            }
+            signals = next;
+        }
+        res.outputs = signals;
+        res
+    }
+}
+
+/// Appends the clauses requiring the specified output literal to equal the
+/// specified combinator of the specified input literals, one per row of its
+/// truth table.
+fn encode_gate(clauses: &mut Vec<Vec<i64>>, left: i64, right: i64, output: i64, combinator: Combinator) {
+    for &(l, r) in &[(false, false), (false, true), (true, false), (true, true)] {
+        let z = if compute_boolean(l, r, combinator) { output } else { -output };
+        clauses.push(vec![if l { -left } else { left }, if r { -right } else { right }, z]);
+    }
+}
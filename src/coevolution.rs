@@ -0,0 +1,154 @@
+//! Contains a coevolutionary trainer for `bnl` networks whose input space is
+//! too large to cover with a fixed dataset.
+//!
+//! Two populations evolve side by side: a `Population` of networks, and a
+//! population of test inputs whose correct outputs are given by an oracle.
+//! Networks are selected for making few errors on the current tests, while
+//! tests are selected for causing many errors across the current networks, so
+//! the tests continuously concentrate on the cases the networks find hardest.
+
+use crate::batch::BitDataset;
+use crate::network::Network;
+use crate::population::{EvolveOptions, Population};
+use rand::Rng;
+
+/// Represents the options controlling `coevolve`.
+#[derive(Clone,Debug)]
+pub struct CoevolveOptions {
+    /// The number of generations of both populations to evolve.
+    pub generations: usize,
+
+    /// The options controlling the evolution of the networks.
+    pub networks: EvolveOptions,
+
+    /// The number of networks in the network population.
+    pub network_population: usize,
+
+    /// The number of the hardest tests carried over unchanged into each new
+    /// generation.
+    pub test_elitism: usize,
+
+    /// The probability with which each bit of a bred test is flipped.
+    pub test_mutation_rate: f64,
+
+    /// The number of tests in the test population.
+    pub test_population: usize,
+
+    /// The number of tests competing in each tournament, the hardest of which
+    /// becomes a parent.
+    pub test_tournament_size: usize
+}
+
+/// Provides reasonable default coevolution options.
+impl Default for CoevolveOptions {
+    fn default() -> Self {
+        CoevolveOptions {
+            generations: 200,
+            networks: EvolveOptions::default(),
+            network_population: 100,
+            test_elitism: 2,
+            test_mutation_rate: 0.05,
+            test_population: 64,
+            test_tournament_size: 3
+        }
+    }
+}
+
+/// Represents the outcome of a call to `coevolve`.
+#[derive(Clone,Debug)]
+pub struct CoevolveReport {
+    /// The network with the lowest error on the final tests.
+    pub best: Network,
+
+    /// The lowest error of any network on the tests of each generation.
+    pub history: Vec<usize>,
+
+    /// The final population of networks.
+    pub networks: Population,
+
+    /// The final population of tests, hardest first.
+    pub tests: Vec<Vec<bool>>
+}
+
+/// Coevolves a population of networks of the specified input length and layer
+/// lengths alongside a population of test inputs, using the specified oracle
+/// to compute the expected output of each test.
+///
+/// Each generation, every network is evaluated on every test. The networks
+/// are bred by `Population::advance` according to their total error, and the
+/// tests by tournament selection on the total error they cause, uniform
+/// crossover and bit-flip mutation.
+///
+/// Panics if the network population is empty.
+pub fn coevolve<F>(input_len: usize, layer_lengths: Vec<usize>, oracle: F, opts: CoevolveOptions) -> CoevolveReport
+where
+    F: Fn(&[bool]) -> Vec<bool>
+{
+    coevolve_with_rng(input_len, layer_lengths, oracle, opts, &mut crate::random::rng())
+}
+
+/// Coevolves networks and tests as with `coevolve`, drawing random choices
+/// from the specified generator.
+pub fn coevolve_with_rng<F, R>(input_len: usize, layer_lengths: Vec<usize>, oracle: F, opts: CoevolveOptions, rng: &mut R) -> CoevolveReport
+where
+    F: Fn(&[bool]) -> Vec<bool>,
+    R: Rng + ?Sized
+{
+    let mut networks = Population::new_with_rng(opts.network_population, input_len, layer_lengths, opts.networks.clone(), rng);
+    let mut tests: Vec<Vec<bool>> = (0..opts.test_population.max(1))
+        .map(|_| (0..input_len).map(|_| rng.gen()).collect())
+        .collect();
+    let mut history: Vec<usize> = Vec::new();
+    if networks.is_empty() {
+        panic!("Cannot coevolve an empty population!");
+    }
+    let mut best = networks.network(0);
+    for generation in 0..opts.generations.max(1) {
+        let samples: Vec<(Vec<bool>, Vec<bool>)> = tests.iter().map(|t| (t.clone(), oracle(t))).collect();
+        let packed = BitDataset::from_samples(&samples);
+        let mut network_errors: Vec<usize> = vec![0; networks.len()];
+        let mut hardness: Vec<usize> = vec![0; tests.len()];
+        for (i, total) in network_errors.iter_mut().enumerate() {
+            let outputs = networks.genomes.apply_batch(i, &packed.inputs);
+            for (j, e) in outputs.sample_distances(&packed.expected).into_iter().enumerate() {
+                *total += e;
+                hardness[j] += e;
+            }
+        }
+        let fittest = (0..network_errors.len()).min_by_key(|&i| network_errors[i]).unwrap();
+        history.push(network_errors[fittest]);
+        let mut ranked: Vec<usize> = (0..tests.len()).collect();
+        ranked.sort_by_key(|&j| std::cmp::Reverse(hardness[j]));
+        if generation + 1 == opts.generations.max(1) {
+            best = networks.network(fittest);
+            tests = ranked.iter().map(|&j| tests[j].clone()).collect();
+            break;
+        }
+        networks.advance_with_rng(&network_errors, rng);
+        let tournament = |rng: &mut R| {
+            (0..opts.test_tournament_size.max(1))
+                .map(|_| rng.gen_range(0, tests.len()))
+                .max_by_key(|&j| hardness[j])
+                .unwrap()
+        };
+        let mut next: Vec<Vec<bool>> = ranked.iter()
+            .take(opts.test_elitism)
+            .map(|&j| tests[j].clone())
+            .collect();
+        while next.len() < tests.len() {
+            let a = &tests[tournament(rng)];
+            let b = &tests[tournament(rng)];
+            next.push(a.iter().zip(b.iter()).map(|(&x, &y)| {
+                let bit = if rng.gen() { x } else { y };
+                bit ^ rng.gen_bool(opts.test_mutation_rate)
+            }).collect());
+        }
+        tests = next;
+    }
+    CoevolveReport {
+        best,
+        history,
+        networks,
+        tests
+    }
+}
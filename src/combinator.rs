@@ -0,0 +1,295 @@
+//! Contains the `Combinator` type enumerating the sixteen two-input boolean
+//! functions from which `bnl` neurons are built, along with their algebra:
+//! negation (`!c`), argument swapping (`Combinator::swap`), duality
+//! (`Combinator::dual`), composition (`Combinator::compose`) and
+//! classification (`Combinator::is_linear`, `Combinator::is_monotone` and
+//! `Combinator::is_symmetric`).
+
+use alloc::format;
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops;
+use rand::distributions::{Distribution, Standard};
+use rand::Rng;
+
+/// Represents one of the sixteen boolean functions of a left and a right
+/// input.
+///
+/// The discriminant of each combinator is its truth table read as a 4-bit
+/// number, with the bit for `(left, right)` at position `3 - 2 * left - right`,
+/// so that the most significant bit gives the output for `(false, false)`.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash,PartialOrd,Ord)]
+#[repr(u8)]
+pub enum Combinator {
+    /// Always `false`.
+    False = 0,
+
+    /// `left && right`.
+    And = 1,
+
+    /// `left && !right`.
+    AndNotRight = 2,
+
+    /// `left`.
+    Left = 3,
+
+    /// `!left && right`.
+    AndNotLeft = 4,
+
+    /// `right`.
+    Right = 5,
+
+    /// `left != right`.
+    Xor = 6,
+
+    /// `left || right`.
+    Or = 7,
+
+    /// `!(left || right)`.
+    Nor = 8,
+
+    /// `left == right`.
+    Xnor = 9,
+
+    /// `!right`.
+    NotRight = 10,
+
+    /// `left || !right`.
+    ImpliedBy = 11,
+
+    /// `!left`.
+    NotLeft = 12,
+
+    /// `!left || right`.
+    Implies = 13,
+
+    /// `!(left && right)`.
+    Nand = 14,
+
+    /// Always `true`.
+    True = 15
+}
+
+/// Implements custom methods for combinators.
+impl Combinator {
+    /// Every combinator, in order of discriminant.
+    pub const ALL: [Combinator; 16] = [
+        Combinator::False, Combinator::And, Combinator::AndNotRight, Combinator::Left,
+        Combinator::AndNotLeft, Combinator::Right, Combinator::Xor, Combinator::Or,
+        Combinator::Nor, Combinator::Xnor, Combinator::NotRight, Combinator::ImpliedBy,
+        Combinator::NotLeft, Combinator::Implies, Combinator::Nand, Combinator::True
+    ];
+
+    /// Every linear (affine) combinator, whose output is the XOR of a constant
+    /// and some of its inputs, in order of discriminant.
+    pub const LINEAR: [Combinator; 8] = [
+        Combinator::False, Combinator::Left, Combinator::Right, Combinator::Xor,
+        Combinator::Xnor, Combinator::NotRight, Combinator::NotLeft, Combinator::True
+    ];
+
+    /// Every monotone combinator, whose output never changes from `true` to
+    /// `false` when either input changes from `false` to `true`, in order of
+    /// discriminant. A network built only from these computes a monotone
+    /// function of its input.
+    pub const MONOTONE: [Combinator; 6] = [
+        Combinator::False, Combinator::And, Combinator::Left,
+        Combinator::Right, Combinator::Or, Combinator::True
+    ];
+
+    /// Computes the output of this combinator for the specified inputs, as
+    /// with `compute_boolean`.
+    pub fn apply(&self, left: bool, right: bool) -> bool {
+        (*self as u8 >> (3 - 2 * left as u8 - right as u8)) & 1 == 1
+    }
+
+    /// Returns the combinator computing this combinator of the outputs of two
+    /// other combinators, `self(left(a, b), right(a, b))`.
+    pub fn compose(&self, left: Combinator, right: Combinator) -> Combinator {
+        Combinator::from_fn(|a, b| self.apply(left.apply(a, b), right.apply(a, b)))
+    }
+
+    /// Returns the table of every composition of this combinator (see
+    /// `Combinator::compose`), where entry `[l][r]` is its composition with
+    /// the combinators of discriminants `l` and `r`.
+    pub fn composition_table(&self) -> [[Combinator; 16]; 16] {
+        let mut res = [[Combinator::False; 16]; 16];
+        for (l, row) in res.iter_mut().enumerate() {
+            for (r, entry) in row.iter_mut().enumerate() {
+                *entry = self.compose(Combinator::ALL[l], Combinator::ALL[r]);
+            }
+        }
+        res
+    }
+
+    /// Returns the dual of this combinator, which negates both its inputs and
+    /// its output, exchanging (for instance) `AND` and `OR`.
+    pub fn dual(&self) -> Combinator {
+        Combinator::from_fn(|l, r| !self.apply(!l, !r))
+    }
+
+    /// Returns the combinator computing the specified function of its left
+    /// and right inputs.
+    pub fn from_fn<F: Fn(bool, bool) -> bool>(f: F) -> Combinator {
+        let table = [(false, false), (false, true), (true, false), (true, true)].iter()
+            .fold(0, |acc, &(l, r)| acc | ((f(l, r) as usize) << (3 - 2 * l as usize - r as usize)));
+        Combinator::ALL[table]
+    }
+
+    /// Returns the combinator whose discriminant is the specified value
+    /// modulo `16`, for leniently recovering combinators from values which may
+    /// be out of range (see `Network::from_bytes_lenient`). Strict conversions
+    /// should use `Combinator::try_from` instead.
+    pub fn from_u8_wrapping(value: u8) -> Combinator {
+        Combinator::ALL[(value % 16) as usize]
+    }
+
+    /// Determines whether this combinator is linear (see
+    /// `Combinator::LINEAR`).
+    pub fn is_linear(&self) -> bool {
+        Combinator::LINEAR.contains(self)
+    }
+
+    /// Determines whether this combinator is monotone (see
+    /// `Combinator::MONOTONE`).
+    pub fn is_monotone(&self) -> bool {
+        Combinator::MONOTONE.contains(self)
+    }
+
+    /// Determines whether this combinator is symmetric, giving the same output
+    /// when its inputs are swapped.
+    pub fn is_symmetric(&self) -> bool {
+        self.swap() == *self
+    }
+
+    /// Returns the name of this combinator, as printed by its `Display`
+    /// implementation.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Combinator::False       => "FALSE",
+            Combinator::And         => "AND",
+            Combinator::AndNotRight => "AND_NOT_RIGHT",
+            Combinator::Left        => "LEFT",
+            Combinator::AndNotLeft  => "AND_NOT_LEFT",
+            Combinator::Right       => "RIGHT",
+            Combinator::Xor         => "XOR",
+            Combinator::Or          => "OR",
+            Combinator::Nor         => "NOR",
+            Combinator::Xnor        => "XNOR",
+            Combinator::NotRight    => "NOT_RIGHT",
+            Combinator::ImpliedBy   => "IMPLIED_BY",
+            Combinator::NotLeft     => "NOT_LEFT",
+            Combinator::Implies     => "IMPLIES",
+            Combinator::Nand        => "NAND",
+            Combinator::True        => "TRUE"
+        }
+    }
+
+    /// Returns the combinator computing this combinator with its inputs
+    /// swapped, exchanging (for instance) `IMPLIES` and `IMPLIED_BY`.
+    pub fn swap(&self) -> Combinator {
+        Combinator::from_fn(|l, r| self.apply(r, l))
+    }
+}
+
+/// Allows combinators to be printed by name.
+impl fmt::Display for Combinator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Allows combinators to be converted back into their discriminants.
+impl From<Combinator> for u8 {
+    fn from(c: Combinator) -> u8 {
+        c as u8
+    }
+}
+
+/// Allows combinators to be negated, giving the combinator whose output is
+/// always the opposite of theirs.
+impl ops::Not for Combinator {
+    type Output = Combinator;
+
+    fn not(self) -> Combinator {
+        Combinator::ALL[15 - self as usize]
+    }
+}
+
+/// Allows uniformly random combinators to be generated via `Rng::gen`.
+impl Distribution<Combinator> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Combinator {
+        Combinator::ALL[rng.gen_range(0, 16)]
+    }
+}
+
+/// Allows combinators to be recovered from their discriminants, rejecting
+/// values above `15`.
+impl TryFrom<u8> for Combinator {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Combinator::ALL.get(value as usize)
+            .copied()
+            .ok_or_else(|| format!("{} is not a valid combinator (expected 0 to 15)", value))
+    }
+}
+
+/// Serializes combinators compactly as their discriminants.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Combinator {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(*self as u8)
+    }
+}
+
+/// Deserializes combinators from their discriminants, rejecting values above
+/// `15` unless deserializing leniently (see `deserialize_leniently`).
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Combinator {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = <u8 as serde::Deserialize>::deserialize(deserializer)?;
+        Combinator::try_from(value).or_else(|e| WRAPPED.with(|w| match w.borrow_mut().as_mut() {
+            Some(wrapped) => {
+                wrapped.push(value);
+                Ok(Combinator::from_u8_wrapping(value))
+            },
+            None => Err(serde::de::Error::custom(e))
+        }))
+    }
+}
+
+#[cfg(feature = "serde")]
+std::thread_local! {
+    /// The out-of-range discriminants normalized by the lenient
+    /// deserialization running on this thread, or `None` if deserialization
+    /// is strict.
+    static WRAPPED: core::cell::RefCell<Option<alloc::vec::Vec<u8>>> = const { core::cell::RefCell::new(None) };
+}
+
+/// Runs the specified deserialization on this thread with combinator values
+/// above `15` normalized modulo `16` rather than rejected, returning its
+/// result along with every out-of-range value normalized, in order.
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_leniently<T, F: FnOnce() -> T>(f: F) -> (T, alloc::vec::Vec<u8>) {
+    let mut guard = RestoreWrapped(Some(WRAPPED.with(|w| w.replace(Some(alloc::vec::Vec::new())))));
+    let res = f();
+    let outer = guard.0.take().unwrap_or_default();
+    let wrapped = WRAPPED.with(|w| w.replace(outer)).unwrap_or_default();
+    (res, wrapped)
+}
+
+/// Restores the deserialization mode of this thread that was in effect before
+/// a lenient deserialization when dropped, so that a deserialization which
+/// panics cannot leave the thread lenient.
+#[cfg(feature = "serde")]
+struct RestoreWrapped(Option<Option<alloc::vec::Vec<u8>>>);
+
+#[cfg(feature = "serde")]
+impl Drop for RestoreWrapped {
+    fn drop(&mut self) {
+        if let Some(outer) = self.0.take() {
+            WRAPPED.with(|w| w.replace(outer));
+        }
+    }
+}
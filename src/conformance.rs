@@ -0,0 +1,153 @@
+//! Contains conformance checks against the reference semantics of `bnl`
+//! networks, so alternative evaluation backends can verify that they match
+//! this implementation.
+//!
+//! Each check takes the backend's implementation of some operation as a
+//! closure and compares it against golden vectors produced by the reference
+//! implementation, returning a description of the first mismatch found.
+
+use crate::combinator::Combinator;
+use crate::network::{Layer, Network, Neuron, Reduction};
+
+/// The golden truth tables of the sixteen boolean combinators, where bit
+/// `2 * left + right` of each entry is the output of the combinator for that
+/// pair of inputs.
+pub const COMBINATOR_TABLE: [u8; 16] = [
+    0b0000, 0b1000, 0b0100, 0b1100, 0b0010, 0b1010, 0b0110, 0b1110,
+    0b0001, 0b1001, 0b0101, 0b1101, 0b0011, 0b1011, 0b0111, 0b1111
+];
+
+/// The golden outputs of the network returned by `golden_network` for each
+/// of its eight possible inputs, where bit `i` of the index is input `i`.
+pub const GOLDEN_OUTPUTS: [[bool; 2]; 8] = [
+    [true, true],
+    [true, true],
+    [false, true],
+    [true, false],
+    [false, true],
+    [true, false],
+    [true, true],
+    [true, true]
+];
+
+/// Checks that the specified implementation of `compute_boolean` agrees with
+/// the golden truth table for every combinator and pair of inputs.
+pub fn check_combinators<F: Fn(bool, bool, Combinator) -> bool>(compute: F) -> Result<(), String> {
+    for (&c, table) in Combinator::ALL.iter().zip(COMBINATOR_TABLE.iter()) {
+        for &left in &[false, true] {
+            for &right in &[false, true] {
+                let expected = (table >> (2 * left as u8 + right as u8)) & 1 == 1;
+                let actual = compute(left, right, c);
+                if actual != expected {
+                    return Err(format!(
+                        "combinator {} on ({}, {}) produced {} but expected {}",
+                        c, left, right, actual, expected
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the specified implementation of `Network::apply` reproduces
+/// the golden outputs of the golden network, and that it agrees with the
+/// reference implementation (and with itself across repeated applications)
+/// on randomly generated networks.
+pub fn check_network<F: Fn(&Network, &[bool]) -> Vec<bool>>(apply: F) -> Result<(), String> {
+    let golden = golden_network();
+    for (x, expected) in GOLDEN_OUTPUTS.iter().enumerate() {
+        let input: Vec<bool> = (0..3).map(|i| (x >> i) & 1 == 1).collect();
+        let actual = apply(&golden, &input);
+        if actual != expected.to_vec() {
+            return Err(format!(
+                "golden network on {:?} produced {:?} but expected {:?}",
+                input, actual, expected
+            ));
+        }
+    }
+    for _i in 0..16 {
+        let network = Network::new(6, vec![6, 7, 6]);
+        for x in 0..64 {
+            let input: Vec<bool> = (0..6).map(|i| (x >> i) & 1 == 1).collect();
+            let expected = network.apply(&input);
+            let first = apply(&network, &input);
+            let second = apply(&network, &input);
+            if first != second {
+                return Err(format!(
+                    "repeated application on {:?} produced {:?} and then {:?}",
+                    input, first, second
+                ));
+            }
+            if first != expected {
+                return Err(format!(
+                    "random network on {:?} produced {:?} but expected {:?} ({:?})",
+                    input, first, expected, network
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Constructs the fixed three-input, two-output network whose outputs are
+/// recorded in `GOLDEN_OUTPUTS`.
+pub fn golden_network() -> Network {
+    Network {
+        layers: vec![
+            Layer {
+                neurons: vec![
+                    Neuron {
+                        arity: 3,
+                        bias: true,
+                        input_combinators: vec![Combinator::And, Combinator::Xor],
+                        inputs: None,
+                        locked: false,
+                        reduction: Reduction::RightFold,
+                        result_combinator: Combinator::Xor
+                    },
+                    Neuron {
+                        arity: 3,
+                        bias: false,
+                        input_combinators: vec![Combinator::Or, Combinator::Xnor],
+                        inputs: None,
+                        locked: false,
+                        reduction: Reduction::RightFold,
+                        result_combinator: Combinator::Or
+                    },
+                    Neuron {
+                        arity: 3,
+                        bias: true,
+                        input_combinators: vec![Combinator::Implies, Combinator::AndNotRight],
+                        inputs: None,
+                        locked: false,
+                        reduction: Reduction::RightFold,
+                        result_combinator: Combinator::ImpliedBy
+                    }
+                ]
+            },
+            Layer {
+                neurons: vec![
+                    Neuron {
+                        arity: 3,
+                        bias: true,
+                        input_combinators: vec![Combinator::Nand, Combinator::AndNotLeft],
+                        inputs: None,
+                        locked: false,
+                        reduction: Reduction::RightFold,
+                        result_combinator: Combinator::And
+                    },
+                    Neuron {
+                        arity: 3,
+                        bias: false,
+                        input_combinators: vec![Combinator::Nor, Combinator::NotLeft],
+                        inputs: None,
+                        locked: false,
+                        reduction: Reduction::RightFold,
+                        result_combinator: Combinator::Implies
+                    }
+                ]
+            }
+        ]
+    }
+}
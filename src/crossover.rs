@@ -0,0 +1,89 @@
+//! Contains crossover operators for recombining `bnl` networks.
+//!
+//! Each operator draws its random choices from the thread's generator (see
+//! the `random` module), or from a caller-supplied one via its `_with_rng`
+//! variant.
+
+use crate::analysis::probe_inputs_with_rng;
+use crate::network::{Layer, Network, Neuron};
+use rand::Rng;
+
+/// Produces a child network by recombining two parent networks of identical
+/// shape, matching neurons by functional similarity before swapping them.
+///
+/// For each layer, every neuron of the first parent is paired with the most
+/// similar not-yet-paired neuron of the second parent (by the number of
+/// disagreements over up to `samples` inputs of that layer), and the child
+/// receives one of the two at random. This avoids the "permutation problem"
+/// of naive crossover, where two parents encoding the same neuron at
+/// different positions produce children missing it entirely.
+pub fn aligned_crossover(a: &Network, b: &Network, samples: usize) -> Network {
+    aligned_crossover_with_rng(a, b, samples, &mut crate::random::rng())
+}
+
+/// Recombines two parent networks as with `aligned_crossover`, drawing random
+/// choices from the specified generator.
+pub fn aligned_crossover_with_rng<R: Rng + ?Sized>(a: &Network, b: &Network, samples: usize, rng: &mut R) -> Network {
+    let mut layers: Vec<Layer> = Vec::new();
+    let mut width = a.input_width();
+    for (la, lb) in a.layers.iter().zip(b.layers.iter()) {
+        let inputs = probe_inputs_with_rng(width, samples, rng);
+        let mut unused: Vec<usize> = (0..lb.neurons.len()).collect();
+        let mut neurons: Vec<Neuron> = Vec::new();
+        for na in &la.neurons {
+            let closest = unused.iter().enumerate()
+                .min_by_key(|(_, &j)| na.distance(&lb.neurons[j], &inputs))
+                .map(|(k, _)| k);
+            match closest {
+                Some(k) if rng.gen() => neurons.push(lb.neurons[unused.remove(k)].clone()),
+                Some(k) => {
+                    unused.remove(k);
+                    neurons.push(na.clone());
+                },
+                None => neurons.push(na.clone())
+            }
+        }
+        width = neurons.len();
+        layers.push(Layer {
+            neurons
+        });
+    }
+    Network {
+        layers
+    }
+}
+
+/// Produces a child network by recombining two parent networks of identical
+/// shape, taking each whole layer from one of the two parents at random.
+pub fn layer_crossover(a: &Network, b: &Network) -> Network {
+    layer_crossover_with_rng(a, b, &mut crate::random::rng())
+}
+
+/// Recombines two parent networks as with `layer_crossover`, drawing random
+/// choices from the specified generator.
+pub fn layer_crossover_with_rng<R: Rng + ?Sized>(a: &Network, b: &Network, rng: &mut R) -> Network {
+    Network {
+        layers: a.layers.iter().zip(b.layers.iter())
+            .map(|(la, lb)| if rng.gen() { lb.clone() } else { la.clone() })
+            .collect()
+    }
+}
+
+/// Produces a child network by recombining two parent networks of identical
+/// shape, taking each neuron from the neuron at the same position of one of
+/// the two parents at random.
+pub fn uniform_crossover(a: &Network, b: &Network) -> Network {
+    uniform_crossover_with_rng(a, b, &mut crate::random::rng())
+}
+
+/// Recombines two parent networks as with `uniform_crossover`, drawing random
+/// choices from the specified generator.
+pub fn uniform_crossover_with_rng<R: Rng + ?Sized>(a: &Network, b: &Network, rng: &mut R) -> Network {
+    Network {
+        layers: a.layers.iter().zip(b.layers.iter()).map(|(la, lb)| Layer {
+            neurons: la.neurons.iter().zip(lb.neurons.iter())
+                .map(|(na, nb)| if rng.gen() { nb.clone() } else { na.clone() })
+                .collect()
+        }).collect()
+    }
+}
@@ -0,0 +1,229 @@
+//! Contains the `DagNetwork` type, a variant of `bnl` networks whose neurons
+//! may read any earlier signal (including the inputs, as skip connections)
+//! rather than only the layer before them.
+//!
+//! The signals of a DAG network are its inputs followed by the outputs of
+//! each of its nodes, so that the node at index `i` reads from the
+//! `input_width + i` signals preceding it. Since every node depends only on
+//! earlier signals, nodes are stored (and evaluated) in topological order.
+//! Each node is a `Neuron` whose arity is the number of signals preceding it,
+//! and which is usually sparsely wired (see `Neuron::inputs`) to a few of
+//! them. Training mutates the wiring along with the other parameters, which
+//! lets the search discover circuits far more compact than layered networks
+//! computing the same function.
+
+use crate::batch::{apply_neuron, BitBatch, BitDataset};
+use crate::network::{Network, Neuron};
+use crate::train::{combinator_pool, mutate_neuron, Batches, TrainOptions, TrainReport};
+use rand::Rng;
+
+/// Represents a `bnl` network whose neurons form a directed acyclic graph.
+#[derive(Clone,Debug,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DagNetwork {
+    /// The width of the input vectors accepted by this network.
+    pub input_width: usize,
+
+    /// The nodes of this network, in topological order.
+    pub nodes: Vec<Neuron>,
+
+    /// The index of the signal routed to each output of this network.
+    pub outputs: Vec<usize>
+}
+
+/// Implements custom methods for DAG networks.
+impl DagNetwork {
+    /// "Applies" this network to a given input vector of boolean values.
+    pub fn apply(&self, input: &[bool]) -> Vec<bool> {
+        let mut signals: Vec<bool> = input.to_vec();
+        for n in &self.nodes {
+            let value = n.apply(&signals);
+            signals.push(value);
+        }
+        self.outputs.iter().map(|&i| signals[i]).collect()
+    }
+
+    /// "Applies" this network to every sample of the specified batch at once,
+    /// producing the batch of their outputs (see the `batch` module).
+    pub fn apply_batch(&self, input: &BitBatch) -> BitBatch {
+        let mut signals: Vec<Vec<u64>> = input.signals.clone();
+        if !input.is_empty() {
+            for n in &self.nodes {
+                let value = apply_neuron(n, &signals);
+                signals.push(value);
+            }
+        }
+        BitBatch {
+            len: input.len,
+            signals: self.outputs.iter().map(|&i| signals.get(i).cloned().unwrap_or_default()).collect()
+        }
+    }
+
+    /// Determines which nodes of this network contribute to at least one of
+    /// its outputs.
+    pub fn live_nodes(&self) -> Vec<bool> {
+        let mut live = vec![false; self.input_width + self.nodes.len()];
+        for &o in &self.outputs {
+            live[o] = true;
+        }
+        for (i, n) in self.nodes.iter().enumerate().rev() {
+            if live[self.input_width + i] {
+                for k in 0..n.fan_in() {
+                    live[n.input_index(k)] = true;
+                }
+            }
+        }
+        live.split_off(self.input_width)
+    }
+
+    /// Creates a new randomized network of the specified number of nodes, each
+    /// sparsely wired to (at most) the specified number of earlier signals,
+    /// whose outputs are its last nodes.
+    ///
+    /// Panics if there are fewer nodes than outputs.
+    pub fn new(input_width: usize, num_nodes: usize, output_width: usize, fan_in: usize) -> Self {
+        DagNetwork::new_with_rng(input_width, num_nodes, output_width, fan_in, &mut crate::random::rng())
+    }
+
+    /// Creates a new randomized network as with `DagNetwork::new`, drawing its
+    /// parameters from the specified generator.
+    pub fn new_with_rng<R: Rng + ?Sized>(input_width: usize, num_nodes: usize, output_width: usize, fan_in: usize, rng: &mut R) -> Self {
+        if num_nodes < output_width {
+            panic!("Cannot route {} outputs from only {} nodes!", output_width, num_nodes);
+        }
+        let total = input_width + num_nodes;
+        DagNetwork {
+            input_width,
+            nodes: (input_width..total).map(|width| Neuron::new_sparse_with_rng(fan_in.min(width), width, rng)).collect(),
+            outputs: ((total - output_width)..total).collect()
+        }
+    }
+
+    /// Returns the number of outputs of this network.
+    pub fn output_width(&self) -> usize {
+        self.outputs.len()
+    }
+
+    /// Removes every node of this network which does not contribute to any of
+    /// its outputs (see `DagNetwork::live_nodes`), without changing the
+    /// function it computes.
+    pub fn prune(&mut self) {
+        let live = self.live_nodes();
+        let mut index: Vec<usize> = (0..self.input_width).collect();
+        let mut nodes: Vec<Neuron> = Vec::new();
+        for (n, &l) in self.nodes.iter().zip(live.iter()) {
+            let next = self.input_width + nodes.len();
+            index.push(next);
+            if l {
+                let mut n = n.clone();
+                n.inputs = Some((0..n.fan_in()).map(|k| index[n.input_index(k)]).collect());
+                n.arity = next;
+                nodes.push(n);
+            }
+        }
+        self.outputs = self.outputs.iter().map(|&o| index[o]).collect();
+        self.nodes = nodes;
+    }
+
+    /// Trains this network in place on the specified dataset via hill
+    /// climbing as with `Network::train`, where each mutation changes a
+    /// single parameter or input of a single unlocked node.
+    pub fn train(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], opts: TrainOptions) -> TrainReport {
+        self.train_with_rng(dataset, opts, &mut crate::random::rng())
+    }
+
+    /// Trains this network in place as with `DagNetwork::train`, drawing
+    /// random choices from the specified generator.
+    pub fn train_with_rng<R: Rng + ?Sized>(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], opts: TrainOptions, rng: &mut R) -> TrainReport {
+        let packed = BitDataset::from_samples(dataset);
+        let error = |n: &DagNetwork| n.apply_batch(&packed.inputs).hamming_distance(&packed.expected);
+        let initial = error(self);
+        let mut report = TrainReport {
+            final_error: initial,
+            history: vec![initial],
+            initial_error: initial,
+            iterations: 0
+        };
+        let unlocked: Vec<usize> = (0..self.nodes.len()).filter(|&i| !self.nodes[i].locked).collect();
+        let batches = Batches::new(dataset.to_vec(), opts.balanced_batch);
+        while report.iterations < opts.iterations && report.final_error > 0 && !unlocked.is_empty() {
+            report.iterations += 1;
+            let mut candidate = self.clone();
+            for _i in 0..opts.mutations_per_step {
+                let index = unlocked[rng.gen_range(0, unlocked.len())];
+                mutate_neuron(&mut candidate.nodes[index], opts.bias_mode, combinator_pool(opts.monotone), rng);
+            }
+            if let Some(b) = &batches {
+                let batch = BitDataset::from_samples(&b.draw(rng));
+                let batch_error = |n: &DagNetwork| n.apply_batch(&batch.inputs).hamming_distance(&batch.expected);
+                let (current, e) = (batch_error(self), batch_error(&candidate));
+                if e < current || (opts.accept_neutral && e == current) {
+                    let e = error(&candidate);
+                    if e < report.final_error {
+                        report.history.push(e);
+                    }
+                    report.final_error = e;
+                    *self = candidate;
+                }
+                continue;
+            }
+            let e = error(&candidate);
+            if e < report.final_error || (opts.accept_neutral && e == report.final_error) {
+                if e < report.final_error {
+                    report.history.push(e);
+                }
+                report.final_error = e;
+                *self = candidate;
+            }
+        }
+        report
+    }
+
+    /// Checks that every node of this network is valid and reads only the
+    /// signals preceding it, and that every output is routed from an existing
+    /// signal, returning a description of the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, n) in self.nodes.iter().enumerate() {
+            n.validate().map_err(|e| format!("node {}: {}", i, e))?;
+            if n.arity != self.input_width + i {
+                return Err(format!("node {}: expected an arity of {} but found {}", i, self.input_width + i, n.arity));
+            }
+        }
+        let total = self.input_width + self.nodes.len();
+        if let Some((i, o)) = self.outputs.iter().enumerate().find(|(_, &o)| o >= total) {
+            return Err(format!("output {}: routed from signal {} of only {}", i, o, total));
+        }
+        Ok(())
+    }
+}
+
+/// Allows DAG networks to be created from layered networks computing the same
+/// function, by wiring each neuron to the signals of the layer before it.
+impl From<&Network> for DagNetwork {
+    fn from(network: &Network) -> Self {
+        let input_width = network.input_width();
+        let mut nodes: Vec<Neuron> = Vec::new();
+        let mut offset = 0;
+        for layer in &network.layers {
+            let next = input_width + nodes.len();
+            for n in &layer.neurons {
+                nodes.push(Neuron {
+                    arity: input_width + nodes.len(),
+                    bias: n.bias,
+                    input_combinators: n.input_combinators.clone(),
+                    inputs: Some((0..n.fan_in()).map(|k| offset + n.input_index(k)).collect()),
+                    locked: n.locked,
+                    reduction: n.reduction,
+                    result_combinator: n.result_combinator
+                });
+            }
+            offset = next;
+        }
+        let total = input_width + nodes.len();
+        DagNetwork {
+            input_width,
+            nodes,
+            outputs: (offset..total).collect()
+        }
+    }
+}
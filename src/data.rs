@@ -0,0 +1,232 @@
+//! Contains utilities for inspecting and preparing the `(input, expected)`
+//! samples that `bnl` networks are trained and evaluated on.
+//!
+//! The `cache` submodule keeps parsed datasets in a binary cache between runs.
+
+pub mod cache;
+
+use crate::dataset::Dataset;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Represents a sampler which draws mini-batches containing equal numbers of
+/// samples of each distinct expected output pattern ("class"), so that small
+/// batches are not dominated by the majority class.
+#[derive(Clone,Debug)]
+pub struct BalancedSampler {
+    /// The indices of the samples belonging to each class, in order of each
+    /// class's first appearance.
+    pub classes: Vec<Vec<usize>>
+}
+
+/// Implements custom methods for balanced samplers.
+impl BalancedSampler {
+    /// Creates a new balanced sampler over the specified samples.
+    pub fn new(samples: &[(Vec<bool>, Vec<bool>)]) -> Self {
+        let mut index: HashMap<&Vec<bool>, usize> = HashMap::new();
+        let mut classes: Vec<Vec<usize>> = Vec::new();
+        for (i, (_, expected)) in samples.iter().enumerate() {
+            let c = *index.entry(expected).or_insert_with(|| {
+                classes.push(Vec::new());
+                classes.len() - 1
+            });
+            classes[c].push(i);
+        }
+        BalancedSampler {
+            classes
+        }
+    }
+
+    /// Draws a mini-batch of sample indices of the specified size.
+    ///
+    /// Classes are visited round-robin (starting from a random class) and a
+    /// random sample of each class is drawn with replacement, so every class
+    /// contributes either `batch_size / n` or one more sample.
+    pub fn sample(&self, batch_size: usize) -> Vec<usize> {
+        self.sample_with_rng(batch_size, &mut crate::random::rng())
+    }
+
+    /// Draws a mini-batch of sample indices as with `BalancedSampler::sample`,
+    /// drawing random choices from the specified generator.
+    pub fn sample_with_rng<R: rand::Rng + ?Sized>(&self, batch_size: usize, rng: &mut R) -> Vec<usize> {
+        if self.classes.is_empty() {
+            return Vec::new();
+        }
+        let offset = rng.gen_range(0, self.classes.len());
+        (0..batch_size).map(|i| {
+            let class = &self.classes[(offset + i) % self.classes.len()];
+            class[rng.gen_range(0, class.len())]
+        }).collect()
+    }
+}
+
+/// Represents the ways in which conflicting samples (identical inputs with
+/// different expected outputs) may be resolved.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum ConflictStrategy {
+    /// Drops every sample whose input has conflicting expected outputs.
+    Drop,
+
+    /// Replaces the conflicting samples of each input with a single sample
+    /// whose expected output is the first one encountered.
+    KeepFirst,
+
+    /// Replaces the conflicting samples of each input with a single sample
+    /// whose expected output is the most common one (ties going to the first
+    /// encountered).
+    Majority
+}
+
+/// Represents the outcome of a sanity check of a collection of samples (see
+/// `check`), summarizing the problems worth fixing before training on them.
+#[derive(Clone,Debug,PartialEq)]
+pub struct DataCheck {
+    /// The number of samples of each distinct expected output pattern
+    /// ("class"), in order of each class's first appearance.
+    pub classes: Vec<usize>,
+
+    /// The indices of the inputs which take the same value in every sample.
+    pub constant_inputs: Vec<usize>,
+
+    /// The indices of the outputs which take the same value in every sample.
+    pub constant_outputs: Vec<usize>,
+
+    /// The number of samples which exactly repeat an earlier sample.
+    pub duplicates: usize,
+
+    /// The input width of the first sample.
+    pub input_width: usize,
+
+    /// The indices of the samples whose input or output width differs from
+    /// that of the first sample, which are left out of every other statistic.
+    pub mismatched: Vec<usize>,
+
+    /// The label noise of the samples, whose `max_accuracy` is the ceiling on
+    /// the accuracy of any network trained on them.
+    pub noise: NoiseReport,
+
+    /// The number of samples in which each output is true.
+    pub ones: Vec<usize>,
+
+    /// The output width of the first sample.
+    pub output_width: usize,
+
+    /// The number of samples checked.
+    pub samples: usize
+}
+
+/// Formats data checks as a report with one line per statistic.
+impl fmt::Display for DataCheck {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let indices = |v: &[usize]| if v.is_empty() {
+            String::from("none")
+        } else {
+            v.iter().map(|i| i.to_string()).collect::<Vec<String>>().join(", ")
+        };
+        let consistent = self.samples - self.mismatched.len();
+        let percent = |n: usize| if consistent == 0 { 0.0 } else { 100.0 * n as f64 / consistent as f64 };
+        writeln!(f, "samples: {}", self.samples)?;
+        write!(f, "widths: {} inputs, {} outputs", self.input_width, self.output_width)?;
+        match self.mismatched.first() {
+            Some(i) => writeln!(f, " ({} samples differ, the first being sample {})", self.mismatched.len(), i)?,
+            None => writeln!(f, " (consistent)")?
+        }
+        for (i, &n) in self.ones.iter().enumerate() {
+            writeln!(f, "output {}: {} true ({:.1}%)", i, n, percent(n))?;
+        }
+        let largest = self.classes.iter().copied().max().unwrap_or(0);
+        writeln!(f, "classes: {} (largest {:.1}%)", self.classes.len(), percent(largest))?;
+        writeln!(f, "duplicates: {}", self.duplicates)?;
+        writeln!(f, "contradictions: {} inputs, {} samples", self.noise.contradictory_inputs, self.noise.contradictory_samples)?;
+        writeln!(f, "constant inputs: {}", indices(&self.constant_inputs))?;
+        writeln!(f, "constant outputs: {}", indices(&self.constant_outputs))?;
+        writeln!(f, "noise ceiling: {:.1}% accuracy", 100.0 * self.noise.max_accuracy)
+    }
+}
+
+/// Represents an estimate of the label noise present in a collection of
+/// samples.
+#[derive(Clone,Debug,PartialEq)]
+pub struct NoiseReport {
+    /// The number of distinct inputs which appear with more than one distinct
+    /// expected output.
+    pub contradictory_inputs: usize,
+
+    /// The total number of samples sharing an input with some other sample
+    /// that has a different expected output.
+    pub contradictory_samples: usize,
+
+    /// The highest fraction of samples any deterministic model could possibly
+    /// reproduce exactly, achieved by predicting the most common expected
+    /// output for every input.
+    pub max_accuracy: f64
+}
+
+/// Checks the specified samples for the problems summarized by `DataCheck`:
+/// inconsistent widths, class imbalance, duplicate and contradictory samples,
+/// constant columns and the accuracy ceiling they impose.
+pub fn check(samples: &[(Vec<bool>, Vec<bool>)]) -> DataCheck {
+    let (input_width, output_width) = samples.first().map_or((0, 0), |(i, e)| (i.len(), e.len()));
+    let (consistent, mismatched): (Vec<usize>, Vec<usize>) = (0..samples.len())
+        .partition(|&s| samples[s].0.len() == input_width && samples[s].1.len() == output_width);
+    let valid: Vec<(Vec<bool>, Vec<bool>)> = consistent.iter().map(|&s| samples[s].clone()).collect();
+    let mut seen: HashSet<&(Vec<bool>, Vec<bool>)> = HashSet::new();
+    let duplicates = valid.iter().filter(|s| !seen.insert(s)).count();
+    DataCheck {
+        classes: BalancedSampler::new(&valid).classes.iter().map(|c| c.len()).collect(),
+        constant_inputs: (0..input_width).filter(|&i| valid.iter().all(|s| s.0[i] == valid[0].0[i])).collect(),
+        constant_outputs: (0..output_width).filter(|&o| valid.iter().all(|s| s.1[o] == valid[0].1[o])).collect(),
+        duplicates,
+        input_width,
+        mismatched,
+        noise: estimate_label_noise(&valid),
+        ones: (0..output_width).map(|o| valid.iter().filter(|s| s.1[o]).count()).collect(),
+        output_width,
+        samples: samples.len()
+    }
+}
+
+/// Estimates the label noise in the specified samples by detecting
+/// contradictory samples (those with identical inputs but different expected
+/// outputs), reporting the upper bound on achievable accuracy which they
+/// impose.
+pub fn estimate_label_noise(samples: &[(Vec<bool>, Vec<bool>)]) -> NoiseReport {
+    let mut report = NoiseReport {
+        contradictory_inputs: 0,
+        contradictory_samples: 0,
+        max_accuracy: 1.0
+    };
+    if samples.is_empty() {
+        return report;
+    }
+    let mut correct = 0;
+    for targets in group_targets(samples).values() {
+        let total: usize = targets.values().sum();
+        if targets.len() > 1 {
+            report.contradictory_inputs += 1;
+            report.contradictory_samples += total;
+        }
+        correct += targets.values().max().unwrap();
+    }
+    report.max_accuracy = correct as f64 / samples.len() as f64;
+    report
+}
+
+/// Counts the occurrences of each expected output for each distinct input of
+/// the specified samples.
+pub(crate) fn group_targets(samples: &[(Vec<bool>, Vec<bool>)]) -> HashMap<&Vec<bool>, HashMap<&Vec<bool>, usize>> {
+    let mut groups: HashMap<&Vec<bool>, HashMap<&Vec<bool>, usize>> = HashMap::new();
+    for (input, expected) in samples {
+        *groups.entry(input).or_default().entry(expected).or_insert(0) += 1;
+    }
+    groups
+}
+
+/// Resolves conflicting samples (identical inputs with different expected
+/// outputs) according to the specified strategy, returning the cleaned
+/// samples, as with `Dataset::resolve_conflicts`.
+pub fn resolve_conflicts(samples: &[(Vec<bool>, Vec<bool>)], strategy: ConflictStrategy) -> Vec<(Vec<bool>, Vec<bool>)> {
+    let mut dataset = Dataset::from(samples.to_vec());
+    dataset.resolve_conflicts(strategy);
+    dataset.samples
+}
@@ -0,0 +1,89 @@
+//! Contains a binary cache of parsed datasets, so that repeated experiments on
+//! large CSV or plain-text datasets need not parse them again on every run.
+//!
+//! Each source file is keyed by a 64-bit FNV-1a hash of its bytes, its format
+//! and (for CSV) the number of output columns, and its parsed samples are
+//! stored in the packed binary format of the `dataset` module as the file
+//! `<key>.bnld` of a cache directory. Editing a source file changes its key,
+//! so stale entries are never read back, but neither are they removed; the
+//! cache directory may be deleted at any time. Entries which fail to load are
+//! parsed again from their source and rewritten.
+
+use crate::cache::bytes_hash;
+use crate::dataset::{invalid_data, Dataset, Format};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The extension of the files holding cached datasets.
+pub const CACHE_EXTENSION: &str = "bnld";
+
+/// Represents a directory of cached datasets.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct DatasetCache {
+    /// The directory holding the cached datasets.
+    pub dir: PathBuf,
+
+    /// The number of loads served from the cache.
+    pub hits: usize,
+
+    /// The number of loads which had to parse their source.
+    pub misses: usize
+}
+
+/// Implements custom methods for dataset caches.
+impl DatasetCache {
+    /// Loads the dataset at the specified path from its cached copy if there is
+    /// one, or parses it and caches the result otherwise. Files with a `.csv`
+    /// extension are parsed as CSV whose last `output_len` columns are the
+    /// expected outputs, and any other file as the plain `0`/`1` format (see
+    /// the `dataset` module).
+    pub fn load<P: AsRef<Path>>(&mut self, path: P, output_len: usize) -> io::Result<Dataset> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)?;
+        let csv = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("csv"));
+        let key = source_key(&bytes, if csv { Some(output_len) } else { None });
+        let cached = self.path_for(key);
+        if let Ok(dataset) = Dataset::from_packed(&cached) {
+            self.hits += 1;
+            return Ok(dataset);
+        }
+        self.misses += 1;
+        let text = String::from_utf8(bytes).map_err(|e| invalid_data(path, e.to_string()))?;
+        let dataset = if csv {
+            Dataset::parse_csv(&text, output_len)
+        } else {
+            Dataset::parse_text(&text)
+        }.map_err(|e| invalid_data(path, e))?;
+        let partial = cached.with_extension("partial");
+        fs::write(&partial, dataset.to_bytes(Format::Packed))?;
+        fs::rename(&partial, &cached)?;
+        Ok(dataset)
+    }
+
+    /// Opens the cache in the specified directory, creating the directory if
+    /// it does not exist.
+    pub fn open<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        Ok(DatasetCache {
+            dir,
+            hits: 0,
+            misses: 0
+        })
+    }
+
+    /// Returns the path of the cached dataset with the specified key.
+    pub fn path_for(&self, key: u64) -> PathBuf {
+        self.dir.join(format!("{:016x}.{}", key, CACHE_EXTENSION))
+    }
+}
+
+/// Computes the key of a cached dataset from the bytes of its source, and the
+/// number of output columns if it is CSV or `None` if it is plain text.
+pub fn source_key(bytes: &[u8], output_len: Option<usize>) -> u64 {
+    match output_len {
+        Some(len) => bytes_hash(&[bytes, b"c", &(len as u64).to_le_bytes()]),
+        None => bytes_hash(&[bytes, b"t"])
+    }
+}
@@ -0,0 +1,547 @@
+//! Contains the `Dataset` type, a collection of `(input, expected)` samples
+//! along with routines for loading, shuffling and splitting it.
+//!
+//! Splits, cross-validation folds and mini-batches are `DatasetView`s, which
+//! borrow contiguous runs of the samples of a dataset rather than cloning
+//! them, and may be passed to the training and analysis routines of this
+//! crate through `DatasetView::as_slice` like datasets themselves.
+//!
+//! Datasets may be loaded from two text formats:
+//!
+//! * CSV, with one sample per row of comma-separated `0`/`1` values, the last
+//!   `output_len` of which are the expected outputs. A first row containing
+//!   anything other than `0`/`1` values is skipped as a header.
+//! * A plain format with one sample per line, consisting of the input bits and
+//!   the expected output bits written as two whitespace-separated words of
+//!   `0`/`1` characters, e.g. `0110 1`.
+//!
+//! In both formats, blank lines and lines starting with `#` are ignored, and
+//! errors name the line at fault.
+//!
+//! Datasets may be saved (see `Dataset::save`) as CSV with a header row, in
+//! the Berkeley PLA format read by logic minimizers such as Espresso, or in a
+//! packed binary format which `Dataset::from_packed` loads back. The packed
+//! format consists of the bytes `BNLD`, the number of samples as a
+//! little-endian `u64`, the input and output widths as little-endian `u32`s,
+//! and then the input bits followed by the output bits of each sample, packed
+//! eight to a byte (least significant bit first) with each sample starting on
+//! a new byte.
+
+use crate::batch::BitDataset;
+use crate::bits::format_bits;
+use crate::cache::dataset_hash;
+use crate::data::{group_targets, ConflictStrategy};
+use std::collections::HashSet;
+use std::convert::TryInto;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The bytes at the start of every dataset in the packed binary format.
+pub const PACKED_MAGIC: &[u8; 4] = b"BNLD";
+
+/// Represents a collection of `(input, expected)` samples of consistent
+/// widths.
+#[derive(Clone,Debug,Default,PartialEq,Eq)]
+pub struct Dataset {
+    /// The samples of this dataset.
+    pub samples: Vec<(Vec<bool>, Vec<bool>)>
+}
+
+/// Represents a borrowed, contiguous run of the samples of a dataset.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub struct DatasetView<'a> {
+    /// The samples of this view.
+    pub samples: &'a [(Vec<bool>, Vec<bool>)]
+}
+
+/// Represents the file formats in which datasets may be saved.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Format {
+    /// Comma-separated `0`/`1` values with a header row naming the inputs
+    /// `x0`, `x1`, ... and the outputs `y0`, `y1`, ....
+    Csv,
+
+    /// The packed binary format described in the module documentation.
+    Packed,
+
+    /// The Berkeley PLA format, with one fully specified cube per sample.
+    Pla
+}
+
+/// Implements custom methods for file formats.
+impl Format {
+    /// Determines the format of the specified path from its extension, which
+    /// is CSV for `.csv` files, PLA for `.pla` files and packed otherwise.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some(e) if e.eq_ignore_ascii_case("csv") => Format::Csv,
+            Some(e) if e.eq_ignore_ascii_case("pla") => Format::Pla,
+            _ => Format::Packed
+        }
+    }
+}
+
+/// Implements custom methods for datasets.
+impl Dataset {
+    /// Returns the samples of this dataset as a slice, as accepted by the
+    /// training and analysis routines of this crate.
+    pub fn as_slice(&self) -> &[(Vec<bool>, Vec<bool>)] {
+        &self.samples
+    }
+
+    /// Returns an iterator over consecutive mini-batches of this dataset (see
+    /// `DatasetView::batches`).
+    ///
+    /// Panics if `batch_len` is zero.
+    pub fn batches(&self, batch_len: usize) -> impl Iterator<Item = DatasetView<'_>> {
+        self.view().batches(batch_len)
+    }
+
+    /// Returns an iterator over the expected outputs of this dataset.
+    pub fn expected(&self) -> impl Iterator<Item = &Vec<bool>> {
+        self.samples.iter().map(|(_, e)| e)
+    }
+
+    /// Runs the specified function on the training and test views of each of
+    /// `k` cross-validation folds of this dataset in turn, the test view of
+    /// fold `i` holding the `i`th of `k` consecutive runs of its samples (the
+    /// first `len % k` of which hold one extra sample) and the training view
+    /// holding the rest, in order.
+    ///
+    /// So that both views borrow contiguous samples, the test samples of each
+    /// fold are rotated past the rest while the function runs, which moves
+    /// only the handles of the samples rather than their bits. The samples are
+    /// left in their original order afterwards.
+    ///
+    /// Panics if `k` is zero or larger than the number of samples.
+    pub fn for_each_fold<F: FnMut(DatasetView, DatasetView)>(&mut self, k: usize, mut f: F) {
+        let len = self.samples.len();
+        if k == 0 || k > len {
+            panic!("Cannot split a dataset of {} samples into {} folds!", len, k);
+        }
+        let mut start = 0;
+        for i in 0..k {
+            let fold_len = len / k + (i < len % k) as usize;
+            self.samples[start..].rotate_left(fold_len);
+            let (train, test) = self.samples.split_at(len - fold_len);
+            f(DatasetView { samples: train }, DatasetView { samples: test });
+            self.samples[start..].rotate_right(fold_len);
+            start += fold_len;
+        }
+    }
+
+    /// Loads a dataset from the specified CSV file, whose last `output_len`
+    /// columns are the expected outputs.
+    pub fn from_csv<P: AsRef<Path>>(path: P, output_len: usize) -> io::Result<Self> {
+        #[cfg(feature = "profiling")]
+        let _timer = crate::profile::timer(crate::profile::Phase::Load);
+        let text = fs::read_to_string(&path)?;
+        Dataset::parse_csv(&text, output_len).map_err(|e| invalid_data(&path, e))
+    }
+
+    /// Loads a dataset from the specified file in the packed binary format.
+    pub fn from_packed<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        #[cfg(feature = "profiling")]
+        let _timer = crate::profile::timer(crate::profile::Phase::Load);
+        let bytes = fs::read(&path)?;
+        Dataset::parse_packed(&bytes).map_err(|e| invalid_data(&path, e))
+    }
+
+    /// Loads a dataset from the specified file in the plain `0`/`1` format.
+    pub fn from_text<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        #[cfg(feature = "profiling")]
+        let _timer = crate::profile::timer(crate::profile::Phase::Load);
+        let text = fs::read_to_string(&path)?;
+        Dataset::parse_text(&text).map_err(|e| invalid_data(&path, e))
+    }
+
+    /// Computes a digest of the samples of this dataset, in order, which is
+    /// stable across platforms, runs and versions of `bnl` (see
+    /// `cache::dataset_hash`), so that it may be saved in checkpoints and
+    /// model metadata to tell whether a network is used with the dataset it
+    /// was trained on.
+    pub fn hash(&self) -> u64 {
+        dataset_hash(&self.samples)
+    }
+
+    /// Returns the width of the inputs of this dataset, or zero if it is
+    /// empty.
+    pub fn input_width(&self) -> usize {
+        self.samples.first().map_or(0, |(i, _)| i.len())
+    }
+
+    /// Returns an iterator over the inputs of this dataset.
+    pub fn inputs(&self) -> impl Iterator<Item = &Vec<bool>> {
+        self.samples.iter().map(|(i, _)| i)
+    }
+
+    /// Determines whether this dataset contains no samples.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the number of samples in this dataset.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Creates a new dataset from the specified samples, checking that their
+    /// widths are consistent.
+    pub fn new(samples: Vec<(Vec<bool>, Vec<bool>)>) -> Result<Self, String> {
+        let dataset = Dataset { samples };
+        dataset.validate()?;
+        Ok(dataset)
+    }
+
+    /// Returns the width of the expected outputs of this dataset, or zero if
+    /// it is empty.
+    pub fn output_width(&self) -> usize {
+        self.samples.first().map_or(0, |(_, e)| e.len())
+    }
+
+    /// Packs the samples of this dataset for bit-parallel evaluation, as the
+    /// `_packed` variants of the trainers accept (see `batch::BitDataset`).
+    /// Since the samples may be changed through `Dataset::samples`, the packed
+    /// copy is not kept up to date and should be made again after any change.
+    pub fn pack(&self) -> BitDataset {
+        BitDataset::from_samples(&self.samples)
+    }
+
+    /// Parses a dataset from the specified CSV text, whose last `output_len`
+    /// columns are the expected outputs.
+    pub fn parse_csv(text: &str, output_len: usize) -> Result<Self, String> {
+        #[cfg(feature = "profiling")]
+        let _timer = crate::profile::timer(crate::profile::Phase::Load);
+        let mut samples: Vec<(Vec<bool>, Vec<bool>)> = Vec::new();
+        let mut first = true;
+        for (i, line) in lines(text) {
+            let row = match parse_csv_fields(line) {
+                Ok(row) => row,
+                Err(_) if first => {
+                    first = false;
+                    continue;
+                },
+                Err(e) => return Err(format!("line {}: {}", i, e))
+            };
+            first = false;
+            let (input, expected) = split_csv_row(row, output_len).map_err(|e| format!("line {}: {}", i, e))?;
+            check_widths(&samples, &input, &expected).map_err(|e| format!("line {}: {}", i, e))?;
+            samples.push((input, expected));
+        }
+        Ok(Dataset { samples })
+    }
+
+    /// Parses a dataset from the specified bytes in the packed binary format.
+    pub fn parse_packed(bytes: &[u8]) -> Result<Self, String> {
+        #[cfg(feature = "profiling")]
+        let _timer = crate::profile::timer(crate::profile::Phase::Load);
+        if bytes.len() < 20 || &bytes[..4] != PACKED_MAGIC {
+            return Err(String::from("not a packed dataset"));
+        }
+        let count = u64::from_le_bytes(bytes[4..12].try_into().unwrap()) as usize;
+        let iw = u32::from_le_bytes(bytes[12..16].try_into().unwrap()) as usize;
+        let ow = u32::from_le_bytes(bytes[16..20].try_into().unwrap()) as usize;
+        let stride = (iw + ow).div_ceil(8);
+        let body = &bytes[20..];
+        if count.checked_mul(stride) != Some(body.len()) {
+            return Err(format!(
+                "expected {} samples of {} bytes but found {} bytes",
+                count, stride, body.len()
+            ));
+        }
+        let samples = (0..count).map(|s| {
+            let sample = &body[(s * stride)..((s + 1) * stride)];
+            let mut bits: Vec<bool> = (0..(iw + ow)).map(|i| (sample[i / 8] >> (i % 8)) & 1 == 1).collect();
+            let expected = bits.split_off(iw);
+            (bits, expected)
+        }).collect();
+        Ok(Dataset { samples })
+    }
+
+    /// Parses a dataset from the specified text in the plain `0`/`1` format.
+    pub fn parse_text(text: &str) -> Result<Self, String> {
+        #[cfg(feature = "profiling")]
+        let _timer = crate::profile::timer(crate::profile::Phase::Load);
+        let mut samples: Vec<(Vec<bool>, Vec<bool>)> = Vec::new();
+        for (i, line) in lines(text) {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let (input, expected) = match words.as_slice() {
+                [input, expected] => (*input, *expected),
+                _ => return Err(format!("line {}: expected input and output bits separated by whitespace", i))
+            };
+            let parse = |w: &str| -> Result<Vec<bool>, String> {
+                w.chars().map(|c| parse_bit(&c.to_string())).collect()
+            };
+            let input = parse(input).map_err(|e| format!("line {}: {}", i, e))?;
+            let expected = parse(expected).map_err(|e| format!("line {}: {}", i, e))?;
+            check_widths(&samples, &input, &expected).map_err(|e| format!("line {}: {}", i, e))?;
+            samples.push((input, expected));
+        }
+        Ok(Dataset { samples })
+    }
+
+    /// Resolves the conflicting samples of this dataset (identical inputs with
+    /// different expected outputs) in place according to the specified
+    /// strategy.
+    ///
+    /// Samples whose inputs have no conflicts are kept untouched (including
+    /// any exact duplicates). Each input with conflicts is collapsed into at
+    /// most one sample, placed at the position of its first occurrence.
+    pub fn resolve_conflicts(&mut self, strategy: ConflictStrategy) {
+        let samples = &self.samples;
+        let groups = group_targets(samples);
+        let mut emitted: HashSet<&Vec<bool>> = HashSet::new();
+        let mut res: Vec<(Vec<bool>, Vec<bool>)> = Vec::new();
+        for (i, (input, expected)) in samples.iter().enumerate() {
+            let targets = &groups[input];
+            if targets.len() == 1 {
+                res.push((input.clone(), expected.clone()));
+                continue;
+            }
+            if !emitted.insert(input) {
+                continue;
+            }
+            let chosen = match strategy {
+                ConflictStrategy::Drop => continue,
+                ConflictStrategy::KeepFirst => expected,
+                ConflictStrategy::Majority => {
+                    let best = targets.values().max().unwrap();
+                    samples[i..].iter()
+                        .filter(|(other, _)| other == input)
+                        .map(|(_, e)| e)
+                        .find(|e| targets[e] == *best)
+                        .unwrap()
+                }
+            };
+            res.push((input.clone(), chosen.clone()));
+        }
+        self.samples = res;
+    }
+
+    /// Saves this dataset to the specified file in the specified format.
+    pub fn save<P: AsRef<Path>>(&self, path: P, format: Format) -> io::Result<()> {
+        fs::write(path, self.to_bytes(format))
+    }
+
+    /// Shuffles the samples of this dataset in place, deterministically for a
+    /// given seed (drawing from a `random::Xoshiro256` generator, so that the
+    /// order stays the same across versions of `rand`).
+    pub fn shuffle(&mut self, seed: u64) {
+        use rand::SeedableRng;
+        use rand::seq::SliceRandom;
+        let mut rng = crate::random::Xoshiro256::seed_from_u64(seed);
+        self.samples.shuffle(&mut rng);
+    }
+
+    /// Splits this dataset into views of a training set holding the first
+    /// `train_fraction` of its samples (rounded down) and a test set holding
+    /// the rest, preserving their order.
+    ///
+    /// Panics if `train_fraction` is not between zero and one.
+    pub fn split(&self, train_fraction: f64) -> (DatasetView<'_>, DatasetView<'_>) {
+        self.view().split(train_fraction)
+    }
+
+    /// Serializes this dataset into the specified format.
+    pub fn to_bytes(&self, format: Format) -> Vec<u8> {
+        let (iw, ow) = (self.input_width(), self.output_width());
+        match format {
+            Format::Csv => {
+                let mut res: Vec<String> = (0..iw).map(|i| format!("x{}", i)).collect();
+                res.extend((0..ow).map(|i| format!("y{}", i)));
+                let mut text = res.join(",") + "\n";
+                for (input, expected) in &self.samples {
+                    let row: Vec<&str> = input.iter().chain(expected.iter())
+                        .map(|&b| if b { "1" } else { "0" })
+                        .collect();
+                    text += &row.join(",");
+                    text += "\n";
+                }
+                text.into_bytes()
+            },
+            Format::Packed => {
+                let stride = (iw + ow).div_ceil(8);
+                let mut res: Vec<u8> = PACKED_MAGIC.to_vec();
+                res.extend_from_slice(&(self.samples.len() as u64).to_le_bytes());
+                res.extend_from_slice(&(iw as u32).to_le_bytes());
+                res.extend_from_slice(&(ow as u32).to_le_bytes());
+                for (input, expected) in &self.samples {
+                    let mut sample = vec![0u8; stride];
+                    for (i, &b) in input.iter().chain(expected.iter()).enumerate() {
+                        sample[i / 8] |= (b as u8) << (i % 8);
+                    }
+                    res.extend_from_slice(&sample);
+                }
+                res
+            },
+            Format::Pla => {
+                let mut text = format!(".i {}\n.o {}\n.p {}\n", iw, ow, self.samples.len());
+                for (input, expected) in &self.samples {
+                    text += &format!("{} {}\n", format_bits(input), format_bits(expected));
+                }
+                text += ".e\n";
+                text.into_bytes()
+            }
+        }
+    }
+
+    /// Checks that every sample of this dataset has the same input and output
+    /// widths as the first.
+    pub fn validate(&self) -> Result<(), String> {
+        let (iw, ow) = (self.input_width(), self.output_width());
+        for (i, (input, expected)) in self.samples.iter().enumerate() {
+            if input.len() != iw || expected.len() != ow {
+                return Err(format!(
+                    "sample {} has input and output widths {} and {}, but sample 0 has {} and {}",
+                    i, input.len(), expected.len(), iw, ow
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a view of every sample of this dataset.
+    pub fn view(&self) -> DatasetView<'_> {
+        DatasetView { samples: &self.samples }
+    }
+}
+
+/// Implements custom methods for dataset views.
+impl<'a> DatasetView<'a> {
+    /// Returns the samples of this view as a slice, as accepted by the
+    /// training and analysis routines of this crate.
+    pub fn as_slice(&self) -> &'a [(Vec<bool>, Vec<bool>)] {
+        self.samples
+    }
+
+    /// Returns an iterator over consecutive mini-batches of this view, each of
+    /// `batch_len` samples except possibly the last.
+    ///
+    /// Panics if `batch_len` is zero.
+    pub fn batches(&self, batch_len: usize) -> impl Iterator<Item = DatasetView<'a>> {
+        if batch_len == 0 {
+            panic!("Cannot split a dataset into mini-batches of zero samples!");
+        }
+        self.samples.chunks(batch_len).map(|samples| DatasetView { samples })
+    }
+
+    /// Returns an iterator over the expected outputs of this view.
+    pub fn expected(&self) -> impl Iterator<Item = &'a Vec<bool>> {
+        self.samples.iter().map(|(_, e)| e)
+    }
+
+    /// Returns the width of the inputs of this view, or zero if it is empty.
+    pub fn input_width(&self) -> usize {
+        self.samples.first().map_or(0, |(i, _)| i.len())
+    }
+
+    /// Returns an iterator over the inputs of this view.
+    pub fn inputs(&self) -> impl Iterator<Item = &'a Vec<bool>> {
+        self.samples.iter().map(|(i, _)| i)
+    }
+
+    /// Determines whether this view contains no samples.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the number of samples in this view.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns the width of the expected outputs of this view, or zero if it
+    /// is empty.
+    pub fn output_width(&self) -> usize {
+        self.samples.first().map_or(0, |(_, e)| e.len())
+    }
+
+    /// Packs the samples of this view for bit-parallel evaluation (see
+    /// `Dataset::pack`).
+    pub fn pack(&self) -> BitDataset {
+        BitDataset::from_samples(self.samples)
+    }
+
+    /// Splits this view into views of a training set holding the first
+    /// `train_fraction` of its samples (rounded down) and a test set holding
+    /// the rest, preserving their order.
+    ///
+    /// Panics if `train_fraction` is not between zero and one.
+    pub fn split(&self, train_fraction: f64) -> (DatasetView<'a>, DatasetView<'a>) {
+        if !(0.0..=1.0).contains(&train_fraction) {
+            panic!("Cannot split a dataset at a fraction of {}!", train_fraction);
+        }
+        let (train, test) = self.samples.split_at((self.samples.len() as f64 * train_fraction) as usize);
+        (DatasetView { samples: train }, DatasetView { samples: test })
+    }
+
+    /// Clones the samples of this view into a new dataset.
+    pub fn to_dataset(&self) -> Dataset {
+        Dataset { samples: self.samples.to_vec() }
+    }
+}
+
+/// Allows datasets to be created from unchecked samples.
+impl From<Vec<(Vec<bool>, Vec<bool>)>> for Dataset {
+    fn from(samples: Vec<(Vec<bool>, Vec<bool>)>) -> Self {
+        Dataset { samples }
+    }
+}
+
+/// Checks that a new sample has the same widths as the first of the specified
+/// samples, if any.
+pub(crate) fn check_widths(samples: &[(Vec<bool>, Vec<bool>)], input: &[bool], expected: &[bool]) -> Result<(), String> {
+    match samples.first() {
+        Some((i, e)) if i.len() != input.len() || e.len() != expected.len() => Err(format!(
+            "expected {} input and {} output bits like the first sample but found {} and {}",
+            i.len(), e.len(), input.len(), expected.len()
+        )),
+        _ => Ok(())
+    }
+}
+
+/// Wraps the specified parse error of the specified file as an I/O error of
+/// kind `InvalidData`.
+pub(crate) fn invalid_data<P: AsRef<Path>>(path: P, e: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.as_ref().display(), e))
+}
+
+/// Returns the specified line trimmed of whitespace, or `None` if it is blank
+/// or a comment.
+pub(crate) fn data_line(line: &str) -> Option<&str> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') { None } else { Some(line) }
+}
+
+/// Returns the non-blank, non-comment lines of the specified text along with
+/// their one-based line numbers.
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(i, l)| data_line(l).map(|l| (i + 1, l)))
+}
+
+/// Parses a single `0` or `1`.
+fn parse_bit(s: &str) -> Result<bool, String> {
+    match s {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        other => Err(format!("{:?} is not 0 or 1", other))
+    }
+}
+
+/// Parses the comma-separated `0`/`1` fields of a single CSV row.
+pub(crate) fn parse_csv_fields(line: &str) -> Result<Vec<bool>, String> {
+    line.split(',').map(|f| parse_bit(f.trim())).collect()
+}
+
+/// Splits the specified CSV row into its inputs and its last `output_len`
+/// values, the expected outputs.
+pub(crate) fn split_csv_row(mut row: Vec<bool>, output_len: usize) -> Result<(Vec<bool>, Vec<bool>), String> {
+    if row.len() <= output_len {
+        return Err(format!("expected more than {} columns but found {}", output_len, row.len()));
+    }
+    let expected = row.split_off(row.len() - output_len);
+    Ok((row, expected))
+}
@@ -0,0 +1,239 @@
+//! Contains `Network::diff`, which compares two versions of a `bnl` network
+//! (such as checkpoints from different generations) both structurally and
+//! behaviorally.
+//!
+//! Neurons are matched by position, as in `Network::to_dot_diff`: neuron `j`
+//! of layer `i` of one network is compared with neuron `j` of layer `i` of
+//! the other, and neurons present in only one of them are reported as added
+//! or removed.
+
+use crate::combinator::Combinator;
+use crate::network::{Network, Neuron};
+use rand::Rng;
+use std::fmt;
+
+/// The number of inputs over which `Network::diff` measures similarity, all of
+/// which are enumerated if there are no more than this many distinct inputs.
+pub const SAMPLES: usize = 1024;
+
+/// Represents a single structural difference between two networks.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Change {
+    /// A neuron present only in the newer network.
+    Added {
+        /// The index of the layer of the neuron.
+        layer: usize,
+
+        /// The index of the neuron within its layer.
+        neuron: usize
+    },
+
+    /// A neuron whose bias changed.
+    Bias {
+        /// The index of the layer of the neuron.
+        layer: usize,
+
+        /// The index of the neuron within its layer.
+        neuron: usize,
+
+        /// The value in the older network.
+        before: bool,
+
+        /// The value in the newer network.
+        after: bool
+    },
+
+    /// A neuron one of whose input combinators changed.
+    InputCombinator {
+        /// The index of the layer of the neuron.
+        layer: usize,
+
+        /// The index of the neuron within its layer.
+        neuron: usize,
+
+        /// The index of the combinator within the chain of the neuron.
+        index: usize,
+
+        /// The value in the older network.
+        before: Combinator,
+
+        /// The value in the newer network.
+        after: Combinator
+    },
+
+    /// A neuron present only in the older network.
+    Removed {
+        /// The index of the layer of the neuron.
+        layer: usize,
+
+        /// The index of the neuron within its layer.
+        neuron: usize
+    },
+
+    /// A neuron whose result combinator changed.
+    ResultCombinator {
+        /// The index of the layer of the neuron.
+        layer: usize,
+
+        /// The index of the neuron within its layer.
+        neuron: usize,
+
+        /// The value in the older network.
+        before: Combinator,
+
+        /// The value in the newer network.
+        after: Combinator
+    },
+
+    /// A neuron whose arity, wiring, reduction or number of input combinators
+    /// changed, so that its input combinators cannot be compared one by one.
+    Wiring {
+        /// The index of the layer of the neuron.
+        layer: usize,
+
+        /// The index of the neuron within its layer.
+        neuron: usize
+    }
+}
+
+/// Formats changes as a single line describing them.
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Change::Added { layer, neuron } => write!(f, "layer {} neuron {}: added", layer, neuron),
+            Change::Bias { layer, neuron, before, after } =>
+                write!(f, "layer {} neuron {}: bias {} -> {}", layer, neuron, before as u8, after as u8),
+            Change::InputCombinator { layer, neuron, index, before, after } =>
+                write!(f, "layer {} neuron {}: input combinator {} {} -> {}", layer, neuron, index, before, after),
+            Change::Removed { layer, neuron } => write!(f, "layer {} neuron {}: removed", layer, neuron),
+            Change::ResultCombinator { layer, neuron, before, after } =>
+                write!(f, "layer {} neuron {}: result combinator {} -> {}", layer, neuron, before, after),
+            Change::Wiring { layer, neuron } => write!(f, "layer {} neuron {}: rewired", layer, neuron)
+        }
+    }
+}
+
+/// Represents the differences between two networks.
+#[derive(Clone,Debug,PartialEq)]
+pub struct NetworkDiff {
+    /// The structural differences between the networks, by layer, then by
+    /// neuron.
+    pub changes: Vec<Change>,
+
+    /// The fraction of output bits on which the networks agree over a sample
+    /// of inputs (see `similarity`), or `None` if their input widths differ.
+    pub similarity: Option<f64>
+}
+
+/// Implements custom methods for network diffs.
+impl NetworkDiff {
+    /// Determines whether the networks have no structural differences.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Formats network diffs with one line per change, followed by the
+/// similarity of the networks.
+impl fmt::Display for NetworkDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for c in &self.changes {
+            writeln!(f, "{}", c)?;
+        }
+        match self.similarity {
+            Some(s) => writeln!(f, "similarity: {:.4}", s),
+            None => writeln!(f, "similarity: n/a (input widths differ)")
+        }
+    }
+}
+
+/// Implements diffing on `bnl` networks.
+impl Network {
+    /// Compares this (older) network with another (newer) network, reporting
+    /// their structural differences and their similarity over a sample of
+    /// `SAMPLES` random inputs.
+    pub fn diff(&self, other: &Network) -> NetworkDiff {
+        self.diff_with_rng(other, &mut crate::random::rng())
+    }
+
+    /// Compares this network with another network as with `Network::diff`,
+    /// drawing the sampled inputs from the specified generator.
+    pub fn diff_with_rng<R: Rng + ?Sized>(&self, other: &Network, rng: &mut R) -> NetworkDiff {
+        let width = self.input_width();
+        let similarity = if width == other.input_width() {
+            let inputs: Vec<Vec<bool>> = if width < usize::BITS as usize && 1 << width <= SAMPLES {
+                (0..(1usize << width)).map(|x| (0..width).map(|b| (x >> b) & 1 == 1).collect()).collect()
+            } else {
+                (0..SAMPLES).map(|_| (0..width).map(|_| rng.gen()).collect()).collect()
+            };
+            Some(similarity(self, other, &inputs))
+        } else {
+            None
+        };
+        NetworkDiff {
+            changes: changes(self, other),
+            similarity
+        }
+    }
+}
+
+/// Computes the structural differences between an older and a newer
+/// network, matching their neurons by position.
+pub fn changes(before: &Network, after: &Network) -> Vec<Change> {
+    let mut res: Vec<Change> = Vec::new();
+    let depth = before.layers.len().max(after.layers.len());
+    for layer in 0..depth {
+        let old: &[Neuron] = before.layers.get(layer).map_or(&[], |l| &l.neurons);
+        let new: &[Neuron] = after.layers.get(layer).map_or(&[], |l| &l.neurons);
+        for neuron in 0..old.len().max(new.len()) {
+            match (old.get(neuron), new.get(neuron)) {
+                (Some(o), Some(n)) => neuron_changes(layer, neuron, o, n, &mut res),
+                (Some(_), None) => res.push(Change::Removed { layer, neuron }),
+                (None, _) => res.push(Change::Added { layer, neuron })
+            }
+        }
+    }
+    res
+}
+
+/// Appends the differences between two versions of the neuron at the
+/// specified position to the specified changes.
+fn neuron_changes(layer: usize, neuron: usize, before: &Neuron, after: &Neuron, changes: &mut Vec<Change>) {
+    if before.arity != after.arity || before.inputs != after.inputs || before.reduction != after.reduction
+        || before.input_combinators.len() != after.input_combinators.len() {
+        changes.push(Change::Wiring { layer, neuron });
+    } else {
+        for (index, (&b, &a)) in before.input_combinators.iter().zip(after.input_combinators.iter()).enumerate() {
+            if b != a {
+                changes.push(Change::InputCombinator { layer, neuron, index, before: b, after: a });
+            }
+        }
+    }
+    if before.bias != after.bias {
+        changes.push(Change::Bias { layer, neuron, before: before.bias, after: after.bias });
+    }
+    if before.result_combinator != after.result_combinator {
+        changes.push(Change::ResultCombinator {
+            layer,
+            neuron,
+            before: before.result_combinator,
+            after: after.result_combinator
+        });
+    }
+}
+
+/// Computes the fraction of output bits on which two networks of the same
+/// input width agree over the specified inputs, where outputs present in only
+/// one of the networks always disagree. Returns `1.0` if there are no inputs
+/// or outputs to compare.
+pub fn similarity(a: &Network, b: &Network, inputs: &[Vec<bool>]) -> f64 {
+    let width = a.output_width().max(b.output_width());
+    if inputs.is_empty() || width == 0 {
+        return 1.0;
+    }
+    let agreeing: usize = inputs.iter().map(|i| {
+        let (x, y) = (a.apply(i), b.apply(i));
+        x.iter().zip(y.iter()).filter(|(p, q)| p == q).count()
+    }).sum();
+    agreeing as f64 / (inputs.len() * width) as f64
+}
@@ -0,0 +1,227 @@
+//! Contains a MAP-Elites quality-diversity trainer for `bnl` networks.
+//!
+//! Rather than searching for a single best network, MAP-Elites maintains an
+//! archive of networks binned by behavioral descriptors (such as their number
+//! of live gates), keeping only the network with the lowest error in each
+//! bin. New candidates are produced by mutating randomly chosen elites, and
+//! replace the elite of their bin if they improve upon it. The result is a
+//! portfolio of good networks spanning the range of each descriptor, such as
+//! the most accurate network achievable at every gate budget.
+
+use crate::analysis::op_count;
+use crate::batch::BitDataset;
+use crate::mutate::replace_parameters;
+use crate::network::Network;
+use crate::random::Map;
+use rand::Rng;
+
+/// Represents the archive of elites maintained by `map_elites`.
+#[derive(Clone,Debug)]
+pub struct Archive {
+    /// The axes along which networks are binned.
+    pub axes: Vec<Axis>,
+
+    /// The elite of each occupied bin, keyed by its coordinates along each
+    /// axis.
+    pub cells: Map<Vec<usize>, Elite>
+}
+
+/// Implements custom methods for archives.
+impl Archive {
+    /// Returns the elite with the lowest error in this archive, if any.
+    pub fn best(&self) -> Option<&Elite> {
+        self.cells.iter().min_by_key(|(cell, e)| (e.error, *cell)).map(|(_, e)| e)
+    }
+
+    /// Returns the fraction of the bins of this archive which are occupied.
+    pub fn coverage(&self) -> f64 {
+        let total: usize = self.axes.iter().map(|a| a.bins.max(1)).product();
+        self.cells.len() as f64 / total as f64
+    }
+
+    /// Creates a new empty archive over the specified axes.
+    pub fn new(axes: Vec<Axis>) -> Self {
+        Archive {
+            axes,
+            cells: Map::new()
+        }
+    }
+
+    /// Inserts the specified network into this archive, replacing the elite
+    /// of its bin if the bin is empty or the network has a strictly lower
+    /// error. Returns whether the network was inserted.
+    pub fn offer(&mut self, network: Network, dataset: &[(Vec<bool>, Vec<bool>)]) -> bool {
+        let descriptors: Vec<f64> = self.axes.iter().map(|a| a.descriptor.measure(&network, dataset)).collect();
+        let cell: Vec<usize> = self.axes.iter().zip(descriptors.iter()).map(|(a, &d)| a.bin(d)).collect();
+        let e = BitDataset::from_samples(dataset).error(&network);
+        if self.cells.get(&cell).is_some_and(|elite| elite.error <= e) {
+            return false;
+        }
+        self.cells.insert(cell, Elite {
+            descriptors,
+            error: e,
+            network
+        });
+        true
+    }
+}
+
+/// Represents an axis of a MAP-Elites archive, dividing the range of a
+/// behavioral descriptor into equally sized bins.
+#[derive(Clone,Debug)]
+pub struct Axis {
+    /// The number of bins along this axis.
+    pub bins: usize,
+
+    /// The descriptor measured along this axis.
+    pub descriptor: Descriptor,
+
+    /// The upper bound of the range of the descriptor. Values above it are
+    /// placed in the last bin.
+    pub max: f64,
+
+    /// The lower bound of the range of the descriptor. Values below it are
+    /// placed in the first bin.
+    pub min: f64
+}
+
+/// Implements custom methods for archive axes.
+impl Axis {
+    /// Computes the bin along this axis containing the specified descriptor
+    /// value.
+    pub fn bin(&self, value: f64) -> usize {
+        if self.bins <= 1 || self.max <= self.min {
+            return 0;
+        }
+        let t = (value - self.min) / (self.max - self.min);
+        ((t * self.bins as f64).floor().max(0.0) as usize).min(self.bins - 1)
+    }
+}
+
+/// Represents the behavioral descriptors by which networks may be binned.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Descriptor {
+    /// The mean binary entropy (in bits) of the activations of every neuron
+    /// over the inputs of the dataset, measuring how much of the network is
+    /// actually switching.
+    ActivationEntropy,
+
+    /// The number of layers of the network.
+    Depth,
+
+    /// The number of live gates of the network, as counted by
+    /// `analysis::op_count`.
+    LiveGates
+}
+
+/// Implements custom methods for descriptors.
+impl Descriptor {
+    /// Measures this descriptor for the specified network on the specified
+    /// dataset.
+    pub fn measure(&self, network: &Network, dataset: &[(Vec<bool>, Vec<bool>)]) -> f64 {
+        match self {
+            Descriptor::ActivationEntropy => {
+                let neurons: usize = network.layers.iter().map(|l| l.neurons.len()).sum();
+                if neurons == 0 || dataset.is_empty() {
+                    return 0.0;
+                }
+                let mut ones: Vec<usize> = vec![0; neurons];
+                for (input, _) in dataset {
+                    let mut signals = input.clone();
+                    let mut index = 0;
+                    for layer in &network.layers {
+                        signals = layer.apply(&signals);
+                        for &s in &signals {
+                            ones[index] += s as usize;
+                            index += 1;
+                        }
+                    }
+                }
+                ones.iter().map(|&k| {
+                    let p = k as f64 / dataset.len() as f64;
+                    if p <= 0.0 || p >= 1.0 {
+                        0.0
+                    } else {
+                        -p * p.log2() - (1.0 - p) * (1.0 - p).log2()
+                    }
+                }).sum::<f64>() / neurons as f64
+            },
+            Descriptor::Depth => network.layers.len() as f64,
+            Descriptor::LiveGates => op_count(network).live_gates as f64
+        }
+    }
+}
+
+/// Represents the elite network of a single bin of an archive.
+#[derive(Clone,Debug)]
+pub struct Elite {
+    /// The measured value of each descriptor of the archive for the network.
+    pub descriptors: Vec<f64>,
+
+    /// The error of the network on the dataset.
+    pub error: usize,
+
+    /// The network itself.
+    pub network: Network
+}
+
+/// Represents the options controlling `map_elites`.
+#[derive(Clone,Debug)]
+pub struct MapElitesOptions {
+    /// The axes of the archive.
+    pub axes: Vec<Axis>,
+
+    /// The number of random networks used to seed the archive.
+    pub initial_networks: usize,
+
+    /// The number of mutated candidates to evaluate after seeding.
+    pub iterations: usize,
+
+    /// The probability with which each individual parameter (bias or
+    /// combinator) of a candidate is replaced by a random value.
+    pub mutation_rate: f64,
+
+    /// The layer lengths of the random seed networks, which are chosen from
+    /// round-robin so that the archive may span several topologies.
+    pub topologies: Vec<Vec<usize>>
+}
+
+/// Fills an archive with networks of the specified input length trained on
+/// the specified dataset via MAP-Elites, returning the archive.
+///
+/// The archive is seeded with `initial_networks` random networks, after which
+/// each iteration mutates a uniformly chosen elite and offers the result to
+/// the archive.
+///
+/// Panics if no topologies are specified.
+pub fn map_elites(input_len: usize, dataset: &[(Vec<bool>, Vec<bool>)], opts: MapElitesOptions) -> Archive {
+    map_elites_with_rng(input_len, dataset, opts, &mut crate::random::rng())
+}
+
+/// Fills an archive via MAP-Elites as with `map_elites`, drawing random
+/// choices from the specified generator.
+pub fn map_elites_with_rng<R: Rng + ?Sized>(input_len: usize, dataset: &[(Vec<bool>, Vec<bool>)], opts: MapElitesOptions, rng: &mut R) -> Archive {
+    if opts.topologies.is_empty() {
+        panic!("MAP-Elites requires at least one seed topology!");
+    }
+    let mut archive = Archive::new(opts.axes.clone());
+    for i in 0..opts.initial_networks.max(1) {
+        let lengths = opts.topologies[i % opts.topologies.len()].clone();
+        archive.offer(Network::new_with_rng(input_len, lengths, rng), dataset);
+    }
+    let occupied = |archive: &Archive| {
+        let mut cells: Vec<Vec<usize>> = archive.cells.keys().cloned().collect();
+        cells.sort_unstable();
+        cells
+    };
+    let mut cells = occupied(&archive);
+    for _i in 0..opts.iterations {
+        let parent = &cells[rng.gen_range(0, cells.len())];
+        let mut child = archive.cells[parent].network.clone();
+        replace_parameters(&mut child, opts.mutation_rate, rng);
+        if archive.offer(child, dataset) && archive.cells.len() > cells.len() {
+            cells = occupied(&archive);
+        }
+    }
+    archive
+}
@@ -0,0 +1,157 @@
+//! Contains helpers for encoding values as vectors of boolean values (and
+//! decoding them again), so that `bnl` networks can work with non-boolean
+//! data.
+//!
+//! Throughout this module, bit `i` of an encoded integer is stored at index
+//! `i` of its boolean vector (least significant bit first). Besides integers,
+//! bounded real values may be thermometer coded, and class labels one-hot
+//! coded.
+
+use crate::network::Network;
+
+/// Represents the ways in which an integer may be coded as boolean values.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Coding {
+    /// Plain binary coding.
+    Binary,
+
+    /// Reflected binary (Gray) coding, in which consecutive integers differ in
+    /// exactly one bit. This tends to make small prediction errors cheaper.
+    Gray
+}
+
+/// Implements custom methods for integer codings.
+impl Coding {
+    /// Decodes the specified boolean vector back into an integer.
+    pub fn decode(&self, bits: &[bool]) -> u64 {
+        let value = bits.iter().rev().fold(0u64, |acc, &b| (acc << 1) | b as u64);
+        match self {
+            Coding::Binary => value,
+            Coding::Gray => {
+                let mut res = value;
+                let mut shift = value >> 1;
+                while shift != 0 {
+                    res ^= shift;
+                    shift >>= 1;
+                }
+                res
+            }
+        }
+    }
+
+    /// Decodes the specified boolean vector back into a `u32`, discarding any
+    /// higher bits.
+    pub fn decode_u32(&self, bits: &[bool]) -> u32 {
+        self.decode(bits) as u32
+    }
+
+    /// Decodes the specified boolean vector back into a `u8`, discarding any
+    /// higher bits.
+    pub fn decode_u8(&self, bits: &[bool]) -> u8 {
+        self.decode(bits) as u8
+    }
+
+    /// Encodes the specified integer as a boolean vector of the specified
+    /// width, discarding any higher bits.
+    pub fn encode(&self, value: u64, width: usize) -> Vec<bool> {
+        let coded = match self {
+            Coding::Binary => value,
+            Coding::Gray => value ^ (value >> 1)
+        };
+        (0..width).map(|i| i < 64 && (coded >> i) & 1 == 1).collect()
+    }
+
+    /// Encodes the specified `u32` as a boolean vector of width 32.
+    pub fn encode_u32(&self, value: u32) -> Vec<bool> {
+        self.encode(value as u64, 32)
+    }
+
+    /// Encodes the specified `u8` as a boolean vector of width 8.
+    pub fn encode_u8(&self, value: u8) -> Vec<bool> {
+        self.encode(value as u64, 8)
+    }
+}
+
+/// Computes the mean absolute error between the integers decoded from the
+/// outputs of the specified network and the expected integers, over a
+/// collection of `(input, expected)` samples.
+pub fn decoded_mae(network: &Network, samples: &[(Vec<bool>, u64)], coding: Coding) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let total: u64 = samples.iter().map(|(input, expected)| {
+        let predicted = coding.decode(&network.apply(input));
+        predicted.max(*expected) - predicted.min(*expected)
+    }).sum();
+    total as f64 / samples.len() as f64
+}
+
+/// Decodes the class label predicted by the specified boolean vector, whose
+/// values are split into equally sized groups of votes for each of the
+/// specified number of classes, as the class with the most `true` votes (the
+/// lowest such class on ties).
+///
+/// With a single vote per class this decodes a (possibly noisy) one-hot
+/// vector. Panics if there are no classes or the width of the vector is not
+/// a multiple of the number of classes.
+pub fn one_hot_decode(bits: &[bool], classes: usize) -> usize {
+    if classes == 0 || !bits.len().is_multiple_of(classes) {
+        panic!("Cannot decode {} votes for {} classes!", bits.len(), classes);
+    }
+    let votes = bits.len() / classes;
+    (0..classes).rev()
+        .max_by_key(|&c| bits[c * votes..(c + 1) * votes].iter().filter(|&&b| b).count())
+        .unwrap()
+}
+
+/// Encodes the specified class label as a one-hot boolean vector, whose only
+/// `true` value is at the index of the label, over the specified number of
+/// classes.
+///
+/// Panics if the label is not one of the classes.
+pub fn one_hot_encode(label: usize, classes: usize) -> Vec<bool> {
+    if label >= classes {
+        panic!("Cannot encode label {} of only {} classes!", label, classes);
+    }
+    (0..classes).map(|c| c == label).collect()
+}
+
+/// Builds a collection of `(input, target)` samples suitable for training a
+/// network to predict integers, by coding each integer target as a boolean
+/// vector of the specified width.
+pub fn regression_samples(inputs: &[Vec<bool>], targets: &[u64], width: usize, coding: Coding) -> Vec<(Vec<bool>, Vec<bool>)> {
+    inputs.iter()
+        .zip(targets.iter())
+        .map(|(i, &t)| (i.clone(), coding.encode(t, width)))
+        .collect()
+}
+
+/// Decodes the specified thermometer coded boolean vector (see
+/// `thermometer_encode`) back into a value within the specified bounds, from
+/// the number of its `true` values.
+///
+/// Counting rather than finding the first `false` value makes decoding
+/// tolerant to imperfect predictions.
+pub fn thermometer_decode(bits: &[bool], min: f64, max: f64) -> f64 {
+    if bits.is_empty() {
+        return min;
+    }
+    let level = bits.iter().filter(|&&b| b).count();
+    min + (max - min) * level as f64 / bits.len() as f64
+}
+
+/// Encodes the specified value within the specified bounds as a thermometer
+/// coded boolean vector of the specified width, whose first `k` values are
+/// `true` when the value lies `k / width` of the way from `min` to `max`
+/// (rounded to the nearest level).
+///
+/// Values outside the bounds are clamped to them. Unlike binary coding, nearby
+/// values always have nearby codes. Panics if `min` is not less than `max`.
+pub fn thermometer_encode(value: f64, min: f64, max: f64, width: usize) -> Vec<bool> {
+    if min.is_nan() || max.is_nan() || min >= max {
+        panic!("Cannot encode values between {} and {}!", min, max);
+    }
+    let fraction = ((value - min) / (max - min)).clamp(0.0, 1.0);
+    let level = (fraction * width as f64).round() as usize;
+    (0..width).map(|i| i < level).collect()
+}
@@ -0,0 +1,120 @@
+//! Contains the `Ensemble` type, for combining several trained `bnl` networks
+//! of the same shape into a more accurate predictor by per-bit majority vote.
+//!
+//! Networks trained by hill climbing or evolution from different starting
+//! points tend to make different mistakes, so the majority of several of
+//! them is usually right more often than any single one. The networks of an
+//! ensemble may be collected by hand or taken from the fittest networks of a
+//! population (see `Population::ensemble`).
+//!
+//! Instead of a simple majority, each output may also be decided by its own
+//! vote threshold (such as one calibrated on held-out data to trade
+//! precision against recall), see `Ensemble::apply_thresholded`.
+
+use crate::network::Network;
+
+/// Represents a collection of networks whose outputs are combined by per-bit
+/// majority vote.
+#[derive(Clone,Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ensemble {
+    /// The networks voting on each output.
+    networks: Vec<Network>
+}
+
+/// Implements custom methods for ensembles.
+impl Ensemble {
+    /// "Applies" this ensemble to a given input vector of boolean values, where
+    /// each output is `true` if more than half of the networks output `true`
+    /// (so that ties are broken towards `false`).
+    pub fn apply(&self, input: &[bool]) -> Vec<bool> {
+        self.votes(input).iter().map(|&v| 2 * v > self.networks.len()).collect()
+    }
+
+    /// "Applies" this ensemble as with `Ensemble::apply`, additionally
+    /// returning the confidence of each output as the vote margin, the
+    /// difference between the numbers of networks voting each way as a
+    /// fraction of all networks (from `0.0` on a tie to `1.0` when they
+    /// agree unanimously).
+    pub fn apply_with_confidence(&self, input: &[bool]) -> Vec<(bool, f64)> {
+        let total = self.networks.len();
+        self.votes(input).iter().map(|&v| {
+            let margin = (2 * v).max(total) - (2 * v).min(total);
+            (2 * v > total, margin as f64 / total as f64)
+        }).collect()
+    }
+
+    /// "Applies" this ensemble to a given input vector of boolean values,
+    /// deciding each output by its own threshold, and returns the decision of
+    /// each output along with the fraction of networks voting `true` for it.
+    /// An output is `true` if that fraction exceeds its threshold, so that
+    /// thresholds of `0.5` decide as `Ensemble::apply` does.
+    ///
+    /// Panics if there is not one threshold per output.
+    pub fn apply_thresholded(&self, input: &[bool], thresholds: &[f64]) -> Vec<(bool, f64)> {
+        if thresholds.len() != self.output_width() {
+            panic!("Cannot apply an ensemble of {} outputs with {} thresholds!", self.output_width(), thresholds.len());
+        }
+        self.vote_fractions(input).into_iter()
+            .zip(thresholds.iter())
+            .map(|(f, &t)| (f > t, f))
+            .collect()
+    }
+
+    /// Returns the width of the input vectors accepted by this ensemble.
+    pub fn input_width(&self) -> usize {
+        self.networks[0].input_width()
+    }
+
+    /// Consumes this ensemble, returning its networks.
+    pub fn into_networks(self) -> Vec<Network> {
+        self.networks
+    }
+
+    /// Creates a new ensemble of the specified networks.
+    ///
+    /// Panics if there are no networks, or if they differ in input or output
+    /// width.
+    pub fn new(networks: Vec<Network>) -> Self {
+        let first = networks.first().expect("Cannot create an ensemble of no networks!");
+        let widths = (first.input_width(), first.output_width());
+        if let Some(n) = networks.iter().find(|n| (n.input_width(), n.output_width()) != widths) {
+            panic!(
+                "Cannot create an ensemble of networks with widths {:?} and {:?}!",
+                widths, (n.input_width(), n.output_width())
+            );
+        }
+        Ensemble {
+            networks
+        }
+    }
+
+    /// Returns the networks of this ensemble.
+    pub fn networks(&self) -> &[Network] {
+        &self.networks
+    }
+
+    /// Returns the width of the output vectors produced by this ensemble.
+    pub fn output_width(&self) -> usize {
+        self.networks[0].output_width()
+    }
+
+    /// Returns the fraction of the networks of this ensemble outputting `true`
+    /// at each output for the specified input.
+    pub fn vote_fractions(&self, input: &[bool]) -> Vec<f64> {
+        let total = self.networks.len() as f64;
+        self.votes(input).into_iter().map(|v| v as f64 / total).collect()
+    }
+
+    /// Counts the networks of this ensemble outputting `true` at each output
+    /// for the specified input.
+    pub fn votes(&self, input: &[bool]) -> Vec<usize> {
+        let mut res = vec![0; self.output_width()];
+        for n in &self.networks {
+            for (v, b) in res.iter_mut().zip(n.apply(input)) {
+                *v += b as usize;
+            }
+        }
+        res
+    }
+}
@@ -0,0 +1,122 @@
+//! Contains the `Error` type returned by the fallible APIs of `bnl`.
+
+use alloc::string::String;
+use core::fmt;
+
+/// Represents the ways in which applying, loading or training a network can
+/// fail.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub enum Error {
+    /// A neuron was given an input whose width differs from its arity.
+    ArityMismatch {
+        /// The index of the layer containing the neuron.
+        layer: usize,
+
+        /// The index of the neuron within its layer.
+        neuron: usize,
+
+        /// The arity of the neuron.
+        expected: usize,
+
+        /// The width of the input it was given.
+        found: usize
+    },
+
+    /// A neuron's input combinator chain is inconsistent with its arity.
+    ChainMismatch {
+        /// The index of the layer containing the neuron.
+        layer: usize,
+
+        /// The index of the neuron within its layer.
+        neuron: usize,
+
+        /// The arity of the neuron.
+        arity: usize,
+
+        /// The number of input combinators of the neuron.
+        combinators: usize
+    },
+
+    /// A saved network could not be decoded, or decoded into an invalid
+    /// network. The loaders of the `persist` module return it wrapped in an
+    /// I/O error of kind `InvalidData`.
+    CorruptModel {
+        /// The byte offset at which decoding failed, or `None` if the bytes
+        /// decoded but describe an invalid network.
+        offset: Option<usize>,
+
+        /// A description of the problem, naming the layer and neuron at fault
+        /// for invalid networks.
+        reason: String
+    },
+
+    /// Training was resumed on a dataset other than the one it was started on,
+    /// as told by their hashes (see `Dataset::hash`). `Trainer::run_for`
+    /// returns it wrapped in an I/O error of kind `InvalidInput`.
+    DatasetMismatch {
+        /// The hash of the dataset training was started on.
+        expected: u64,
+
+        /// The hash of the dataset training was resumed on.
+        found: u64
+    },
+
+    /// A sparsely wired neuron is wired to a signal beyond the end of its
+    /// input.
+    InputIndex {
+        /// The index of the layer containing the neuron.
+        layer: usize,
+
+        /// The index of the neuron within its layer.
+        neuron: usize,
+
+        /// The index of the signal it is wired to.
+        index: usize,
+
+        /// The width of its input.
+        width: usize
+    },
+
+    /// A network was given an input whose width differs from its input width.
+    InputWidth {
+        /// The input width of the network.
+        expected: usize,
+
+        /// The width of the input it was given.
+        found: usize
+    }
+}
+
+/// Describes errors in the style of the panic messages of the infallible
+/// APIs.
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::ArityMismatch { layer, neuron, expected, found } => write!(
+                f, "Layer {} neuron {} expects an input of width {} but was given {}",
+                layer, neuron, expected, found
+            ),
+            Error::ChainMismatch { layer, neuron, arity, combinators } => write!(
+                f, "Layer {} neuron {} has an arity of {} but {} input combinators",
+                layer, neuron, arity, combinators
+            ),
+            Error::CorruptModel { offset: Some(offset), reason } => write!(f, "Corrupt model at byte {}: {}", offset, reason),
+            Error::CorruptModel { offset: None, reason } => write!(f, "Corrupt model: {}", reason),
+            Error::DatasetMismatch { expected, found } => write!(
+                f, "Training was started on a dataset with hash {:016x} but resumed on one with hash {:016x}",
+                expected, found
+            ),
+            Error::InputIndex { layer, neuron, index, width } => write!(
+                f, "Layer {} neuron {} is wired to input {} of an input of width {}",
+                layer, neuron, index, width
+            ),
+            Error::InputWidth { expected, found } => write!(
+                f, "Network expects an input of width {} but was given {}",
+                expected, found
+            )
+        }
+    }
+}
+
+/// Allows errors to be used with `?` in functions returning boxed errors.
+impl core::error::Error for Error {}
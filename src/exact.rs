@@ -0,0 +1,160 @@
+//! Contains an exact, SAT-based trainer for very small `bnl` networks.
+//!
+//! Rather than searching for good parameters, this trainer encodes the
+//! question "does an assignment of combinators and biases exist for this
+//! topology which reproduces every sample?" as a boolean satisfiability
+//! problem and hands it to a SAT solver. The result is either a network which
+//! is provably correct on every sample, or a proof that none exists.
+//!
+//! Each combinator is encoded by four variables (its truth table), each bias
+//! by one variable, and every intermediate signal of every sample by one
+//! variable tied to its gate via clauses of its truth table, so the problem
+//! size grows with `samples * gates`. This is only practical for tiny
+//! topologies.
+
+use crate::combinator::Combinator;
+use crate::network::{Layer, Network, Neuron, Reduction};
+use varisat::{ExtendFormula, Lit, Solver};
+
+/// Represents a signal within the encoded network, which is either known in
+/// advance or determined by a solver variable.
+#[derive(Clone,Copy,Debug)]
+enum Signal {
+    /// A signal whose value is known (such as a network input).
+    Const(bool),
+
+    /// A signal whose value is given by a solver variable.
+    Var(Lit)
+}
+
+/// Represents the solver variables encoding the parameters of a single
+/// neuron.
+struct NeuronVars {
+    /// The variable encoding the bias of the neuron.
+    bias: Lit,
+
+    /// The truth tables of the input combinators of the neuron, indexed by
+    /// `2 * left + right`.
+    input_tables: Vec<[Lit; 4]>,
+
+    /// The truth table of the result combinator of the neuron, indexed by
+    /// `2 * left + right`.
+    result_table: [Lit; 4]
+}
+
+/// Finds a network of the specified input length and layer lengths which
+/// reproduces every one of the specified `(input, expected)` samples exactly,
+/// or returns `None` if no such network exists.
+///
+/// Panics if any sample does not match the requested input and output widths.
+pub fn train(input_len: usize, layer_lengths: Vec<usize>, samples: &[(Vec<bool>, Vec<bool>)]) -> Option<Network> {
+    let output_len = layer_lengths.last().copied().unwrap_or(input_len);
+    for (input, expected) in samples {
+        if input.len() != input_len || expected.len() != output_len {
+            panic!("Sample widths do not match the requested topology!");
+        }
+    }
+    let mut solver = Solver::new();
+    let mut vars: Vec<Vec<NeuronVars>> = Vec::new();
+    let mut width = input_len;
+    for &len in &layer_lengths {
+        let layer: Vec<NeuronVars> = (0..len).map(|_| NeuronVars {
+            bias: solver.new_lit(),
+            input_tables: (1..width).map(|_| new_table(&mut solver)).collect(),
+            result_table: new_table(&mut solver)
+        }).collect();
+        vars.push(layer);
+        width = len;
+    }
+    for (input, expected) in samples {
+        let mut signals: Vec<Signal> = input.iter().map(|&b| Signal::Const(b)).collect();
+        for layer in &vars {
+            signals = layer.iter().map(|n| {
+                let mut acc = signals[signals.len() - 1];
+                for (i, table) in n.input_tables.iter().enumerate().rev() {
+                    acc = gate(&mut solver, table, signals[i], acc);
+                }
+                gate(&mut solver, &n.result_table, acc, Signal::Var(n.bias))
+            }).collect();
+        }
+        for (signal, &e) in signals.iter().zip(expected.iter()) {
+            match signal {
+                Signal::Const(b) if *b != e => return None,
+                Signal::Const(_) => {},
+                Signal::Var(l) => solver.add_clause(&[if e { *l } else { !*l }])
+            }
+        }
+    }
+    if !solver.solve().unwrap_or(false) {
+        return None;
+    }
+    let model = solver.model()?;
+    let value = |l: Lit| model.contains(&l);
+    let mut layers: Vec<Layer> = Vec::new();
+    let mut width = input_len;
+    for layer in &vars {
+        layers.push(Layer {
+            neurons: layer.iter().map(|n| Neuron {
+                arity: width,
+                bias: value(n.bias),
+                input_combinators: n.input_tables.iter().map(|t| decode_table(t, &value)).collect(),
+                inputs: None,
+                locked: false,
+                reduction: Reduction::RightFold,
+                result_combinator: decode_table(&n.result_table, &value)
+            }).collect()
+        });
+        width = layer.len();
+    }
+    Some(Network {
+        layers
+    })
+}
+
+/// Decodes the truth table of a combinator into the combinator itself.
+fn decode_table<F: Fn(Lit) -> bool>(table: &[Lit; 4], value: &F) -> Combinator {
+    let mut c = 0;
+    for (index, &l) in table.iter().enumerate() {
+        if value(l) {
+            c |= 1 << (3 - index);
+        }
+    }
+    Combinator::ALL[c]
+}
+
+/// Encodes a gate computing the combinator with the specified truth table
+/// over two signals, returning its output signal.
+fn gate(solver: &mut Solver, table: &[Lit; 4], left: Signal, right: Signal) -> Signal {
+    if let (Signal::Const(l), Signal::Const(r)) = (left, right) {
+        return Signal::Var(table[2 * l as usize + r as usize]);
+    }
+    let out = solver.new_lit();
+    for l in 0..2 {
+        for r in 0..2 {
+            let mut premise: Vec<Lit> = Vec::new();
+            let active = [(left, l == 1), (right, r == 1)].iter().all(|&(s, v)| match s {
+                Signal::Const(b) => b == v,
+                Signal::Var(x) => {
+                    premise.push(if v { !x } else { x });
+                    true
+                }
+            });
+            if !active {
+                continue;
+            }
+            let entry = table[2 * l + r];
+            let mut on = premise.clone();
+            on.extend_from_slice(&[!entry, out]);
+            solver.add_clause(&on);
+            let mut off = premise;
+            off.extend_from_slice(&[entry, !out]);
+            solver.add_clause(&off);
+        }
+    }
+    Signal::Var(out)
+}
+
+/// Creates four fresh solver variables encoding a combinator's truth table.
+fn new_table(solver: &mut Solver) -> [Lit; 4] {
+    [solver.new_lit(), solver.new_lit(), solver.new_lit(), solver.new_lit()]
+}
@@ -0,0 +1,73 @@
+//! Contains routines for explaining the predictions of `bnl` networks.
+
+use crate::analysis::hamming_distance;
+use crate::network::Network;
+use std::collections::HashSet;
+
+/// The number of candidate inputs retained between steps of the beam search
+/// used by `counterfactual` for wide inputs.
+pub const BEAM_WIDTH: usize = 16;
+
+/// The largest input width for which `counterfactual` searches exhaustively.
+pub const EXHAUSTIVE_WIDTH: usize = 16;
+
+/// Finds a minimal set of input bits which, when flipped, change the output
+/// of the specified network on the specified input to the desired output.
+///
+/// For inputs of at most `EXHAUSTIVE_WIDTH` bits every set of flips is tried
+/// in order of increasing size, so the result is guaranteed minimal. For wider
+/// inputs a beam search is performed which greedily flips the bits bringing
+/// the output closest to the desired output, so the result is small but not
+/// necessarily minimal. Returns the (ascending) indices of the bits to flip, or
+/// `None` if no counterfactual could be found.
+pub fn counterfactual(network: &Network, input: &[bool], desired: &[bool]) -> Option<Vec<usize>> {
+    let width = input.len();
+    if width <= EXHAUSTIVE_WIDTH {
+        let mut masks: Vec<usize> = (0..(1usize << width)).collect();
+        masks.sort_by_key(|m| m.count_ones());
+        masks.into_iter()
+            .map(|m| (0..width).filter(|i| (m >> i) & 1 == 1).collect::<Vec<usize>>())
+            .find(|flips| network.apply(&flip(input, flips)) == desired)
+    } else {
+        let mut beam: Vec<Vec<usize>> = vec![Vec::new()];
+        for _step in 0..=width {
+            let mut scored: Vec<(usize, Vec<usize>)> = Vec::new();
+            for flips in &beam {
+                let distance = hamming_distance(&network.apply(&flip(input, flips)), desired);
+                if distance == 0 {
+                    return Some(flips.clone());
+                }
+                scored.push((distance, flips.clone()));
+            }
+            let mut seen: HashSet<Vec<usize>> = HashSet::new();
+            let mut candidates: Vec<(usize, Vec<usize>)> = Vec::new();
+            for (_, flips) in &scored {
+                for i in (0..width).filter(|i| !flips.contains(i)) {
+                    let mut next = flips.clone();
+                    next.push(i);
+                    next.sort_unstable();
+                    if seen.insert(next.clone()) {
+                        let distance = hamming_distance(&network.apply(&flip(input, &next)), desired);
+                        candidates.push((distance, next));
+                    }
+                }
+            }
+            if candidates.is_empty() {
+                return None;
+            }
+            candidates.sort_by_key(|(d, _)| *d);
+            beam = candidates.into_iter().take(BEAM_WIDTH).map(|(_, f)| f).collect();
+        }
+        None
+    }
+}
+
+/// Returns a copy of the specified input with the bits at the specified
+/// indices flipped.
+fn flip(input: &[bool], flips: &[usize]) -> Vec<bool> {
+    let mut res: Vec<bool> = input.to_vec();
+    for &i in flips {
+        res[i] = !res[i];
+    }
+    res
+}
@@ -0,0 +1,741 @@
+//! Contains routines for exporting `bnl` networks to other representations
+//! and estimating their cost on hardware targets.
+//!
+//! Since every neuron is a chain of two-input gates, networks translate
+//! directly into gate-level netlists, emitted as synthesizable Verilog (see
+//! `Network::to_verilog`) or as BLIF (see `Network::to_blif`) for logic
+//! synthesis tools. Each combinator of each neuron becomes one gate, named
+//! `l{layer}_n{neuron}_g{index}` in evaluation order, and the neuron's output
+//! is named `l{layer}_n{neuron}`. The same netlist can also be emitted as a
+//! dependency-free Rust function (see `rust_source`), so that trained models
+//! can be vendored directly into other Rust projects.
+//!
+//! Verilog and BLIF netlists may also be restricted to the cells of a small
+//! library, such as two-input NAND gates and inverters (see `CellLibrary` and
+//! `Network::to_verilog_mapped`), in which case each combinator is rewritten
+//! into a few cells during export, cancelling the double inverters this
+//! introduces within each neuron. Combinators which merely pass a signal or a
+//! constant through are then wired directly rather than becoming cells.
+//!
+//! To check that an exported circuit is faithful, `test_vectors` samples
+//! golden input/output pairs from the Rust evaluator, which may be saved as a
+//! vector file (such as in the PLA format, see `Dataset::save`) for any
+//! simulator, or rendered as a self-checking Verilog testbench of the
+//! exported module (see `Network::to_verilog_testbench`).
+
+use crate::analysis::probe_inputs_with_rng;
+use crate::bits::format_bits;
+use crate::combinator::Combinator;
+use crate::dataset::Dataset;
+use crate::network::{compute_boolean, Network, Neuron, Reduction};
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// The largest input width for which `rules` will enumerate truth tables.
+pub const MAX_RULE_WIDTH: usize = 16;
+
+/// Represents the cell libraries to which the gates of exported netlists may be
+/// restricted (see `Network::to_verilog_mapped`).
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum CellLibrary {
+    /// Two-input AND and OR gates and inverters.
+    AndOrInv,
+
+    /// Any two-input gate, with one gate per combinator.
+    Any,
+
+    /// Two-input NAND gates and inverters.
+    NandInv,
+
+    /// Two-input NOR gates and inverters.
+    NorInv
+}
+
+/// Implements custom methods for cell libraries.
+impl CellLibrary {
+    /// Every cell library, in alphabetical order of their names.
+    pub const ALL: [CellLibrary; 4] = [CellLibrary::AndOrInv, CellLibrary::Any, CellLibrary::NandInv, CellLibrary::NorInv];
+}
+
+/// Names cell libraries as the command-line interface does, such as
+/// `nand-inv`.
+impl fmt::Display for CellLibrary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            CellLibrary::AndOrInv => "and-or-inv",
+            CellLibrary::Any => "any",
+            CellLibrary::NandInv => "nand-inv",
+            CellLibrary::NorInv => "nor-inv"
+        })
+    }
+}
+
+/// Represents the gates of a single neuron of a netlist being built from the
+/// cells of a library.
+struct Cells<'a> {
+    /// The number of gates of the neuron so far.
+    count: usize,
+
+    /// The gates of the netlist.
+    gates: &'a mut Vec<Gate>,
+
+    /// The input of each inverter of the neuron, keyed by its output.
+    inverted: HashMap<String, String>,
+
+    /// The library the gates are restricted to.
+    library: CellLibrary,
+
+    /// The name of the output of the neuron, which prefixes the names of its
+    /// gates.
+    prefix: String
+}
+
+/// Implements custom methods for netlists being built from cells.
+impl<'a> Cells<'a> {
+    /// Adds cells computing the AND of the specified nets, returning the net
+    /// of their output.
+    fn and(&mut self, left: String, right: String) -> String {
+        match self.library {
+            CellLibrary::NandInv => {
+                let nand = self.cell(Combinator::Nand, left, right);
+                self.inv(nand)
+            },
+            CellLibrary::NorInv => {
+                let (left, right) = (self.inv(left), self.inv(right));
+                self.cell(Combinator::Nor, left, right)
+            },
+            _ => self.cell(Combinator::And, left, right)
+        }
+    }
+
+    /// Adds a gate computing the specified combinator of the specified nets,
+    /// returning the net of its output.
+    fn cell(&mut self, combinator: Combinator, left: String, right: String) -> String {
+        let output = format!("{}_g{}", self.prefix, self.count);
+        self.count += 1;
+        self.gates.push(Gate { combinator, left, output: output.clone(), right });
+        output
+    }
+
+    /// Adds cells computing the specified combinator of the specified nets,
+    /// returning the net of their output, and folding constant inputs away.
+    fn gate(&mut self, combinator: Combinator, left: String, right: String) -> String {
+        if self.library == CellLibrary::Any {
+            return self.cell(combinator, left, right);
+        }
+        let constant = |net: &str| match net {
+            "const0" => Some(false),
+            "const1" => Some(true),
+            _ => None
+        };
+        let partial = match (constant(&left), constant(&right)) {
+            (Some(l), Some(r)) => return String::from(if compute_boolean(l, r, combinator) { "const1" } else { "const0" }),
+            (None, Some(r)) => Some((left.clone(), compute_boolean(false, r, combinator), compute_boolean(true, r, combinator))),
+            (Some(l), None) => Some((right.clone(), compute_boolean(l, false, combinator), compute_boolean(l, true, combinator))),
+            (None, None) => None
+        };
+        if let Some((net, on_false, on_true)) = partial {
+            return match (on_false, on_true) {
+                (false, false) => String::from("const0"),
+                (false, true) => net,
+                (true, false) => self.inv(net),
+                (true, true) => String::from("const1")
+            };
+        }
+        match combinator {
+            Combinator::False => String::from("const0"),
+            Combinator::And => self.and(left, right),
+            Combinator::AndNotRight => {
+                let right = self.inv(right);
+                self.and(left, right)
+            },
+            Combinator::Left => left,
+            Combinator::AndNotLeft => {
+                let left = self.inv(left);
+                self.and(left, right)
+            },
+            Combinator::Right => right,
+            Combinator::Xor => self.xor(left, right),
+            Combinator::Or => self.or(left, right),
+            Combinator::Nor => self.nor(left, right),
+            Combinator::Xnor => self.xnor(left, right),
+            Combinator::NotRight => self.inv(right),
+            Combinator::ImpliedBy => {
+                let right = self.inv(right);
+                self.or(left, right)
+            },
+            Combinator::NotLeft => self.inv(left),
+            Combinator::Implies => {
+                let left = self.inv(left);
+                self.or(left, right)
+            },
+            Combinator::Nand => self.nand(left, right),
+            Combinator::True => String::from("const1")
+        }
+    }
+
+    /// Adds an inverter of the specified net, unless it is a constant or the
+    /// output of another inverter, returning the net of the inverted signal.
+    fn inv(&mut self, net: String) -> String {
+        match net.as_str() {
+            "const0" => return String::from("const1"),
+            "const1" => return String::from("const0"),
+            _ => {}
+        }
+        if let Some(input) = self.inverted.get(&net) {
+            return input.clone();
+        }
+        let output = self.cell(Combinator::NotLeft, net.clone(), net.clone());
+        self.inverted.insert(output.clone(), net);
+        output
+    }
+
+    /// Adds cells computing the NAND of the specified nets, returning the net
+    /// of their output.
+    fn nand(&mut self, left: String, right: String) -> String {
+        if self.library == CellLibrary::NandInv {
+            return self.cell(Combinator::Nand, left, right);
+        }
+        let and = self.and(left, right);
+        self.inv(and)
+    }
+
+    /// Adds cells computing the NOR of the specified nets, returning the net
+    /// of their output.
+    fn nor(&mut self, left: String, right: String) -> String {
+        if self.library == CellLibrary::NorInv {
+            return self.cell(Combinator::Nor, left, right);
+        }
+        let or = self.or(left, right);
+        self.inv(or)
+    }
+
+    /// Adds cells computing the OR of the specified nets, returning the net of
+    /// their output.
+    fn or(&mut self, left: String, right: String) -> String {
+        match self.library {
+            CellLibrary::NandInv => {
+                let (left, right) = (self.inv(left), self.inv(right));
+                self.cell(Combinator::Nand, left, right)
+            },
+            CellLibrary::NorInv => {
+                let nor = self.cell(Combinator::Nor, left, right);
+                self.inv(nor)
+            },
+            _ => self.cell(Combinator::Or, left, right)
+        }
+    }
+
+    /// Adds cells computing the XNOR of the specified nets, returning the net
+    /// of their output.
+    fn xnor(&mut self, left: String, right: String) -> String {
+        if self.library != CellLibrary::NorInv {
+            let xor = self.xor(left, right);
+            return self.inv(xor);
+        }
+        let nor = self.nor(left.clone(), right.clone());
+        let (left, right) = (self.nor(left, nor.clone()), self.nor(right, nor));
+        self.nor(left, right)
+    }
+
+    /// Adds cells computing the XOR of the specified nets, returning the net
+    /// of their output.
+    fn xor(&mut self, left: String, right: String) -> String {
+        match self.library {
+            CellLibrary::NandInv => {
+                let nand = self.nand(left.clone(), right.clone());
+                let (left, right) = (self.nand(left, nand.clone()), self.nand(right, nand));
+                self.nand(left, right)
+            },
+            CellLibrary::NorInv => {
+                let xnor = self.xnor(left, right);
+                self.inv(xnor)
+            },
+            _ => {
+                let (not_left, not_right) = (self.inv(left.clone()), self.inv(right.clone()));
+                let (left, right) = (self.and(left, not_right), self.and(not_left, right));
+                self.or(left, right)
+            }
+        }
+    }
+}
+
+/// Represents a single human-readable IF/THEN rule under which an output of a
+/// network is true.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct DecisionRule {
+    /// The conditions of the rule, as `(input, value)` pairs which must all
+    /// hold. An empty list of conditions always holds.
+    pub conditions: Vec<(usize, bool)>,
+
+    /// The number of inputs (out of the full input space) covered by this
+    /// rule.
+    pub coverage: usize,
+
+    /// The index of the output this rule concludes.
+    pub output: usize
+}
+
+/// Displays decision rules as `IF x0 AND NOT x2 THEN out1`.
+impl fmt::Display for DecisionRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.conditions.is_empty() {
+            return write!(f, "IF TRUE THEN out{}", self.output);
+        }
+        let terms: Vec<String> = self.conditions.iter()
+            .map(|&(i, v)| if v { format!("x{}", i) } else { format!("NOT x{}", i) })
+            .collect();
+        write!(f, "IF {} THEN out{}", terms.join(" AND "), self.output)
+    }
+}
+
+/// Represents a single two-input gate of a netlist.
+struct Gate {
+    /// The function computed by the gate.
+    combinator: Combinator,
+
+    /// The net driving the left input of the gate.
+    left: String,
+
+    /// The net driven by the gate.
+    output: String,
+
+    /// The net driving the right input of the gate.
+    right: String
+}
+
+/// Implements exporting on `bnl` networks.
+impl Network {
+    /// Renders this network as a gate-level netlist in the Berkeley Logic
+    /// Interchange Format (BLIF), as a model of the specified name with inputs
+    /// `x0`, `x1`, ... and outputs `out0`, `out1`, ....
+    ///
+    /// Each gate is written as a `.names` table listing the input rows on
+    /// which it is true, and the bias constants as the nets `const0` and
+    /// `const1`.
+    pub fn to_blif(&self, model_name: &str) -> String {
+        self.to_blif_mapped(model_name, CellLibrary::Any)
+    }
+
+    /// Renders this network as a BLIF netlist as with `Network::to_blif`, but
+    /// built only from the cells of the specified library, with inverters and
+    /// direct connections written as single-input tables.
+    pub fn to_blif_mapped(&self, model_name: &str, library: CellLibrary) -> String {
+        let inputs: Vec<String> = (0..self.input_width()).map(|i| format!("x{}", i)).collect();
+        let (gates, outputs) = netlist(self, &inputs, library);
+        let mut res = format!(".model {}\n.inputs {}\n", model_name, inputs.join(" "));
+        res += &format!(".outputs {}\n", (0..outputs.len()).map(|i| format!("out{}", i)).collect::<Vec<String>>().join(" "));
+        res += ".names const0\n.names const1\n1\n";
+        for g in &gates {
+            if g.left == g.right {
+                res += &format!(".names {} {}\n", g.left, g.output);
+                for &v in &[false, true] {
+                    if compute_boolean(v, v, g.combinator) {
+                        res += &format!("{} 1\n", v as u8);
+                    }
+                }
+                continue;
+            }
+            res += &format!(".names {} {} {}\n", g.left, g.right, g.output);
+            for &(l, r) in &[(false, false), (false, true), (true, false), (true, true)] {
+                if compute_boolean(l, r, g.combinator) {
+                    res += &format!("{}{} 1\n", l as u8, r as u8);
+                }
+            }
+        }
+        for (i, o) in outputs.iter().enumerate() {
+            res += &format!(".names {} out{}\n1 1\n", o, i);
+        }
+        res += ".end\n";
+        res
+    }
+
+    /// Renders the topology of this network as a Graphviz DOT graph.
+    ///
+    /// Inputs are drawn as boxes `x0`, `x1`, ... and outputs as boxes `out0`,
+    /// `out1`, ..., with each layer grouped into a cluster of neurons labeled
+    /// by their input combinator chain, result combinator and bias. The edges
+    /// into each neuron are labeled by the position of their signal within
+    /// its input chain.
+    pub fn to_dot(&self) -> String {
+        dot_graph(self, None)
+    }
+
+    /// Renders the topology of this network as with `Network::to_dot`,
+    /// highlighting how it differs from the specified earlier version of it
+    /// (such as the network before training or pruning).
+    ///
+    /// Neurons are compared by their position within their layer: neurons
+    /// whose parameters changed are filled orange and also labeled by their
+    /// earlier parameters, neurons not present before are filled green, and
+    /// neurons (or whole layers) since removed are drawn dashed in red without
+    /// edges.
+    pub fn to_dot_diff(&self, before: &Network) -> String {
+        dot_graph(self, Some(before))
+    }
+
+    /// Renders this network as a synthesizable Verilog module of the specified
+    /// name, with an input vector `x` and an output vector `out`, implementing
+    /// it with continuous assignments of one two-input gate each.
+    pub fn to_verilog(&self, module_name: &str) -> String {
+        self.to_verilog_mapped(module_name, CellLibrary::Any)
+    }
+
+    /// Renders this network as a Verilog module as with `Network::to_verilog`,
+    /// but with each continuous assignment computing a single cell of the
+    /// specified library (or directly connecting two nets) instead, so that
+    /// the module may be mapped onto that library without a synthesis pass.
+    pub fn to_verilog_mapped(&self, module_name: &str, library: CellLibrary) -> String {
+        let (input_width, output_width) = (self.input_width(), self.output_width());
+        let inputs: Vec<String> = (0..input_width).map(|i| format!("x[{}]", i)).collect();
+        let (gates, outputs) = netlist(self, &inputs, library);
+        let mut res = format!(
+            "module {} (\n    input wire [{}:0] x,\n    output wire [{}:0] out\n);\n",
+            module_name, input_width.max(1) - 1, output_width.max(1) - 1
+        );
+        res += "    wire const0 = 1'b0;\n    wire const1 = 1'b1;\n";
+        for g in &gates {
+            res += &format!("    wire {} = {};\n", g.output, verilog_expr(g.combinator, &g.left, &g.right));
+        }
+        for (i, o) in outputs.iter().enumerate() {
+            res += &format!("    assign out[{}] = {};\n", i, o);
+        }
+        res += "endmodule\n";
+        res
+    }
+
+    /// Renders a self-checking Verilog testbench, named after the specified
+    /// module name with a `_tb` suffix, for the module of that name exported
+    /// by `Network::to_verilog`.
+    ///
+    /// The testbench applies the input of each of the specified vectors (see
+    /// `test_vectors`) in turn, compares the output of the module with the
+    /// expected output, displaying each mismatch, and finally displays
+    /// `PASS` or `FAIL` along with the number of vectors which failed.
+    pub fn to_verilog_testbench(&self, module_name: &str, vectors: &Dataset) -> String {
+        let (input_width, output_width) = (self.input_width(), self.output_width());
+        // Verilog literals list the most significant bit (the last signal) first.
+        let literal = |bits: &[bool]| {
+            let reversed: Vec<bool> = bits.iter().rev().cloned().collect();
+            format!("{}'b{}", bits.len(), format_bits(&reversed))
+        };
+        let mut res = String::from("`timescale 1ns / 1ps\n");
+        res += &format!("module {}_tb;\n", module_name);
+        res += &format!("    reg [{}:0] x;\n    wire [{}:0] out;\n", input_width - 1, output_width - 1);
+        res += "    integer failures = 0;\n";
+        res += &format!("    {} dut (.x(x), .out(out));\n", module_name);
+        res += &format!("    task check(input [{}:0] input_bits, input [{}:0] expected);\n", input_width - 1, output_width - 1);
+        res += "        begin\n            x = input_bits;\n            #1;\n";
+        res += "            if (out !== expected) begin\n                failures = failures + 1;\n";
+        res += "                $display(\"mismatch: x = %b, out = %b, expected %b\", input_bits, out, expected);\n";
+        res += "            end\n        end\n    endtask\n";
+        res += "    initial begin\n";
+        for (input, expected) in vectors.as_slice() {
+            res += &format!("        check({}, {});\n", literal(input), literal(expected));
+        }
+        res += &format!(
+            "        if (failures == 0) $display(\"PASS: %0d vectors\", {0});\n        else $display(\"FAIL: %0d of %0d vectors\", failures, {0});\n",
+            vectors.len()
+        );
+        res += "        $finish;\n    end\nendmodule\n";
+        res
+    }
+}
+
+/// Estimates the number of `k`-input lookup tables (LUTs) the specified
+/// network would occupy on an FPGA.
+///
+/// Each neuron is reduced to the inputs it structurally depends on (inputs
+/// masked off by combinators such as `LEFT` or constant `FALSE` are dropped
+/// and the bias is folded in as a constant). Neurons depending on at most one
+/// input are assumed to be absorbed into their consumers, while the remaining
+/// chains are packed into a cascade of LUTs where the first LUT consumes `k`
+/// inputs and each subsequent LUT consumes `k - 1` new inputs plus the
+/// previous partial result.
+///
+/// Panics if `k` is less than two.
+pub fn estimate_luts(network: &Network, k: usize) -> usize {
+    if k < 2 {
+        panic!("LUTs must have at least two inputs!");
+    }
+    network.layers.iter()
+        .flat_map(|l| l.neurons.iter())
+        .map(|n| match n.support().len() {
+            0 | 1 => 0,
+            s => 1 + (s - 2) / (k - 1)
+        })
+        .sum()
+}
+
+/// Renders the specified network as a DOT graph, highlighting its differences
+/// from the specified earlier network, if any.
+fn dot_graph(network: &Network, before: Option<&Network>) -> String {
+    let mut res = String::from("digraph bnl {\n    rankdir=LR;\n    node [shape=box];\n");
+    for i in 0..network.input_width() {
+        res += &format!("    x{};\n", i);
+    }
+    let mut sources: Vec<String> = (0..network.input_width()).map(|i| format!("x{}", i)).collect();
+    let depth = network.layers.len().max(before.map_or(0, |b| b.layers.len()));
+    for i in 0..depth {
+        let neurons: &[Neuron] = network.layers.get(i).map_or(&[], |l| &l.neurons);
+        let old: &[Neuron] = before.and_then(|b| b.layers.get(i)).map_or(&[], |l| &l.neurons);
+        let suffix = if i >= network.layers.len() { " (removed)" } else { "" };
+        res += &format!("    subgraph cluster_{} {{\n        label=\"layer {}{}\";\n", i, i, suffix);
+        for (j, n) in neurons.iter().enumerate() {
+            let style = match (before, old.get(j)) {
+                (None, _) => "",
+                (Some(_), None) => ", style=filled, fillcolor=palegreen",
+                (Some(_), Some(o)) if o == n => "",
+                (Some(_), Some(_)) => ", style=filled, fillcolor=orange"
+            };
+            let label = match old.get(j) {
+                Some(o) if o != n => format!("{}\\nwas {}", dot_label(n), dot_label(o)),
+                _ => dot_label(n)
+            };
+            res += &format!("        n{}_{} [shape=ellipse, label=\"{}\"{}];\n", i, j, label, style);
+        }
+        for (j, o) in old.iter().enumerate().skip(neurons.len()) {
+            res += &format!(
+                "        removed{}_{} [shape=ellipse, style=dashed, color=red, label=\"{}\"];\n",
+                i, j, dot_label(o)
+            );
+        }
+        res += "    }\n";
+        for (j, n) in neurons.iter().enumerate() {
+            for k in 0..n.fan_in() {
+                res += &format!("    {} -> n{}_{} [label=\"{}\"];\n", sources[n.input_index(k)], i, j, k);
+            }
+        }
+        if i < network.layers.len() {
+            sources = (0..neurons.len()).map(|j| format!("n{}_{}", i, j)).collect();
+        }
+    }
+    for (i, s) in sources.iter().enumerate().take(network.output_width()) {
+        res += &format!("    out{};\n    {} -> out{};\n", i, s, i);
+    }
+    res += "}\n";
+    res
+}
+
+/// Labels the node of the specified neuron in a DOT graph with its input
+/// combinator chain (and its reduction, unless a right fold), result
+/// combinator and bias.
+fn dot_label(neuron: &Neuron) -> String {
+    let chain: Vec<&str> = neuron.input_combinators.iter().map(|c| c.name()).collect();
+    let reduction = match neuron.reduction {
+        Reduction::RightFold => String::new(),
+        r => format!(" ({})", r.name())
+    };
+    format!(
+        "[{}]{}\\nresult {}, bias {}",
+        chain.join(", "), reduction, neuron.result_combinator.name(), neuron.bias as u8
+    )
+}
+
+/// Flattens the specified network into a list of gates of the specified cell
+/// library in evaluation order, given the names of the nets of its inputs,
+/// along with the nets driving each of its outputs.
+///
+/// Inverters are written as `NOT_LEFT` gates and direct connections as `LEFT`
+/// gates, both with the same net as their left and right inputs. Gates of a
+/// mapped netlist whose outputs are unused within their neuron are removed,
+/// while every neuron keeps its named output.
+fn netlist(network: &Network, inputs: &[String], library: CellLibrary) -> (Vec<Gate>, Vec<String>) {
+    let mut gates: Vec<Gate> = Vec::new();
+    let mut signals: Vec<String> = inputs.to_vec();
+    for (i, layer) in network.layers.iter().enumerate() {
+        let mut next: Vec<String> = Vec::with_capacity(layer.neurons.len());
+        for (j, n) in layer.neurons.iter().enumerate() {
+            let first = gates.len();
+            let name = format!("l{}_n{}", i, j);
+            let mut cells = Cells {
+                count: 0,
+                gates: &mut gates,
+                inverted: HashMap::new(),
+                library,
+                prefix: name.clone()
+            };
+            let selected: Vec<String> = (0..n.fan_in()).map(|k| signals[n.input_index(k)].clone()).collect();
+            let acc = n.reduction.reduce(selected, &n.input_combinators, |left, right, c| cells.gate(c, left, right));
+            let bias = String::from(if n.bias { "const1" } else { "const0" });
+            let output = cells.gate(n.result_combinator, acc, bias);
+            let created = gates.len() > first;
+            match gates.last_mut() {
+                Some(last) if created && last.output == output => last.output = name.clone(),
+                _ => gates.push(Gate { combinator: Combinator::Left, left: output.clone(), output: name.clone(), right: output })
+            }
+            next.push(name);
+        }
+        signals = next;
+    }
+    if library != CellLibrary::Any {
+        let mut used: HashSet<String> = HashSet::new();
+        let mut kept: Vec<Gate> = Vec::with_capacity(gates.len());
+        for g in gates.into_iter().rev() {
+            if !g.output.contains("_g") || used.contains(&g.output) {
+                used.insert(g.left.clone());
+                used.insert(g.right.clone());
+                kept.push(g);
+            }
+        }
+        kept.reverse();
+        gates = kept;
+    }
+    (gates, signals)
+}
+
+/// Computes the prime implicants of the boolean function which is true on
+/// exactly the specified minterms, as `(value, mask)` pairs where `mask`
+/// selects the inputs the implicant cares about.
+fn prime_implicants(minterms: &[u32], full: u32) -> Vec<(u32, u32)> {
+    let mut primes: Vec<(u32, u32)> = Vec::new();
+    let mut current: Vec<(u32, u32)> = minterms.iter().map(|&m| (m, full)).collect();
+    while !current.is_empty() {
+        let mut next: HashSet<(u32, u32)> = HashSet::new();
+        let mut merged: HashSet<(u32, u32)> = HashSet::new();
+        for (i, a) in current.iter().enumerate() {
+            for b in &current[(i + 1)..] {
+                let diff = a.0 ^ b.0;
+                if a.1 == b.1 && diff.count_ones() == 1 {
+                    next.insert((a.0 & !diff, a.1 & !diff));
+                    merged.insert(*a);
+                    merged.insert(*b);
+                }
+            }
+        }
+        primes.extend(current.iter().filter(|i| !merged.contains(i)));
+        current = next.into_iter().collect();
+        current.sort_unstable();
+    }
+    primes
+}
+
+/// Produces an ordered list of IF/THEN rules which together cover exactly the
+/// inputs for which the specified output of the network is true (the output is
+/// false whenever no rule applies).
+///
+/// The rules are derived from the prime implicants of the output's truth table
+/// (via the Quine-McCluskey method), selected greedily so that each rule covers
+/// as many not-yet-covered inputs as possible, which also orders them from
+/// most to least general. An output which is never true produces no rules.
+///
+/// Panics if the network's input is wider than `MAX_RULE_WIDTH` bits.
+pub fn rules(network: &Network, output_index: usize) -> Vec<DecisionRule> {
+    let width = network.input_width();
+    if width > MAX_RULE_WIDTH {
+        panic!("Cannot derive rules for networks wider than {} inputs!", MAX_RULE_WIDTH);
+    }
+    let full: u32 = if width == 0 { 0 } else { u32::MAX >> (32 - width) };
+    let minterms: Vec<u32> = (0..(1u32 << width))
+        .filter(|&x| network.apply(&(0..width).map(|i| (x >> i) & 1 == 1).collect::<Vec<bool>>())[output_index])
+        .collect();
+    let mut uncovered: HashSet<u32> = minterms.iter().copied().collect();
+    let implicants = prime_implicants(&minterms, full);
+    let mut res: Vec<DecisionRule> = Vec::new();
+    while !uncovered.is_empty() {
+        let (value, mask) = *implicants.iter()
+            .max_by_key(|(v, m)| uncovered.iter().filter(|&&x| x & m == *v).count())
+            .unwrap();
+        uncovered.retain(|&x| x & mask != value);
+        res.push(DecisionRule {
+            conditions: (0..width).filter(|i| (mask >> i) & 1 == 1).map(|i| (i, (value >> i) & 1 == 1)).collect(),
+            coverage: 1 << (width - mask.count_ones() as usize),
+            output: output_index
+        });
+    }
+    res
+}
+
+/// Writes the Rust expression computing the specified combinator on the
+/// specified boolean variables.
+fn rust_expr(combinator: Combinator, left: &str, right: &str) -> String {
+    match combinator {
+        Combinator::False       => String::from("false"),
+        Combinator::And         => format!("{} & {}", left, right),
+        Combinator::AndNotRight => format!("{} & !{}", left, right),
+        Combinator::Left        => left.to_string(),
+        Combinator::AndNotLeft  => format!("!{} & {}", left, right),
+        Combinator::Right       => right.to_string(),
+        Combinator::Xor         => format!("{} ^ {}", left, right),
+        Combinator::Or          => format!("{} | {}", left, right),
+        Combinator::Nor         => format!("!({} | {})", left, right),
+        Combinator::Xnor        => format!("{} == {}", left, right),
+        Combinator::NotRight    => format!("!{}", right),
+        Combinator::ImpliedBy   => format!("{} | !{}", left, right),
+        Combinator::NotLeft     => format!("!{}", left),
+        Combinator::Implies     => format!("!{} | {}", left, right),
+        Combinator::Nand        => format!("!({} & {})", left, right),
+        Combinator::True        => String::from("true")
+    }
+}
+
+/// Renders the specified network as a standalone Rust source file, with no
+/// dependencies, defining the constants `INPUT_WIDTH` and `OUTPUT_WIDTH` and a
+/// pure function `apply` computing the network on an array of input bits.
+///
+/// The function evaluates one boolean variable per gate, named as in the
+/// netlists of `Network::to_verilog`, so the optimizer of the including crate
+/// is free to simplify the gates away.
+pub fn rust_source(network: &Network) -> String {
+    let (input_width, output_width) = (network.input_width(), network.output_width());
+    let inputs: Vec<String> = (0..input_width).map(|i| format!("x[{}]", i)).collect();
+    let (gates, outputs) = netlist(network, &inputs, CellLibrary::Any);
+    let mut res = String::from("//! A boolean network generated by `bnl`.\n\n");
+    res += &format!("/// The number of inputs of the network.\npub const INPUT_WIDTH: usize = {};\n\n", input_width);
+    res += &format!("/// The number of outputs of the network.\npub const OUTPUT_WIDTH: usize = {};\n\n", output_width);
+    res += "/// Computes the outputs of the network on the specified inputs.\n";
+    res += "#[allow(unused_variables, clippy::all)]\n";
+    res += "pub fn apply(x: &[bool; INPUT_WIDTH]) -> [bool; OUTPUT_WIDTH] {\n";
+    res += "    let const0 = false;\n    let const1 = true;\n";
+    for g in &gates {
+        res += &format!("    let {} = {};\n", g.output, rust_expr(g.combinator, &g.left, &g.right));
+    }
+    res += &format!("    [{}]\n}}\n", outputs.join(", "));
+    res
+}
+
+/// Samples golden test vectors for checking an export of the specified
+/// network, pairing inputs with the outputs computed by `Network::apply`.
+///
+/// Every input is included if there are at most `max_samples` of them, and
+/// otherwise `max_samples` random inputs are (see
+/// `analysis::probe_inputs`).
+pub fn test_vectors(network: &Network, max_samples: usize) -> Dataset {
+    test_vectors_with_rng(network, max_samples, &mut crate::random::rng())
+}
+
+/// Samples golden test vectors as with `test_vectors`, drawing any random
+/// inputs from the specified generator.
+pub fn test_vectors_with_rng<R: Rng + ?Sized>(network: &Network, max_samples: usize, rng: &mut R) -> Dataset {
+    let samples = probe_inputs_with_rng(network.input_width(), max_samples, rng).into_iter()
+        .map(|input| {
+            let output = network.apply(&input);
+            (input, output)
+        })
+        .collect::<Vec<_>>();
+    Dataset::from(samples)
+}
+
+/// Writes the Verilog expression computing the specified combinator on the
+/// specified nets.
+fn verilog_expr(combinator: Combinator, left: &str, right: &str) -> String {
+    match combinator {
+        Combinator::False       => String::from("1'b0"),
+        Combinator::And         => format!("{} & {}", left, right),
+        Combinator::AndNotRight => format!("{} & ~{}", left, right),
+        Combinator::Left        => left.to_string(),
+        Combinator::AndNotLeft  => format!("~{} & {}", left, right),
+        Combinator::Right       => right.to_string(),
+        Combinator::Xor         => format!("{} ^ {}", left, right),
+        Combinator::Or          => format!("{} | {}", left, right),
+        Combinator::Nor         => format!("~({} | {})", left, right),
+        Combinator::Xnor        => format!("~({} ^ {})", left, right),
+        Combinator::NotRight    => format!("~{}", right),
+        Combinator::ImpliedBy   => format!("{} | ~{}", left, right),
+        Combinator::NotLeft     => format!("~{}", left),
+        Combinator::Implies     => format!("~{} | {}", left, right),
+        Combinator::Nand        => format!("~({} & {})", left, right),
+        Combinator::True        => String::from("1'b1")
+    }
+}
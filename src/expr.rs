@@ -0,0 +1,197 @@
+//! Contains the `Expr` type of symbolic boolean expressions, and routines for
+//! describing the outputs of `bnl` networks as expressions over their inputs.
+//!
+//! Expressions are built through constructors which simplify as they go
+//! (folding constants, cancelling double negations, collapsing repeated or
+//! complementary operands and moving negations out of exclusive
+//! disjunctions), so that neurons whose combinators ignore some of
+//! their inputs yield correspondingly small expressions. Since every neuron
+//! reads the whole of the layer before it, the expressions of deep networks
+//! may nevertheless grow large.
+
+use crate::combinator::Combinator;
+use crate::network::{Network, Neuron};
+use std::fmt;
+
+/// Represents a boolean expression over the inputs of a network.
+#[derive(Clone,Debug,PartialEq,Eq,Hash)]
+pub enum Expr {
+    /// The conjunction of two expressions.
+    And(Box<Expr>, Box<Expr>),
+
+    /// A constant value.
+    Const(bool),
+
+    /// The negation of an expression.
+    Not(Box<Expr>),
+
+    /// The disjunction of two expressions.
+    Or(Box<Expr>, Box<Expr>),
+
+    /// The input at the given index, printed as `x0`, `x1`, ....
+    Var(usize),
+
+    /// The exclusive disjunction of two expressions.
+    Xor(Box<Expr>, Box<Expr>)
+}
+
+/// Implements custom methods for expressions.
+impl Expr {
+    /// Creates the simplified conjunction of two expressions.
+    pub fn and(left: Expr, right: Expr) -> Expr {
+        match (left, right) {
+            (Expr::Const(false), _) | (_, Expr::Const(false)) => Expr::Const(false),
+            (Expr::Const(true), e) | (e, Expr::Const(true)) => e,
+            (l, r) if l == r => l,
+            (l, r) if l.is_negation_of(&r) => Expr::Const(false),
+            (l, r) => Expr::And(Box::new(l), Box::new(r))
+        }
+    }
+
+    /// Creates the simplified expression computing the specified combinator of
+    /// two expressions (see `compute_boolean`).
+    pub fn combine(left: Expr, right: Expr, combinator: Combinator) -> Expr {
+        match combinator {
+            Combinator::False       => Expr::Const(false),
+            Combinator::And         => Expr::and(left, right),
+            Combinator::AndNotRight => Expr::and(left, Expr::negate(right)),
+            Combinator::Left        => left,
+            Combinator::AndNotLeft  => Expr::and(Expr::negate(left), right),
+            Combinator::Right       => right,
+            Combinator::Xor         => Expr::xor(left, right),
+            Combinator::Or          => Expr::or(left, right),
+            Combinator::Nor         => Expr::negate(Expr::or(left, right)),
+            Combinator::Xnor        => Expr::negate(Expr::xor(left, right)),
+            Combinator::NotRight    => Expr::negate(right),
+            Combinator::ImpliedBy   => Expr::or(left, Expr::negate(right)),
+            Combinator::NotLeft     => Expr::negate(left),
+            Combinator::Implies     => Expr::or(Expr::negate(left), right),
+            Combinator::Nand        => Expr::negate(Expr::and(left, right)),
+            Combinator::True        => Expr::Const(true)
+        }
+    }
+
+    /// Evaluates this expression on the specified input.
+    ///
+    /// Panics if the expression refers to an input beyond the end of the
+    /// specified one.
+    pub fn eval(&self, input: &[bool]) -> bool {
+        match self {
+            Expr::And(l, r) => l.eval(input) && r.eval(input),
+            Expr::Const(b) => *b,
+            Expr::Not(e) => !e.eval(input),
+            Expr::Or(l, r) => l.eval(input) || r.eval(input),
+            Expr::Var(i) => input[*i],
+            Expr::Xor(l, r) => l.eval(input) != r.eval(input)
+        }
+    }
+
+    /// Writes this expression as an operand of the specified binary operator,
+    /// parenthesizing it unless it is atomic or uses the same (associative)
+    /// operator.
+    fn fmt_operand(&self, f: &mut fmt::Formatter, parent: &str) -> fmt::Result {
+        let op = match self {
+            Expr::And(..) => "AND",
+            Expr::Or(..) => "OR",
+            Expr::Xor(..) => "XOR",
+            _ => return write!(f, "{}", self)
+        };
+        if op == parent {
+            write!(f, "{}", self)
+        } else {
+            write!(f, "({})", self)
+        }
+    }
+
+    /// Determines whether this expression is syntactically the negation of
+    /// another.
+    fn is_negation_of(&self, other: &Expr) -> bool {
+        match (self, other) {
+            (Expr::Not(e), o) | (o, Expr::Not(e)) => **e == *o,
+            _ => false
+        }
+    }
+
+    /// Creates the simplified negation of an expression.
+    pub fn negate(expr: Expr) -> Expr {
+        match expr {
+            Expr::Const(b) => Expr::Const(!b),
+            Expr::Not(e) => *e,
+            e => Expr::Not(Box::new(e))
+        }
+    }
+
+    /// Creates the simplified disjunction of two expressions.
+    pub fn or(left: Expr, right: Expr) -> Expr {
+        match (left, right) {
+            (Expr::Const(true), _) | (_, Expr::Const(true)) => Expr::Const(true),
+            (Expr::Const(false), e) | (e, Expr::Const(false)) => e,
+            (l, r) if l == r => l,
+            (l, r) if l.is_negation_of(&r) => Expr::Const(true),
+            (l, r) => Expr::Or(Box::new(l), Box::new(r))
+        }
+    }
+
+    /// Creates the simplified exclusive disjunction of two expressions.
+    pub fn xor(left: Expr, right: Expr) -> Expr {
+        match (left, right) {
+            (Expr::Const(false), e) | (e, Expr::Const(false)) => e,
+            (Expr::Const(true), e) | (e, Expr::Const(true)) => Expr::negate(e),
+            (l, r) if l == r => Expr::Const(false),
+            (l, r) if l.is_negation_of(&r) => Expr::Const(true),
+            (Expr::Not(l), r) => Expr::negate(Expr::xor(*l, r)),
+            (l, Expr::Not(r)) => Expr::negate(Expr::xor(l, *r)),
+            (l, r) => Expr::Xor(Box::new(l), Box::new(r))
+        }
+    }
+}
+
+/// Displays expressions as `(x0 XOR x2) AND !x5`.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (l, r, op) = match self {
+            Expr::And(l, r) => (l, r, "AND"),
+            Expr::Const(b) => return write!(f, "{}", if *b { "TRUE" } else { "FALSE" }),
+            Expr::Not(e) => return match **e {
+                Expr::Const(_) | Expr::Not(_) | Expr::Var(_) => write!(f, "!{}", e),
+                _ => write!(f, "!({})", e)
+            },
+            Expr::Or(l, r) => (l, r, "OR"),
+            Expr::Var(i) => return write!(f, "x{}", i),
+            Expr::Xor(l, r) => (l, r, "XOR")
+        };
+        l.fmt_operand(f, op)?;
+        write!(f, " {} ", op)?;
+        r.fmt_operand(f, op)
+    }
+}
+
+/// Implements symbolic export on `bnl` networks.
+impl Network {
+    /// Computes a simplified expression over the inputs of this network for
+    /// each of its outputs.
+    pub fn expressions(&self) -> Vec<Expr> {
+        let mut signals: Vec<Expr> = (0..self.input_width()).map(Expr::Var).collect();
+        for layer in &self.layers {
+            signals = layer.neurons.iter().map(|n| neuron_expr(n, &signals)).collect();
+        }
+        signals
+    }
+
+    /// Describes each output of this network as a simplified expression over
+    /// its inputs, such as `out0 = (x0 XOR x2) AND !x5`.
+    pub fn to_expressions(&self) -> Vec<String> {
+        self.expressions().iter()
+            .enumerate()
+            .map(|(i, e)| format!("out{} = {}", i, e))
+            .collect()
+    }
+}
+
+/// Computes the expression of the specified neuron given the expressions of
+/// its inputs, reducing its input chain in the neuron's order.
+fn neuron_expr(neuron: &Neuron, inputs: &[Expr]) -> Expr {
+    let selected: Vec<Expr> = (0..neuron.fan_in()).map(|k| inputs[neuron.input_index(k)].clone()).collect();
+    let acc = neuron.reduction.reduce(selected, &neuron.input_combinators, Expr::combine);
+    Expr::combine(acc, Expr::Const(neuron.bias), neuron.result_combinator)
+}
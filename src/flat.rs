@@ -0,0 +1,196 @@
+//! Contains a flat, contiguous representation of `bnl` network parameters.
+//!
+//! A `FlatNetwork` stores the combinators and biases of every neuron in a
+//! handful of contiguous arrays indexed via per-layer and per-neuron offsets,
+//! which is friendlier to the cache and to bulk operations (such as mutating
+//! or serializing every parameter at once) than the nested `Network` type.
+//!
+//! Converting a network to a flat network and back (see
+//! `FlatNetwork::to_network`) yields exactly the same network, and the
+//! parameters of a flat network may be written back into the network it was
+//! created from without reallocating its neurons (see `FlatNetwork::write_to`).
+
+use crate::combinator::Combinator;
+use crate::network::{compute_boolean, Layer, Network, Neuron, Reduction};
+use std::ops::Range;
+
+/// Represents a `bnl` network whose parameters are stored contiguously.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct FlatNetwork {
+    /// The bias of every neuron, in layer order.
+    pub biases: Vec<bool>,
+
+    /// The offset of each neuron's input combinators within
+    /// `input_combinators`, followed by the total number of combinators.
+    pub combinator_offsets: Vec<usize>,
+
+    /// The input combinators of every neuron, concatenated in layer order.
+    pub input_combinators: Vec<Combinator>,
+
+    /// The index of the signal read at each position of each neuron's input
+    /// chain, concatenated in layer order. The indices of the neuron at flat
+    /// index `n` start at `combinator_offsets[n] + n`.
+    pub input_indices: Vec<usize>,
+
+    /// The width of the input vectors accepted by this network.
+    pub input_width: usize,
+
+    /// The index of the first neuron of each layer, followed by the total
+    /// number of neurons.
+    pub layer_offsets: Vec<usize>,
+
+    /// Whether every neuron is locked (see `Neuron::locked`), in layer order.
+    pub locked: Vec<bool>,
+
+    /// The reduction of every neuron, in layer order.
+    pub reductions: Vec<Reduction>,
+
+    /// The result combinator of every neuron, in layer order.
+    pub result_combinators: Vec<Combinator>,
+
+    /// Whether every neuron is sparsely wired (see `Neuron::inputs`), in layer
+    /// order.
+    pub sparse: Vec<bool>
+}
+
+/// Implements custom methods for flat networks.
+impl FlatNetwork {
+    /// "Applies" this network on the specified input vector of boolean values.
+    pub fn apply(&self, input: &[bool]) -> Vec<bool> {
+        let mut res: Vec<bool> = input.to_vec();
+        for l in 0..self.num_layers() {
+            res = self.layer_neurons(l).map(|n| self.apply_neuron(n, &res)).collect();
+        }
+        res
+    }
+
+    /// "Applies" the neuron at the specified flat index to a given input vector
+    /// of boolean values.
+    pub fn apply_neuron(&self, neuron: usize, input: &[bool]) -> bool {
+        let combinators = self.neuron_combinators(neuron);
+        let indices = self.neuron_inputs(neuron);
+        let acc = match self.reductions[neuron] {
+            Reduction::RightFold => {
+                let mut acc = input[indices[combinators.len()]];
+                for (i, &c) in combinators.iter().enumerate().rev() {
+                    acc = compute_boolean(input[indices[i]], acc, c);
+                }
+                acc
+            },
+            reduction => reduction.reduce(indices.iter().map(|&i| input[i]).collect(), combinators, compute_boolean)
+        };
+        compute_boolean(acc, self.biases[neuron], self.result_combinators[neuron])
+    }
+
+    /// Returns the range of flat neuron indices belonging to the specified
+    /// layer.
+    pub fn layer_neurons(&self, layer: usize) -> Range<usize> {
+        self.layer_offsets[layer]..self.layer_offsets[layer + 1]
+    }
+
+    /// Returns the input combinators of the neuron at the specified flat index.
+    pub fn neuron_combinators(&self, neuron: usize) -> &[Combinator] {
+        &self.input_combinators[self.combinator_offsets[neuron]..self.combinator_offsets[neuron + 1]]
+    }
+
+    /// Returns the indices of the signals read by the input chain of the
+    /// neuron at the specified flat index.
+    pub fn neuron_inputs(&self, neuron: usize) -> &[usize] {
+        &self.input_indices[(self.combinator_offsets[neuron] + neuron)..(self.combinator_offsets[neuron + 1] + neuron + 1)]
+    }
+
+    /// Creates a new flat network holding the parameters of the specified
+    /// network.
+    pub fn new(network: &Network) -> Self {
+        let mut flat = FlatNetwork {
+            biases: Vec::new(),
+            combinator_offsets: vec![0],
+            input_combinators: Vec::new(),
+            input_indices: Vec::new(),
+            input_width: network.input_width(),
+            layer_offsets: vec![0],
+            locked: Vec::new(),
+            reductions: Vec::new(),
+            result_combinators: Vec::new(),
+            sparse: Vec::new()
+        };
+        for layer in &network.layers {
+            for n in &layer.neurons {
+                flat.biases.push(n.bias);
+                flat.input_combinators.extend_from_slice(&n.input_combinators);
+                flat.input_indices.extend((0..n.fan_in()).map(|k| n.input_index(k)));
+                flat.combinator_offsets.push(flat.input_combinators.len());
+                flat.locked.push(n.locked);
+                flat.reductions.push(n.reduction);
+                flat.result_combinators.push(n.result_combinator);
+                flat.sparse.push(n.inputs.is_some());
+            }
+            flat.layer_offsets.push(flat.biases.len());
+        }
+        flat
+    }
+
+    /// Returns the number of layers in this network.
+    pub fn num_layers(&self) -> usize {
+        self.layer_offsets.len() - 1
+    }
+
+    /// Converts this flat network back into the nested `Network`
+    /// representation.
+    pub fn to_network(&self) -> Network {
+        let mut layers: Vec<Layer> = Vec::new();
+        let mut width = self.input_width;
+        for l in 0..self.num_layers() {
+            let neurons: Vec<Neuron> = self.layer_neurons(l).map(|n| {
+                Neuron {
+                    arity: width,
+                    bias: self.biases[n],
+                    input_combinators: self.neuron_combinators(n).to_vec(),
+                    inputs: if self.sparse[n] { Some(self.neuron_inputs(n).to_vec()) } else { None },
+                    locked: self.locked[n],
+                    reduction: self.reductions[n],
+                    result_combinator: self.result_combinators[n]
+                }
+            }).collect();
+            width = neurons.len();
+            layers.push(Layer {
+                neurons
+            });
+        }
+        Network {
+            layers
+        }
+    }
+
+    /// Copies the parameters of this flat network back into the specified
+    /// network, which must have the shape of the network this flat network
+    /// was created from, without reallocating any of its neurons.
+    ///
+    /// Panics if the shapes of the networks differ.
+    pub fn write_to(&self, network: &mut Network) {
+        let shape: Vec<usize> = network.layers.iter().map(|l| l.neurons.len()).collect();
+        if shape.len() != self.num_layers() || shape.iter().enumerate().any(|(l, &w)| w != self.layer_neurons(l).len()) {
+            panic!("Cannot write the parameters of a flat network into a network of a different shape!");
+        }
+        for (i, neuron) in network.layers.iter_mut().flat_map(|l| l.neurons.iter_mut()).enumerate() {
+            if neuron.input_combinators.len() != self.neuron_combinators(i).len() || neuron.inputs.is_some() != self.sparse[i] {
+                panic!("Cannot write the parameters of a flat network into a network of a different shape!");
+            }
+            neuron.bias = self.biases[i];
+            neuron.input_combinators.copy_from_slice(self.neuron_combinators(i));
+            if let Some(inputs) = neuron.inputs.as_mut() {
+                inputs.copy_from_slice(self.neuron_inputs(i));
+            }
+            neuron.locked = self.locked[i];
+            neuron.reduction = self.reductions[i];
+            neuron.result_combinator = self.result_combinators[i];
+        }
+    }
+}
+
+/// Allows flat networks to be created from nested networks.
+impl From<&Network> for FlatNetwork {
+    fn from(network: &Network) -> Self {
+        FlatNetwork::new(network)
+    }
+}
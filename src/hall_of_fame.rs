@@ -0,0 +1,115 @@
+//! Contains the `HallOfFame` type, a bounded archive of the best distinct
+//! networks encountered over a training run, so that good intermediate
+//! solutions are not lost when later search drifts away from them.
+//!
+//! Networks are told apart by their structural fingerprint (see
+//! `population::fingerprint`), so that copies of a network carried over
+//! between generations by elitism occupy a single entry. Evolution maintains
+//! a hall of fame when `EvolveOptions::hall_of_fame` is set, and it may be
+//! saved alongside the final model with `HallOfFame::save`.
+
+use crate::network::Network;
+use crate::population::fingerprint;
+#[cfg(feature = "serde")]
+use std::io;
+#[cfg(feature = "serde")]
+use std::path::{Path, PathBuf};
+
+/// Represents a single network of a hall of fame.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Entry {
+    /// The error of the network when it was admitted.
+    pub error: usize,
+
+    /// The structural fingerprint of the network.
+    pub fingerprint: u64,
+
+    /// The network itself.
+    pub network: Network
+}
+
+/// Represents a bounded archive of the best distinct networks offered to it.
+#[derive(Clone,Debug,PartialEq)]
+pub struct HallOfFame {
+    /// The maximum number of networks kept.
+    pub capacity: usize,
+
+    /// The networks kept, ordered by their error, with ties going to the
+    /// network admitted first.
+    entries: Vec<Entry>
+}
+
+/// Implements custom methods for halls of fame.
+impl HallOfFame {
+    /// Returns the entry of the network with the lowest error, if any.
+    pub fn best(&self) -> Option<&Entry> {
+        self.entries.first()
+    }
+
+    /// Returns the entries of this hall of fame, from best to worst.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Determines whether this hall of fame holds no networks.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of networks held by this hall of fame.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Creates a new empty hall of fame keeping up to the specified number of
+    /// networks.
+    pub fn new(capacity: usize) -> Self {
+        HallOfFame {
+            capacity,
+            entries: Vec::with_capacity(capacity)
+        }
+    }
+
+    /// Offers the specified network of the specified error to this hall of
+    /// fame, which admits a copy of it if no network of the same fingerprint
+    /// is held and it is either not full or the network has a strictly lower
+    /// error than its worst network, which is then dropped. Returns whether
+    /// the network was admitted.
+    pub fn offer(&mut self, network: &Network, error: usize) -> bool {
+        if self.entries.len() >= self.capacity && self.entries.last().is_none_or(|e| e.error <= error) {
+            return false;
+        }
+        let fingerprint = fingerprint(network);
+        if self.entries.iter().any(|e| e.fingerprint == fingerprint) {
+            return false;
+        }
+        let position = self.entries.iter().position(|e| e.error > error).unwrap_or(self.entries.len());
+        self.entries.insert(position, Entry {
+            error,
+            fingerprint,
+            network: network.clone()
+        });
+        self.entries.truncate(self.capacity);
+        true
+    }
+
+    /// Saves the networks of this hall of fame alongside the model saved at
+    /// the specified path, in the format given by its extension (see
+    /// `Network::save`), inserting `.hof-1`, `.hof-2`, ... from best to worst
+    /// before its extension (such as `model.hof-1.json` for `model.json`).
+    /// Returns the paths written.
+    #[cfg(feature = "serde")]
+    pub fn save<P: AsRef<Path>>(&self, model_path: P) -> io::Result<Vec<PathBuf>> {
+        let path = model_path.as_ref();
+        let stem = path.file_stem().map_or_else(Default::default, |s| s.to_string_lossy().into_owned());
+        self.entries.iter().enumerate().map(|(i, e)| {
+            let name = match path.extension() {
+                Some(ext) => format!("{}.hof-{}.{}", stem, i + 1, ext.to_string_lossy()),
+                None => format!("{}.hof-{}", stem, i + 1)
+            };
+            let p = path.with_file_name(name);
+            e.network.save(&p)?;
+            Ok(p)
+        }).collect()
+    }
+}
@@ -0,0 +1,114 @@
+//! Contains metrics for evolving `bnl` networks as hash-like boolean mixing
+//! functions.
+
+use crate::network::Network;
+use std::collections::HashSet;
+
+/// Represents the measured hash-like qualities of a network.
+#[derive(Clone,Debug,PartialEq)]
+pub struct HashQuality {
+    /// The mean fraction of output bits which flip when a single input bit is
+    /// flipped. An ideal mixing function has an avalanche of `0.5`.
+    pub avalanche: f64,
+
+    /// The mean absolute correlation between the flips of each pair of output
+    /// bits when single input bits are flipped. An ideal mixing function has a
+    /// bit independence of `0.0`.
+    pub bit_independence: f64,
+
+    /// The fraction of distinct inputs whose outputs collide with the output
+    /// of some other input.
+    pub collision_rate: f64
+}
+
+/// Implements custom methods for hash quality measurements.
+impl HashQuality {
+    /// Combines the individual measurements into a single fitness score in
+    /// `[0, 1]`, where `1` corresponds to an ideal mixing function.
+    pub fn score(&self) -> f64 {
+        let avalanche = 1.0 - 2.0 * (self.avalanche - 0.5).abs();
+        let independence = 1.0 - self.bit_independence;
+        let collisions = 1.0 - self.collision_rate;
+        (avalanche + independence + collisions) / 3.0
+    }
+}
+
+/// Computes the mean fraction of output bits which flip when each input bit
+/// of each of the specified sample inputs is flipped.
+pub fn avalanche(network: &Network, inputs: &[Vec<bool>]) -> f64 {
+    let flips = flip_matrix(network, inputs);
+    let total: usize = flips.iter().map(|f| f.iter().filter(|&&x| x).count()).sum();
+    let bits: usize = flips.iter().map(|f| f.len()).sum();
+    if bits == 0 {
+        0.0
+    } else {
+        total as f64 / bits as f64
+    }
+}
+
+/// Computes the mean absolute correlation between flips of each pair of output
+/// bits when each input bit of each of the specified sample inputs is flipped.
+///
+/// Pairs involving an output bit which always (or never) flips are treated as
+/// uncorrelated.
+pub fn bit_independence(network: &Network, inputs: &[Vec<bool>]) -> f64 {
+    let flips = flip_matrix(network, inputs);
+    let width = network.output_width();
+    if flips.is_empty() || width < 2 {
+        return 0.0;
+    }
+    let n = flips.len() as f64;
+    let mean: Vec<f64> = (0..width)
+        .map(|j| flips.iter().filter(|f| f[j]).count() as f64 / n)
+        .collect();
+    let mut total = 0.0;
+    let mut pairs = 0;
+    for j in 0..width {
+        for k in (j + 1)..width {
+            let both = flips.iter().filter(|f| f[j] && f[k]).count() as f64 / n;
+            let variance = mean[j] * (1.0 - mean[j]) * mean[k] * (1.0 - mean[k]);
+            if variance > 0.0 {
+                total += ((both - mean[j] * mean[k]) / variance.sqrt()).abs();
+            }
+            pairs += 1;
+        }
+    }
+    total / pairs as f64
+}
+
+/// Computes the fraction of the distinct specified sample inputs whose
+/// outputs collide with the output of another distinct input.
+pub fn collision_rate(network: &Network, inputs: &[Vec<bool>]) -> f64 {
+    let distinct: HashSet<&Vec<bool>> = inputs.iter().collect();
+    if distinct.is_empty() {
+        return 0.0;
+    }
+    let outputs: HashSet<Vec<bool>> = distinct.iter().map(|i| network.apply(i)).collect();
+    1.0 - outputs.len() as f64 / distinct.len() as f64
+}
+
+/// Measures all of the hash-like qualities of the specified network over the
+/// specified sample inputs.
+pub fn measure(network: &Network, inputs: &[Vec<bool>]) -> HashQuality {
+    HashQuality {
+        avalanche: avalanche(network, inputs),
+        bit_independence: bit_independence(network, inputs),
+        collision_rate: collision_rate(network, inputs)
+    }
+}
+
+/// Computes, for every sample input and every input bit, which output bits
+/// flip when that input bit is flipped.
+fn flip_matrix(network: &Network, inputs: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let mut res: Vec<Vec<bool>> = Vec::new();
+    for input in inputs {
+        let output = network.apply(input);
+        for bit in 0..input.len() {
+            let mut flipped = input.clone();
+            flipped[bit] = !flipped[bit];
+            let other = network.apply(&flipped);
+            res.push(output.iter().zip(other.iter()).map(|(a, b)| a != b).collect());
+        }
+    }
+    res
+}
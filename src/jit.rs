@@ -0,0 +1,199 @@
+//! Contains `Network::compile`, built with the `jit` feature, which compiles a
+//! `bnl` network to native machine code at runtime via Cranelift, for
+//! inference without the overhead of interpreting its combinators.
+//!
+//! The compiled function evaluates the network bit-parallel over 64 samples
+//! at once, as `Network::apply_batch` does: it reads one `u64` word per input
+//! signal, in which bit `j` is the signal of sample `j`, and writes one word
+//! per output. Every combinator becomes one or two bitwise instructions, and
+//! the signals of the whole network are kept in registers where possible.
+
+use crate::batch::BitBatch;
+use crate::combinator::Combinator;
+use crate::network::Network;
+use cranelift_codegen::ir::{types, AbiParam, InstBuilder, MemFlagsData, Value};
+use cranelift_codegen::settings::{self, Configurable};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{default_libcall_names, Linkage, Module};
+
+/// Represents the signature of compiled networks, reading the words of their
+/// inputs from the first pointer and writing those of their outputs to the
+/// second.
+type Kernel = extern "C" fn(*const u64, *mut u64);
+
+/// Represents a network compiled to native code.
+pub struct CompiledNetwork {
+    /// The compiled function.
+    function: Kernel,
+
+    /// The width of the input vectors accepted by the network.
+    input_width: usize,
+
+    /// The module owning the memory of the compiled function, which is freed
+    /// when this network is dropped.
+    module: Option<JITModule>,
+
+    /// The number of outputs of the network.
+    output_width: usize
+}
+
+/// Implements custom methods for compiled networks.
+impl CompiledNetwork {
+    /// "Applies" this network to a given input vector of boolean values.
+    ///
+    /// Panics if the input is not as wide as the inputs of the network.
+    pub fn apply(&self, input: &[bool]) -> Vec<bool> {
+        if input.len() != self.input_width {
+            panic!("Cannot apply a compiled network of {} inputs to {} values!", self.input_width, input.len());
+        }
+        let words: Vec<u64> = input.iter().map(|&b| b as u64).collect();
+        self.apply_word(&words).iter().map(|&w| w & 1 == 1).collect()
+    }
+
+    /// "Applies" this network to every sample of the specified batch at once,
+    /// producing the batch of their outputs.
+    ///
+    /// Panics if the samples of the batch are not as wide as the inputs of the
+    /// network.
+    pub fn apply_batch(&self, input: &BitBatch) -> BitBatch {
+        if input.width() != self.input_width {
+            panic!("Cannot apply a compiled network of {} inputs to samples of width {}!", self.input_width, input.width());
+        }
+        let words = input.len.div_ceil(64);
+        let mut signals: Vec<Vec<u64>> = vec![Vec::with_capacity(words); self.output_width];
+        let mut word: Vec<u64> = vec![0; self.input_width];
+        for w in 0..words {
+            word.iter_mut().zip(input.signals.iter()).for_each(|(x, s)| *x = s[w]);
+            for (s, o) in signals.iter_mut().zip(self.apply_word(&word)) {
+                s.push(o);
+            }
+        }
+        BitBatch {
+            len: input.len,
+            signals
+        }
+    }
+
+    /// "Applies" this network to 64 samples at once, given as the word of
+    /// each of their input signals, returning the word of each of their
+    /// outputs.
+    ///
+    /// Panics if there are not as many words as inputs of the network.
+    pub fn apply_word(&self, input: &[u64]) -> Vec<u64> {
+        if input.len() != self.input_width {
+            panic!("Cannot apply a compiled network of {} inputs to {} words!", self.input_width, input.len());
+        }
+        let mut output = vec![0u64; self.output_width];
+        (self.function)(input.as_ptr(), output.as_mut_ptr());
+        output
+    }
+
+    /// Returns the width of the input vectors accepted by this network.
+    pub fn input_width(&self) -> usize {
+        self.input_width
+    }
+
+    /// Returns the number of outputs of this network.
+    pub fn output_width(&self) -> usize {
+        self.output_width
+    }
+}
+
+/// Frees the native code of compiled networks.
+impl Drop for CompiledNetwork {
+    fn drop(&mut self) {
+        if let Some(module) = self.module.take() {
+            // SAFETY: the only pointer into the module is `function`, which is
+            // dropped along with this network.
+            unsafe { module.free_memory() };
+        }
+    }
+}
+
+/// Implements compilation on `bnl` networks.
+impl Network {
+    /// Compiles this network to native code for the host machine, returning
+    /// a description of the problem if it cannot be compiled.
+    pub fn compile(&self) -> Result<CompiledNetwork, String> {
+        self.validate()?;
+        let mut flags = settings::builder();
+        flags.set("opt_level", "speed").map_err(|e| e.to_string())?;
+        let isa = cranelift_native::builder()
+            .map_err(String::from)?
+            .finish(settings::Flags::new(flags))
+            .map_err(|e| e.to_string())?;
+        let mut module = JITModule::new(JITBuilder::with_isa(isa, default_libcall_names()));
+        let pointer = module.target_config().pointer_type();
+        let mut ctx = module.make_context();
+        ctx.func.signature.params.push(AbiParam::new(pointer));
+        ctx.func.signature.params.push(AbiParam::new(pointer));
+        let mut builder_ctx = FunctionBuilderContext::new();
+        let mut b = FunctionBuilder::new(&mut ctx.func, &mut builder_ctx);
+        let block = b.create_block();
+        b.append_block_params_for_function_params(block);
+        b.switch_to_block(block);
+        b.seal_block(block);
+        let (input, output) = (b.block_params(block)[0], b.block_params(block)[1]);
+        let mut signals: Vec<Value> = (0..self.input_width())
+            .map(|i| b.ins().load(types::I64, MemFlagsData::trusted(), input, (8 * i) as i32))
+            .collect();
+        for layer in &self.layers {
+            signals = layer.neurons.iter().map(|n| {
+                let values: Vec<Value> = (0..n.fan_in()).map(|k| signals[n.input_index(k)]).collect();
+                let acc = n.reduction.reduce(values, &n.input_combinators, |l, r, c| emit(&mut b, l, r, c));
+                let bias = b.ins().iconst(types::I64, if n.bias { -1 } else { 0 });
+                emit(&mut b, acc, bias, n.result_combinator)
+            }).collect();
+        }
+        for (i, &s) in signals.iter().enumerate() {
+            b.ins().store(MemFlagsData::trusted(), s, output, (8 * i) as i32);
+        }
+        b.ins().return_(&[]);
+        b.finalize(module.target_config());
+        let id = module.declare_function("network", Linkage::Export, &ctx.func.signature).map_err(|e| e.to_string())?;
+        module.define_function(id, &mut ctx).map_err(|e| e.to_string())?;
+        module.clear_context(&mut ctx);
+        module.finalize_definitions().map_err(|e| e.to_string())?;
+        let code = module.get_finalized_function(id);
+        // SAFETY: the function was compiled with the signature of `Kernel`,
+        // and reads and writes exactly as many words as `apply_word` provides.
+        let function = unsafe { std::mem::transmute::<*const u8, Kernel>(code) };
+        Ok(CompiledNetwork {
+            function,
+            input_width: self.input_width(),
+            module: Some(module),
+            output_width: self.output_width()
+        })
+    }
+}
+
+/// Emits the instructions computing the specified combinator on each of the
+/// pairs of bits of two words.
+fn emit(b: &mut FunctionBuilder, left: Value, right: Value, combinator: Combinator) -> Value {
+    let ins = b.ins();
+    match combinator {
+        Combinator::False       => ins.iconst(types::I64, 0),
+        Combinator::And         => ins.band(left, right),
+        Combinator::AndNotRight => ins.band_not(left, right),
+        Combinator::Left        => left,
+        Combinator::AndNotLeft  => ins.band_not(right, left),
+        Combinator::Right       => right,
+        Combinator::Xor         => ins.bxor(left, right),
+        Combinator::Or          => ins.bor(left, right),
+        Combinator::Nor         => {
+            let or = ins.bor(left, right);
+            b.ins().bnot(or)
+        },
+        Combinator::Xnor        => ins.bxor_not(left, right),
+        Combinator::NotRight    => ins.bnot(right),
+        Combinator::ImpliedBy   => ins.bor_not(left, right),
+        Combinator::NotLeft     => ins.bnot(left),
+        Combinator::Implies     => ins.bor_not(right, left),
+        Combinator::Nand        => {
+            let and = ins.band(left, right);
+            b.ins().bnot(and)
+        },
+        Combinator::True        => ins.iconst(types::I64, -1)
+    }
+}
@@ -0,0 +1,153 @@
+//! A machine learning thingy.
+//!
+//! `bnl` builds and trains networks made entirely of boolean neurons. The
+//! core types live in the `network` module and are re-exported here, while
+//! the remaining modules provide training, analysis and export routines
+//! built on top of them.
+//!
+//! With the default `std` feature disabled, only the core types and the
+//! `apply` path are built, under `#![no_std]` with `alloc`, so that trained
+//! networks can run on embedded targets. This build is checked both on the
+//! host and for a bare-metal target:
+//!
+//! ```text
+//! cargo check --lib --no-default-features
+//! cargo check --lib --no-default-features --target thumbv7m-none-eabi
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(all(feature = "strict-determinism", feature = "parallel"))]
+compile_error!("The `strict-determinism` feature cannot be combined with `parallel`, whose work is scheduled across threads!");
+
+#[cfg(all(feature = "strict-determinism", feature = "tensorboard"))]
+compile_error!("The `strict-determinism` feature cannot be combined with `tensorboard`, whose event files record wall-clock times!");
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod adapt;
+#[cfg(feature = "std")]
+pub mod analysis;
+#[cfg(feature = "std")]
+pub mod arena;
+#[cfg(feature = "std")]
+pub mod balance;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "std")]
+pub mod benchmark;
+pub mod bits;
+#[cfg(feature = "std")]
+pub mod builder;
+#[cfg(feature = "std")]
+pub mod ca;
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "serde")]
+pub mod checkpoint;
+#[cfg(feature = "std")]
+pub mod cnf;
+#[cfg(feature = "std")]
+pub mod coevolution;
+pub mod combinator;
+#[cfg(feature = "std")]
+pub mod conformance;
+#[cfg(feature = "std")]
+pub mod crossover;
+#[cfg(feature = "std")]
+pub mod dag;
+#[cfg(feature = "std")]
+pub mod data;
+#[cfg(feature = "std")]
+pub mod dataset;
+#[cfg(feature = "std")]
+pub mod diff;
+#[cfg(feature = "std")]
+pub mod elites;
+#[cfg(feature = "std")]
+pub mod encode;
+#[cfg(feature = "std")]
+pub mod ensemble;
+pub mod error;
+#[cfg(feature = "sat")]
+pub mod exact;
+#[cfg(feature = "std")]
+pub mod explain;
+#[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "std")]
+pub mod expr;
+#[cfg(feature = "std")]
+pub mod flat;
+#[cfg(feature = "std")]
+pub mod hall_of_fame;
+#[cfg(feature = "std")]
+pub mod hashing;
+#[cfg(feature = "jit")]
+pub mod jit;
+#[cfg(feature = "std")]
+pub mod linear;
+#[cfg(feature = "std")]
+pub mod lut;
+#[cfg(feature = "std")]
+pub mod metrics;
+#[cfg(feature = "std")]
+pub mod mutate;
+pub mod network;
+#[cfg(feature = "std")]
+pub mod observe;
+#[cfg(feature = "std")]
+pub mod online;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+#[cfg(feature = "serde")]
+pub mod persist;
+#[cfg(feature = "std")]
+pub mod population;
+#[cfg(feature = "std")]
+pub mod probe;
+#[cfg(feature = "profiling")]
+pub mod profile;
+#[cfg(feature = "std")]
+pub mod proto;
+#[cfg(feature = "std")]
+pub mod prune;
+#[cfg(feature = "std")]
+pub mod random;
+#[cfg(feature = "std")]
+pub mod registry;
+#[cfg(feature = "std")]
+pub mod replay;
+#[cfg(feature = "std")]
+pub mod report;
+#[cfg(feature = "std")]
+pub mod repro;
+#[cfg(feature = "std")]
+pub mod simplify;
+#[cfg(feature = "std")]
+pub mod search;
+#[cfg(feature = "std")]
+pub mod selftest;
+#[cfg(feature = "std")]
+pub mod stateful;
+#[cfg(feature = "std")]
+pub mod surrogate;
+#[cfg(feature = "tensorboard")]
+pub mod tensorboard;
+#[cfg(feature = "std")]
+pub mod trace;
+#[cfg(feature = "std")]
+pub mod train;
+#[cfg(feature = "std")]
+pub mod truth;
+#[cfg(feature = "std")]
+pub mod uncertainty;
+#[cfg(feature = "std")]
+pub mod verify;
+#[cfg(feature = "std")]
+pub mod word;
+
+pub use crate::combinator::Combinator;
+pub use crate::error::Error;
+pub use crate::network::{compute_boolean, reads_left, reads_right, zip_combinator, InitProfile, Layer, Network, Neuron, Reduction};
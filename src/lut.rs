@@ -0,0 +1,147 @@
+//! Contains the `LutNeuron` type, a neuron storing the full truth table of a
+//! function of up to `MAX_LUT_INPUTS` selected signals of its input.
+//!
+//! Unlike a `Neuron`, whose input combinator chain folds pairwise combinators
+//! in a fixed order and so cannot express every function of its inputs, a
+//! lookup-table (LUT) neuron can compute any function of the signals it is
+//! wired to, as in the LUTs of an FPGA. Bit `x` of its table is its output
+//! when the signal at position `i` of its wiring equals bit `i` of `x`.
+
+use crate::network::Neuron;
+use rand::Rng;
+
+/// The largest number of signals a LUT neuron may be wired to, so that its
+/// truth table fits in a `u64`.
+pub const MAX_LUT_INPUTS: usize = 6;
+
+/// Represents a neuron computing an arbitrary function of a few selected
+/// signals of its input, given by its truth table.
+#[derive(Clone,Debug,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LutNeuron {
+    /// The width of the input read by this neuron.
+    pub arity: usize,
+
+    /// The indices of the signals of the input this neuron is wired to, at
+    /// most `MAX_LUT_INPUTS` of them.
+    pub inputs: Vec<usize>,
+
+    /// The truth table of this neuron, of which only the lowest
+    /// `2^inputs.len()` bits are used.
+    pub table: u64
+}
+
+/// Implements custom methods for LUT neurons.
+impl LutNeuron {
+    /// "Applies" this neuron to a given input vector of boolean values.
+    pub fn apply(&self, input: &[bool]) -> bool {
+        let index = self.inputs.iter()
+            .enumerate()
+            .fold(0, |acc, (i, &s)| acc | ((input[s] as usize) << i));
+        (self.table >> index) & 1 == 1
+    }
+
+    /// "Applies" this neuron to 64 inputs at once, given as the packed words
+    /// of each of their signals (see the `batch` module).
+    pub fn apply_word(&self, signals: &[u64]) -> u64 {
+        (0..self.table_len()).filter(|&x| (self.table >> x) & 1 == 1)
+            .fold(0, |acc, x| {
+                acc | self.inputs.iter().enumerate().fold(!0, |term, (i, &s)| {
+                    term & if (x >> i) & 1 == 1 { signals[s] } else { !signals[s] }
+                })
+            })
+    }
+
+    /// Creates a LUT neuron computing the same function as the specified
+    /// neuron, wired to the signals it structurally depends on (see
+    /// `Neuron::support`), or `None` if it depends on more than
+    /// `MAX_LUT_INPUTS` of them.
+    pub fn from_neuron(neuron: &Neuron) -> Option<Self> {
+        let inputs = neuron.support();
+        if inputs.len() > MAX_LUT_INPUTS {
+            return None;
+        }
+        let mut input = vec![false; neuron.arity];
+        let table = (0..(1usize << inputs.len())).fold(0u64, |acc, x| {
+            inputs.iter().enumerate().for_each(|(i, &s)| input[s] = (x >> i) & 1 == 1);
+            acc | ((neuron.apply(&input) as u64) << x)
+        });
+        Some(LutNeuron {
+            arity: neuron.arity,
+            inputs,
+            table
+        })
+    }
+
+    /// Randomly changes this neuron by flipping a single bit of its truth
+    /// table or rewiring a single one of its inputs, with equal probability.
+    pub fn mutate(&mut self) {
+        self.mutate_with_rng(&mut crate::random::rng())
+    }
+
+    /// Randomly changes this neuron as with `LutNeuron::mutate`, drawing from
+    /// the specified generator.
+    pub fn mutate_with_rng<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        if self.inputs.is_empty() || rng.gen() {
+            self.table ^= 1 << rng.gen_range(0, self.table_len());
+        } else {
+            let i = rng.gen_range(0, self.inputs.len());
+            self.inputs[i] = rng.gen_range(0, self.arity);
+        }
+    }
+
+    /// Creates a new LUT neuron with a random truth table, reading an input of
+    /// the specified width and wired to the specified number of its signals
+    /// chosen at random (in random order).
+    ///
+    /// Panics if the number of signals exceeds `MAX_LUT_INPUTS` or the input
+    /// width.
+    pub fn new(k: usize, prev_width: usize) -> Self {
+        LutNeuron::new_with_rng(k, prev_width, &mut crate::random::rng())
+    }
+
+    /// Creates a new random LUT neuron as with `LutNeuron::new`, drawing its
+    /// wiring and truth table from the specified generator.
+    pub fn new_with_rng<R: Rng + ?Sized>(k: usize, prev_width: usize, rng: &mut R) -> Self {
+        if k > MAX_LUT_INPUTS {
+            panic!("Cannot wire a LUT neuron to more than {} inputs!", MAX_LUT_INPUTS);
+        }
+        if k > prev_width {
+            panic!("Cannot wire a neuron to {} of only {} inputs!", k, prev_width);
+        }
+        let mut res = LutNeuron {
+            arity: prev_width,
+            inputs: rand::seq::index::sample(rng, prev_width, k).into_vec(),
+            table: 0
+        };
+        res.table = rng.gen::<u64>() & res.table_mask();
+        res
+    }
+
+    /// Returns the number of rows of the truth table of this neuron.
+    fn table_len(&self) -> usize {
+        1 << self.inputs.len()
+    }
+
+    /// Returns the mask selecting the used bits of the truth table of this
+    /// neuron.
+    fn table_mask(&self) -> u64 {
+        if self.inputs.len() == MAX_LUT_INPUTS { !0 } else { (1 << self.table_len()) - 1 }
+    }
+
+    /// Checks that this neuron is wired to at most `MAX_LUT_INPUTS` signals
+    /// which exist in its input and that its truth table has no bits set
+    /// beyond its rows, returning a description of the problem if not.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.inputs.len() > MAX_LUT_INPUTS {
+            return Err(format!("neuron is wired to {} inputs (at most {} allowed)", self.inputs.len(), MAX_LUT_INPUTS));
+        }
+        if let Some(i) = self.inputs.iter().find(|&&i| i >= self.arity) {
+            return Err(format!("neuron is wired to input {} of an input of width {}", i, self.arity));
+        }
+        if self.table & !self.table_mask() != 0 {
+            return Err(format!("neuron has truth table bits set beyond its {} rows", self.table_len()));
+        }
+        Ok(())
+    }
+}
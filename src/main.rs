@@ -1,11 +1,1399 @@
-//! A machine learning thingy.
+//! The `bnl` command-line interface, built with the `cli` feature.
+//!
+//! Usage:
+//!
+//! ```text
+//! bnl train --data DATA --layers 8,8,4 --out MODEL [--iterations N] [--seed S] [--fitness NAME] [--balanced-batch N] [--dataset-cache DIR] [--record FILE | --replay FILE] [--watch] [--json] [--profile]
+//! bnl apply --model MODEL --input 101101 [--hex-width W]
+//! bnl benchmark-suite [--task NAME ...]
+//! bnl compare --before MODEL --after MODEL [--json]
+//! bnl data check DATA [--outputs N]
+//! bnl dot --model MODEL [--before MODEL] [--out FILE]
+//! bnl eval --model MODEL --data DATA [--data DATA ...] [--dataset-cache DIR] [--json]
+//! bnl export --model MODEL --out FILE [--name NAME] [--cells LIBRARY] [--vectors FILE] [--testbench FILE] [--samples N] [--seed S]
+//! bnl inspect --model MODEL [--neurons] [--json]
+//! bnl infer --model MODEL --data DATA --out DIR [--shard-size N] [--hex-width W]
+//! bnl repro --golden FILE [--write]
+//! bnl selftest
+//! bnl table --model MODEL --out FILE [--max-bits B]
+//! bnl trace --model MODEL --data DATA --layer L --out FILE
+//! ```
+//!
+//! `train` loads `DATA` (as CSV whose last columns are the outputs if its
+//! extension is `.csv`, and in the plain `0`/`1` format otherwise; see the
+//! `dataset` module), trains a new network with the given layer lengths on it
+//! by hill climbing and saves it to `MODEL` (see the `persist` module),
+//! minimizing the built-in fitness function named by `--fitness` if given (see
+//! the `registry` module) and the number of wrong output bits otherwise,
+//! comparing candidates on class-balanced mini-batches of `--balanced-batch`
+//! samples if given (see `TrainOptions::balanced_batch`). With `--watch`, it
+//! then polls `DATA` for changes (every `--poll-interval` milliseconds) and
+//! retrains the latest network on each new version of it, saving the models
+//! to `MODEL` with a version number inserted before its extension
+//! (`model.1.json`, `model.2.json`, ...). A round that fails (such
+//! as on a half-written dataset) is reported and skipped, keeping the latest
+//! network and its version number, and a dataset that briefly vanishes counts
+//! as unchanged. With `--profile` (which requires the `profiling` feature),
+//! the time each round spent loading the dataset, evaluating and mutating
+//! candidates and serializing the network is printed on standard error after
+//! it (see the `profile` module). With `--record`, every value drawn from the
+//! random number generator during training is saved to a log, which `--replay`
+//! feeds back to a later run (such as on another platform) while comparing it
+//! with the draws of the generator of `--seed`, failing with the first draw on
+//! which they differ (see the `replay` module).
+//!
+//! `apply` prints the output of the network saved at `MODEL` for a single
+//! input, `inspect` prints its architecture, and `dot` renders it as a
+//! Graphviz graph (see `Network::to_dot`), printed unless `--out` is given,
+//! highlighting how it differs from the network saved at `--before`, if given.
+//!
+//! `benchmark-suite` evolves networks for the standard battery of boolean
+//! tasks (parity, multiplexers, adders and majority; see the `benchmark`
+//! module) with fixed budgets and seeds, or for only the tasks named by
+//! `--task` (such as `parity-8` or `mux-6`), and prints a report of the
+//! errors reached on each task and the overall score, which is comparable
+//! across versions of the crate.
+//!
+//! `compare` prints the structural differences between the networks saved at
+//! `--before` and `--after`, one per line, and the fraction of output bits on
+//! which they agree (see `Network::diff`), over inputs sampled with a fixed
+//! seed so that repeated comparisons agree.
+//!
+//! `data check` loads `DATA` as `train` does, with its last `N` columns (one by
+//! default) as the outputs if it is CSV, and prints a sanity check of it (see
+//! `data::check`): its widths, the balance of its outputs and classes, its
+//! duplicate and contradictory samples, its constant columns and the ceiling
+//! its contradictions impose on the accuracy of any network trained on it.
+//! Rows of inconsistent widths are reported as errors naming the first of them.
+//!
+//! `eval` evaluates the network saved at `MODEL` on each of the given datasets
+//! (loaded as `train` does, with as many output columns as the network has
+//! outputs) and prints a matrix of its metrics (see `metrics::evaluate`), with
+//! one row per dataset. Precision, recall and F1 count every output bit
+//! together.
+//!
+//! `export` writes the network saved at `MODEL` as a Verilog module, a BLIF
+//! model or a standalone Rust source file (see the `export` module), or in the
+//! portable Protocol Buffers interchange format (see the `proto` module), as
+//! told by the extension of `FILE` (`.v`, `.blif`, `.rs` or `.pb`), named
+//! `NAME` (`bnl` by default). Every subcommand reading `MODEL` also loads
+//! networks in the interchange format from `.pb` files. With `--cells`,
+//! Verilog and BLIF netlists are built only from the cells of the named
+//! library, such as `nand-inv` for two-input NAND gates and inverters (see
+//! `export::CellLibrary`). With `--vectors`, it also writes golden test vectors of up to
+//! `N` inputs (256 by default, every input for narrower networks) sampled
+//! with the seed `S` (zero by default) and their outputs as computed by `bnl`
+//! itself, as a dataset in the format given by the extension of the vector
+//! file (see `dataset::Format::from_path`), such as PLA. With `--testbench`,
+//! it writes the same vectors as a self-checking Verilog testbench of the
+//! exported module.
+//!
+//! `infer` streams the rows of `DATA` (one input per line, in any format
+//! accepted by `bits::parse_bits`, such as `1,0,1`, `101` or `t f t`, or as
+//! `W`-bit hexadecimal numbers if `--hex-width` is given) through the network
+//! saved at `MODEL`, writing its outputs as comma-separated `0`/`1` values to
+//! the files `part-00000.csv`, `part-00001.csv`, ... of `DIR`, each holding at
+//! most `N` rows (one million by default; scientific notation such as `1e6` is
+//! accepted). The number of rows scored and the throughput are reported on
+//! standard error.
+//!
+//! `repro` runs a fixed seeded workload of network generation, training,
+//! inference and evolution, and compares the fingerprint of each stage with
+//! those stored in the golden file `FILE` (see the `repro` module), exiting
+//! with an error naming the stages which differ. With `--write`, the
+//! fingerprints are written to `FILE` instead, to produce a new golden file.
+//!
+//! `selftest` checks that every evaluation backend built into `bnl` computes
+//! the reference semantics on known circuits and random networks, and that
+//! training still converges (see the `selftest` module), printing one line
+//! per check and exiting with an error if any failed.
+//!
+//! `table` writes the complete truth table of the network saved at `MODEL` to
+//! `FILE` as packed bits (see `Network::truth_table_packed`): for each output
+//! in turn, `ceil(2^n / 64)` little-endian 64-bit words for a network of `n`
+//! inputs, where bit `x` is the output for the input whose bit `i` is input
+//! `i`. Networks of more than `B` inputs (24 by default) are rejected.
+//!
+//! `trace` runs every sample of `DATA` (loaded as `eval` does) through the
+//! network saved at `MODEL` and writes the activations of its layer `L`
+//! (counting from `0`; see `Network::apply_traced`) to `FILE` as CSV, with one
+//! row of comma-separated `0`/`1` values per sample and one column per neuron,
+//! so that its hidden representations can be analyzed externally.
+//!
+//! The global `--json` flag makes `compare`, `eval`, `inspect` and `train`
+//! print machine-readable JSON to standard output instead of text: for
+//! `compare`, an object of the changes and the similarity; for `eval`, an
+//! array of one object per dataset; for `inspect`, an object describing the
+//! layers (and, with `--neurons`, the parameters of every neuron); and for
+//! `train`, one object per line summarizing each round of training, so that
+//! the rounds of `--watch` can be consumed as they finish.
+//!
+//! While a dataset loads, `train` trains and `eval` works through its
+//! datasets, a progress bar is drawn on standard error and cleared once done,
+//! unless `--json` is given or standard output or standard error is not a
+//! terminal, so that piped and scripted runs see none of it.
+//!
+//! Any model, dataset or output path may be given as `-` to read from standard
+//! input or write to standard output instead, so that subcommands can be
+//! chained in pipelines. Models read from standard input may be JSON or
+//! bincode, and are written to standard output as JSON. Datasets read from
+//! standard input are parsed as CSV if their first line contains a comma, and
+//! in the plain format otherwise. `infer --out -` prints its predictions
+//! rather than sharding them.
 
-pub mod network;
+use bnl::batch::BitBatch;
+use bnl::benchmark::{self, SuiteReport, Task};
+use bnl::bits::{format_bits, parse_bits};
+use bnl::data;
+use bnl::data::cache::DatasetCache;
+use bnl::dataset::{self, Dataset};
+use bnl::export::{self, CellLibrary};
+use bnl::metrics::{accuracy, evaluate};
+use bnl::observe::{Control, GenerationStats};
+use bnl::repro::Fingerprints;
+use bnl::selftest;
+use bnl::train::TrainOptions;
+use bnl::persist::{Format, Metadata};
+use bnl::proto;
+use bnl::random::Xoshiro256;
+use bnl::registry::Registry;
+use bnl::replay::{Recorder, Replayer, RngLog};
+use bnl::Network;
+use clap::{Args, Parser, Subcommand};
+use rand::{RngCore, SeedableRng};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+/// The number of rows evaluated together as a single batch.
+const BATCH_LEN: usize = 4096;
+
+/// The number of characters filled by a complete progress bar.
+const PROGRESS_WIDTH: usize = 30;
+
+/// The path standing for standard input or output.
+const STDIO: &str = "-";
+
+/// Represents the arguments of the `apply` subcommand.
+#[derive(Args)]
+struct ApplyArgs {
+    /// The width of the input, if it is written in hexadecimal.
+    #[arg(long)]
+    hex_width: Option<usize>,
+
+    /// The input bits, such as `101101`, `1,0,1` or `t f t`.
+    #[arg(long)]
+    input: String,
+
+    /// The path of the saved network.
+    #[arg(long)]
+    model: PathBuf
+}
+
+/// Trains and runs boolean networks.
+#[derive(Parser)]
+#[command(name = "bnl")]
+struct Cli {
+    /// The subcommand to run.
+    #[command(subcommand)]
+    command: Command,
+
+    /// Whether to print machine-readable JSON instead of text, for the
+    /// `compare`, `eval`, `inspect` and `train` subcommands.
+    #[arg(long, global = true)]
+    json: bool
+}
+
+/// Represents the subcommands of the command-line interface.
+#[derive(Subcommand)]
+enum Command {
+    /// Prints the output of a saved network for a single input.
+    Apply(ApplyArgs),
+
+    /// Runs a standard battery of boolean tasks with fixed budgets and seeds.
+    BenchmarkSuite(BenchmarkSuiteArgs),
+
+    /// Prints the differences between two saved networks.
+    Compare(CompareArgs),
+
+    /// Inspects datasets.
+    Data(DataArgs),
+
+    /// Renders a saved network as a Graphviz DOT graph.
+    Dot(DotArgs),
+
+    /// Prints the metrics of a saved network on each of several datasets.
+    Eval(EvalArgs),
+
+    /// Exports a saved network as a netlist, with golden test vectors.
+    Export(ExportArgs),
+
+    /// Scores a file of inputs with a saved network, writing sharded
+    /// predictions.
+    Infer(InferArgs),
+
+    /// Prints the architecture of a saved network.
+    Inspect(InspectArgs),
+
+    /// Checks that seeded runs reproduce the fingerprints of a golden file.
+    Repro(ReproArgs),
+
+    /// Checks that this build computes known circuits correctly and trains.
+    Selftest,
+
+    /// Writes the complete truth table of a saved network as packed bits.
+    Table(TableArgs),
+
+    /// Writes the activations of a layer of a saved network across a dataset
+    /// as CSV.
+    Trace(TraceArgs),
+
+    /// Trains a new network on a dataset and saves it.
+    Train(TrainArgs)
+}
+
+/// Represents the arguments of the `benchmark-suite` subcommand.
+#[derive(Args)]
+struct BenchmarkSuiteArgs {
+    /// The names of the tasks to run, such as `parity-8`, instead of every
+    /// task of the standard battery.
+    #[arg(long)]
+    task: Vec<String>
+}
+
+/// Represents the arguments of the `compare` subcommand.
+#[derive(Args)]
+struct CompareArgs {
+    /// The path of the newer network.
+    #[arg(long)]
+    after: PathBuf,
+
+    /// The path of the older network.
+    #[arg(long)]
+    before: PathBuf
+}
+
+/// Represents the differences printed by the `compare` subcommand.
+#[derive(serde::Serialize)]
+struct CompareReport {
+    /// The structural differences between the networks, one per line.
+    changes: Vec<String>,
+
+    /// The fraction of output bits on which the networks agree, or `None` if
+    /// their input widths differ.
+    similarity: Option<f64>
+}
+
+/// Represents the arguments of the `data` subcommand.
+#[derive(Args)]
+struct DataArgs {
+    /// The subcommand to run on the dataset.
+    #[command(subcommand)]
+    command: DataCommand
+}
+
+/// Represents the arguments of the `data check` subcommand.
+#[derive(Args)]
+struct DataCheckArgs {
+    /// The path of the dataset.
+    data: PathBuf,
+
+    /// The number of trailing columns which are outputs, if the dataset is
+    /// CSV.
+    #[arg(long, default_value_t = 1)]
+    outputs: usize
+}
+
+/// Represents the subcommands of the `data` subcommand.
+#[derive(Subcommand)]
+enum DataCommand {
+    /// Reports problems with a dataset before any time is spent training on
+    /// it.
+    Check(DataCheckArgs)
+}
+
+/// Represents the arguments of the `dot` subcommand.
+#[derive(Args)]
+struct DotArgs {
+    /// The path of an earlier version of the network, whose differences from
+    /// it are highlighted.
+    #[arg(long)]
+    before: Option<PathBuf>,
+
+    /// The path of the saved network.
+    #[arg(long)]
+    model: PathBuf,
+
+    /// The path to which the graph is written, instead of standard output.
+    #[arg(long)]
+    out: Option<PathBuf>
+}
+
+/// Represents the arguments of the `eval` subcommand.
+#[derive(Args)]
+struct EvalArgs {
+    /// The paths of the datasets, each of which is a row of the matrix.
+    #[arg(long, required = true)]
+    data: Vec<PathBuf>,
+
+    /// The directory in which parsed datasets are cached between runs (see
+    /// `data::cache`).
+    #[arg(long)]
+    dataset_cache: Option<PathBuf>,
+
+    /// The path of the saved network.
+    #[arg(long)]
+    model: PathBuf
+}
+
+/// Represents a row of the matrix printed by the `eval` subcommand.
+#[derive(serde::Serialize)]
+struct EvalRow {
+    /// The exact-match accuracy.
+    accuracy: f64,
+
+    /// The accuracy of each output bit.
+    bit_accuracy: Vec<f64>,
+
+    /// The path of the dataset.
+    data: String,
+
+    /// The F1 score over every output bit.
+    f1: f64,
+
+    /// The fraction of output bits which are wrong.
+    hamming_loss: f64,
+
+    /// The precision over every output bit.
+    precision: f64,
+
+    /// The recall over every output bit.
+    recall: f64,
+
+    /// The number of samples of the dataset.
+    samples: usize
+}
+
+/// Represents the arguments of the `export` subcommand.
+#[derive(Args)]
+struct ExportArgs {
+    /// The name of the cell library (`nand-inv`, `nor-inv`, `and-or-inv` or
+    /// `any`) which the gates of an exported Verilog or BLIF netlist are
+    /// restricted to (see `export::CellLibrary`).
+    #[arg(long, default_value = "any")]
+    cells: String,
+
+    /// The path of the saved network.
+    #[arg(long)]
+    model: PathBuf,
+
+    /// The name of the exported module or model.
+    #[arg(long, default_value = "bnl")]
+    name: String,
+
+    /// The path to which the network is exported, whose extension (`.v`,
+    /// `.blif`, `.rs` or `.pb`) gives the format.
+    #[arg(long)]
+    out: PathBuf,
+
+    /// The maximum number of test vectors to sample.
+    #[arg(long, default_value_t = 256)]
+    samples: usize,
+
+    /// The seed of the generator the test vectors are sampled with.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// The path to which a self-checking Verilog testbench of the exported
+    /// module is written.
+    #[arg(long)]
+    testbench: Option<PathBuf>,
+
+    /// The path to which the test vectors are written as a dataset.
+    #[arg(long)]
+    vectors: Option<PathBuf>
+}
+
+/// Represents the arguments of the `infer` subcommand.
+#[derive(Args)]
+struct InferArgs {
+    /// The path of the input rows.
+    #[arg(long)]
+    data: PathBuf,
+
+    /// The width of the inputs, if they are written in hexadecimal.
+    #[arg(long)]
+    hex_width: Option<usize>,
+
+    /// The path of the saved network.
+    #[arg(long)]
+    model: PathBuf,
+
+    /// The directory into which prediction shards are written.
+    #[arg(long)]
+    out: PathBuf,
+
+    /// The maximum number of rows per shard.
+    #[arg(long, default_value = "1e6", value_parser = parse_count)]
+    shard_size: usize
+}
+
+/// Represents the arguments of the `inspect` subcommand.
+#[derive(Args)]
+struct InspectArgs {
+    /// The path of the saved network.
+    #[arg(long)]
+    model: PathBuf,
+
+    /// Whether to also print the parameters of every neuron.
+    #[arg(long)]
+    neurons: bool
+}
+
+/// Represents a layer of the architecture printed by the `inspect` subcommand.
+#[derive(serde::Serialize)]
+struct InspectLayer {
+    /// The number of neurons of the layer.
+    len: usize,
+
+    /// The parameters of every neuron of the layer, if requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    neurons: Option<Vec<InspectNeuron>>
+}
+
+/// Represents a neuron of the architecture printed by the `inspect`
+/// subcommand.
+#[derive(serde::Serialize)]
+struct InspectNeuron {
+    /// The bias of the neuron.
+    bias: bool,
+
+    /// The names of the input combinators of the neuron.
+    chain: Vec<&'static str>,
+
+    /// The name of the reduction of the neuron.
+    reduction: &'static str,
+
+    /// The name of the result combinator of the neuron.
+    result: &'static str
+}
+
+/// Represents the architecture printed by the `inspect` subcommand.
+#[derive(serde::Serialize)]
+struct InspectReport {
+    /// The hash of the dataset the network was trained on, in hexadecimal, if
+    /// it was saved with the network.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dataset_hash: Option<String>,
+
+    /// The input width of the network.
+    inputs: usize,
+
+    /// The layers of the network, the last of which is its output layer.
+    layers: Vec<InspectLayer>,
+
+    /// The output width of the network.
+    outputs: usize,
+
+    /// The number of parameters of the network.
+    parameters: usize
+}
+
+/// Represents a progress bar drawn on standard error while a long operation
+/// runs and cleared when dropped, which draws nothing if JSON output was
+/// requested or standard output or standard error is not a terminal.
+struct Progress {
+    /// Whether the bar is drawn.
+    enabled: bool,
+
+    /// The description of the operation.
+    label: String,
+
+    /// The percentage last drawn, if any.
+    percent: Option<usize>,
+
+    /// The number of steps of the operation, or zero if it is unknown.
+    total: usize
+}
+
+/// Implements custom methods for progress bars.
+impl Progress {
+    /// Creates and draws a new progress bar of the specified number of steps
+    /// (or zero if it is unknown), suppressed if JSON output was requested.
+    fn new(label: String, total: usize, json: bool) -> Self {
+        let mut progress = Progress {
+            enabled: !json && io::stdout().is_terminal() && io::stderr().is_terminal(),
+            label,
+            percent: None,
+            total
+        };
+        progress.set(0);
+        progress
+    }
+
+    /// Redraws this bar after the specified number of steps if its percentage
+    /// changed.
+    fn set(&mut self, done: usize) {
+        let percent = (100 * done.min(self.total)).checked_div(self.total).unwrap_or(0);
+        if !self.enabled || self.percent == Some(percent) {
+            return;
+        }
+        self.percent = Some(percent);
+        if self.total == 0 {
+            eprint!("\r{}...", self.label);
+        } else {
+            let filled = percent * PROGRESS_WIDTH / 100;
+            eprint!(
+                "\r{} [{}{}] {:>3}% ({}/{})",
+                self.label, "#".repeat(filled), "-".repeat(PROGRESS_WIDTH - filled), percent, done.min(self.total), self.total
+            );
+        }
+        let _ = io::stderr().flush();
+    }
+}
+
+/// Clears progress bars from the terminal once their operation ends.
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if self.enabled && self.percent.is_some() {
+            eprint!("\r\x1b[2K");
+            let _ = io::stderr().flush();
+        }
+    }
+}
+
+/// Represents the arguments of the `repro` subcommand.
+#[derive(Args)]
+struct ReproArgs {
+    /// The path of the golden file of fingerprints.
+    #[arg(long)]
+    golden: PathBuf,
+
+    /// Whether to write the computed fingerprints to the golden file instead
+    /// of comparing against it.
+    #[arg(long)]
+    write: bool
+}
+
+/// Writes rows of predictions to consecutively numbered shard files.
+struct ShardWriter {
+    /// The directory containing the shards.
+    dir: PathBuf,
+
+    /// The file of the current shard, if opened.
+    file: Option<BufWriter<File>>,
+
+    /// The number of rows written to the current shard.
+    rows: usize,
+
+    /// The number of shards opened so far.
+    shard: usize,
+
+    /// The maximum number of rows per shard.
+    shard_size: usize
+}
+
+/// Implements custom methods for shard writers.
+impl ShardWriter {
+    /// Flushes the current shard.
+    fn finish(&mut self) -> Result<(), String> {
+        if let Some(mut f) = self.file.take() {
+            f.flush().map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Creates a new shard writer into the specified directory.
+    fn new(dir: &Path, shard_size: usize) -> Self {
+        ShardWriter {
+            dir: dir.to_path_buf(),
+            file: None,
+            rows: 0,
+            shard: 0,
+            shard_size
+        }
+    }
+
+    /// Writes the specified rows, starting new shards as needed.
+    fn write(&mut self, rows: &[Vec<bool>]) -> Result<(), String> {
+        for row in rows {
+            if self.file.is_none() || self.rows == self.shard_size {
+                self.finish()?;
+                let path = self.dir.join(format!("part-{:05}.csv", self.shard));
+                let file = File::create(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+                self.file = Some(BufWriter::new(file));
+                self.rows = 0;
+                self.shard += 1;
+            }
+            let f = self.file.as_mut().unwrap();
+            writeln!(f, "{}", format_row(row)).map_err(|e| e.to_string())?;
+            self.rows += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Represents the arguments of the `table` subcommand.
+#[derive(Args)]
+struct TableArgs {
+    /// The largest input width whose truth table may be written.
+    #[arg(long, default_value_t = 24)]
+    max_bits: usize,
+
+    /// The path of the saved network.
+    #[arg(long)]
+    model: PathBuf,
+
+    /// The path to which the truth table is written.
+    #[arg(long)]
+    out: PathBuf
+}
+
+/// Represents the arguments of the `trace` subcommand.
+#[derive(Args)]
+struct TraceArgs {
+    /// The path of the dataset.
+    #[arg(long)]
+    data: PathBuf,
+
+    /// The index of the layer whose activations are written.
+    #[arg(long)]
+    layer: usize,
+
+    /// The path of the saved network.
+    #[arg(long)]
+    model: PathBuf,
+
+    /// The path to which the activations are written.
+    #[arg(long)]
+    out: PathBuf
+}
+
+/// Represents the summary of a round of training printed by the `train`
+/// subcommand.
+#[derive(serde::Serialize)]
+struct TrainReport {
+    /// The exact-match accuracy of the trained network.
+    accuracy: f64,
+
+    /// The path of the dataset.
+    data: String,
+
+    /// The number of candidate mutations evaluated.
+    iterations: usize,
+
+    /// The path to which the trained network was saved.
+    model: String,
+
+    /// The number of output bits of the dataset.
+    output_bits: usize,
+
+    /// The number of samples of the dataset.
+    samples: usize,
+
+    /// The number of output bits which are wrong.
+    wrong_bits: usize
+}
+
+/// Represents the arguments of the `train` subcommand.
+#[derive(Args)]
+struct TrainArgs {
+    /// The number of samples of the class-balanced mini-batch on which each
+    /// candidate mutation is compared, rather than on the whole dataset (see
+    /// `TrainOptions::balanced_batch`).
+    #[arg(long)]
+    balanced_batch: Option<usize>,
+
+    /// The path of the dataset.
+    #[arg(long)]
+    data: PathBuf,
+
+    /// The directory in which parsed datasets are cached between runs (see
+    /// `data::cache`).
+    #[arg(long)]
+    dataset_cache: Option<PathBuf>,
+
+    /// The name of the built-in fitness function to minimize (see
+    /// `registry::Registry::builtin`), rather than the number of wrong output
+    /// bits.
+    #[arg(long)]
+    fitness: Option<String>,
+
+    /// The maximum number of candidate mutations to evaluate.
+    #[arg(long, default_value_t = TrainOptions::default().iterations)]
+    iterations: usize,
+
+    /// The comma-separated lengths of the layers of the network, the last of
+    /// which is its output width.
+    #[arg(long, value_delimiter = ',', required = true)]
+    layers: Vec<usize>,
+
+    /// The path to which the trained network is saved.
+    #[arg(long)]
+    out: PathBuf,
+
+    /// The number of milliseconds between checks of the dataset for changes
+    /// in watch mode.
+    #[arg(long, default_value_t = 1000)]
+    poll_interval: u64,
+
+    /// Whether to print the time spent in each phase of training after each
+    /// round.
+    #[arg(long)]
+    profile: bool,
+
+    /// The path to which every value drawn from the random number generator
+    /// during training is saved (see the `replay` module).
+    #[arg(long)]
+    record: Option<PathBuf>,
+
+    /// The path of the draws recorded by an earlier run with `--record`, which
+    /// training replays while comparing them with the draws of the generator
+    /// of `--seed`, failing at the first on which they differ.
+    #[arg(long)]
+    replay: Option<PathBuf>,
+
+    /// The seed of the random number generator, for training which is
+    /// reproducible across platforms and versions of `rand` (see
+    /// `random::Xoshiro256`).
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Whether to keep watching the dataset after training, retraining the
+    /// latest network whenever it changes and saving each version separately.
+    #[arg(long)]
+    watch: bool
+}
 
 fn main() {
-    let n = network::Network::new(6, vec![6, 7, 6]);
-    let input = vec![true, false, true, true, false, true];
+    let cli = Cli::parse();
+    let json = cli.json;
+    let res = match cli.command {
+        Command::Apply(args) => apply(&args),
+        Command::BenchmarkSuite(args) => benchmark_suite(&args),
+        Command::Compare(args) => compare(&args, json),
+        Command::Data(args) => match args.command {
+            DataCommand::Check(args) => data_check(&args)
+        },
+        Command::Dot(args) => dot(&args),
+        Command::Eval(args) => eval(&args, json),
+        Command::Export(args) => export(&args),
+        Command::Infer(args) => infer(&args),
+        Command::Inspect(args) => inspect(&args, json),
+        Command::Repro(args) => repro(&args),
+        Command::Selftest => selftest(),
+        Command::Table(args) => table(&args),
+        Command::Trace(args) => trace(&args),
+        Command::Train(args) => train(&args, json)
+    };
+    if let Err(e) = res {
+        eprintln!("bnl: {}", e);
+        process::exit(1);
+    }
+}
+
+/// Runs the `apply` subcommand.
+fn apply(args: &ApplyArgs) -> Result<(), String> {
+    let network = load(&args.model)?;
+    let input = parse_bits(&args.input, args.hex_width)?;
+    let output = network.try_apply(&input).map_err(|e| e.to_string())?;
+    println!("{}", format_bits(&output));
+    Ok(())
+}
+
+/// Runs the `benchmark-suite` subcommand.
+fn benchmark_suite(args: &BenchmarkSuiteArgs) -> Result<(), String> {
+    let standard = Task::standard();
+    let tasks: Vec<Task> = if args.task.is_empty() {
+        standard
+    } else {
+        args.task.iter().map(|name| {
+            standard.iter().copied().find(|t| t.to_string() == *name).ok_or_else(|| format!("unknown task {}", name))
+        }).collect::<Result<Vec<Task>, String>>()?
+    };
+    let mut report = SuiteReport {
+        results: Vec::with_capacity(tasks.len())
+    };
+    for (i, &task) in tasks.iter().enumerate() {
+        eprintln!("running {} ({}/{})", task, i + 1, tasks.len());
+        report.results.push(benchmark::run_task(task));
+    }
+    print!("{}", report);
+    Ok(())
+}
+
+/// Checks that at most one of the specified paths reads from standard input.
+fn check_stdin(paths: &[Option<&PathBuf>]) -> Result<(), String> {
+    if paths.iter().flatten().filter(|p| is_stdio(p)).count() > 1 {
+        return Err(String::from("only one argument may read from standard input"));
+    }
+    Ok(())
+}
+
+/// Runs the `compare` subcommand.
+fn compare(args: &CompareArgs, json: bool) -> Result<(), String> {
+    check_stdin(&[Some(&args.before), Some(&args.after)])?;
+    let before = load(&args.before)?;
+    let after = load(&args.after)?;
+    let diff = before.diff_with_rng(&after, &mut Xoshiro256::seed_from_u64(0));
+    if json {
+        let report = CompareReport {
+            changes: diff.changes.iter().map(|c| c.to_string()).collect(),
+            similarity: diff.similarity
+        };
+        println!("{}", serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?);
+        return Ok(());
+    }
+    print!("{}", diff);
+    Ok(())
+}
+
+/// Runs the `data check` subcommand.
+fn data_check(args: &DataCheckArgs) -> Result<(), String> {
+    let dataset = load_dataset(&args.data, args.outputs, false, None)?;
+    print!("{}", data::check(dataset.as_slice()));
+    Ok(())
+}
+
+/// Runs the `dot` subcommand.
+fn dot(args: &DotArgs) -> Result<(), String> {
+    check_stdin(&[Some(&args.model), args.before.as_ref()])?;
+    let network = load(&args.model)?;
+    let graph = match &args.before {
+        Some(path) => network.to_dot_diff(&load(path)?),
+        None => network.to_dot()
+    };
+    match &args.out {
+        Some(path) if !is_stdio(path) => fs::write(path, graph).map_err(|e| format!("{}: {}", path.display(), e)),
+        _ => {
+            print!("{}", graph);
+            Ok(())
+        }
+    }
+}
+
+/// Runs the `eval` subcommand.
+fn eval(args: &EvalArgs, json: bool) -> Result<(), String> {
+    let mut paths: Vec<Option<&PathBuf>> = args.data.iter().map(Some).collect();
+    paths.push(Some(&args.model));
+    check_stdin(&paths)?;
+    let network = load(&args.model)?;
+    let mut rows: Vec<EvalRow> = Vec::with_capacity(args.data.len());
+    let mut progress = Progress::new(String::from("evaluating"), args.data.len(), json);
+    for (i, path) in args.data.iter().enumerate() {
+        let dataset = load_dataset(path, network.output_width(), json, args.dataset_cache.as_deref())?;
+        if !dataset.is_empty() && dataset.input_width() != network.input_width() {
+            return Err(format!(
+                "{}: expected {} inputs like the network but found {}",
+                path.display(), network.input_width(), dataset.input_width()
+            ));
+        }
+        let e = evaluate(&network, dataset.as_slice());
+        rows.push(EvalRow {
+            accuracy: e.accuracy,
+            bit_accuracy: e.bit_accuracy,
+            data: path.display().to_string(),
+            f1: e.confusion.f1(),
+            hamming_loss: e.hamming_loss,
+            precision: e.confusion.precision(),
+            recall: e.confusion.recall(),
+            samples: e.samples
+        });
+        progress.set(i + 1);
+    }
+    drop(progress);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&rows).map_err(|e| e.to_string())?);
+        return Ok(());
+    }
+    let width = rows.iter().map(|r| r.data.len()).max().unwrap_or(0).max(4);
+    println!(
+        "{:<w$} {:>8} {:>8} {:>8} {:>9} {:>8} {:>8}",
+        "data", "samples", "accuracy", "hamming", "precision", "recall", "f1", w = width
+    );
+    for r in &rows {
+        println!(
+            "{:<w$} {:>8} {:>8.4} {:>8.4} {:>9.4} {:>8.4} {:>8.4}",
+            r.data, r.samples, r.accuracy, r.hamming_loss, r.precision, r.recall, r.f1, w = width
+        );
+    }
+    Ok(())
+}
+
+/// Formats the specified row of bits as comma-separated `0`/`1` values.
+fn format_row(bits: &[bool]) -> String {
+    bits.iter().map(|&b| if b { "1" } else { "0" }).collect::<Vec<&str>>().join(",")
+}
+
+/// Runs the `export` subcommand.
+fn export(args: &ExportArgs) -> Result<(), String> {
+    check_stdin(&[Some(&args.model)])?;
+    let library = CellLibrary::ALL.iter().copied().find(|l| l.to_string() == args.cells)
+        .ok_or_else(|| format!("unknown cell library {}", args.cells))?;
+    let (network, metadata) = load_with_metadata(&args.model)?;
+    let extension = args.out.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase());
+    if library != CellLibrary::Any && !matches!(extension.as_deref(), Some("v") | Some("blif")) {
+        return Err(String::from("--cells requires exporting to a Verilog (.v) or BLIF (.blif) file"));
+    }
+    let source = match extension.as_deref() {
+        Some("v") => network.to_verilog_mapped(&args.name, library).into_bytes(),
+        Some("blif") => network.to_blif_mapped(&args.name, library).into_bytes(),
+        Some("pb") => proto::encode(&network, metadata.dataset_hash),
+        Some("rs") => export::rust_source(&network).into_bytes(),
+        _ => return Err(format!("{}: expected a .v, .blif, .pb or .rs file", args.out.display()))
+    };
+    if args.testbench.is_some() && extension.as_deref() != Some("v") {
+        return Err(String::from("--testbench requires exporting to a Verilog (.v) file"));
+    }
+    fs::write(&args.out, source).map_err(|e| format!("{}: {}", args.out.display(), e))?;
+    let vectors = export::test_vectors_with_rng(&network, args.samples, &mut Xoshiro256::seed_from_u64(args.seed));
+    if let Some(path) = &args.vectors {
+        vectors.save(path, dataset::Format::from_path(path)).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    if let Some(path) = &args.testbench {
+        let testbench = network.to_verilog_testbench(&args.name, &vectors);
+        fs::write(path, testbench).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    if args.vectors.is_some() || args.testbench.is_some() {
+        eprintln!("sampled {} test vectors", vectors.len());
+    }
+    Ok(())
+}
+
+/// Runs the `infer` subcommand.
+fn infer(args: &InferArgs) -> Result<(), String> {
+    check_stdin(&[Some(&args.model), Some(&args.data)])?;
+    let network = load(&args.model)?;
+    let reader: Box<dyn BufRead> = if is_stdio(&args.data) {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(&args.data).map_err(|e| format!("{}: {}", args.data.display(), e))?))
+    };
+    let mut shards = if is_stdio(&args.out) {
+        None
+    } else {
+        fs::create_dir_all(&args.out).map_err(|e| format!("{}: {}", args.out.display(), e))?;
+        Some(ShardWriter::new(&args.out, args.shard_size))
+    };
+    let mut stdout = BufWriter::new(io::stdout().lock());
+    let mut write = |rows: Vec<Vec<bool>>| -> Result<(), String> {
+        match shards.as_mut() {
+            Some(s) => s.write(&rows),
+            None => rows.iter().try_for_each(|r| writeln!(stdout, "{}", format_row(r))).map_err(|e| e.to_string())
+        }
+    };
+    let start = Instant::now();
+    let mut batch: Vec<Vec<bool>> = Vec::with_capacity(BATCH_LEN);
+    let mut rows = 0;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("{}: {}", args.data.display(), e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row = parse_bits(&line, args.hex_width).map_err(|e| format!("{}:{}: {}", args.data.display(), i + 1, e))?;
+        if row.len() != network.input_width() {
+            return Err(format!(
+                "{}:{}: expected {} values but found {}",
+                args.data.display(), i + 1, network.input_width(), row.len()
+            ));
+        }
+        batch.push(row);
+        if batch.len() == BATCH_LEN {
+            rows += batch.len();
+            write(network.apply_batch(&BitBatch::from_samples(&batch)).to_samples())?;
+            batch.clear();
+        }
+    }
+    rows += batch.len();
+    write(network.apply_batch(&BitBatch::from_samples(&batch)).to_samples())?;
+    stdout.flush().map_err(|e| e.to_string())?;
+    let seconds = start.elapsed().as_secs_f64();
+    let destination = match shards.as_mut() {
+        Some(s) => {
+            s.finish()?;
+            format!("{} shards", s.shard)
+        },
+        None => String::from("standard output")
+    };
+    eprintln!(
+        "scored {} rows into {} in {:.2}s ({:.0} rows/s)",
+        rows, destination, seconds, rows as f64 / seconds.max(1e-9)
+    );
+    Ok(())
+}
+
+/// Runs the `inspect` subcommand.
+fn inspect(args: &InspectArgs, json: bool) -> Result<(), String> {
+    let (network, metadata) = load_with_metadata(&args.model)?;
+    let parameters: usize = network.layers.iter()
+        .flat_map(|l| l.neurons.iter())
+        .map(|n| n.input_combinators.len() + 2)
+        .sum();
+    if json {
+        let report = InspectReport {
+            dataset_hash: metadata.dataset_hash.map(|h| format!("{:016x}", h)),
+            inputs: network.input_width(),
+            layers: network.layers.iter().map(|l| InspectLayer {
+                len: l.neurons.len(),
+                neurons: if args.neurons {
+                    Some(l.neurons.iter().map(|n| InspectNeuron {
+                        bias: n.bias,
+                        chain: n.input_combinators.iter().map(|c| c.name()).collect(),
+                        reduction: n.reduction.name(),
+                        result: n.result_combinator.name()
+                    }).collect())
+                } else {
+                    None
+                }
+            }).collect(),
+            outputs: network.output_width(),
+            parameters
+        };
+        println!("{}", serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?);
+        return Ok(());
+    }
+    println!("inputs: {}", network.input_width());
+    for (i, layer) in network.layers.iter().enumerate() {
+        let role = if i + 1 == network.layers.len() { " (output)" } else { "" };
+        println!("layer {}: {} neurons{}", i, layer.neurons.len(), role);
+        if args.neurons {
+            for (j, n) in layer.neurons.iter().enumerate() {
+                let chain: Vec<&str> = n.input_combinators.iter().map(|c| c.name()).collect();
+                println!(
+                    "  neuron {}: bias {}, chain [{}] ({}), result {}",
+                    j, n.bias as u8, chain.join(", "), n.reduction.name(), n.result_combinator.name()
+                );
+            }
+        }
+    }
+    println!("outputs: {}", network.output_width());
+    println!("parameters: {}", parameters);
+    if let Some(hash) = metadata.dataset_hash {
+        println!("trained on: dataset {:016x}", hash);
+    }
+    Ok(())
+}
+
+/// Returns the time at which the file at the specified path was last
+/// modified, or `None` if it cannot be read (such as while it is being
+/// replaced).
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Determines whether the specified path stands for standard input or output.
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == STDIO
+}
+
+/// Loads the network saved at the specified path (in the interchange format if
+/// its extension is `.pb`), or read from standard input (as JSON if it starts
+/// with `{`, and as bincode otherwise).
+fn load(path: &Path) -> Result<Network, String> {
+    load_with_metadata(path).map(|(network, _)| network)
+}
+
+/// Loads the dataset at the specified path, or read from standard input, whose
+/// last `output_len` columns are the outputs if it is CSV, through the dataset
+/// cache in the specified directory if any, showing progress unless JSON
+/// output was requested.
+fn load_dataset(path: &Path, output_len: usize, json: bool, cache: Option<&Path>) -> Result<Dataset, String> {
+    let _progress = Progress::new(format!("loading {}", path.display()), 0, json);
+    if is_stdio(path) {
+        let text = String::from_utf8(read_input(path)?).map_err(|e| format!("standard input: {}", e))?;
+        let is_csv = text.lines()
+            .find(|l| !l.trim().is_empty() && !l.starts_with('#'))
+            .is_some_and(|l| l.contains(','));
+        return if is_csv {
+            Dataset::parse_csv(&text, output_len)
+        } else {
+            Dataset::parse_text(&text)
+        }.map_err(|e| format!("standard input: {}", e));
+    }
+    if let Some(dir) = cache {
+        return DatasetCache::open(dir).and_then(|mut c| c.load(path, output_len)).map_err(|e| e.to_string());
+    }
+    if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("csv")) {
+        #[cfg(feature = "parallel")]
+        let dataset = Dataset::from_csv_par(path, output_len);
+        #[cfg(not(feature = "parallel"))]
+        let dataset = Dataset::from_csv(path, output_len);
+        dataset
+    } else {
+        Dataset::from_text(path)
+    }.map_err(|e| e.to_string())
+}
+
+/// Loads the network saved at the specified path as with `load`, along with
+/// the metadata saved with it.
+fn load_with_metadata(path: &Path) -> Result<(Network, Metadata), String> {
+    if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pb")) {
+        let (network, dataset_hash) = proto::load(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        return Ok((network, Metadata { dataset_hash }));
+    }
+    if !is_stdio(path) {
+        return Network::load_with_metadata(path).map_err(|e| format!("{}: {}", path.display(), e));
+    }
+    let bytes = read_input(path)?;
+    let format = match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+        Some(b'{') => Format::Json,
+        _ => Format::Bincode
+    };
+    Network::from_bytes_with_metadata(&bytes, format).map_err(|e| format!("standard input: {}", e))
+}
+
+/// Parses a positive count, accepting scientific notation such as `1e6`.
+fn parse_count(value: &str) -> Result<usize, String> {
+    value.parse::<f64>().ok()
+        .filter(|n| *n >= 1.0 && n.fract() == 0.0)
+        .map(|n| n as usize)
+        .ok_or_else(|| format!("{} is not a positive whole number", value))
+}
+
+/// Reads the whole of the file at the specified path, or of standard input.
+fn read_input(path: &Path) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    if is_stdio(path) {
+        io::stdin().read_to_end(&mut bytes).map_err(|e| format!("standard input: {}", e))?;
+    } else {
+        bytes = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    Ok(bytes)
+}
+
+/// Runs the `repro` subcommand.
+fn repro(args: &ReproArgs) -> Result<(), String> {
+    let fingerprints = Fingerprints::compute();
+    if args.write {
+        fs::write(&args.golden, fingerprints.to_string()).map_err(|e| format!("{}: {}", args.golden.display(), e))?;
+        eprintln!("wrote fingerprints to {}", args.golden.display());
+        return Ok(());
+    }
+    let text = String::from_utf8(read_input(&args.golden)?).map_err(|e| format!("{}: {}", args.golden.display(), e))?;
+    let golden = Fingerprints::parse(&text).map_err(|e| format!("{}: {}", args.golden.display(), e))?;
+    let mismatches = fingerprints.mismatches(&golden);
+    if !mismatches.is_empty() {
+        return Err(format!("fingerprints differ from {} in stages: {}", args.golden.display(), mismatches.join(", ")));
+    }
+    eprintln!("all fingerprints match {}", args.golden.display());
+    Ok(())
+}
+
+/// Saves the specified network along with the specified metadata to the
+/// specified path, or writes them to standard output as JSON.
+fn save(network: &Network, metadata: &Metadata, path: &Path) -> Result<(), String> {
+    if !is_stdio(path) {
+        return network.save_with_metadata(path, metadata).map_err(|e| format!("{}: {}", path.display(), e));
+    }
+    let bytes = network.to_bytes_with_metadata(metadata, Format::Json).map_err(|e| e.to_string())?;
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(&bytes).and_then(|_| writeln!(stdout)).map_err(|e| format!("standard output: {}", e))
+}
+
+/// Runs the `selftest` subcommand.
+fn selftest() -> Result<(), String> {
+    let report = selftest::run();
+    print!("{}", report);
+    if !report.passed() {
+        return Err(String::from("self-test failed"));
+    }
+    Ok(())
+}
+
+/// Runs the `table` subcommand.
+fn table(args: &TableArgs) -> Result<(), String> {
+    let network = load(&args.model)?;
+    let table = network.truth_table_packed(args.max_bits).map_err(|e| format!("{}: {}", args.model.display(), e))?;
+    let bytes: Vec<u8> = table.signals.iter().flatten().flat_map(|w| w.to_le_bytes()).collect();
+    let destination = if is_stdio(&args.out) {
+        io::stdout().lock().write_all(&bytes).map_err(|e| format!("standard output: {}", e))?;
+        String::from("standard output")
+    } else {
+        fs::write(&args.out, &bytes).map_err(|e| format!("{}: {}", args.out.display(), e))?;
+        args.out.display().to_string()
+    };
+    eprintln!("wrote {} rows of {} outputs ({} bytes) to {}", table.len, table.width(), bytes.len(), destination);
+    Ok(())
+}
+
+/// Runs the `trace` subcommand.
+fn trace(args: &TraceArgs) -> Result<(), String> {
+    check_stdin(&[Some(&args.model), Some(&args.data)])?;
+    let network = load(&args.model)?;
+    if args.layer >= network.layers.len() {
+        return Err(format!(
+            "{}: expected a layer below {} but found {}",
+            args.model.display(), network.layers.len(), args.layer
+        ));
+    }
+    let dataset = load_dataset(&args.data, network.output_width(), false, None)?;
+    if !dataset.is_empty() && dataset.input_width() != network.input_width() {
+        return Err(format!(
+            "{}: expected {} inputs like the network but found {}",
+            args.data.display(), network.input_width(), dataset.input_width()
+        ));
+    }
+    let (writer, destination): (Box<dyn Write>, String) = if is_stdio(&args.out) {
+        (Box::new(io::stdout().lock()), String::from("standard output"))
+    } else {
+        let file = File::create(&args.out).map_err(|e| format!("{}: {}", args.out.display(), e))?;
+        (Box::new(file), args.out.display().to_string())
+    };
+    let mut writer = BufWriter::new(writer);
+    for (input, _) in dataset.as_slice() {
+        let activations = &network.apply_traced(input).layers[args.layer];
+        writeln!(writer, "{}", format_row(activations)).map_err(|e| format!("{}: {}", destination, e))?;
+    }
+    writer.flush().map_err(|e| format!("{}: {}", destination, e))?;
+    eprintln!(
+        "wrote the activations of {} neurons of layer {} for {} samples to {}",
+        network.layers[args.layer].neurons.len(), args.layer, dataset.len(), destination
+    );
+    Ok(())
+}
+
+/// Runs the `train` subcommand.
+fn train(args: &TrainArgs, json: bool) -> Result<(), String> {
+    if args.watch && (is_stdio(&args.data) || is_stdio(&args.out)) {
+        return Err(String::from("--watch requires a dataset file and a model file"));
+    }
+    if json && is_stdio(&args.out) {
+        return Err(String::from("--json requires a model file, as the report is printed to standard output"));
+    }
+    if args.profile && !cfg!(feature = "profiling") {
+        return Err(String::from("--profile requires bnl to be built with the profiling feature"));
+    }
+    if args.watch && (args.record.is_some() || args.replay.is_some()) {
+        return Err(String::from("--record and --replay cannot be combined with --watch"));
+    }
+    if args.record.is_some() && args.replay.is_some() {
+        return Err(String::from("--record and --replay cannot be combined"));
+    }
+    if args.replay.is_some() && args.seed.is_none() {
+        return Err(String::from("--replay requires --seed, whose generator the recorded draws are compared with"));
+    }
+    let mut rng: Box<dyn RngCore> = match args.seed {
+        Some(seed) => Box::new(Xoshiro256::seed_from_u64(seed)),
+        None if cfg!(feature = "strict-determinism") => {
+            return Err(String::from("--seed is required when bnl is built with the strict-determinism feature"));
+        },
+        None => Box::new(bnl::random::rng())
+    };
+    if let Some(path) = &args.record {
+        let mut recorder = Recorder::new(rng);
+        train_round(args, json, None, &args.out, &mut recorder)?;
+        return recorder.into_log().save(path).map_err(|e| format!("{}: {}", path.display(), e));
+    }
+    if let Some(path) = &args.replay {
+        let log = RngLog::load(path).map_err(|e| e.to_string())?;
+        let mut replayer = Replayer::new(log, rng);
+        train_round(args, json, None, &args.out, &mut replayer)?;
+        return replayer.finish().map_err(|d| format!("{}: {}", path.display(), d));
+    }
+    if !args.watch {
+        return train_round(args, json, None, &args.out, &mut *rng).map(|_| ());
+    }
+    let mut network: Option<Network> = None;
+    let mut version = 1;
+    loop {
+        let modified = modified_time(&args.data);
+        match train_round(args, json, network.clone(), &versioned_path(&args.out, version), &mut *rng) {
+            Ok(n) => {
+                network = Some(n);
+                version += 1;
+            },
+            Err(e) => eprintln!("bnl: {}", e)
+        }
+        eprintln!("watching {} for changes", args.data.display());
+        loop {
+            match modified_time(&args.data) {
+                Some(t) if Some(t) != modified => break,
+                _ => thread::sleep(Duration::from_millis(args.poll_interval))
+            }
+        }
+    }
+}
+
+/// Trains a network on the dataset of the `train` subcommand, continuing from
+/// the specified network if it reads inputs of the same width, saves it to the
+/// specified path and reports how it fares, as JSON if specified.
+fn train_round(args: &TrainArgs, json: bool, network: Option<Network>, out: &Path, rng: &mut dyn RngCore) -> Result<Network, String> {
+    let output_len = *args.layers.last().ok_or("at least one layer length is required")?;
+    #[cfg(feature = "profiling")]
+    bnl::profile::take();
+    let dataset = load_dataset(&args.data, output_len, json, args.dataset_cache.as_deref())?;
+    if dataset.is_empty() {
+        return Err(format!("{}: dataset contains no samples", args.data.display()));
+    }
+    if dataset.output_width() != output_len {
+        return Err(format!(
+            "{}: dataset has {} outputs but the last layer has {} neurons",
+            args.data.display(), dataset.output_width(), output_len
+        ));
+    }
+    let opts = TrainOptions {
+        balanced_batch: args.balanced_batch,
+        iterations: args.iterations,
+        ..TrainOptions::default()
+    };
+    let mut network = match network {
+        Some(n) if n.input_width() == dataset.input_width() => n,
+        _ => Network::new_with_rng(dataset.input_width(), args.layers.clone(), rng)
+    };
+    let (iterations, wrong_bits) = match &args.fitness {
+        Some(name) => {
+            let fitness = Registry::builtin().fitness(name)?;
+            let report = network.train_with_fitness_rng(dataset.as_slice(), opts, &*fitness, rng);
+            (report.iterations, bnl::train::error(&network, dataset.as_slice()))
+        },
+        None => {
+            let mut progress = Progress::new(String::from("training"), opts.iterations, json);
+            let mut observer = |stats: &GenerationStats| {
+                progress.set(stats.generation);
+                Control::Continue
+            };
+            let report = network.train_observed_packed_with_rng(&dataset.pack(), opts, &mut observer, rng);
+            (report.iterations, report.final_error)
+        }
+    };
+    let metadata = Metadata {
+        dataset_hash: Some(dataset.hash())
+    };
+    save(&network, &metadata, out)?;
+    let summary = TrainReport {
+        accuracy: accuracy(&network, dataset.as_slice()),
+        data: args.data.display().to_string(),
+        iterations,
+        model: if is_stdio(out) { String::from("standard output") } else { out.display().to_string() },
+        output_bits: dataset.len() * output_len,
+        samples: dataset.len(),
+        wrong_bits
+    };
+    if json {
+        println!("{}", serde_json::to_string(&summary).map_err(|e| e.to_string())?);
+    } else {
+        eprintln!(
+            "trained for {} iterations: {} of {} output bits wrong ({:.1}% of samples exact), saved to {}",
+            summary.iterations, summary.wrong_bits, summary.output_bits, 100.0 * summary.accuracy, summary.model
+        );
+    }
+    #[cfg(feature = "profiling")]
+    if args.profile {
+        eprint!("{}", bnl::profile::take());
+    }
+    Ok(network)
+}
 
-    println!("n = {:?}\n", n);
-    println!("Result = {:?}", n.apply(input));
+/// Returns the specified path with the specified version number inserted
+/// before its extension, such as `model.3.json` for `model.json`.
+fn versioned_path(path: &Path, version: usize) -> PathBuf {
+    let stem = path.file_stem().map_or_else(String::new, |s| s.to_string_lossy().into_owned());
+    let name = match path.extension() {
+        Some(e) => format!("{}.{}.{}", stem, version, e.to_string_lossy()),
+        None => format!("{}.{}", stem, version)
+    };
+    path.with_file_name(name)
 }
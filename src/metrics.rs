@@ -0,0 +1,222 @@
+//! Contains metrics describing how well `bnl` networks fit datasets of
+//! `(input, expected)` samples, along with the `Fitness` trait through which
+//! custom training objectives are supplied to `Network::train_with`.
+
+use crate::analysis::hamming_distance;
+use crate::batch::BitDataset;
+use crate::network::Network;
+
+/// Represents the counts of correct and incorrect predictions of a
+/// single-output classifier.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub struct ConfusionMatrix {
+    /// The number of samples expected to be `true` but predicted `false`.
+    pub false_negatives: usize,
+
+    /// The number of samples expected to be `false` but predicted `true`.
+    pub false_positives: usize,
+
+    /// The number of samples expected and predicted to be `false`.
+    pub true_negatives: usize,
+
+    /// The number of samples expected and predicted to be `true`.
+    pub true_positives: usize
+}
+
+/// Implements custom methods for confusion matrices.
+impl ConfusionMatrix {
+    /// Returns the fraction of samples predicted correctly, or zero if there
+    /// are no samples.
+    pub fn accuracy(&self) -> f64 {
+        ratio(self.true_positives + self.true_negatives, self.total())
+    }
+
+    /// Returns the harmonic mean of the precision and recall, or zero if both
+    /// are zero.
+    pub fn f1(&self) -> f64 {
+        let (p, r) = (self.precision(), self.recall());
+        if p + r == 0.0 { 0.0 } else { 2.0 * p * r / (p + r) }
+    }
+
+    /// Returns the fraction of samples predicted to be `true` which were
+    /// expected to be `true`, or zero if none were predicted to be `true`.
+    pub fn precision(&self) -> f64 {
+        ratio(self.true_positives, self.true_positives + self.false_positives)
+    }
+
+    /// Returns the fraction of samples expected to be `true` which were
+    /// predicted to be `true`, or zero if none were expected to be `true`.
+    pub fn recall(&self) -> f64 {
+        ratio(self.true_positives, self.true_positives + self.false_negatives)
+    }
+
+    /// Returns the total number of samples counted.
+    pub fn total(&self) -> usize {
+        self.false_negatives + self.false_positives + self.true_negatives + self.true_positives
+    }
+}
+
+/// Represents the metrics of a network on a single dataset, as computed by
+/// `evaluate`.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Evaluation {
+    /// The fraction of samples whose outputs are reproduced exactly.
+    pub accuracy: f64,
+
+    /// The fraction of samples on which each output bit is correct.
+    pub bit_accuracy: Vec<f64>,
+
+    /// The confusion matrix of every output bit of every sample, counted
+    /// together.
+    pub confusion: ConfusionMatrix,
+
+    /// The fraction of output bits which differ from the expected outputs.
+    pub hamming_loss: f64,
+
+    /// The number of samples evaluated.
+    pub samples: usize
+}
+
+/// Represents a training objective: a measure of how badly a network fits a
+/// dataset, where lower is better and zero means the network fits it
+/// perfectly.
+///
+/// Any closure taking a network and a dataset and returning an `f64` is a
+/// fitness function.
+pub trait Fitness {
+    /// Computes the error of the specified network on the specified dataset.
+    fn error(&self, network: &Network, dataset: &[(Vec<bool>, Vec<bool>)]) -> f64;
+
+    /// Computes the error of the specified network on the specified dataset,
+    /// given along with a packed copy of it (see `batch::BitDataset`) so that
+    /// fitness functions may evaluate the network bit-parallel, as
+    /// `Network::train_with` does for every candidate. By default the packed
+    /// copy is ignored in favour of `Fitness::error`.
+    fn error_packed(&self, network: &Network, dataset: &[(Vec<bool>, Vec<bool>)], _packed: &BitDataset) -> f64 {
+        self.error(network, dataset)
+    }
+}
+
+/// Allows closures to be used as fitness functions.
+impl<F> Fitness for F
+where
+    F: Fn(&Network, &[(Vec<bool>, Vec<bool>)]) -> f64
+{
+    fn error(&self, network: &Network, dataset: &[(Vec<bool>, Vec<bool>)]) -> f64 {
+        self(network, dataset)
+    }
+}
+
+/// Represents the fitness function given by `hamming_loss`.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub struct HammingLoss;
+
+/// Allows the Hamming loss to be used as a fitness function.
+impl Fitness for HammingLoss {
+    fn error(&self, network: &Network, dataset: &[(Vec<bool>, Vec<bool>)]) -> f64 {
+        hamming_loss(network, dataset)
+    }
+
+    fn error_packed(&self, network: &Network, _dataset: &[(Vec<bool>, Vec<bool>)], packed: &BitDataset) -> f64 {
+        ratio(packed.error(network), packed.len() * packed.expected.width())
+    }
+}
+
+/// Computes the fraction of samples of the specified dataset whose outputs the
+/// specified network reproduces exactly, or zero if the dataset is empty.
+pub fn accuracy(network: &Network, dataset: &[(Vec<bool>, Vec<bool>)]) -> f64 {
+    let correct = dataset.iter().filter(|(input, expected)| network.apply(input) == *expected).count();
+    ratio(correct, dataset.len())
+}
+
+/// Computes, for each output bit, the fraction of samples of the specified
+/// dataset for which the specified network produces the expected value.
+///
+/// Returns an empty vector if the dataset is empty.
+pub fn bit_accuracy(network: &Network, dataset: &[(Vec<bool>, Vec<bool>)]) -> Vec<f64> {
+    let width = dataset.first().map_or(0, |(_, e)| e.len());
+    let mut correct: Vec<usize> = vec![0; width];
+    for (input, expected) in dataset {
+        for (i, (o, e)) in network.apply(input).iter().zip(expected.iter()).enumerate() {
+            if o == e {
+                correct[i] += 1;
+            }
+        }
+    }
+    correct.into_iter().map(|c| ratio(c, dataset.len())).collect()
+}
+
+/// Computes the confusion matrix of the specified single-output network on the
+/// specified dataset.
+///
+/// Panics if the network does not have exactly one output.
+pub fn confusion_matrix(network: &Network, dataset: &[(Vec<bool>, Vec<bool>)]) -> ConfusionMatrix {
+    if network.output_width() != 1 {
+        panic!("Cannot compute a confusion matrix of a network with {} outputs!", network.output_width());
+    }
+    let mut res = ConfusionMatrix::default();
+    for (input, expected) in dataset {
+        match (expected[0], network.apply(input)[0]) {
+            (true, true) => res.true_positives += 1,
+            (true, false) => res.false_negatives += 1,
+            (false, true) => res.false_positives += 1,
+            (false, false) => res.true_negatives += 1
+        }
+    }
+    res
+}
+
+/// Computes every metric of `Evaluation` for the specified network on the
+/// specified dataset, applying the network once per sample.
+///
+/// Panics if the outputs of the network are not as wide as those of the
+/// dataset.
+pub fn evaluate(network: &Network, dataset: &[(Vec<bool>, Vec<bool>)]) -> Evaluation {
+    let width = dataset.first().map_or(0, |(_, e)| e.len());
+    if !dataset.is_empty() && network.output_width() != width {
+        panic!("Cannot evaluate a network of {} outputs on samples of {} outputs!", network.output_width(), width);
+    }
+    let mut confusion = ConfusionMatrix::default();
+    let mut correct_bits: Vec<usize> = vec![0; width];
+    let mut correct = 0;
+    for (input, expected) in dataset {
+        let output = network.apply(input);
+        if output == *expected {
+            correct += 1;
+        }
+        for (i, (&o, &e)) in output.iter().zip(expected.iter()).enumerate() {
+            match (e, o) {
+                (true, true) => confusion.true_positives += 1,
+                (true, false) => confusion.false_negatives += 1,
+                (false, true) => confusion.false_positives += 1,
+                (false, false) => confusion.true_negatives += 1
+            }
+            if o == e {
+                correct_bits[i] += 1;
+            }
+        }
+    }
+    Evaluation {
+        accuracy: ratio(correct, dataset.len()),
+        bit_accuracy: correct_bits.into_iter().map(|c| ratio(c, dataset.len())).collect(),
+        confusion,
+        hamming_loss: ratio(confusion.false_negatives + confusion.false_positives, confusion.total()),
+        samples: dataset.len()
+    }
+}
+
+/// Computes the fraction of the output bits of the specified network over the
+/// specified dataset which differ from the expected outputs, or zero if there
+/// are no output bits.
+pub fn hamming_loss(network: &Network, dataset: &[(Vec<bool>, Vec<bool>)]) -> f64 {
+    let total: usize = dataset.iter().map(|(_, expected)| expected.len()).sum();
+    let wrong: usize = dataset.iter()
+        .map(|(input, expected)| hamming_distance(&network.apply(input), expected))
+        .sum();
+    ratio(wrong, total)
+}
+
+/// Divides the specified counts, returning zero if the denominator is zero.
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 { 0.0 } else { numerator as f64 / denominator as f64 }
+}
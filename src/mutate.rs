@@ -0,0 +1,421 @@
+//! Contains mutation operators for building custom training loops over `bnl`
+//! networks, alongside the crossover operators of the `crossover` module.
+//!
+//! Every operator modifies a network in place, applying its change at each
+//! site (parameter, neuron or layer) independently with the specified rate,
+//! and draws its random choices from the specified generator. Operators which
+//! change the shape of a network keep it valid and never change its input or
+//! output width; `add_neurons` and `insert_layers` also preserve the function
+//! it computes. Locked neurons (see `Neuron::locked`) are never changed, and
+//! changes to the shape of a network which would have to change them are
+//! skipped.
+//!
+//! Whole populations may instead be mutated in a single pass with
+//! `apply_batch`, which treats their parameters as one long sequence and
+//! visits only the parameters it changes, in place.
+
+use crate::combinator::Combinator;
+use crate::network::{InitProfile, Layer, Network, Neuron};
+use rand::{Rng, RngCore};
+
+/// Represents the per-parameter rates at which `apply_batch` mutates a
+/// population.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct BatchOps {
+    /// The probability that each bias is flipped.
+    pub bias_rate: f64,
+
+    /// The probability that each combinator (input or result) is replaced by
+    /// a random combinator.
+    pub combinator_rate: f64,
+
+    /// The probability that each input of each sparsely wired neuron is
+    /// rewired to a random signal of its input.
+    pub wiring_rate: f64
+}
+
+/// Provides reasonable default batch mutation rates.
+impl Default for BatchOps {
+    fn default() -> Self {
+        BatchOps {
+            bias_rate: 0.01,
+            combinator_rate: 0.01,
+            wiring_rate: 0.01
+        }
+    }
+}
+
+/// Represents a mutation operator which may be chosen at run time, such as by
+/// name from a `registry::Registry`.
+///
+/// Any closure taking a network, a rate and a random number generator, like
+/// the operators of this module, is a mutation operator.
+pub trait Mutation: Send + Sync {
+    /// Mutates the specified network in place with the specified rate.
+    fn mutate(&self, network: &mut Network, rate: f64, rng: &mut dyn RngCore);
+}
+
+/// Allows closures to be used as mutation operators.
+impl<F> Mutation for F
+where
+    F: Fn(&mut Network, f64, &mut dyn RngCore) + Send + Sync
+{
+    fn mutate(&self, network: &mut Network, rate: f64, rng: &mut dyn RngCore) {
+        self(network, rate, rng)
+    }
+}
+
+/// Tracks the position of the next parameter to mutate within a sequence of
+/// parameters which are each mutated independently with the same
+/// probability.
+struct Skipper {
+    /// The number of parameters to pass over before the next one to mutate.
+    next: u64,
+
+    /// The probability of mutating each parameter.
+    rate: f64
+}
+
+/// Implements custom methods for skippers.
+impl Skipper {
+    /// Creates a new skipper over parameters mutated with the specified
+    /// probability.
+    fn new<R: Rng + ?Sized>(rate: f64, rng: &mut R) -> Self {
+        Skipper {
+            next: gap(rate, rng),
+            rate
+        }
+    }
+
+    /// Calls the specified function on the index of each parameter to mutate
+    /// among the next `len` parameters of the sequence.
+    fn visit<R: Rng + ?Sized, F: FnMut(usize, &mut R)>(&mut self, len: usize, rng: &mut R, mut f: F) {
+        let mut position = self.next;
+        while position < len as u64 {
+            f(position as usize, rng);
+            position = position.saturating_add(1).saturating_add(gap(self.rate, rng));
+        }
+        self.next = position - len as u64;
+    }
+}
+
+/// Appends a new random neuron to each hidden layer of the specified network
+/// with the specified probability (see `Network::widen`).
+pub fn add_neurons<R: Rng + ?Sized>(network: &mut Network, rate: f64, rng: &mut R) {
+    for i in 0..network.layers.len().saturating_sub(1) {
+        if rng.gen_bool(rate) && !is_locked(&network.layers[i + 1]) {
+            network.widen_with_rng(i, 1, rng);
+        }
+    }
+}
+
+/// Appends a new random neuron to each hidden layer of the specified network
+/// with the specified probability as with `add_neurons`, initializing the new
+/// neurons according to the specified profile (such as
+/// `InitProfile::monotone`).
+pub fn add_neurons_with_profile<R: Rng + ?Sized>(network: &mut Network, rate: f64, profile: &InitProfile, rng: &mut R) {
+    for i in 0..network.layers.len().saturating_sub(1) {
+        if rng.gen_bool(rate) && !is_locked(&network.layers[i + 1]) {
+            network.widen_with_profile_with_rng(i, 1, profile, rng);
+        }
+    }
+}
+
+/// Mutates every network of the specified population in place, changing each
+/// of their parameters independently with the rate given for its kind.
+///
+/// The population is treated as one long sequence of parameters of each kind,
+/// and the distance to the next parameter to change is drawn directly (from
+/// a geometric distribution) rather than drawing once per parameter, so at
+/// low rates the cost is dominated by the single pass over the neurons of the
+/// networks, which are mutated in place.
+pub fn apply_batch<R: Rng + ?Sized>(population: &mut [Network], ops: BatchOps, rng: &mut R) {
+    apply_batch_from(population, ops, &Combinator::ALL, rng)
+}
+
+/// Mutates every network of the specified population in place as with
+/// `apply_batch`, drawing replacement combinators uniformly from the
+/// specified pool (such as `Combinator::MONOTONE`).
+///
+/// Panics if the pool is empty.
+pub fn apply_batch_from<R: Rng + ?Sized>(population: &mut [Network], ops: BatchOps, combinators: &[Combinator], rng: &mut R) {
+    #[cfg(feature = "profiling")]
+    let _timer = crate::profile::timer(crate::profile::Phase::Mutate);
+    if combinators.is_empty() {
+        panic!("Cannot replace combinators from an empty pool!");
+    }
+    let mut biases = Skipper::new(ops.bias_rate, rng);
+    let mut replaced = Skipper::new(ops.combinator_rate, rng);
+    let mut wiring = Skipper::new(ops.wiring_rate, rng);
+    let neurons = population.iter_mut().flat_map(|n| n.layers.iter_mut()).flat_map(|l| l.neurons.iter_mut());
+    for neuron in neurons.filter(|n| !n.locked) {
+        biases.visit(1, rng, |_, _| neuron.bias = !neuron.bias);
+        let len = neuron.input_combinators.len();
+        replaced.visit(len + 1, rng, |i, rng| {
+            let c = combinators[rng.gen_range(0, combinators.len())];
+            if i == len {
+                neuron.result_combinator = c;
+            } else {
+                neuron.input_combinators[i] = c;
+            }
+        });
+        let arity = neuron.arity;
+        if let Some(inputs) = neuron.inputs.as_mut() {
+            wiring.visit(inputs.len(), rng, |i, rng| inputs[i] = rng.gen_range(0, arity));
+        }
+    }
+}
+
+/// Deletes each hidden layer of the specified network with the specified
+/// probability.
+///
+/// The neurons of the layer after a deleted layer are resized to read the
+/// input of the deleted layer, by dropping inputs from or appending ignored
+/// inputs to the ends of their chains (or, if sparsely wired, by wrapping
+/// their indices around the new input width).
+pub fn delete_layers<R: Rng + ?Sized>(network: &mut Network, rate: f64, rng: &mut R) {
+    let mut i = 0;
+    while i + 1 < network.layers.len() {
+        if rng.gen_bool(rate) && !is_locked(&network.layers[i]) && !is_locked(&network.layers[i + 1]) {
+            let width = if i == 0 { network.input_width() } else { network.layers[i - 1].neurons.len() };
+            network.layers.remove(i);
+            for n in network.layers[i].neurons.iter_mut() {
+                resize_arity(n, width);
+            }
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Flips the bias of each neuron of the specified network with the specified
+/// probability.
+pub fn flip_biases<R: Rng + ?Sized>(network: &mut Network, rate: f64, rng: &mut R) {
+    for neuron in network.layers.iter_mut().flat_map(|l| l.neurons.iter_mut()).filter(|n| !n.locked) {
+        if rng.gen_bool(rate) {
+            neuron.bias = !neuron.bias;
+        }
+    }
+}
+
+/// Inserts a layer passing its input through unchanged (see
+/// `Network::deepen`) before each layer of the specified network with the
+/// specified probability.
+pub fn insert_layers<R: Rng + ?Sized>(network: &mut Network, rate: f64, rng: &mut R) {
+    let mut i = 0;
+    while i < network.layers.len() {
+        if rng.gen_bool(rate) {
+            network.deepen(i);
+            i += 1;
+        }
+        i += 1;
+    }
+}
+
+/// Removes a random neuron from each hidden layer of the specified network
+/// containing more than one neuron with the specified probability, dropping
+/// the corresponding input from the neurons of the following layer.
+///
+/// A sparsely wired neuron left wired to no input is rewired to a random one.
+pub fn remove_neurons<R: Rng + ?Sized>(network: &mut Network, rate: f64, rng: &mut R) {
+    for i in 0..network.layers.len().saturating_sub(1) {
+        let len = network.layers[i].neurons.len();
+        if len > 1 && rng.gen_bool(rate) && !is_locked(&network.layers[i + 1]) {
+            let unlocked: Vec<usize> = (0..len).filter(|&j| !network.layers[i].neurons[j].locked).collect();
+            if unlocked.is_empty() {
+                continue;
+            }
+            let j = unlocked[rng.gen_range(0, unlocked.len())];
+            network.layers[i].neurons.remove(j);
+            for n in network.layers[i + 1].neurons.iter_mut() {
+                remove_signal(n, j, rng);
+            }
+        }
+    }
+}
+
+/// Replaces each combinator (input or result) of each neuron of the specified
+/// network by a random combinator with the specified probability.
+pub fn replace_combinators<R: Rng + ?Sized>(network: &mut Network, rate: f64, rng: &mut R) {
+    replace_combinators_from(network, rate, &Combinator::ALL, rng)
+}
+
+/// Replaces each combinator of each neuron of the specified network as with
+/// `replace_combinators`, drawing replacement combinators uniformly from the
+/// specified pool (such as `Combinator::MONOTONE`).
+///
+/// Panics if the pool is empty.
+pub fn replace_combinators_from<R: Rng + ?Sized>(network: &mut Network, rate: f64, combinators: &[Combinator], rng: &mut R) {
+    if combinators.is_empty() {
+        panic!("Cannot replace combinators from an empty pool!");
+    }
+    for neuron in network.layers.iter_mut().flat_map(|l| l.neurons.iter_mut()).filter(|n| !n.locked) {
+        for c in neuron.input_combinators.iter_mut() {
+            if rng.gen_bool(rate) {
+                *c = combinators[rng.gen_range(0, combinators.len())];
+            }
+        }
+        if rng.gen_bool(rate) {
+            neuron.result_combinator = combinators[rng.gen_range(0, combinators.len())];
+        }
+    }
+}
+
+/// Replaces each individual parameter (bias or combinator) of the specified
+/// network by a random value with the specified probability, as done to the
+/// offspring of `Population::evolve`, returning the number of parameters
+/// replaced.
+pub fn replace_parameters<R: Rng + ?Sized>(network: &mut Network, rate: f64, rng: &mut R) -> usize {
+    replace_parameters_from(network, rate, &Combinator::ALL, rng)
+}
+
+/// Replaces each individual parameter of the specified network as with
+/// `replace_parameters`, drawing replacement combinators uniformly from the
+/// specified pool (such as `Combinator::MONOTONE`).
+///
+/// Panics if the pool is empty.
+pub fn replace_parameters_from<R: Rng + ?Sized>(network: &mut Network, rate: f64, combinators: &[Combinator], rng: &mut R) -> usize {
+    #[cfg(feature = "profiling")]
+    let _timer = crate::profile::timer(crate::profile::Phase::Mutate);
+    if combinators.is_empty() {
+        panic!("Cannot replace combinators from an empty pool!");
+    }
+    let mut res = 0;
+    for neuron in network.layers.iter_mut().flat_map(|l| l.neurons.iter_mut()).filter(|n| !n.locked) {
+        if rng.gen_bool(rate) {
+            neuron.bias = rng.gen();
+            res += 1;
+        }
+        if rng.gen_bool(rate) {
+            neuron.result_combinator = combinators[rng.gen_range(0, combinators.len())];
+            res += 1;
+        }
+        for c in neuron.input_combinators.iter_mut() {
+            if rng.gen_bool(rate) {
+                *c = combinators[rng.gen_range(0, combinators.len())];
+                res += 1;
+            }
+        }
+    }
+    res
+}
+
+/// Replaces each neuron of the specified network by a new random neuron of
+/// the same arity (and, if sparsely wired, fan-in) with the specified
+/// probability. Locked neurons are never replaced, and every new neuron keeps
+/// the reduction and locked flag of the neuron it replaces.
+pub fn rerandomize_neurons<R: Rng + ?Sized>(network: &mut Network, rate: f64, rng: &mut R) {
+    for neuron in network.layers.iter_mut().flat_map(|l| l.neurons.iter_mut()).filter(|n| !n.locked) {
+        if rng.gen_bool(rate) {
+            let (locked, reduction) = (neuron.locked, neuron.reduction);
+            *neuron = match neuron.inputs {
+                Some(_) => Neuron::new_sparse_with_rng(neuron.fan_in(), neuron.arity, rng),
+                None => Neuron::new_with_rng(neuron.arity, rng)
+            };
+            neuron.locked = locked;
+            neuron.reduction = reduction;
+        }
+    }
+}
+
+/// Replaces each neuron of the specified network as with
+/// `rerandomize_neurons`, initializing the new neurons according to the
+/// specified profile (such as `InitProfile::monotone`), except that they keep
+/// the wiring density of the neurons they replace: a sparsely wired neuron is
+/// replaced by one of the same fan-in, and a densely wired one by one reading
+/// its whole input. The new neurons also keep the reduction and locked flag
+/// of the neurons they replace, whatever the reduction of the profile.
+pub fn rerandomize_neurons_with_profile<R: Rng + ?Sized>(network: &mut Network, rate: f64, profile: &InitProfile, rng: &mut R) {
+    for neuron in network.layers.iter_mut().flat_map(|l| l.neurons.iter_mut()).filter(|n| !n.locked) {
+        if rng.gen_bool(rate) {
+            let profile = InitProfile {
+                fan_in: neuron.inputs.as_ref().map(|v| v.len()),
+                max_fan_in: None,
+                ..profile.clone()
+            };
+            let (locked, reduction) = (neuron.locked, neuron.reduction);
+            *neuron = profile.neuron_with_rng(neuron.arity, rng);
+            neuron.locked = locked;
+            neuron.reduction = reduction;
+        }
+    }
+}
+
+/// Rewires each input of each sparsely wired neuron of the specified network
+/// to a random signal of its input with the specified probability.
+pub fn rewire_inputs<R: Rng + ?Sized>(network: &mut Network, rate: f64, rng: &mut R) {
+    for neuron in network.layers.iter_mut().flat_map(|l| l.neurons.iter_mut()).filter(|n| !n.locked) {
+        let arity = neuron.arity;
+        for i in neuron.inputs.iter_mut().flatten() {
+            if rng.gen_bool(rate) {
+                *i = rng.gen_range(0, arity);
+            }
+        }
+    }
+}
+
+/// Draws the number of parameters passed over before the next one to mutate,
+/// when each is mutated with the specified probability.
+fn gap<R: Rng + ?Sized>(rate: f64, rng: &mut R) -> u64 {
+    if rate <= 0.0 {
+        return u64::MAX;
+    }
+    if rate >= 1.0 {
+        return 0;
+    }
+    let u: f64 = 1.0 - rng.gen::<f64>();
+    (u.ln() / (1.0 - rate).ln()).floor().min(u64::MAX as f64) as u64
+}
+
+/// Determines whether the specified layer contains a locked neuron.
+fn is_locked(layer: &Layer) -> bool {
+    layer.neurons.iter().any(|n| n.locked)
+}
+
+/// Removes the input at the specified index from the chain of the specified
+/// densely wired neuron, which must read at least two inputs.
+fn remove_input(neuron: &mut Neuron, index: usize) {
+    let last = neuron.input_combinators.len() - 1;
+    neuron.input_combinators.remove(index.min(last));
+    neuron.arity -= 1;
+}
+
+/// Removes the signal at the specified index from the input of the specified
+/// neuron, rewiring it to a random signal if it is sparsely wired to that
+/// signal alone.
+pub(crate) fn remove_signal<R: Rng + ?Sized>(neuron: &mut Neuron, index: usize, rng: &mut R) {
+    let inputs = match neuron.inputs.as_mut() {
+        Some(inputs) => inputs,
+        None => return remove_input(neuron, index)
+    };
+    neuron.arity -= 1;
+    while let Some(k) = inputs.iter().position(|&i| i == index) {
+        if inputs.len() == 1 {
+            inputs[0] = rng.gen_range(0, neuron.arity);
+            return;
+        }
+        inputs.remove(k);
+        neuron.input_combinators.remove(k.min(inputs.len() - 1));
+    }
+    for i in inputs.iter_mut().filter(|i| **i > index) {
+        *i -= 1;
+    }
+}
+
+/// Resizes the specified neuron to read the specified number of inputs, by
+/// dropping inputs from the end of its chain or appending inputs which are
+/// ignored (or, if sparsely wired, by wrapping its indices around).
+pub(crate) fn resize_arity(neuron: &mut Neuron, arity: usize) {
+    if let Some(inputs) = neuron.inputs.as_mut() {
+        inputs.iter_mut().for_each(|i| *i %= arity);
+        neuron.arity = arity;
+        return;
+    }
+    while neuron.arity > arity {
+        remove_input(neuron, neuron.arity - 1);
+    }
+    if neuron.arity < arity {
+        neuron.input_combinators.extend((neuron.arity..arity).map(|_| Combinator::Left));
+        neuron.arity = arity;
+    }
+}
@@ -1,7 +1,381 @@
 //! Contains the definition of components within a `bnl` network.
 
+use crate::bits::{hamming_distance, InputVec, OutputVec};
+use crate::combinator::Combinator;
+use crate::error::Error;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops;
+use rand::Rng;
+
+/// Represents an adapter placed between two composed networks whose widths do
+/// not match exactly.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub enum Adapter {
+    /// Appends `false` signals until the signal vector has the given width.
+    Pad(usize),
+
+    /// Keeps only the first signals of the signal vector, up to the given
+    /// width.
+    Truncate(usize),
+
+    /// Routes the signals at the given indices (in order) to the output, like
+    /// a `PermutationLayer`.
+    Select(Vec<usize>)
+}
+
+/// Implements custom functions on network adapters.
+impl Adapter {
+    /// "Applies" this adapter to a given input vector of boolean values.
+    pub fn apply(&self, input: &[bool]) -> Vec<bool> {
+        match self {
+            Adapter::Pad(width) => {
+                let mut res: Vec<bool> = input.to_vec();
+                res.resize(res.len().max(*width), false);
+                res
+            },
+            Adapter::Truncate(width) => input.iter().take(*width).copied().collect(),
+            Adapter::Select(indices) => route(input, indices)
+        }
+    }
+
+    /// Computes the output width of this adapter given its input width.
+    pub fn output_len(&self, input_len: usize) -> usize {
+        match self {
+            Adapter::Pad(width) => input_len.max(*width),
+            Adapter::Truncate(width) => input_len.min(*width),
+            Adapter::Select(indices) => indices.len()
+        }
+    }
+
+    /// Converts this adapter into an equivalent layer reading an input of the
+    /// specified length, so that it may be placed within a `Network`.
+    pub fn to_layer(&self, input_len: usize) -> Layer {
+        match self {
+            Adapter::Pad(width) => ConstLayer::new(vec![false; width.saturating_sub(input_len)]).to_layer(input_len),
+            Adapter::Truncate(width) => PermutationLayer::new((0..input_len.min(*width)).collect()).to_layer(input_len),
+            Adapter::Select(indices) => PermutationLayer::new(indices.clone()).to_layer(input_len)
+        }
+    }
+}
+
+/// Represents how the bias stage of neurons (the result combinator applied
+/// between the output of the input chain and the bias) is initialized and
+/// trained.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BiasMode {
+    /// Neurons have no bias stage: their result combinator is `LEFT`, passing
+    /// the output of the input chain through unchanged, and is never mutated.
+    Disabled,
+
+    /// Neurons have the given bias, which is never mutated, while their result
+    /// combinator is initialized and trained as usual.
+    Fixed(bool),
+
+    /// Both the bias and the result combinator are initialized randomly and
+    /// trained.
+    Learned
+}
+
+/// Provides the default bias mode, in which biases are learned.
+impl Default for BiasMode {
+    fn default() -> Self {
+        BiasMode::Learned
+    }
+}
+
+/// Represents the order in which the input combinators of a neuron combine
+/// the signals of its input chain `x0, x1, ..., xk` (where combinator `ci` is
+/// written `ci(left, right)`), each using exactly `k` combinators.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash,PartialOrd,Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Reduction {
+    /// Each combinator combines the result of the combinators before it with
+    /// the next signal, as `c1(c0(x0, x1), x2)`.
+    LeftFold,
+
+    /// Each combinator combines its signal with the result of the combinators
+    /// after it, as `c0(x0, c1(x1, x2))`. This is the original reduction of
+    /// `bnl` neurons.
+    RightFold,
+
+    /// The signals are combined pairwise in rounds, in order and each round
+    /// carrying an odd signal out over to the next, as
+    /// `c2(c0(x0, x1), c1(x2, x3))`, so that the depth of the reduction is
+    /// logarithmic in the number of signals.
+    Tree
+}
+
+/// Provides the default reduction, a right fold.
+impl Default for Reduction {
+    fn default() -> Self {
+        Reduction::RightFold
+    }
+}
+
+/// Implements custom methods for reductions.
+impl Reduction {
+    /// Returns the name of this reduction, such as `tree`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Reduction::LeftFold => "left fold",
+            Reduction::RightFold => "right fold",
+            Reduction::Tree => "tree"
+        }
+    }
+
+    /// Reduces the specified non-empty values to one with the specified
+    /// combinators (one fewer than the values) in this order, where `f`
+    /// combines two values with a combinator.
+    ///
+    /// Every reduction is computed iteratively, so arbitrarily many values
+    /// may be reduced.
+    ///
+    /// Panics if there are no values or too few combinators.
+    pub fn reduce<T, F>(self, mut values: Vec<T>, combinators: &[Combinator], mut f: F) -> T
+    where
+        F: FnMut(T, T, Combinator) -> T
+    {
+        if values.is_empty() || combinators.len() + 1 < values.len() {
+            panic!("Cannot reduce {} values with {} combinators!", values.len(), combinators.len());
+        }
+        match self {
+            Reduction::LeftFold => {
+                let mut rest = values.drain(..);
+                let first = rest.next().unwrap();
+                rest.zip(combinators.iter()).fold(first, |acc, (v, &c)| f(acc, v, c))
+            },
+            Reduction::RightFold => {
+                let last = values.pop().unwrap();
+                values.into_iter().zip(combinators.iter()).rev().fold(last, |acc, (v, &c)| f(v, acc, c))
+            },
+            Reduction::Tree => {
+                let mut cs = combinators.iter();
+                while values.len() > 1 {
+                    let mut next: Vec<T> = Vec::with_capacity(values.len().div_ceil(2));
+                    let mut it = values.into_iter();
+                    while let Some(l) = it.next() {
+                        next.push(match it.next() {
+                            Some(r) => f(l, r, *cs.next().unwrap()),
+                            None => l
+                        });
+                    }
+                    values = next;
+                }
+                values.pop().unwrap()
+            }
+        }
+    }
+}
+
+/// Represents a parameter-free layer which passes its input through unchanged
+/// and appends a fixed set of constant signals to it.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct ConstLayer {
+    /// The constant values appended after the input signals.
+    pub constants: Vec<bool>
+}
+
+/// Implements custom functions on constant injection layers.
+impl ConstLayer {
+    /// "Applies" this layer to a given input vector of boolean values.
+    pub fn apply(&self, input: &[bool]) -> Vec<bool> {
+        let mut res: Vec<bool> = input.to_vec();
+        res.extend_from_slice(&self.constants);
+        res
+    }
+
+    /// Creates a new constant injection layer appending the specified values.
+    pub fn new(constants: Vec<bool>) -> Self {
+        ConstLayer {
+            constants
+        }
+    }
+
+    /// Converts this constant injection layer into an equivalent layer of
+    /// selector and constant neurons reading an input of the specified length,
+    /// so that it may be placed within a `Network`.
+    pub fn to_layer(&self, input_len: usize) -> Layer {
+        let mut n: Vec<Neuron> = (0..input_len).map(|i| Neuron::selector(input_len, i)).collect();
+        n.extend(self.constants.iter().map(|&c| Neuron::constant(input_len, c)));
+        Layer {
+            neurons: n
+        }
+    }
+}
+
+/// Represents the settings from which the neurons of a layer are randomly
+/// initialized, so that (for instance) wide early layers may be initialized
+/// differently from narrow output layers.
+#[derive(Clone,Debug,PartialEq)]
+pub struct InitProfile {
+    /// How the bias stage of each neuron is initialized.
+    pub bias_mode: BiasMode,
+
+    /// The probability that a neuron's bias is `true`, when biases are
+    /// learned.
+    pub bias_probability: f64,
+
+    /// The combinators from which each input combinator is drawn uniformly.
+    /// Listing a combinator more than once makes it proportionally more
+    /// likely.
+    pub combinators: Vec<Combinator>,
+
+    /// The number of inputs each neuron is wired to at random (see
+    /// `Neuron::new_sparse`), capped at the width of its layer's input, or
+    /// `None` for each neuron to read the whole input.
+    pub fan_in: Option<usize>,
+
+    /// The largest number of inputs each sparsely wired neuron may be wired
+    /// to, if its fan-in is instead drawn uniformly between `fan_in` and this
+    /// number (both inclusive), or `None` for every neuron to be wired to
+    /// exactly `fan_in` inputs.
+    pub max_fan_in: Option<usize>,
+
+    /// The combinators from which each result combinator is drawn uniformly.
+    pub result_combinators: Vec<Combinator>,
+
+    /// The order in which each neuron's input combinators combine its inputs.
+    pub reduction: Reduction,
+
+    /// The probability that a neuron ignores each of its inputs other than
+    /// the last, by passing the rest of its chain through with a `RIGHT`
+    /// combinator.
+    pub sparsity: f64
+}
+
+/// Provides a reasonable default initialization profile, matching the
+/// distribution of `Neuron::new`.
+impl Default for InitProfile {
+    fn default() -> Self {
+        InitProfile {
+            bias_mode: BiasMode::Learned,
+            bias_probability: 0.5,
+            combinators: Combinator::ALL.to_vec(),
+            fan_in: None,
+            max_fan_in: None,
+            reduction: Reduction::RightFold,
+            result_combinators: Combinator::ALL.to_vec(),
+            sparsity: 0.0
+        }
+    }
+}
+
+/// Implements custom methods for initialization profiles.
+impl InitProfile {
+    /// Creates the default initialization profile restricted to monotone
+    /// combinators (see `Combinator::MONOTONE`).
+    pub fn monotone() -> Self {
+        InitProfile {
+            combinators: Combinator::MONOTONE.to_vec(),
+            result_combinators: Combinator::MONOTONE.to_vec(),
+            ..InitProfile::default()
+        }
+    }
+
+    /// Creates the default initialization profile without the constant
+    /// combinators `FALSE` and `TRUE`, which discard everything combined
+    /// before them and so produce many degenerate neurons.
+    pub fn nonconstant() -> Self {
+        let pool: Vec<Combinator> = Combinator::ALL.iter()
+            .copied()
+            .filter(|&c| c != Combinator::False && c != Combinator::True)
+            .collect();
+        InitProfile {
+            combinators: pool.clone(),
+            result_combinators: pool,
+            ..InitProfile::default()
+        }
+    }
+
+    /// Creates the default initialization profile drawing both input and
+    /// result combinators with probabilities proportional to the specified
+    /// weights, such as `&[(Xor, 3), (And, 2), (Or, 1)]`. Combinators which
+    /// are not listed are never drawn.
+    pub fn weighted(weights: &[(Combinator, usize)]) -> Self {
+        let pool: Vec<Combinator> = weights.iter()
+            .flat_map(|&(c, w)| core::iter::repeat_n(c, w))
+            .collect();
+        InitProfile {
+            combinators: pool.clone(),
+            result_combinators: pool,
+            ..InitProfile::default()
+        }
+    }
+
+    /// Creates a new randomized layer of the specified input length and number
+    /// of neurons according to this profile.
+    #[cfg(feature = "std")]
+    pub fn layer(&self, input_len: usize, num_neurons: usize) -> Layer {
+        self.layer_with_rng(input_len, num_neurons, &mut crate::random::rng())
+    }
+
+    /// Creates a new randomized layer as with `InitProfile::layer`, drawing its
+    /// parameters from the specified generator.
+    pub fn layer_with_rng<R: Rng + ?Sized>(&self, input_len: usize, num_neurons: usize, rng: &mut R) -> Layer {
+        Layer {
+            neurons: (0..num_neurons).map(|_| self.neuron_with_rng(input_len, rng)).collect()
+        }
+    }
+
+    /// Creates a new randomized neuron with the given input vector length
+    /// according to this profile.
+    ///
+    /// Panics if either pool of combinators is empty or either probability is
+    /// not between zero and one.
+    #[cfg(feature = "std")]
+    pub fn neuron(&self, input_len: usize) -> Neuron {
+        self.neuron_with_rng(input_len, &mut crate::random::rng())
+    }
+
+    /// Creates a new randomized neuron as with `InitProfile::neuron`, drawing
+    /// its parameters from the specified generator.
+    pub fn neuron_with_rng<R: Rng + ?Sized>(&self, input_len: usize, rng: &mut R) -> Neuron {
+        assert_arity(input_len);
+        if self.combinators.is_empty() || self.result_combinators.is_empty() {
+            panic!("Cannot initialize a neuron from an empty pool of combinators!");
+        }
+        let inputs = self.fan_in.map(|k| {
+            let k = match self.max_fan_in {
+                Some(max) if max > k => rng.gen_range(k, max + 1),
+                _ => k
+            };
+            random_wiring(k.min(input_len), input_len, rng)
+        });
+        let mut ic: Vec<Combinator> = Vec::new();
+        for _i in 1..inputs.as_ref().map_or(input_len, |v| v.len()) {
+            ic.push(if rng.gen_bool(self.sparsity) {
+                Combinator::Right
+            } else {
+                self.combinators[rng.gen_range(0, self.combinators.len())]
+            });
+        }
+        let (bias, result_combinator) = match self.bias_mode {
+            BiasMode::Disabled => (false, Combinator::Left),
+            BiasMode::Fixed(bias) => (bias, self.result_combinators[rng.gen_range(0, self.result_combinators.len())]),
+            BiasMode::Learned => (
+                rng.gen_bool(self.bias_probability),
+                self.result_combinators[rng.gen_range(0, self.result_combinators.len())]
+            )
+        };
+        Neuron {
+            arity: input_len,
+            bias,
+            input_combinators: ic,
+            inputs,
+            locked: false,
+            reduction: self.reduction,
+            result_combinator
+        }
+    }
+}
+
 /// Represents a single layer of neurons in a `bnl` network.
-#[derive(Clone,Debug)]
+#[derive(Clone,Debug,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Layer {
     /// The collection of neurons present in this layer.
     pub neurons: Vec<Neuron>
@@ -10,25 +384,80 @@ pub struct Layer {
 /// Implements custom functions on `bnl` layers.
 impl Layer {
     /// "Applies" this layer to a given input vector of boolean values.
-    pub fn apply(&self, input: Vec<bool>) -> Vec<bool> {
-        self.neurons.iter().map(|n| n.apply(input.clone())).collect()
+    pub fn apply(&self, input: &[bool]) -> Vec<bool> {
+        self.neurons.iter().map(|n| n.apply(input)).collect()
+    }
+
+    /// Checks that every neuron within this layer (at the specified index
+    /// within its network) can read the specified input, returning a
+    /// description of the first mismatch found. Used by `Network::try_apply`
+    /// and the `checked-apply` feature.
+    fn check_input(&self, layer: usize, input: &[bool]) -> Result<(), Error> {
+        for (i, n) in self.neurons.iter().enumerate() {
+            if n.arity != input.len() {
+                return Err(Error::ArityMismatch {
+                    layer,
+                    neuron: i,
+                    expected: n.arity,
+                    found: input.len()
+                });
+            }
+            if n.input_combinators.len() + 1 != n.fan_in() {
+                return Err(Error::ChainMismatch {
+                    layer,
+                    neuron: i,
+                    arity: n.fan_in(),
+                    combinators: n.input_combinators.len()
+                });
+            }
+            if let Some(&index) = n.inputs.iter().flatten().find(|&&j| j >= n.arity) {
+                return Err(Error::InputIndex {
+                    layer,
+                    neuron: i,
+                    index,
+                    width: n.arity
+                });
+            }
+        }
+        Ok(())
     }
 
     /// Creates a new randomized layer of the specified input length and number
     /// of neurons.
+    #[cfg(feature = "std")]
     pub fn new(input_len: usize, num_neurons: usize) -> Self {
+        Layer::new_with_rng(input_len, num_neurons, &mut crate::random::rng())
+    }
+
+    /// Creates a new randomized layer of the specified input length and number
+    /// of neurons, drawing its parameters from the specified generator.
+    pub fn new_with_rng<R: Rng + ?Sized>(input_len: usize, num_neurons: usize, rng: &mut R) -> Self {
         let mut n: Vec<Neuron> = Vec::new();
         for _i in 0..num_neurons {
-            n.push(Neuron::new(input_len));
+            n.push(Neuron::new_with_rng(input_len, rng));
         };
         Layer {
             neurons: n
         }
     }
+
+    /// Checks that every neuron within this layer is valid and reads an input
+    /// of the specified length, returning a description of the first problem
+    /// found.
+    pub fn validate(&self, input_len: usize) -> Result<(), String> {
+        for (i, n) in self.neurons.iter().enumerate() {
+            n.validate().map_err(|e| format!("neuron {}: {}", i, e))?;
+            if n.arity != input_len {
+                return Err(format!("neuron {}: expected an arity of {} but found {}", i, input_len, n.arity));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Represents a `bnl` network.
-#[derive(Clone,Debug)]
+#[derive(Clone,Debug,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Network {
     /// The collection of layers present in this network.
     pub layers: Vec<Layer>
@@ -36,59 +465,721 @@ pub struct Network {
 
 /// Implement custom methods for `bnl` networks.
 impl Network {
+    /// Computes the activations of the layer at the specified index (counting
+    /// from zero) for the given input vector of boolean values.
+    pub fn activations(&self, input: &[bool], layer: usize) -> Vec<bool> {
+        let mut res: Vec<bool> = input.to_vec();
+        for l in &self.layers[..=layer] {
+            res = l.apply(&res);
+        }
+        res
+    }
+
+    /// Creates a new network computing the element-wise AND of the outputs of
+    /// this network and another network on the same input.
+    ///
+    /// Panics if the networks differ in input or output width.
+    pub fn and(&self, other: &Network) -> Network {
+        self.combine_outputs(other, Combinator::And)
+    }
+
+    /// Computes how anomalous the specified input is to this network, when the
+    /// network has been trained to reconstruct its input (see
+    /// `Network::new_autoencoder`), as the Hamming distance between the input
+    /// and its reconstruction.
+    ///
+    /// Panics if this network's output width differs from its input width.
+    pub fn anomaly_score(&self, input: &[bool]) -> usize {
+        if self.output_width() != self.input_width() {
+            panic!("Anomaly scores require a network whose output width matches its input width!");
+        }
+        hamming_distance(input, &self.apply(input))
+    }
+
     /// "Applies" this network on the specified input vector of boolean values.
-    pub fn apply(&self, input: Vec<bool>) -> Vec<bool> {
-        let mut res: Vec<bool> = input.clone();
-        for layer in &self.layers {
-            res = layer.apply(res);
+    pub fn apply(&self, input: &[bool]) -> Vec<bool> {
+        let mut res: Vec<bool> = input.to_vec();
+        for (i, layer) in self.layers.iter().enumerate() {
+            if cfg!(feature = "checked-apply") {
+                if let Err(e) = layer.check_input(i, &res) {
+                    panic!("{}!", e);
+                }
+            }
+            res = layer.apply(&res);
         }
         res
     }
-    
+
+    /// "Applies" this network on the specified width-checked input vector.
+    ///
+    /// Panics if the width of the input does not match the input width of
+    /// this network.
+    pub fn apply_vec(&self, input: &InputVec) -> OutputVec {
+        if !self.layers.is_empty() && input.width() != self.input_width() {
+            panic!("{}!", Error::InputWidth { expected: self.input_width(), found: input.width() });
+        }
+        OutputVec::new(self.apply(input.as_slice()))
+    }
+
+    /// Creates a new network evaluating this network and another network on
+    /// the same input side by side, whose signals at each layer are those
+    /// of this network followed by those of the other. The shallower network
+    /// is deepened (see `Network::deepen`) to match the depth of the other.
+    ///
+    /// Panics if either network has no layers or the networks differ in input
+    /// width.
+    fn beside(&self, other: &Network) -> Network {
+        if self.layers.is_empty() || other.layers.is_empty() || self.input_width() != other.input_width() {
+            panic!(
+                "Cannot evaluate networks of input widths {} and {} side by side!",
+                self.input_width(), other.input_width()
+            );
+        }
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while a.layers.len() < b.layers.len() {
+            a.deepen(a.layers.len());
+        }
+        while b.layers.len() < a.layers.len() {
+            b.deepen(b.layers.len());
+        }
+        let mut layers: Vec<Layer> = Vec::new();
+        let (mut wa, mut wb) = (0, 0);
+        for (la, lb) in a.layers.iter().zip(b.layers.iter()) {
+            let mut neurons: Vec<Neuron> = Vec::new();
+            if layers.is_empty() {
+                neurons.extend(la.neurons.iter().chain(lb.neurons.iter()).cloned());
+            } else {
+                neurons.extend(la.neurons.iter().map(|n| offset_wiring(n, 0, wa + wb)));
+                neurons.extend(lb.neurons.iter().map(|n| offset_wiring(n, wa, wa + wb)));
+            }
+            wa = la.neurons.len();
+            wb = lb.neurons.len();
+            layers.push(Layer {
+                neurons
+            });
+        }
+        Network {
+            layers
+        }
+    }
+
+    /// Creates a new network combining each output of this network with the
+    /// corresponding output of another network via the specified combinator.
+    ///
+    /// Panics if the networks differ in input or output width.
+    fn combine_outputs(&self, other: &Network, combinator: Combinator) -> Network {
+        let width = self.output_width();
+        if width != other.output_width() {
+            panic!("Cannot combine networks of output widths {} and {}!", width, other.output_width());
+        }
+        let mut res = self.beside(other);
+        res.layers.push(Layer {
+            neurons: (0..width).map(|i| Neuron {
+                arity: 2 * width,
+                bias: false,
+                input_combinators: vec![combinator],
+                inputs: Some(vec![i, width + i]),
+                locked: false,
+                reduction: Reduction::RightFold,
+                result_combinator: Combinator::Left
+            }).collect()
+        });
+        res
+    }
+
+    /// Creates a new network which feeds the output of this network through
+    /// the specified adapter and into another network.
+    ///
+    /// Panics if the width produced by the adapter does not match the input
+    /// width of the other network.
+    pub fn compose(&self, adapter: &Adapter, other: &Network) -> Network {
+        let width = self.output_width();
+        let adapted = adapter.output_len(width);
+        if !other.layers.is_empty() && adapted != other.input_width() {
+            panic!("Adapter produces {} signals but the next network expects {}!", adapted, other.input_width());
+        }
+        let mut l: Vec<Layer> = self.layers.clone();
+        l.push(adapter.to_layer(width));
+        l.extend(other.layers.iter().cloned());
+        Network {
+            layers: l
+        }
+    }
+
+    /// Creates a new network running the specified networks side by side on
+    /// consecutive slices of its input, whose output is the concatenation of
+    /// their outputs. The input width of the new network is the sum of their
+    /// input widths, with the first network reading the first signals, and so
+    /// on. Shallower networks are deepened (see `Network::deepen`) to match
+    /// the deepest.
+    ///
+    /// Panics if no networks are specified or any of them has no layers.
+    pub fn concat(networks: &[Network]) -> Network {
+        if networks.is_empty() || networks.iter().any(|n| n.layers.is_empty()) {
+            panic!("Cannot concatenate an empty network or no networks at all!");
+        }
+        let width: usize = networks.iter().map(|n| n.input_width()).sum();
+        let mut offset = 0;
+        let mut res: Option<Network> = None;
+        for n in networks {
+            let mut shifted = n.clone();
+            for neuron in shifted.layers[0].neurons.iter_mut() {
+                *neuron = offset_wiring(neuron, offset, width);
+            }
+            offset += n.input_width();
+            res = Some(match res {
+                Some(r) => r.beside(&shifted),
+                None => shifted
+            });
+        }
+        res.unwrap()
+    }
+
+    /// Extracts the smallest sub-network of this network driving the output at
+    /// the specified index, as a network of the same input width with that
+    /// single output.
+    ///
+    /// Only the neurons the output structurally depends on (see
+    /// `Neuron::support`) are kept, except that every layer keeps at least
+    /// one neuron. Neurons reading a layer which lost neurons are rewired
+    /// sparsely to the kept ones.
+    ///
+    /// Panics if the index is not an output of this network.
+    pub fn cone_of(&self, output_index: usize) -> Network {
+        if output_index >= self.output_width() {
+            panic!(
+                "Cannot extract the cone of output {} of a network with {} outputs!",
+                output_index, self.output_width()
+            );
+        }
+        self.subnetwork(&[output_index])
+    }
+
+    /// Inserts a layer before the layer at the specified index (or after the
+    /// last layer, if the index equals the number of layers) which passes its
+    /// input through unchanged, so the function computed by this network is
+    /// preserved.
+    ///
+    /// Panics if the index exceeds the number of layers, or if this network
+    /// has no layers.
+    pub fn deepen(&mut self, index: usize) {
+        if self.layers.is_empty() || index > self.layers.len() {
+            panic!("Cannot insert a layer at index {} of a network with {} layers!", index, self.layers.len());
+        }
+        let width = if index == 0 {
+            self.input_width()
+        } else {
+            self.layers[index - 1].neurons.len()
+        };
+        self.layers.insert(index, Layer {
+            neurons: (0..width).map(|i| Neuron::selector(width, i)).collect()
+        });
+    }
+
+    /// Splices the specified circuit (such as one built with a
+    /// `NetworkBuilder`) into the layers of this network within the specified
+    /// range, one circuit layer per layer, as a lane starting at the specified
+    /// position: the first layer of the circuit reads the signals of the input
+    /// of the first layer of the range from that position onwards, and the
+    /// neurons of each layer of the circuit are inserted into the
+    /// corresponding layer of this network at that position.
+    ///
+    /// The neurons of the circuit are locked (see `Neuron::locked`), so that
+    /// training leaves them untouched. The existing neurons reading a layer
+    /// which grew are resized so that the function they compute is preserved;
+    /// if the range ends with the output layer, the outputs of the circuit
+    /// become outputs of this network.
+    ///
+    /// Panics if the range does not have one layer per circuit layer within
+    /// this network, or if the lane does not fit the input of the range or
+    /// any of its layers.
+    pub fn embed(&mut self, circuit: &Network, layers: ops::Range<usize>, position: usize) {
+        if circuit.layers.is_empty() || circuit.layers.len() != layers.len() || layers.end > self.layers.len() {
+            panic!(
+                "Cannot embed a circuit of {} layers into layers {:?} of a network with {} layers!",
+                circuit.layers.len(), layers, self.layers.len()
+            );
+        }
+        let input = if layers.start == 0 { self.input_width() } else { self.layers[layers.start - 1].neurons.len() };
+        if position + circuit.input_width() > input || self.layers[layers.clone()].iter().any(|l| position > l.neurons.len()) {
+            panic!("Cannot embed a circuit of input width {} at position {} of an input of width {}!", circuit.input_width(), position, input);
+        }
+        let mut grown = 0;
+        for (l, layer) in layers.clone().zip(circuit.layers.iter()) {
+            let width = if l == 0 { self.input_width() } else { self.layers[l - 1].neurons.len() };
+            for n in self.layers[l].neurons.iter_mut() {
+                make_room(n, position, grown);
+            }
+            let neurons = layer.neurons.iter().map(|n| Neuron {
+                locked: true,
+                ..offset_wiring(n, position, width)
+            });
+            self.layers[l].neurons.splice(position..position, neurons);
+            grown = layer.neurons.len();
+        }
+        if let Some(next) = self.layers.get_mut(layers.end) {
+            for n in next.neurons.iter_mut() {
+                make_room(n, position, grown);
+            }
+        }
+    }
+
+    /// Returns the width of the input vectors accepted by this network, or zero
+    /// if the network has no layers.
+    pub fn input_width(&self) -> usize {
+        self.layers.first()
+            .and_then(|l| l.neurons.first())
+            .map_or(0, |n| n.arity)
+    }
+
+    /// Determines whether this network is structurally identical to another
+    /// network up to the ordering of the neurons within each hidden layer,
+    /// so that both compute the same function.
+    ///
+    /// The networks are isomorphic if the neurons of each hidden layer of this
+    /// network can be reordered, renaming the signals read by the neurons of
+    /// the next layer to match (so that a densely wired neuron reads its
+    /// permuted input as the corresponding sparse chain), such that every
+    /// neuron becomes identical to the neuron at the same position of the
+    /// other network. The order of the output layer is significant. The
+    /// permutations are found by backtracking search, layer by layer.
+    pub fn isomorphic_to(&self, other: &Network) -> bool {
+        let inputs: Vec<usize> = (0..self.input_width()).collect();
+        self.input_width() == other.input_width() && isomorphic_from(&self.layers, &other.layers, &inputs)
+    }
+
+    /// Makes room for the specified number of new signals at the end of the
+    /// input of the layer after the one at the specified index (if any), so
+    /// that its neurons ignore them.
+    fn make_room_after(&mut self, layer: usize, count: usize) {
+        if let Some(next) = self.layers.get_mut(layer + 1) {
+            for n in next.neurons.iter_mut() {
+                let arity = n.arity;
+                make_room(n, arity, count);
+            }
+        }
+    }
+
     /// Creates a new randomized network of the specified input length and
     /// vector of layer lengths (number of neurons in each layer).
+    #[cfg(feature = "std")]
     pub fn new(input_len: usize, layer_lengths: Vec<usize>) -> Self {
+        Network::new_with_rng(input_len, layer_lengths, &mut crate::random::rng())
+    }
+
+    /// Creates a new randomized autoencoder-style network of the specified
+    /// input length, passing through the specified hidden layer lengths (which
+    /// should include a narrow "bottleneck" layer) before a final layer that
+    /// reconstructs an output of the same width as the input.
+    #[cfg(feature = "std")]
+    pub fn new_autoencoder(input_len: usize, hidden_lengths: Vec<usize>) -> Self {
+        let mut lengths: Vec<usize> = hidden_lengths;
+        lengths.push(input_len);
+        Network::new(input_len, lengths)
+    }
+
+    /// Creates a new randomized network of the specified input length and
+    /// layer lengths built only from monotone combinators, so that it computes
+    /// a monotone function of its input (see `InitProfile::monotone`).
+    #[cfg(feature = "std")]
+    pub fn new_monotone(input_len: usize, layer_lengths: Vec<usize>) -> Self {
+        Network::new_monotone_with_rng(input_len, layer_lengths, &mut crate::random::rng())
+    }
+
+    /// Creates a new randomized monotone network as with
+    /// `Network::new_monotone`, drawing its parameters from the specified
+    /// generator.
+    pub fn new_monotone_with_rng<R: Rng + ?Sized>(input_len: usize, layer_lengths: Vec<usize>, rng: &mut R) -> Self {
+        let profiles = vec![InitProfile::monotone(); layer_lengths.len()];
+        Network::new_profiled_with_rng(input_len, layer_lengths, &profiles, rng)
+    }
+
+    /// Creates a new randomized network of the specified input length and
+    /// layer lengths, initializing each layer according to the corresponding
+    /// profile.
+    ///
+    /// Panics if the number of profiles differs from the number of layers.
+    #[cfg(feature = "std")]
+    pub fn new_profiled(input_len: usize, layer_lengths: Vec<usize>, profiles: &[InitProfile]) -> Self {
+        Network::new_profiled_with_rng(input_len, layer_lengths, profiles, &mut crate::random::rng())
+    }
+
+    /// Creates a new randomized network as with `Network::new_profiled`,
+    /// drawing its parameters from the specified generator.
+    pub fn new_profiled_with_rng<R: Rng + ?Sized>(input_len: usize, layer_lengths: Vec<usize>, profiles: &[InitProfile], rng: &mut R) -> Self {
+        if profiles.len() != layer_lengths.len() {
+            panic!("Cannot initialize {} layers from {} profiles!", layer_lengths.len(), profiles.len());
+        }
+        let mut width = input_len;
+        let mut l: Vec<Layer> = Vec::new();
+        for (&len, profile) in layer_lengths.iter().zip(profiles.iter()) {
+            l.push(profile.layer_with_rng(width, len, rng));
+            width = len;
+        }
+        Network {
+            layers: l
+        }
+    }
+
+    /// Creates a new randomized network of the specified input length and
+    /// layer lengths, whose parameters are determined entirely by the
+    /// specified seed (drawn from a `random::Xoshiro256` generator, so that
+    /// it stays the same across versions of `rand`).
+    #[cfg(feature = "std")]
+    pub fn new_seeded(seed: u64, input_len: usize, layer_lengths: Vec<usize>) -> Self {
+        use rand::SeedableRng;
+        Network::new_with_rng(input_len, layer_lengths, &mut crate::random::Xoshiro256::seed_from_u64(seed))
+    }
+
+    /// Creates a new randomized network of the specified input length and
+    /// layer lengths, initializing every layer according to the specified
+    /// profile (see `Network::new_profiled` to vary it per layer).
+    #[cfg(feature = "std")]
+    pub fn new_with_profile(input_len: usize, layer_lengths: Vec<usize>, profile: &InitProfile) -> Self {
+        Network::new_with_profile_with_rng(input_len, layer_lengths, profile, &mut crate::random::rng())
+    }
+
+    /// Creates a new randomized network as with `Network::new_with_profile`,
+    /// drawing its parameters from the specified generator.
+    pub fn new_with_profile_with_rng<R: Rng + ?Sized>(input_len: usize, layer_lengths: Vec<usize>, profile: &InitProfile, rng: &mut R) -> Self {
+        let profiles = vec![profile.clone(); layer_lengths.len()];
+        Network::new_profiled_with_rng(input_len, layer_lengths, &profiles, rng)
+    }
+
+    /// Creates a new randomized network of the specified input length and
+    /// layer lengths, drawing its parameters from the specified generator.
+    pub fn new_with_rng<R: Rng + ?Sized>(input_len: usize, layer_lengths: Vec<usize>, rng: &mut R) -> Self {
         let mut l: Vec<Layer> = Vec::new();
         for i in 0..layer_lengths.len() {
             if i == 0 {
-                l.push(Layer::new(input_len, layer_lengths[i]));
+                l.push(Layer::new_with_rng(input_len, layer_lengths[i], rng));
             } else {
-                l.push(Layer::new(layer_lengths[i - 1], layer_lengths[i]));
+                l.push(Layer::new_with_rng(layer_lengths[i - 1], layer_lengths[i], rng));
             }
         }
         Network {
             layers: l
         }
     }
+
+    /// Creates a new network computing the element-wise OR of the outputs of
+    /// this network and another network on the same input.
+    ///
+    /// Panics if the networks differ in input or output width.
+    pub fn or(&self, other: &Network) -> Network {
+        self.combine_outputs(other, Combinator::Or)
+    }
+
+    /// Returns the width of the output vectors produced by this network, or zero
+    /// if the network has no layers.
+    pub fn output_width(&self) -> usize {
+        self.layers.last().map_or(0, |l| l.neurons.len())
+    }
+
+    /// Extracts the smallest sub-network of this network computing only the
+    /// outputs at the specified indices, in the specified order, as a network
+    /// of the same input width.
+    ///
+    /// As with `Network::cone_of`, the neurons none of the selected outputs
+    /// structurally depend on are removed.
+    ///
+    /// Panics if no indices are specified or if any index is not an output of
+    /// this network.
+    pub fn project_outputs(&self, indices: &[usize]) -> Network {
+        if indices.is_empty() {
+            panic!("Cannot project a network onto no outputs!");
+        }
+        if let Some(i) = indices.iter().find(|&&i| i >= self.output_width()) {
+            panic!("Cannot project output {} of a network with {} outputs!", i, self.output_width());
+        }
+        self.subnetwork(indices)
+    }
+
+    /// Creates a new network computing the same function as this network on
+    /// inputs whose signals are rearranged per the specified index map, where
+    /// input `i` of this network is read from signal `map[i]` of the new
+    /// input (as with `PermutationLayer`), so that signals may be reordered,
+    /// duplicated or left unused.
+    ///
+    /// The input width of the new network is one more than the largest index
+    /// of the map. Only the wiring of the first layer changes, so that no
+    /// layer is added. Panics if the length of the map does not match the
+    /// input width of this network.
+    pub fn remap_inputs(&self, map: &[usize]) -> Network {
+        if self.layers.is_empty() || map.len() != self.input_width() {
+            panic!("Cannot remap the {} inputs of a network with an index map of {}!", self.input_width(), map.len());
+        }
+        let width = map.iter().max().map_or(0, |&m| m + 1);
+        let mut res = self.clone();
+        if map.iter().enumerate().all(|(i, &m)| i == m) {
+            return res;
+        }
+        for n in &mut res.layers[0].neurons {
+            n.inputs = Some((0..n.fan_in()).map(|k| map[n.input_index(k)]).collect());
+            n.arity = width;
+        }
+        res
+    }
+
+    /// Builds the network computing the outputs of this network at the
+    /// specified indices (in order), keeping only the neurons they
+    /// structurally depend on, and at least one neuron per layer.
+    fn subnetwork(&self, outputs: &[usize]) -> Network {
+        let depth = self.layers.len();
+        let mut needed: Vec<Vec<bool>> = self.layers.iter().map(|l| vec![false; l.neurons.len()]).collect();
+        for &o in outputs {
+            needed[depth - 1][o] = true;
+        }
+        for l in (1..depth).rev() {
+            for (j, n) in self.layers[l].neurons.iter().enumerate() {
+                if needed[l][j] {
+                    n.support().into_iter().for_each(|i| needed[l - 1][i] = true);
+                }
+            }
+            if !needed[l - 1].contains(&true) {
+                needed[l - 1][0] = true;
+            }
+        }
+        let mut layers: Vec<Layer> = Vec::new();
+        let mut width = self.input_width();
+        let mut prev: Vec<usize> = (0..width).collect();
+        for (l, layer) in self.layers.iter().enumerate() {
+            let kept: Vec<usize> = if l + 1 == depth {
+                outputs.to_vec()
+            } else {
+                (0..layer.neurons.len()).filter(|&j| needed[l][j]).collect()
+            };
+            layers.push(Layer {
+                neurons: kept.iter().map(|&j| rewire(&layer.neurons[j], &prev, width)).collect()
+            });
+            width = layer.neurons.len();
+            prev = kept;
+        }
+        Network {
+            layers
+        }
+    }
+
+    /// Creates a new network which feeds the output of this network into
+    /// another network, as `compose` does without an adapter.
+    ///
+    /// Panics if the output width of this network does not match the input
+    /// width of the other.
+    pub fn then(&self, other: &Network) -> Network {
+        if !self.layers.is_empty() && !other.layers.is_empty() && self.output_width() != other.input_width() {
+            panic!(
+                "Cannot feed the {} outputs of a network into a network with {} inputs!",
+                self.output_width(), other.input_width()
+            );
+        }
+        let mut l: Vec<Layer> = self.layers.clone();
+        l.extend(other.layers.iter().cloned());
+        Network {
+            layers: l
+        }
+    }
+
+    /// "Applies" this network on the specified input vector of boolean values,
+    /// returning an error instead of panicking or producing garbage if the
+    /// input width does not match this network or any neuron cannot read the
+    /// output of the layer before it.
+    pub fn try_apply(&self, input: &[bool]) -> Result<Vec<bool>, Error> {
+        if !self.layers.is_empty() && input.len() != self.input_width() {
+            return Err(Error::InputWidth {
+                expected: self.input_width(),
+                found: input.len()
+            });
+        }
+        let mut res: Vec<bool> = input.to_vec();
+        for (i, layer) in self.layers.iter().enumerate() {
+            layer.check_input(i, &res)?;
+            res = layer.apply(&res);
+        }
+        Ok(res)
+    }
+
+    /// Checks that every layer of this network is valid and reads the output
+    /// of the layer before it, returning a description of the first problem
+    /// found.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut width = self.input_width();
+        for (i, l) in self.layers.iter().enumerate() {
+            if l.neurons.is_empty() {
+                return Err(format!("layer {}: layer contains no neurons", i));
+            }
+            l.validate(width).map_err(|e| format!("layer {}: {}", i, e))?;
+            width = l.neurons.len();
+        }
+        Ok(())
+    }
+
+    /// Checks that this network is valid (see `Network::validate`) and built
+    /// only from monotone combinators, so that it computes a monotone function
+    /// of its input, returning a description of the first problem found.
+    pub fn validate_monotone(&self) -> Result<(), String> {
+        self.validate()?;
+        for (i, l) in self.layers.iter().enumerate() {
+            for (j, n) in l.neurons.iter().enumerate() {
+                if let Some((k, c)) = n.input_combinators.iter().enumerate().find(|(_, c)| !c.is_monotone()) {
+                    return Err(format!("layer {}: neuron {}: input combinator {} is {}, which is not monotone", i, j, k, c));
+                }
+                if !n.result_combinator.is_monotone() {
+                    return Err(format!("layer {}: neuron {}: result combinator is {}, which is not monotone", i, j, n.result_combinator));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends the specified number of new randomized neurons to the layer at
+    /// the specified index.
+    ///
+    /// The neurons of the following layer (if any) are extended to read the
+    /// new signals at the end of their input chains through combinators which
+    /// ignore them (or, if sparsely wired or reduced as a tree, are left
+    /// unconnected to them), so the function computed by this network is
+    /// preserved unless the widened layer is the output layer.
+    #[cfg(feature = "std")]
+    pub fn widen(&mut self, layer: usize, count: usize) {
+        self.widen_with_rng(layer, count, &mut crate::random::rng())
+    }
+
+    /// Widens the layer at the specified index as with `Network::widen`,
+    /// initializing the new neurons according to the specified profile (such
+    /// as `InitProfile::monotone`, so that a monotone network stays monotone).
+    #[cfg(feature = "std")]
+    pub fn widen_with_profile(&mut self, layer: usize, count: usize, profile: &InitProfile) {
+        self.widen_with_profile_with_rng(layer, count, profile, &mut crate::random::rng())
+    }
+
+    /// Widens the layer at the specified index as with
+    /// `Network::widen_with_profile`, drawing the parameters of the new neurons
+    /// from the specified generator.
+    pub fn widen_with_profile_with_rng<R: Rng + ?Sized>(&mut self, layer: usize, count: usize, profile: &InitProfile, rng: &mut R) {
+        let width = if layer == 0 {
+            self.input_width()
+        } else {
+            self.layers[layer - 1].neurons.len()
+        };
+        for _i in 0..count {
+            let neuron = profile.neuron_with_rng(width, rng);
+            self.layers[layer].neurons.push(neuron);
+        }
+        self.make_room_after(layer, count);
+    }
+
+    /// Widens the layer at the specified index as with `Network::widen`,
+    /// drawing the parameters of the new neurons from the specified generator.
+    pub fn widen_with_rng<R: Rng + ?Sized>(&mut self, layer: usize, count: usize, rng: &mut R) {
+        let width = if layer == 0 {
+            self.input_width()
+        } else {
+            self.layers[layer - 1].neurons.len()
+        };
+        for _i in 0..count {
+            self.layers[layer].neurons.push(Neuron::new_with_rng(width, rng));
+        }
+        self.make_room_after(layer, count);
+    }
+
+    /// Creates a new network computing the element-wise XOR of the outputs of
+    /// this network and another network on the same input, which is `true`
+    /// wherever the networks disagree.
+    ///
+    /// Panics if the networks differ in input or output width.
+    pub fn xor(&self, other: &Network) -> Network {
+        self.combine_outputs(other, Combinator::Xor)
+    }
+}
+
+/// Allows networks to be negated, computing the element-wise NOT of their
+/// outputs without adding a layer.
+impl ops::Not for Network {
+    type Output = Network;
+
+    fn not(mut self) -> Network {
+        if let Some(l) = self.layers.last_mut() {
+            for n in &mut l.neurons {
+                n.result_combinator = !n.result_combinator;
+            }
+        }
+        self
+    }
+}
+
+/// Allows network references to be negated as with `Network`.
+impl ops::Not for &Network {
+    type Output = Network;
+
+    fn not(self) -> Network {
+        !self.clone()
+    }
 }
 
 /// Represents a single neuron within a `bnl` network.
-#[derive(Clone,Debug)]
+#[derive(Clone,Debug,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Neuron {
+    /// The width of the input read by this neuron (that is, of the previous
+    /// layer). Unless the neuron is sparsely wired, its input combinator chain
+    /// always contains exactly `arity - 1` combinators.
+    pub arity: usize,
+
     /// The bias of this neuron as a boolean value.
     pub bias: bool,
     
-    /// A vector containing the boolean function input combinator numbers.
+    /// A vector containing the boolean function input combinators.
     /// The way that this works is by applying the specified boolean function
     /// to the result of each stage.
-    pub input_combinators: Vec<u8>,
+    pub input_combinators: Vec<Combinator>,
+
+    /// The indices of the signals of the input which this neuron combines, in
+    /// chain order, or `None` if it combines the whole input in order. A
+    /// sparsely wired neuron's input combinator chain always contains exactly
+    /// one combinator fewer than it has indices.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub inputs: Option<Vec<usize>>,
+
+    /// Whether this neuron is left untouched by every mutation operator (of
+    /// training, evolution and the `mutate` module), so that hand-designed
+    /// sub-circuits survive training.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub locked: bool,
+
+    /// The order in which the input combinators of this neuron combine its
+    /// input chain.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reduction: Reduction,
 
     /// The "result" combinator of this neuron (the function to apply between
     /// the initial result and the bias).
-    pub result_combinator: u8
+    pub result_combinator: Combinator
 }
 
 /// Implements custom methods available to `Neuron` structures.
 impl Neuron {
     /// "Applies" this neuron to a given input vector of boolen values.
-    pub fn apply(&self, input: Vec<bool>) -> bool {
+    pub fn apply(&self, input: &[bool]) -> bool {
         self.apply_result(self.apply_input(input))
     }
 
     /// "Applies" only the input combinator of this neuron to a given input
     /// vector of boolean values.
-    pub fn apply_input(&self, input: Vec<bool>) -> bool {
-        zip_combinator(input[0], input[1..].to_vec(), &self.input_combinators)
+    pub fn apply_input(&self, input: &[bool]) -> bool {
+        if cfg!(feature = "checked-apply") && input.is_empty() {
+            panic!("Neuron of arity {} was given an empty input!", self.arity);
+        }
+        match (&self.inputs, self.reduction) {
+            (None, Reduction::RightFold) => zip_combinator(input[0], &input[1..], &self.input_combinators),
+            (Some(inputs), Reduction::RightFold) => {
+                let selected: Vec<bool> = inputs.iter().map(|&i| input[i]).collect();
+                zip_combinator(selected[0], &selected[1..], &self.input_combinators)
+            },
+            (_, reduction) => {
+                let selected: Vec<bool> = (0..self.fan_in()).map(|k| input[self.input_index(k)]).collect();
+                reduction.reduce(selected, &self.input_combinators, compute_boolean)
+            }
+        }
     }
 
     /// "Applies" the result combinator of this neuron to a given input boolean
@@ -97,50 +1188,399 @@ impl Neuron {
         compute_boolean(input, self.bias, self.result_combinator)
     }
 
+    /// Creates a new neuron with the given input vector length which always
+    /// outputs the specified value, stored as its bias.
+    pub fn constant(input_len: usize, value: bool) -> Self {
+        assert_arity(input_len);
+        Neuron {
+            arity: input_len,
+            bias: value,
+            input_combinators: vec![Combinator::False; input_len - 1],
+            inputs: None,
+            locked: false,
+            reduction: Reduction::RightFold,
+            result_combinator: Combinator::Right
+        }
+    }
+
+    /// Measures the functional disagreement between this neuron and another
+    /// neuron as the number of the specified sample inputs for which their
+    /// outputs differ.
+    pub fn distance(&self, other: &Neuron, sample_inputs: &[Vec<bool>]) -> usize {
+        sample_inputs.iter()
+            .filter(|i| self.apply(i) != other.apply(i))
+            .count()
+    }
+
+    /// Returns the number of input values combined by the input combinator
+    /// chain of this neuron.
+    pub fn fan_in(&self) -> usize {
+        self.inputs.as_ref().map_or(self.arity, |v| v.len())
+    }
+
+    /// Returns the index within the input of the signal at the specified
+    /// position of the input combinator chain of this neuron.
+    pub fn input_index(&self, position: usize) -> usize {
+        self.inputs.as_ref().map_or(position, |v| v[position])
+    }
+
     /// Creates a new randomized neuron with the given input vector length.
+    #[cfg(feature = "std")]
     pub fn new(input_len: usize) -> Self {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-        let mut ic: Vec<u8> = Vec::new();
-        for _i in 0..input_len {
-            ic.push(rng.gen_range(0, 16));
+        Neuron::new_with_rng(input_len, &mut crate::random::rng())
+    }
+
+    /// Creates a new randomized neuron with the given input vector length,
+    /// drawing its parameters from the specified generator.
+    pub fn new_with_rng<R: Rng + ?Sized>(input_len: usize, rng: &mut R) -> Self {
+        assert_arity(input_len);
+        let mut ic: Vec<Combinator> = Vec::new();
+        for _i in 1..input_len {
+            ic.push(rng.gen());
         }
         Neuron {
+            arity: input_len,
             bias: rng.gen(),
             input_combinators: ic,
-            result_combinator: rng.gen_range(0, 16)
+            inputs: None,
+            locked: false,
+            reduction: Reduction::RightFold,
+            result_combinator: rng.gen()
         }
     }
+
+    /// Creates a new randomized neuron reading an input of the specified
+    /// width, wired to the specified number of its signals chosen at random
+    /// (in random order).
+    ///
+    /// Panics if the fan-in is zero or exceeds the input width.
+    #[cfg(feature = "std")]
+    pub fn new_sparse(fan_in: usize, prev_width: usize) -> Self {
+        Neuron::new_sparse_with_rng(fan_in, prev_width, &mut crate::random::rng())
+    }
+
+    /// Creates a new sparsely wired neuron as with `Neuron::new_sparse`,
+    /// drawing its wiring and parameters from the specified generator.
+    pub fn new_sparse_with_rng<R: Rng + ?Sized>(fan_in: usize, prev_width: usize, rng: &mut R) -> Self {
+        assert_arity(fan_in);
+        let inputs = random_wiring(fan_in, prev_width, rng);
+        Neuron {
+            arity: prev_width,
+            bias: rng.gen(),
+            input_combinators: (1..fan_in).map(|_| rng.gen()).collect(),
+            inputs: Some(inputs),
+            locked: false,
+            reduction: Reduction::RightFold,
+            result_combinator: rng.gen()
+        }
+    }
+
+    /// Creates a new neuron with the given input vector length which simply
+    /// outputs the input value at the specified index.
+    ///
+    /// Every combinator preceding the selected input in the chain passes its
+    /// right-hand value through, the combinator at the selected input keeps
+    /// its left-hand value, and the result combinator ignores the bias.
+    pub fn selector(input_len: usize, index: usize) -> Self {
+        assert_arity(input_len);
+        if index >= input_len {
+            panic!("Cannot select input {} of a neuron with only {} inputs!", index, input_len);
+        }
+        let mut ic: Vec<Combinator> = Vec::new();
+        for i in 1..input_len {
+            ic.push(if i <= index { Combinator::Right } else { Combinator::Left });
+        }
+        Neuron {
+            arity: input_len,
+            bias: false,
+            input_combinators: ic,
+            inputs: None,
+            locked: false,
+            reduction: Reduction::RightFold,
+            result_combinator: Combinator::Left
+        }
+    }
+
+    /// Computes the indices of the signals of the input that the output of
+    /// this neuron structurally depends on, in ascending order.
+    ///
+    /// Signals masked off by combinators such as `LEFT` or constant `FALSE`
+    /// are excluded, as is every signal if the result combinator ignores the
+    /// input chain.
+    pub fn support(&self) -> Vec<usize> {
+        let result = self.result_combinator;
+        if compute_boolean(false, self.bias, result) == compute_boolean(true, self.bias, result) {
+            return Vec::new();
+        }
+        let signals: Vec<Vec<usize>> = (0..self.fan_in()).map(|k| vec![self.input_index(k)]).collect();
+        let mut inputs = self.reduction.reduce(signals, &self.input_combinators, |l, r, c| {
+            let mut res = if reads_left(c) { l } else { Vec::new() };
+            if reads_right(c) {
+                res.extend(r);
+            }
+            res
+        });
+        inputs.sort_unstable();
+        inputs.dedup();
+        inputs
+    }
+
+    /// Checks that the input combinator chain of this neuron is consistent with
+    /// its arity, returning a description of the problem if it is not.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.arity == 0 {
+            return Err(String::from("neuron has an arity of zero"));
+        }
+        if let Some(inputs) = &self.inputs {
+            if inputs.is_empty() {
+                return Err(String::from("neuron is wired to no inputs"));
+            }
+            if let Some(i) = inputs.iter().find(|&&i| i >= self.arity) {
+                return Err(format!("neuron is wired to input {} of an input of width {}", i, self.arity));
+            }
+            if self.input_combinators.len() + 1 != inputs.len() {
+                return Err(format!(
+                    "neuron is wired to {} inputs but has {} input combinators (expected {})",
+                    inputs.len(),
+                    self.input_combinators.len(),
+                    inputs.len() - 1
+                ));
+            }
+            return Ok(());
+        }
+        if self.input_combinators.len() + 1 != self.arity {
+            return Err(format!(
+                "neuron has an arity of {} but {} input combinators (expected {})",
+                self.arity,
+                self.input_combinators.len(),
+                self.arity - 1
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Represents a parameter-free layer which reorders and/or duplicates the
+/// signals of its input according to a fixed index map.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct PermutationLayer {
+    /// The index of the input signal routed to each output position.
+    pub map: Vec<usize>
+}
+
+/// Implements custom functions on permutation layers.
+impl PermutationLayer {
+    /// "Applies" this layer to a given input vector of boolean values.
+    pub fn apply(&self, input: &[bool]) -> Vec<bool> {
+        route(input, &self.map)
+    }
+
+    /// Creates a new permutation layer from the specified index map.
+    pub fn new(map: Vec<usize>) -> Self {
+        PermutationLayer {
+            map
+        }
+    }
+
+    /// Converts this permutation layer into an equivalent layer of selector
+    /// neurons (see `Neuron::selector`) reading an input of the specified
+    /// length, so that it may be placed within a `Network`.
+    pub fn to_layer(&self, input_len: usize) -> Layer {
+        Layer {
+            neurons: self.map.iter().map(|&i| Neuron::selector(input_len, i)).collect()
+        }
+    }
+}
+
+/// Panics if the specified input length is not a valid neuron arity.
+fn assert_arity(input_len: usize) {
+    if input_len == 0 {
+        panic!("A neuron must read at least one input!");
+    }
 }
 
 /// Computes the result of the specified boolean combinator on two input boolean
 /// values.
-pub fn compute_boolean(left: bool, right: bool, combinator: u8) -> bool {
+pub fn compute_boolean(left: bool, right: bool, combinator: Combinator) -> bool {
     match combinator {
-        0  => false,
-        1  => left && right,
-        2  => left && !right,
-        3  => left,
-        4  => !left && right,
-        5  => right,
-        6  => (left && !right) || (!left && right),
-        7  => left || right,
-        8  => !(left || right),
-        9  => (left && right) || (!left && !right),
-        10 => !right,
-        11 => left || !right,
-        12 => !left,
-        13 => !left || right,
-        14 => !(left && right),
-        _  => true
+        Combinator::False       => false,
+        Combinator::And         => left && right,
+        Combinator::AndNotRight => left && !right,
+        Combinator::Left        => left,
+        Combinator::AndNotLeft  => !left && right,
+        Combinator::Right       => right,
+        Combinator::Xor         => (left && !right) || (!left && right),
+        Combinator::Or          => left || right,
+        Combinator::Nor         => !(left || right),
+        Combinator::Xnor        => (left && right) || (!left && !right),
+        Combinator::NotRight    => !right,
+        Combinator::ImpliedBy   => left || !right,
+        Combinator::NotLeft     => !left,
+        Combinator::Implies     => !left || right,
+        Combinator::Nand        => !(left && right),
+        Combinator::True        => true
+    }
+}
+
+/// Determines whether the specified layers of one network are isomorphic to
+/// the specified layers of another (see `Network::isomorphic_to`), given the
+/// position within the other network of each signal of the input of the
+/// first of them.
+fn isomorphic_from(layers: &[Layer], others: &[Layer], renamed: &[usize]) -> bool {
+    let (layer, other) = match (layers.first(), others.first()) {
+        (Some(l), Some(o)) if l.neurons.len() == o.neurons.len() => (l, o),
+        (None, None) => return true,
+        _ => return false
+    };
+    if layers.len() == 1 {
+        return others.len() == 1 && layer.neurons.iter().zip(other.neurons.iter()).all(|(a, b)| renames_to(a, b, renamed));
+    }
+    let mut position: Vec<usize> = Vec::with_capacity(layer.neurons.len());
+    let mut used = vec![false; other.neurons.len()];
+    permute(layer, other, renamed, &mut position, &mut used, &|p| isomorphic_from(&layers[1..], &others[1..], p))
+}
+
+/// Resizes the specified neuron to read an input into which the specified
+/// number of signals are inserted at the specified position, without reading
+/// them, so that the function it computes is preserved.
+///
+/// Densely wired neurons read the new signals through combinators which
+/// ignore them if they are appended to a chain which is not reduced as a
+/// tree, and are otherwise rewired sparsely to the signals they read before.
+fn make_room(neuron: &mut Neuron, position: usize, count: usize) {
+    if count == 0 {
+        return;
+    }
+    let shift = |i: usize| if i < position { i } else { i + count };
+    match neuron.inputs.as_mut() {
+        Some(inputs) => inputs.iter_mut().for_each(|i| *i = shift(*i)),
+        None if position == neuron.arity && neuron.reduction != Reduction::Tree => {
+            neuron.input_combinators.extend((0..count).map(|_| Combinator::Left));
+        },
+        None => neuron.inputs = Some((0..neuron.arity).map(shift).collect())
+    }
+    neuron.arity += count;
+}
+
+/// Rewires the specified neuron to read an input of the specified width, in
+/// which the signals it reads are shifted by the specified offset.
+fn offset_wiring(neuron: &Neuron, offset: usize, width: usize) -> Neuron {
+    Neuron {
+        arity: width,
+        inputs: Some((0..neuron.fan_in()).map(|k| offset + neuron.input_index(k)).collect()),
+        ..neuron.clone()
     }
 }
 
+/// Extends the specified partial assignment of the neurons of one layer to the
+/// positions of identical neurons of another layer (see
+/// `Network::isomorphic_to`) in every possible way, determining whether the
+/// specified continuation accepts any of the complete assignments.
+fn permute<F>(layer: &Layer, other: &Layer, renamed: &[usize], position: &mut Vec<usize>, used: &mut [bool], accept: &F) -> bool
+where
+    F: Fn(&[usize]) -> bool
+{
+    let j = position.len();
+    if j == layer.neurons.len() {
+        return accept(position);
+    }
+    for k in 0..other.neurons.len() {
+        if !used[k] && renames_to(&layer.neurons[j], &other.neurons[k], renamed) {
+            used[k] = true;
+            position.push(k);
+            if permute(layer, other, renamed, position, used, accept) {
+                return true;
+            }
+            position.pop();
+            used[k] = false;
+        }
+    }
+    false
+}
+
+/// Chooses the specified number of distinct indices below the specified width,
+/// in random order.
+///
+/// Panics if the number exceeds the width.
+fn random_wiring<R: Rng + ?Sized>(fan_in: usize, width: usize, rng: &mut R) -> Vec<usize> {
+    if fan_in > width {
+        panic!("Cannot wire a neuron to {} of only {} inputs!", fan_in, width);
+    }
+    rand::seq::index::sample(rng, width, fan_in).into_vec()
+}
+
+/// Determines whether the specified combinator depends on its left input.
+pub fn reads_left(combinator: Combinator) -> bool {
+    [false, true].iter().any(|&r| compute_boolean(false, r, combinator) != compute_boolean(true, r, combinator))
+}
+
+/// Determines whether the specified combinator depends on its right input.
+pub fn reads_right(combinator: Combinator) -> bool {
+    [false, true].iter().any(|&l| compute_boolean(l, false, combinator) != compute_boolean(l, true, combinator))
+}
+
+/// Determines whether the specified neuron becomes identical to another neuron
+/// when each signal of its input is renamed to the specified position, where
+/// densely wired neurons are compared by the (renamed) chain they read.
+fn renames_to(neuron: &Neuron, other: &Neuron, renamed: &[usize]) -> bool {
+    neuron.arity == other.arity
+        && neuron.bias == other.bias
+        && neuron.input_combinators == other.input_combinators
+        && neuron.locked == other.locked
+        && neuron.reduction == other.reduction
+        && neuron.result_combinator == other.result_combinator
+        && neuron.fan_in() == other.fan_in()
+        && (0..neuron.fan_in()).all(|k| renamed.get(neuron.input_index(k)) == Some(&other.input_index(k)))
+}
+
+/// Rewires the specified neuron, which reads an input of the specified width,
+/// to read only the signals of that input at the specified indices (which
+/// must include every signal it structurally depends on), leaving it
+/// unchanged if every signal is kept.
+fn rewire(neuron: &Neuron, kept: &[usize], width: usize) -> Neuron {
+    if kept.len() == width {
+        return neuron.clone();
+    }
+    let mut index: Vec<Option<usize>> = vec![None; width];
+    kept.iter().enumerate().for_each(|(k, &i)| index[i] = Some(k));
+    Neuron {
+        arity: kept.len(),
+        inputs: Some((0..neuron.fan_in()).map(|k| index[neuron.input_index(k)].unwrap_or(0)).collect()),
+        ..neuron.clone()
+    }
+}
+
+/// Routes the signals of the specified input to the positions given by an
+/// index map.
+fn route(input: &[bool], map: &[usize]) -> Vec<bool> {
+    map.iter().enumerate().map(|(position, &i)| {
+        if cfg!(feature = "checked-apply") && i >= input.len() {
+            panic!(
+                "Output {} routes from input {} but the input only has width {}!",
+                position, i, input.len()
+            );
+        }
+        input[i]
+    }).collect()
+}
+
 /// "Zips" and input vector across an input combinator vector.
-pub fn zip_combinator(left: bool, remaining: Vec<bool>, combinators: &Vec<u8>) -> bool {
-    match remaining.len() {
-        0 => panic!("This shouldn't happen!"),
-        1 => compute_boolean(left, remaining[0], combinators[0]),
-        _ => compute_boolean(left, zip_combinator(remaining[0], remaining[1..].to_vec(), &combinators[1..].to_vec()), combinators[0])
+///
+/// The chain is right-nested, i.e. `c0(left, c1(r0, c2(r1, ...)))`, and is
+/// evaluated as an iterative fold from its innermost end without allocating.
+/// An empty chain (that of a neuron reading a single input) yields `left`.
+pub fn zip_combinator(left: bool, remaining: &[bool], combinators: &[Combinator]) -> bool {
+    if cfg!(feature = "checked-apply") && combinators.len() < remaining.len() {
+        panic!(
+            "Cannot zip {} remaining inputs across only {} input combinators!",
+            remaining.len(), combinators.len()
+        );
     }
+    let (&last, rest) = match remaining.split_last() {
+        Some(split) => split,
+        None => return left
+    };
+    let acc = rest.iter().zip(combinators[1..].iter()).rev()
+        .fold(last, |acc, (&l, &c)| compute_boolean(l, acc, c));
+    compute_boolean(left, acc, combinators[0])
 }
@@ -0,0 +1,97 @@
+//! Contains the `TrainObserver` trait through which progress reports are
+//! delivered from the training loops of `bnl`, for logging, progress bars or
+//! early stopping, along with the built-in `EarlyStopping` observer.
+//!
+//! Observers are invoked once per generation of `Population::evolve_observed`
+//! and once per candidate mutation of `Network::train_observed`, and may stop
+//! training early by returning `Control::Stop`. Any closure taking the
+//! statistics of a generation and returning a `Control` is an observer.
+
+/// Represents whether training should continue after a call to an observer.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Control {
+    /// Training continues as usual.
+    Continue,
+
+    /// Training stops, as if it had run for every generation.
+    Stop
+}
+
+/// Represents an observer which stops training once the best error has not
+/// improved for a number of consecutive generations.
+#[derive(Clone,Debug)]
+pub struct EarlyStopping {
+    /// The lowest error observed so far.
+    best: Option<usize>,
+
+    /// The number of consecutive generations without improvement after which
+    /// training stops.
+    pub patience: usize,
+
+    /// The number of consecutive generations observed without improvement.
+    stale: usize
+}
+
+/// Implements custom methods for early stopping observers.
+impl EarlyStopping {
+    /// Creates a new early stopping observer with the specified patience.
+    pub fn new(patience: usize) -> Self {
+        EarlyStopping {
+            best: None,
+            patience,
+            stale: 0
+        }
+    }
+}
+
+/// Allows early stopping observers to observe training.
+impl TrainObserver for EarlyStopping {
+    fn observe(&mut self, stats: &GenerationStats) -> Control {
+        if self.best.is_none_or(|b| stats.best_error < b) {
+            self.best = Some(stats.best_error);
+            self.stale = 0;
+        } else {
+            self.stale += 1;
+        }
+        if self.stale >= self.patience { Control::Stop } else { Control::Continue }
+    }
+}
+
+/// Represents the statistics of a single generation (or, for hill climbing,
+/// candidate mutation) of training.
+#[derive(Clone,Debug,PartialEq)]
+pub struct GenerationStats {
+    /// The lowest error within the generation (for hill climbing, the error of
+    /// the current network).
+    pub best_error: usize,
+
+    /// The total number of networks evaluated so far.
+    pub evaluations: usize,
+
+    /// The index of the generation, counting from zero for the initial one.
+    pub generation: usize,
+
+    /// The mean error within the generation (for hill climbing, the error of
+    /// the latest candidate).
+    pub mean_error: f64,
+
+    /// The number of parameters mutated while producing the generation.
+    pub mutations: usize
+}
+
+/// Represents an observer of the progress of training.
+pub trait TrainObserver {
+    /// Observes the statistics of the latest generation, returning whether
+    /// training should continue.
+    fn observe(&mut self, stats: &GenerationStats) -> Control;
+}
+
+/// Allows closures to be used as observers.
+impl<F> TrainObserver for F
+where
+    F: FnMut(&GenerationStats) -> Control
+{
+    fn observe(&mut self, stats: &GenerationStats) -> Control {
+        self(stats)
+    }
+}
@@ -0,0 +1,152 @@
+//! Contains the `OnlineTrainer` type, which trains a `bnl` network on a stream
+//! of samples arriving one at a time, for data whose distribution may change
+//! over time.
+//!
+//! Whenever the network gets an observed sample wrong, the trainer tries a
+//! bounded number of single-parameter mutations (as made by `Network::train`)
+//! and keeps the first which lowers the error of the network on a small
+//! memory of the most recent samples, so that it adapts to new samples
+//! without forgetting everything it learned from those just before them. The
+//! fraction of the samples in a sliding window which the network got wrong
+//! when they arrived is tracked as an estimate of its current error, so that
+//! callers can monitor drift.
+
+use crate::batch::BitDataset;
+use crate::network::{BiasMode, Network};
+use crate::train::{combinator_pool, mutate};
+use rand::Rng;
+use std::collections::VecDeque;
+
+/// Represents the options controlling an `OnlineTrainer`.
+#[derive(Clone,Debug)]
+pub struct OnlineOptions {
+    /// The maximum number of candidate mutations tried after each sample the
+    /// network gets wrong.
+    pub attempts: usize,
+
+    /// Which parts of the bias stage of each neuron may be mutated.
+    pub bias_mode: BiasMode,
+
+    /// The number of most recent samples on which candidate mutations are
+    /// evaluated.
+    pub memory: usize,
+
+    /// Whether mutations may only introduce monotone combinators (see
+    /// `TrainOptions::monotone`).
+    pub monotone: bool,
+
+    /// The number of most recent samples over which the error estimate of
+    /// the trainer is measured.
+    pub window: usize
+}
+
+/// Provides reasonable default online training options.
+impl Default for OnlineOptions {
+    fn default() -> Self {
+        OnlineOptions {
+            attempts: 32,
+            bias_mode: BiasMode::Learned,
+            memory: 64,
+            monotone: false,
+            window: 256
+        }
+    }
+}
+
+/// Represents a network being trained on a stream of samples.
+#[derive(Clone,Debug)]
+pub struct OnlineTrainer {
+    /// The most recent samples, up to `OnlineOptions::memory` of them.
+    memory: VecDeque<(Vec<bool>, Vec<bool>)>,
+
+    /// The network being trained.
+    network: Network,
+
+    /// The number of samples observed so far.
+    observed: usize,
+
+    /// The options controlling training.
+    pub options: OnlineOptions,
+
+    /// Whether the network got each of the most recent samples wrong when it
+    /// was observed, up to `OnlineOptions::window` of them.
+    window: VecDeque<bool>
+}
+
+/// Implements custom methods for online trainers.
+impl OnlineTrainer {
+    /// Returns the trained network, consuming this trainer.
+    pub fn into_network(self) -> Network {
+        self.network
+    }
+
+    /// Returns the network being trained.
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+
+    /// Creates a new online trainer continuing from the specified network.
+    pub fn new(network: Network, options: OnlineOptions) -> Self {
+        OnlineTrainer {
+            memory: VecDeque::new(),
+            network,
+            observed: 0,
+            options,
+            window: VecDeque::new()
+        }
+    }
+
+    /// Observes a single sample, adapting the network if it gets the sample
+    /// wrong, and returns whether it got the sample right before adapting.
+    pub fn observe(&mut self, input: &[bool], expected: &[bool]) -> bool {
+        self.observe_with_rng(input, expected, &mut crate::random::rng())
+    }
+
+    /// Observes a single sample as with `OnlineTrainer::observe`, drawing the
+    /// candidate mutations from the specified generator.
+    pub fn observe_with_rng<R: Rng + ?Sized>(&mut self, input: &[bool], expected: &[bool], rng: &mut R) -> bool {
+        let correct = self.network.apply(input) == expected;
+        self.observed += 1;
+        push_bounded(&mut self.window, !correct, self.options.window);
+        push_bounded(&mut self.memory, (input.to_vec(), expected.to_vec()), self.options.memory.max(1));
+        if correct {
+            return true;
+        }
+        let memory = BitDataset::from_samples(self.memory.make_contiguous());
+        let current = memory.error(&self.network);
+        for _i in 0..self.options.attempts {
+            let mut candidate = self.network.clone();
+            mutate(&mut candidate, self.options.bias_mode, combinator_pool(self.options.monotone), rng);
+            if memory.error(&candidate) < current {
+                self.network = candidate;
+                break;
+            }
+        }
+        false
+    }
+
+    /// Returns the number of samples observed so far.
+    pub fn observed(&self) -> usize {
+        self.observed
+    }
+
+    /// Returns the fraction of the samples in the window of the most recent
+    /// ones which the network got wrong when they were observed, or `0.0` if
+    /// no samples have been observed.
+    pub fn window_error(&self) -> f64 {
+        if self.window.is_empty() {
+            0.0
+        } else {
+            self.window.iter().filter(|&&w| w).count() as f64 / self.window.len() as f64
+        }
+    }
+}
+
+/// Appends the specified value to the specified queue, dropping its oldest
+/// values so that it holds at most the specified number of them.
+fn push_bounded<T>(queue: &mut VecDeque<T>, value: T, capacity: usize) {
+    queue.push_back(value);
+    while queue.len() > capacity {
+        queue.pop_front();
+    }
+}
@@ -0,0 +1,127 @@
+//! Contains routines for evaluating `bnl` networks on all cores via `rayon`.
+//!
+//! Datasets are split into chunks of `CHUNK_LEN` samples, each of which is
+//! evaluated bit-parallel (see the `batch` module) on its own thread. When
+//! this feature is enabled, `Population::evolve` also scores the networks of
+//! each generation concurrently.
+//!
+//! Large CSV datasets may likewise be loaded with `Dataset::from_csv_par`,
+//! which reads its file in chunks of `LOAD_CHUNK_LEN` lines and parses each
+//! chunk on all cores before reading the next, so that the whole file is never
+//! held in memory as text.
+
+use crate::arena::NetworkArena;
+use crate::batch::{BitBatch, BitDataset};
+use crate::dataset::{check_widths, data_line, invalid_data, parse_csv_fields, split_csv_row, Dataset};
+use crate::network::Network;
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+/// The number of samples evaluated together by a single task.
+pub const CHUNK_LEN: usize = 4096;
+
+/// The number of lines of a dataset file read before being parsed
+/// concurrently.
+pub const LOAD_CHUNK_LEN: usize = 65536;
+
+/// Implements parallel loading on datasets.
+impl Dataset {
+    /// Loads a dataset from the specified CSV file as with
+    /// `Dataset::from_csv`, parsing it concurrently as it is read.
+    pub fn from_csv_par<P: AsRef<Path>>(path: P, output_len: usize) -> io::Result<Self> {
+        #[cfg(feature = "profiling")]
+        let _timer = crate::profile::timer(crate::profile::Phase::Load);
+        let reader = BufReader::new(File::open(&path)?);
+        Dataset::read_csv_par(reader, output_len).map_err(|e| match e.kind() {
+            io::ErrorKind::InvalidData => invalid_data(&path, e.to_string()),
+            _ => e
+        })
+    }
+
+    /// Reads a dataset in the CSV format of `Dataset::parse_csv` from the
+    /// specified reader, parsing it concurrently in chunks of `LOAD_CHUNK_LEN`
+    /// lines as they are read.
+    ///
+    /// Malformed rows are reported as errors of kind `InvalidData` naming the
+    /// first line at fault.
+    pub fn read_csv_par<R: BufRead>(mut reader: R, output_len: usize) -> io::Result<Self> {
+        #[cfg(feature = "profiling")]
+        let _timer = crate::profile::timer(crate::profile::Phase::Load);
+        let mut samples: Vec<(Vec<bool>, Vec<bool>)> = Vec::new();
+        let mut first = true;
+        let mut text = String::new();
+        let mut ends: Vec<usize> = Vec::with_capacity(LOAD_CHUNK_LEN);
+        let mut line_offset = 1;
+        loop {
+            text.clear();
+            ends.clear();
+            while ends.len() < LOAD_CHUNK_LEN && reader.read_line(&mut text)? > 0 {
+                ends.push(text.len());
+            }
+            if ends.is_empty() {
+                return Ok(Dataset { samples });
+            }
+            let rows: Vec<(usize, Result<Vec<bool>, String>)> = ends.par_iter()
+                .enumerate()
+                .filter_map(|(j, &end)| {
+                    let start = if j == 0 { 0 } else { ends[j - 1] };
+                    data_line(&text[start..end]).map(|l| (line_offset + j, parse_csv_fields(l)))
+                })
+                .collect();
+            line_offset += ends.len();
+            for (i, row) in rows {
+                let row = match row {
+                    Ok(row) => row,
+                    Err(_) if first => {
+                        first = false;
+                        continue;
+                    },
+                    Err(e) => return Err(invalid_line(i, e))
+                };
+                first = false;
+                let (input, expected) = split_csv_row(row, output_len).map_err(|e| invalid_line(i, e))?;
+                check_widths(&samples, &input, &expected).map_err(|e| invalid_line(i, e))?;
+                samples.push((input, expected));
+            }
+        }
+    }
+}
+
+/// Implements parallel evaluation on `bnl` networks.
+impl Network {
+    /// "Applies" this network to each of the specified inputs concurrently,
+    /// returning their outputs in order.
+    pub fn apply_many_par(&self, inputs: &[Vec<bool>]) -> Vec<Vec<bool>> {
+        inputs.par_chunks(CHUNK_LEN)
+            .flat_map_iter(|chunk| self.apply_batch(&BitBatch::from_samples(chunk)).to_samples())
+            .collect()
+    }
+
+    /// Computes the total error of this network on the specified dataset
+    /// concurrently (see `train::error`).
+    pub fn error_par(&self, dataset: &[(Vec<bool>, Vec<bool>)]) -> usize {
+        dataset.par_chunks(CHUNK_LEN)
+            .map(|chunk| BitDataset::from_samples(chunk).error(self))
+            .sum()
+    }
+}
+
+/// Computes the total error of each of the specified networks on the specified
+/// packed dataset concurrently.
+pub fn errors_par(networks: &[&Network], dataset: &BitDataset) -> Vec<usize> {
+    networks.par_iter().map(|n| dataset.error(n)).collect()
+}
+
+/// Computes the total error of each of the specified genomes of the specified
+/// arena on the specified packed dataset concurrently.
+pub fn genome_errors_par(arena: &NetworkArena, genomes: &[usize], dataset: &BitDataset) -> Vec<usize> {
+    genomes.par_iter().map(|&g| arena.error(g, dataset)).collect()
+}
+
+/// Describes the specified parse error of the specified line as an I/O error
+/// of kind `InvalidData`.
+fn invalid_line(line: usize, e: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line, e))
+}
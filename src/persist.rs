@@ -0,0 +1,218 @@
+//! Contains routines for saving `bnl` networks to and loading them from
+//! files, either as human-readable JSON or as compact bincode.
+//!
+//! Combinator values above `15` are rejected when loading, since no
+//! combinator has them as its discriminant. Files written by other tools may
+//! instead be loaded leniently (see `Network::from_bytes_lenient`), which
+//! normalizes such values modulo `16` and reports a warning for each.
+//!
+//! Every network loaded is checked with `Network::validate`, so that
+//! truncated or malformed files, layers without neurons and neurons whose
+//! arity differs from the width of the previous layer are rejected rather
+//! than panicking later. Such files fail with an I/O error of kind
+//! `InvalidData` wrapping an `Error::CorruptModel`, which gives the byte
+//! offset at which decoding failed (if it did) and may be recovered with
+//! `io::Error::get_ref`.
+//!
+//! A network may be saved along with `Metadata` describing it, such as the
+//! hash of the dataset it was trained on (see `Network::save_with_metadata`).
+//! In JSON the metadata is an additional `metadata` field of the network's
+//! object, and in bincode it follows the network's bytes, so that files with
+//! metadata still load as plain networks and files without it load with
+//! empty metadata.
+
+use crate::combinator::{deserialize_leniently, Combinator};
+use crate::error::Error;
+use crate::network::Network;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Represents the file formats in which networks may be saved.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Format {
+    /// The compact binary format of the `bincode` crate.
+    Bincode,
+
+    /// Human-readable JSON.
+    Json
+}
+
+/// Implements custom methods for file formats.
+impl Format {
+    /// Determines the format of the specified path from its extension, which
+    /// is JSON for `.json` files and bincode otherwise.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some(e) if e.eq_ignore_ascii_case("json") => Format::Json,
+            _ => Format::Bincode
+        }
+    }
+}
+
+/// Represents the metadata saved along with a network.
+#[derive(Clone,Debug,Default,PartialEq,Eq,serde::Serialize,serde::Deserialize)]
+pub struct Metadata {
+    /// The hash of the dataset the network was trained on (see
+    /// `Dataset::hash`), if known.
+    #[serde(default)]
+    pub dataset_hash: Option<u64>
+}
+
+/// Implements saving and loading on `bnl` networks.
+impl Network {
+    /// Deserializes a network from the specified bytes in the specified
+    /// format, checking that the result is a valid network.
+    ///
+    /// Malformed bytes are reported along with the byte offset at which
+    /// decoding failed, and invalid networks with the layer and neuron at
+    /// fault (see `Error::CorruptModel`).
+    pub fn from_bytes(bytes: &[u8], format: Format) -> io::Result<Self> {
+        let network: Network = deserialize(bytes, format)?;
+        network.validate().map_err(|e| corrupt_model(None, e))?;
+        Ok(network)
+    }
+
+    /// Deserializes a network as with `Network::from_bytes`, but normalizes
+    /// combinator values above `15` modulo `16` instead of rejecting them,
+    /// returning a warning describing each value normalized.
+    pub fn from_bytes_lenient(bytes: &[u8], format: Format) -> io::Result<(Self, Vec<String>)> {
+        let (network, wrapped) = deserialize_leniently(|| deserialize::<Network>(bytes, format));
+        let network = network?;
+        network.validate().map_err(|e| corrupt_model(None, e))?;
+        let warnings = wrapped.into_iter()
+            .map(|v| format!("combinator value {} is out of range and was read as {}", v, Combinator::from_u8_wrapping(v)))
+            .collect();
+        Ok((network, warnings))
+    }
+
+    /// Deserializes a network as with `Network::from_bytes`, along with the
+    /// metadata saved with it (see `Network::to_bytes_with_metadata`), which
+    /// is empty if none was saved.
+    pub fn from_bytes_with_metadata(bytes: &[u8], format: Format) -> io::Result<(Self, Metadata)> {
+        #[derive(serde::Deserialize)]
+        struct Saved {
+            #[serde(default)]
+            metadata: Metadata
+        }
+        #[cfg(feature = "profiling")]
+        let _timer = crate::profile::timer(crate::profile::Phase::Serialize);
+        let (network, metadata) = match format {
+            Format::Bincode => {
+                let (network, end): (Network, usize) = deserialize_bincode(bytes, 0)?;
+                let metadata = if end == bytes.len() { Metadata::default() } else { deserialize_bincode(bytes, end)?.0 };
+                (network, metadata)
+            },
+            Format::Json => (deserialize::<Network>(bytes, format)?, deserialize::<Saved>(bytes, format)?.metadata)
+        };
+        network.validate().map_err(|e| corrupt_model(None, e))?;
+        Ok((network, metadata))
+    }
+
+    /// Loads a network from the specified file, in the format given by its
+    /// extension (see `Format::from_path`).
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let format = Format::from_path(&path);
+        Network::from_bytes(&fs::read(path)?, format)
+    }
+
+    /// Loads a network from the specified file as with `Network::load`, but
+    /// leniently (see `Network::from_bytes_lenient`).
+    pub fn load_lenient<P: AsRef<Path>>(path: P) -> io::Result<(Self, Vec<String>)> {
+        let format = Format::from_path(&path);
+        Network::from_bytes_lenient(&fs::read(path)?, format)
+    }
+
+    /// Loads a network from the specified file as with `Network::load`, along
+    /// with the metadata saved with it (see `Network::from_bytes_with_metadata`).
+    pub fn load_with_metadata<P: AsRef<Path>>(path: P) -> io::Result<(Self, Metadata)> {
+        let format = Format::from_path(&path);
+        Network::from_bytes_with_metadata(&fs::read(path)?, format)
+    }
+
+    /// Saves this network to the specified file, in the format given by its
+    /// extension (see `Format::from_path`).
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let format = Format::from_path(&path);
+        fs::write(path, self.to_bytes(format)?)
+    }
+
+    /// Saves this network along with the specified metadata to the specified
+    /// file, in the format given by its extension (see `Format::from_path`).
+    pub fn save_with_metadata<P: AsRef<Path>>(&self, path: P, metadata: &Metadata) -> io::Result<()> {
+        let format = Format::from_path(&path);
+        fs::write(path, self.to_bytes_with_metadata(metadata, format)?)
+    }
+
+    /// Serializes this network into bytes in the specified format.
+    pub fn to_bytes(&self, format: Format) -> io::Result<Vec<u8>> {
+        #[cfg(feature = "profiling")]
+        let _timer = crate::profile::timer(crate::profile::Phase::Serialize);
+        match format {
+            Format::Bincode => bincode::serialize(self).map_err(invalid_data),
+            Format::Json => serde_json::to_vec_pretty(self).map_err(invalid_data)
+        }
+    }
+
+    /// Serializes this network along with the specified metadata into bytes in
+    /// the specified format.
+    pub fn to_bytes_with_metadata(&self, metadata: &Metadata, format: Format) -> io::Result<Vec<u8>> {
+        #[derive(serde::Serialize)]
+        struct Saved<'a> {
+            #[serde(flatten)]
+            network: &'a Network,
+            metadata: &'a Metadata
+        }
+        #[cfg(feature = "profiling")]
+        let _timer = crate::profile::timer(crate::profile::Phase::Serialize);
+        match format {
+            Format::Bincode => {
+                let mut bytes = self.to_bytes(format)?;
+                bytes.extend(bincode::serialize(metadata).map_err(invalid_data)?);
+                Ok(bytes)
+            },
+            Format::Json => serde_json::to_vec_pretty(&Saved { network: self, metadata }).map_err(invalid_data)
+        }
+    }
+}
+
+/// Wraps the specified problem with a saved network, found at the specified
+/// byte offset if any, as an I/O error of kind `InvalidData`.
+pub(crate) fn corrupt_model<E: ToString>(offset: Option<usize>, e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, Error::CorruptModel { offset, reason: e.to_string() })
+}
+
+/// Deserializes a network (or a value containing one) from the specified
+/// bytes in the specified format, without checking that it is valid.
+pub(crate) fn deserialize<T: DeserializeOwned>(bytes: &[u8], format: Format) -> io::Result<T> {
+    #[cfg(feature = "profiling")]
+    let _timer = crate::profile::timer(crate::profile::Phase::Serialize);
+    match format {
+        Format::Bincode => deserialize_bincode(bytes, 0).map(|(value, _)| value),
+        Format::Json => serde_json::from_slice(bytes).map_err(|e| corrupt_model(Some(json_offset(bytes, &e)), e))
+    }
+}
+
+/// Deserializes a value from the specified bincode bytes starting at the
+/// specified offset, returning it along with the offset at which it ends.
+fn deserialize_bincode<T: DeserializeOwned>(bytes: &[u8], start: usize) -> io::Result<(T, usize)> {
+    let mut rest = &bytes[start..];
+    let value = bincode::deserialize_from(&mut rest).map_err(|e| corrupt_model(Some(bytes.len() - rest.len()), e))?;
+    Ok((value, bytes.len() - rest.len()))
+}
+
+/// Computes the byte offset within the specified JSON bytes of the position
+/// reported by the specified error.
+fn json_offset(bytes: &[u8], e: &serde_json::Error) -> usize {
+    let line_start: usize = bytes.split(|&b| b == b'\n')
+        .take(e.line().saturating_sub(1))
+        .map(|l| l.len() + 1)
+        .sum();
+    (line_start + e.column().saturating_sub(1)).min(bytes.len())
+}
+
+/// Wraps the specified error as an I/O error of kind `InvalidData`.
+pub(crate) fn invalid_data<E: ToString>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
@@ -0,0 +1,806 @@
+//! Contains a genetic algorithm for training `bnl` networks.
+//!
+//! A population of networks of identical shape is evolved over a number of
+//! generations. Each generation, the fittest networks (those with the lowest
+//! error under `train::error`) are carried over unchanged ("elitism"), and the
+//! rest of the next generation is bred from parents chosen by tournament
+//! selection, recombined by crossover and then mutated. Selection may
+//! optionally reward behavioral novelty in addition to (or instead of) low
+//! error, see `NoveltyOptions`, and offspring may be pre-screened by a
+//! surrogate model so that only promising ones are evaluated, see
+//! `SurrogateOptions`. Networks are scored concurrently when the `parallel`
+//! feature is enabled, and errors may also be looked up in a persistent
+//! `EvalCache`, see `Population::evolve_cached`.
+//!
+//! The networks of each generation are stored contiguously in a
+//! `NetworkArena` (see the `arena` module), where they are scored, recombined
+//! and mutated without being converted into nested `Network`s, and the arena
+//! of the previous generation is reused to breed the next one into.
+
+//!
+//! Evolution may optionally record the ancestry of every network it creates
+//! (see `Lineage`), periodic snapshots of the whole population and periodic
+//! logs of the samples its fittest network gets wrong (see `FailureLog`), for
+//! studying how solutions emerge, may keep the best distinct networks it
+//! encounters (see `HallOfFame`), and may report the statistics of each
+//! generation to a `TrainObserver` (see `Population::evolve_observed`).
+
+use crate::analysis::hamming_distance;
+use crate::arena::NetworkArena;
+use crate::batch::{BitBatch, BitDataset};
+use crate::cache::{dataset_hash, network_hash, EvalCache};
+use crate::crossover::aligned_crossover_with_rng;
+use crate::ensemble::Ensemble;
+use crate::hall_of_fame::HallOfFame;
+use crate::network::Network;
+use crate::observe::{Control, GenerationStats, TrainObserver};
+use crate::random::Map;
+use crate::surrogate::{Surrogate, SurrogateOptions};
+use crate::train::{combinator_pool, Batches};
+use rand::{Rng, RngCore};
+use std::fmt;
+use std::sync::Arc;
+
+/// Represents the crossover operators available to `Population::evolve`.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Crossover {
+    /// Recombines parents with `crossover::aligned_crossover`, matching
+    /// neurons by their behavior over up to the given number of inputs.
+    Aligned(usize),
+
+    /// Recombines parents by swapping whole layers.
+    Layer,
+
+    /// Recombines parents by swapping individual neurons.
+    Neuron
+}
+
+/// Represents the options controlling `Population::evolve`.
+#[derive(Clone,Debug)]
+pub struct EvolveOptions {
+    /// If specified, parents are selected by their errors on a fresh
+    /// mini-batch of this many samples each generation, drawn with equal
+    /// numbers of samples of each expected output pattern (see
+    /// `data::BalancedSampler`), rather than by their errors on the whole
+    /// dataset. Elites and the reported errors remain those on the whole
+    /// dataset.
+    pub balanced_batch: Option<usize>,
+
+    /// Whether to record the fittest network of every generation in
+    /// `EvolveReport::champions` (see `report::churn_heatmap`).
+    pub champions: bool,
+
+    /// The crossover operator used to recombine parents.
+    pub crossover: Crossover,
+
+    /// The probability that a child is bred by crossover of two parents,
+    /// rather than being a copy of a single parent.
+    pub crossover_rate: f64,
+
+    /// The number of the fittest networks carried over unchanged into each
+    /// new generation.
+    pub elitism: usize,
+
+    /// If specified, the indices of the samples of the dataset which the
+    /// fittest network of the generation gets wrong are recorded in
+    /// `EvolveReport::failures` every this many generations (starting with
+    /// the initial population), to reveal systematic failure modes.
+    pub failure_interval: Option<usize>,
+
+    /// If specified, the best distinct networks of up to this capacity
+    /// encountered during evolution are kept in `EvolveReport::hall_of_fame`.
+    pub hall_of_fame: Option<usize>,
+
+    /// Whether to record the ancestry of every network created during
+    /// evolution in `EvolveReport::lineage`.
+    pub lineage: bool,
+
+    /// Whether the networks of a new population are built only from monotone
+    /// combinators, and mutations may only introduce monotone combinators, so
+    /// that every network evolved computes a monotone function of its input
+    /// (see `Network::new_monotone`).
+    pub monotone: bool,
+
+    /// The probability with which each individual parameter (bias or
+    /// combinator) of a child is replaced by a random value.
+    pub mutation_rate: f64,
+
+    /// If specified, parents are selected by a combination of error and
+    /// behavioral novelty rather than by error alone.
+    pub novelty: Option<NoveltyOptions>,
+
+    /// If specified, offspring are pre-screened by a surrogate model of error
+    /// and only the most promising are evaluated on the dataset, with
+    /// offspring identical to already evaluated networks considered least
+    /// promising.
+    pub surrogate: Option<SurrogateOptions>,
+
+    /// If specified, parents are chosen by this selection strategy (such as
+    /// one looked up by name in a `registry::Registry`) rather than by
+    /// tournaments of `tournament_size` networks.
+    pub selection: Option<Arc<dyn Selection>>,
+
+    /// If specified, the whole population is recorded in
+    /// `EvolveReport::snapshots` every this many generations (starting with
+    /// the initial population).
+    pub snapshot_interval: Option<usize>,
+
+    /// The number of networks competing in each tournament, the fittest of
+    /// which becomes a parent.
+    pub tournament_size: usize
+}
+
+/// Provides reasonable default evolution options.
+impl Default for EvolveOptions {
+    fn default() -> Self {
+        EvolveOptions {
+            balanced_batch: None,
+            champions: false,
+            crossover: Crossover::Neuron,
+            crossover_rate: 0.7,
+            elitism: 2,
+            failure_interval: None,
+            hall_of_fame: None,
+            lineage: false,
+            monotone: false,
+            mutation_rate: 0.02,
+            novelty: None,
+            selection: None,
+            snapshot_interval: None,
+            surrogate: None,
+            tournament_size: 3
+        }
+    }
+}
+
+/// Represents the outcome of a call to `Population::evolve`.
+#[derive(Clone,Debug)]
+pub struct EvolveReport {
+    /// The fittest network encountered during evolution.
+    pub best: Network,
+
+    /// The error of the fittest network encountered during evolution.
+    pub best_error: usize,
+
+    /// The number of networks evaluated on the dataset.
+    pub evaluations: usize,
+
+    /// The identifier of the fittest network encountered during evolution,
+    /// as used by `lineage` and `snapshots`.
+    pub best_id: usize,
+
+    /// The fittest network of each evaluated generation, if requested via
+    /// `EvolveOptions::champions`.
+    pub champions: Vec<Network>,
+
+    /// The samples got wrong by the fittest network of every few
+    /// generations, if requested via `EvolveOptions::failure_interval`.
+    pub failures: Vec<FailureLog>,
+
+    /// The number of generations evaluated.
+    pub generations: usize,
+
+    /// The best distinct networks encountered during evolution, if requested
+    /// via `EvolveOptions::hall_of_fame`.
+    pub hall_of_fame: Option<HallOfFame>,
+
+    /// The lowest error within the population at each evaluated generation.
+    pub history: Vec<usize>,
+
+    /// The ancestry of every network created during evolution, if requested
+    /// via `EvolveOptions::lineage`.
+    pub lineage: Option<Lineage>,
+
+    /// The recorded snapshots of the population, if requested via
+    /// `EvolveOptions::snapshot_interval`.
+    pub snapshots: Vec<Snapshot>
+}
+
+/// Represents the samples of the dataset got wrong by the fittest network of
+/// a single generation.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct FailureLog {
+    /// The error of the fittest network.
+    pub error: usize,
+
+    /// The generation of the fittest network.
+    pub generation: usize,
+
+    /// The indices of the samples for which the output of the fittest network
+    /// differs from the expected output, in ascending order.
+    pub samples: Vec<usize>
+}
+
+/// Formats failure logs as a single line listing the indices of the failed
+/// samples.
+impl fmt::Display for FailureLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let samples: Vec<String> = self.samples.iter().map(|s| s.to_string()).collect();
+        write!(f, "generation {} (error {}): {}", self.generation, self.error, samples.join(" "))
+    }
+}
+
+/// Represents a single network created during evolution.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct Individual {
+    /// The error of the network on the dataset.
+    pub error: usize,
+
+    /// A hash of the structure of the network, for cheaply identifying
+    /// structurally identical networks.
+    pub fingerprint: u64,
+
+    /// The generation in which the network was created (counting from zero,
+    /// the initial population).
+    pub generation: usize,
+
+    /// The unique identifier of the network.
+    pub id: usize,
+
+    /// The identifiers of the one or two parents the network was bred from,
+    /// which is empty for the initial population.
+    pub parents: Vec<usize>
+}
+
+/// Represents the recorded ancestry of every network created during
+/// evolution. Networks carried over by elitism keep their identifier, so each
+/// appears only once.
+#[derive(Clone,Debug,Default)]
+pub struct Lineage {
+    /// Every recorded network, by identifier.
+    pub individuals: Map<usize, Individual>
+}
+
+/// Implements custom methods for lineages.
+impl Lineage {
+    /// Returns the network with the specified identifier along with all of its
+    /// recorded ancestors, ordered by generation (oldest first) and then by
+    /// identifier.
+    pub fn ancestry(&self, id: usize) -> Vec<&Individual> {
+        let mut seen: Vec<usize> = Vec::new();
+        let mut pending: Vec<usize> = vec![id];
+        while let Some(next) = pending.pop() {
+            if seen.contains(&next) {
+                continue;
+            }
+            if let Some(individual) = self.individuals.get(&next) {
+                seen.push(next);
+                pending.extend(individual.parents.iter().copied());
+            }
+        }
+        let mut res: Vec<&Individual> = seen.iter().map(|i| &self.individuals[i]).collect();
+        res.sort_by_key(|i| (i.generation, i.id));
+        res
+    }
+
+    /// Returns the chain of first parents leading to the network with the
+    /// specified identifier, from its founding member of the initial
+    /// population to the network itself.
+    pub fn primary_line(&self, id: usize) -> Vec<&Individual> {
+        let mut res: Vec<&Individual> = Vec::new();
+        let mut current = self.individuals.get(&id);
+        while let Some(individual) = current {
+            res.push(individual);
+            current = individual.parents.first().and_then(|p| self.individuals.get(p));
+        }
+        res.reverse();
+        res
+    }
+}
+
+/// Represents the options controlling novelty search within
+/// `Population::evolve`.
+///
+/// The behavior of a network is the concatenation of its outputs over a set
+/// of probe inputs, and its novelty is the mean Hamming distance between its
+/// behavior and the `k` nearest behaviors among the rest of the population
+/// and an archive of past behaviors. Selection then minimizes
+/// `fitness_weight * e - (1 - fitness_weight) * n`, where `e` and `n` are the
+/// error and novelty normalized to the range `[0, 1]`. Elitism and the
+/// reported best network remain based on error alone.
+#[derive(Clone,Debug)]
+pub struct NoveltyOptions {
+    /// The number of the most novel behaviors of each generation added to the
+    /// archive.
+    pub archive_per_generation: usize,
+
+    /// The weight given to error (rather than novelty) during selection,
+    /// where `0.0` yields pure novelty search.
+    pub fitness_weight: f64,
+
+    /// The number of nearest behaviors over which novelty is averaged.
+    pub k: usize,
+
+    /// The inputs over which behaviors are measured, or `None` to use the
+    /// inputs of the dataset.
+    pub probes: Option<Vec<Vec<bool>>>
+}
+
+/// Provides reasonable default novelty search options.
+impl Default for NoveltyOptions {
+    fn default() -> Self {
+        NoveltyOptions {
+            archive_per_generation: 1,
+            fitness_weight: 0.5,
+            k: 15,
+            probes: None
+        }
+    }
+}
+
+/// Represents a population of networks of identical shape.
+#[derive(Clone,Debug)]
+pub struct Population {
+    /// The networks making up the current generation, stored contiguously and
+    /// referred to by their index (see the `arena` module).
+    pub genomes: NetworkArena,
+
+    /// The options controlling evolution.
+    pub options: EvolveOptions,
+
+    /// The arena into which the next generation is bred, kept between
+    /// generations so that its arrays stay allocated.
+    spare: NetworkArena
+}
+
+/// Implements custom methods for populations.
+impl Population {
+    /// Replaces the networks of this population with the next generation, bred
+    /// according to the specified errors of the current networks (one per
+    /// network, in order).
+    ///
+    /// This performs a single generation of `Population::evolve` with
+    /// externally computed errors, such as errors on a changing set of test
+    /// cases. Novelty search, lineage and snapshots are not applied.
+    pub fn advance(&mut self, errors: &[usize]) {
+        self.advance_with_rng(errors, &mut crate::random::rng())
+    }
+
+    /// Replaces the networks of this population with the next generation as
+    /// with `Population::advance`, drawing random choices from the specified
+    /// generator.
+    pub fn advance_with_rng<R: Rng + ?Sized>(&mut self, errors: &[usize], rng: &mut R) {
+        let mut ranked: Vec<usize> = (0..self.genomes.len()).collect();
+        ranked.sort_by_key(|&i| errors[i]);
+        let scores: Vec<f64> = errors.iter().map(|&e| e as f64).collect();
+        let mut next = self.take_spare();
+        for &i in ranked.iter().take(self.options.elitism) {
+            next.push_from(&self.genomes, i);
+        }
+        self.breed(&scores, self.genomes.len() - next.len(), &mut next, rng);
+        self.spare = std::mem::replace(&mut self.genomes, next);
+    }
+
+    /// Breeds the specified number of children from the current networks into
+    /// the specified arena, choosing parents by tournament selection over the
+    /// specified scores (lower being fitter), and returning the indices of the
+    /// parents of each child, and the number of parameters mutated.
+    fn breed<R: Rng + ?Sized>(&self, scores: &[f64], count: usize, into: &mut NetworkArena, rng: &mut R) -> (Vec<Vec<usize>>, usize) {
+        let opts = &self.options;
+        let tournament = |mut rng: &mut R| match &opts.selection {
+            Some(selection) => selection.select(scores, &mut rng),
+            None => Tournament(opts.tournament_size).select(scores, &mut rng)
+        };
+        let mut res: Vec<Vec<usize>> = Vec::new();
+        let mut mutations = 0;
+        for _i in 0..count {
+            let ia = tournament(rng);
+            let (child, from) = if rng.gen_bool(opts.crossover_rate) {
+                let ib = tournament(rng);
+                let child = match opts.crossover {
+                    Crossover::Aligned(samples) => {
+                        let (a, b) = (self.genomes.get(ia), self.genomes.get(ib));
+                        into.push(&aligned_crossover_with_rng(&a, &b, samples, rng))
+                    },
+                    Crossover::Layer => into.push_layer_crossover_with_rng(&self.genomes, ia, ib, rng),
+                    Crossover::Neuron => into.push_uniform_crossover_with_rng(&self.genomes, ia, ib, rng)
+                };
+                (child, vec![ia, ib])
+            } else {
+                (into.push_from(&self.genomes, ia), vec![ia])
+            };
+            mutations += into.replace_parameters_from(child, opts.mutation_rate, combinator_pool(opts.monotone), rng);
+            res.push(from);
+        }
+        (res, mutations)
+    }
+
+    /// Creates an ensemble of the specified number of networks of this
+    /// population with the lowest error on the specified dataset (or of every
+    /// network, if the population is smaller), such as after evolution.
+    ///
+    /// Panics if the population is empty.
+    pub fn ensemble(&self, dataset: &[(Vec<bool>, Vec<bool>)], size: usize) -> Ensemble {
+        let mut ranked: Vec<usize> = (0..self.genomes.len()).collect();
+        let errors = score(&self.genomes, &ranked, &BitDataset::from_samples(dataset));
+        ranked.sort_by_key(|&i| errors[i]);
+        Ensemble::new(ranked.iter().take(size.max(1)).map(|&i| self.genomes.get(i)).collect())
+    }
+
+    /// Evolves this population in place on the specified dataset for up to the
+    /// specified number of generations, stopping early if some network
+    /// reproduces every sample.
+    ///
+    /// Panics if the population is empty.
+    pub fn evolve(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], generations: usize) -> EvolveReport {
+        self.run(dataset, generations, None, None, &mut crate::random::rng())
+    }
+
+    /// Evolves this population as with `Population::evolve`, but looks up the
+    /// error of each network in the specified cache before evaluating it, and
+    /// records the errors of the networks it does evaluate there.
+    ///
+    /// `EvolveReport::evaluations` only counts the networks which were not
+    /// already cached. The new entries are not written to disk until
+    /// `EvalCache::flush` is called.
+    pub fn evolve_cached(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], generations: usize, cache: &mut EvalCache) -> EvolveReport {
+        self.run(dataset, generations, Some(cache), None, &mut crate::random::rng())
+    }
+
+    /// Evolves this population as with `Population::evolve_cached`, drawing
+    /// random choices from the specified generator.
+    pub fn evolve_cached_with_rng<R: Rng + ?Sized>(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], generations: usize, cache: &mut EvalCache, rng: &mut R) -> EvolveReport {
+        self.run(dataset, generations, Some(cache), None, rng)
+    }
+
+    /// Evolves this population as with `Population::evolve`, but passes the
+    /// statistics of each evaluated generation to the specified observer,
+    /// stopping early if it returns `Control::Stop`.
+    pub fn evolve_observed(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], generations: usize, observer: &mut dyn TrainObserver) -> EvolveReport {
+        self.run(dataset, generations, None, Some(observer), &mut crate::random::rng())
+    }
+
+    /// Evolves this population as with `Population::evolve_observed`, drawing
+    /// random choices from the specified generator.
+    pub fn evolve_observed_with_rng<R: Rng + ?Sized>(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], generations: usize, observer: &mut dyn TrainObserver, rng: &mut R) -> EvolveReport {
+        self.run(dataset, generations, None, Some(observer), rng)
+    }
+
+    /// Evolves this population as with `Population::evolve`, drawing random
+    /// choices from the specified generator.
+    pub fn evolve_with_rng<R: Rng + ?Sized>(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], generations: usize, rng: &mut R) -> EvolveReport {
+        self.run(dataset, generations, None, None, rng)
+    }
+
+    /// Creates a new population of the specified networks, which should be of
+    /// identical shape.
+    pub fn from_networks(networks: &[Network], options: EvolveOptions) -> Self {
+        let mut genomes = NetworkArena::new();
+        for n in networks {
+            genomes.push(n);
+        }
+        Population {
+            genomes,
+            options,
+            spare: NetworkArena::new()
+        }
+    }
+
+    /// Determines whether this population contains no networks.
+    pub fn is_empty(&self) -> bool {
+        self.genomes.is_empty()
+    }
+
+    /// Returns the number of networks in this population.
+    pub fn len(&self) -> usize {
+        self.genomes.len()
+    }
+
+    /// Returns the network at the specified index of this population (see
+    /// `NetworkArena::get`).
+    ///
+    /// Panics if there is no such network.
+    pub fn network(&self, index: usize) -> Network {
+        self.genomes.get(index)
+    }
+
+    /// Returns every network of this population, in order.
+    pub fn networks(&self) -> Vec<Network> {
+        self.genomes.to_networks()
+    }
+
+    /// Creates a new population of the specified number of random networks of
+    /// the specified input length and layer lengths.
+    pub fn new(size: usize, input_len: usize, layer_lengths: Vec<usize>, options: EvolveOptions) -> Self {
+        Population::new_with_rng(size, input_len, layer_lengths, options, &mut crate::random::rng())
+    }
+
+    /// Creates a new population as with `Population::new`, drawing the
+    /// parameters of its networks from the specified generator.
+    pub fn new_with_rng<R: Rng + ?Sized>(size: usize, input_len: usize, layer_lengths: Vec<usize>, options: EvolveOptions, rng: &mut R) -> Self {
+        let networks: Vec<Network> = (0..size).map(|_| if options.monotone {
+            Network::new_monotone_with_rng(input_len, layer_lengths.clone(), rng)
+        } else {
+            Network::new_with_rng(input_len, layer_lengths.clone(), rng)
+        }).collect();
+        Population::from_networks(&networks, options)
+    }
+
+    /// Implements `Population::evolve` and its variants.
+    fn run<R: Rng + ?Sized>(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], generations: usize, mut cache: Option<&mut EvalCache>, mut observer: Option<&mut dyn TrainObserver>, rng: &mut R) -> EvolveReport {
+        if self.genomes.is_empty() {
+            panic!("Cannot evolve an empty population!");
+        }
+        let mut report = EvolveReport {
+            best: self.genomes.get(0),
+            best_error: usize::MAX,
+            best_id: 0,
+            champions: Vec::new(),
+            evaluations: 0,
+            failures: Vec::new(),
+            generations: 0,
+            hall_of_fame: self.options.hall_of_fame.map(HallOfFame::new),
+            history: Vec::new(),
+            lineage: if self.options.lineage { Some(Lineage::default()) } else { None },
+            snapshots: Vec::new()
+        };
+        let mut ids: Vec<usize> = (0..self.genomes.len()).collect();
+        let mut parents: Vec<Vec<usize>> = vec![Vec::new(); self.genomes.len()];
+        let mut next_id = self.genomes.len();
+        let mut archive: Vec<Vec<bool>> = Vec::new();
+        let max_error: usize = dataset.iter().map(|(_, expected)| expected.len()).sum();
+        let packed = BitDataset::from_samples(dataset);
+        let batches = Batches::new(dataset.to_vec(), self.options.balanced_batch);
+        let hash = if cache.is_some() { dataset_hash(dataset) } else { 0 };
+        let mut surrogate = self.options.surrogate.as_ref().map(|s| Surrogate::new(s.capacity, s.k));
+        let materialize = cache.is_some() || surrogate.is_some() || report.hall_of_fame.is_some() || report.lineage.is_some();
+        let mut mutations = 0;
+        loop {
+            let generation = report.generations;
+            let networks: Vec<Network> = if materialize { self.genomes.to_networks() } else { Vec::new() };
+            let mut errors: Vec<Option<usize>> = match cache.as_mut() {
+                Some(c) => networks.iter().map(|n| c.get(n, hash)).collect(),
+                None => vec![None; self.genomes.len()]
+            };
+            let missing: Vec<usize> = (0..errors.len()).filter(|&i| errors[i].is_none()).collect();
+            let fresh = score(&self.genomes, &missing, &packed);
+            for (&i, &e) in missing.iter().zip(fresh.iter()) {
+                if let Some(c) = cache.as_mut() {
+                    c.insert(&networks[i], hash, e);
+                }
+                errors[i] = Some(e);
+            }
+            report.evaluations += missing.len();
+            let errors: Vec<usize> = errors.into_iter().map(Option::unwrap).collect();
+            if let Some(s) = surrogate.as_mut() {
+                for (network, &e) in networks.iter().zip(errors.iter()) {
+                    s.observe(network, e);
+                }
+            }
+            let mut ranked: Vec<usize> = (0..self.genomes.len()).collect();
+            ranked.sort_by_key(|&i| errors[i]);
+            let fittest = ranked[0];
+            report.generations += 1;
+            report.history.push(errors[fittest]);
+            if self.options.champions {
+                report.champions.push(self.genomes.get(fittest));
+            }
+            if errors[fittest] < report.best_error {
+                report.best = self.genomes.get(fittest);
+                report.best_error = errors[fittest];
+                report.best_id = ids[fittest];
+            }
+            if let Some(hall) = report.hall_of_fame.as_mut() {
+                for &i in &ranked {
+                    hall.offer(&networks[i], errors[i]);
+                }
+            }
+            if let Some(lineage) = report.lineage.as_mut() {
+                for (i, network) in networks.iter().enumerate() {
+                    lineage.individuals.entry(ids[i]).or_insert_with(|| Individual {
+                        error: errors[i],
+                        fingerprint: fingerprint(network),
+                        generation,
+                        id: ids[i],
+                        parents: parents[i].clone()
+                    });
+                }
+            }
+            if self.options.failure_interval.is_some_and(|n| n > 0 && generation.is_multiple_of(n)) {
+                let distances = self.genomes.apply_batch(fittest, &packed.inputs).sample_distances(&packed.expected);
+                report.failures.push(FailureLog {
+                    error: errors[fittest],
+                    generation,
+                    samples: distances.into_iter().enumerate().filter(|&(_, e)| e > 0).map(|(i, _)| i).collect()
+                });
+            }
+            if self.options.snapshot_interval.is_some_and(|n| n > 0 && generation.is_multiple_of(n)) {
+                report.snapshots.push(Snapshot {
+                    errors: errors.clone(),
+                    generation,
+                    ids: ids.clone(),
+                    networks: self.genomes.to_networks()
+                });
+            }
+            if let Some(o) = observer.as_mut() {
+                let stats = GenerationStats {
+                    best_error: errors[fittest],
+                    evaluations: report.evaluations,
+                    generation,
+                    mean_error: errors.iter().sum::<usize>() as f64 / errors.len() as f64,
+                    mutations
+                };
+                if o.observe(&stats) == Control::Stop {
+                    break;
+                }
+            }
+            if report.generations >= generations || report.best_error == 0 {
+                break;
+            }
+            let batch_errors = batches.as_ref().map(|b| {
+                let batch = BitDataset::from_samples(&b.draw(rng));
+                let all: Vec<usize> = (0..self.genomes.len()).collect();
+                (score(&self.genomes, &all, &batch), batch.expected.len * batch.expected.width())
+            });
+            let (selected, max_error) = match &batch_errors {
+                Some((e, max)) => (e, *max),
+                None => (&errors, max_error)
+            };
+            let mut next = self.take_spare();
+            let opts = &self.options;
+            let scores: Vec<f64> = match &opts.novelty {
+                Some(novelty) => {
+                    let probes = match &novelty.probes {
+                        Some(p) => BitBatch::from_samples(p),
+                        None => packed.inputs.clone()
+                    };
+                    let behaviors: Vec<Vec<bool>> = (0..self.genomes.len())
+                        .map(|i| self.genomes.apply_batch(i, &probes).to_samples().concat())
+                        .collect();
+                    let novelties = novelty_scores(&behaviors, &archive, novelty.k);
+                    let mut order: Vec<usize> = (0..behaviors.len()).collect();
+                    order.sort_by(|&a, &b| novelties[b].partial_cmp(&novelties[a]).unwrap());
+                    archive.extend(order.iter().take(novelty.archive_per_generation).map(|&i| behaviors[i].clone()));
+                    let width = behaviors[0].len().max(1) as f64;
+                    selected.iter().zip(novelties.iter()).map(|(&e, &n)| {
+                        novelty.fitness_weight * e as f64 / max_error.max(1) as f64
+                            - (1.0 - novelty.fitness_weight) * n / width
+                    }).collect()
+                },
+                None => selected.iter().map(|&e| e as f64).collect()
+            };
+            let elites: Vec<usize> = ranked.iter().take(opts.elitism).copied().collect();
+            for &i in &elites {
+                next.push_from(&self.genomes, i);
+            }
+            let mut next_ids: Vec<usize> = elites.iter().map(|&i| ids[i]).collect();
+            let mut next_parents: Vec<Vec<usize>> = elites.iter().map(|&i| parents[i].clone()).collect();
+            let count = self.genomes.len() - next.len();
+            let children = match (&opts.surrogate, &surrogate) {
+                (Some(options), Some(s)) => {
+                    let mut bred = NetworkArena::new();
+                    let (from, m) = self.breed(&scores, count * options.oversample.max(1), &mut bred, rng);
+                    mutations = m;
+                    let mut candidates: Vec<(f64, usize)> = (0..bred.len())
+                        .map(|k| {
+                            let child = bred.get(k);
+                            let predicted = if s.has_observed(&child) { f64::INFINITY } else { s.predict(&child).unwrap_or(0.0) };
+                            (predicted, k)
+                        })
+                        .collect();
+                    candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+                    candidates.into_iter().take(count).map(|(_, k)| {
+                        next.push_from(&bred, k);
+                        from[k].clone()
+                    }).collect()
+                },
+                _ => {
+                    let (from, m) = self.breed(&scores, count, &mut next, rng);
+                    mutations = m;
+                    from
+                }
+            };
+            for from in children {
+                next_ids.push(next_id);
+                next_parents.push(from.iter().map(|&i| ids[i]).collect());
+                next_id += 1;
+            }
+            self.spare = std::mem::replace(&mut self.genomes, next);
+            ids = next_ids;
+            parents = next_parents;
+        }
+        report
+    }
+
+    /// Takes the spare arena of this population, cleared for breeding the next
+    /// generation into.
+    fn take_spare(&mut self) -> NetworkArena {
+        let mut spare = std::mem::take(&mut self.spare);
+        spare.clear();
+        spare
+    }
+}
+
+/// Represents a strategy for choosing the parents of each child bred by
+/// `Population::evolve`, which may be chosen at run time (see
+/// `EvolveOptions::selection`).
+pub trait Selection: fmt::Debug + Send + Sync {
+    /// Chooses the index of a parent among networks with the specified
+    /// scores, where lower scores are fitter.
+    fn select(&self, scores: &[f64], rng: &mut dyn RngCore) -> usize;
+}
+
+/// Represents a recording of the whole population at a single generation.
+#[derive(Clone,Debug)]
+pub struct Snapshot {
+    /// The errors of the networks on the dataset.
+    pub errors: Vec<usize>,
+
+    /// The generation at which the snapshot was taken.
+    pub generation: usize,
+
+    /// The identifiers of the networks.
+    pub ids: Vec<usize>,
+
+    /// The networks making up the population.
+    pub networks: Vec<Network>
+}
+
+/// Represents the selection strategy choosing the fittest of the given number
+/// of networks drawn uniformly at random (with replacement), as
+/// `Population::evolve` does by default.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct Tournament(pub usize);
+
+/// Allows tournaments to be used as selection strategies.
+impl Selection for Tournament {
+    fn select(&self, scores: &[f64], rng: &mut dyn RngCore) -> usize {
+        (0..self.0.max(1))
+            .map(|_| rng.gen_range(0, scores.len()))
+            .min_by(|&a, &b| scores[a].partial_cmp(&scores[b]).unwrap())
+            .unwrap()
+    }
+}
+
+/// Represents the selection strategy choosing uniformly at random among the
+/// given fraction of the fittest networks (at least one).
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Truncation(pub f64);
+
+/// Allows truncation to be used as a selection strategy.
+impl Selection for Truncation {
+    fn select(&self, scores: &[f64], rng: &mut dyn RngCore) -> usize {
+        let mut ranked: Vec<usize> = (0..scores.len()).collect();
+        ranked.sort_by(|&a, &b| scores[a].partial_cmp(&scores[b]).unwrap());
+        let kept = ((self.0 * scores.len() as f64).ceil() as usize).clamp(1, scores.len());
+        ranked[rng.gen_range(0, kept)]
+    }
+}
+
+/// Computes a hash of the structure of the specified network, which is
+/// stable across runs, platforms and compiler versions (see
+/// `cache::network_hash`).
+pub fn fingerprint(network: &Network) -> u64 {
+    network_hash(network)
+}
+
+/// Computes the novelty of each of the specified behaviors, as the mean Hamming
+/// distance to the `k` nearest of the other behaviors and the archived
+/// behaviors.
+fn novelty_scores(behaviors: &[Vec<bool>], archive: &[Vec<bool>], k: usize) -> Vec<f64> {
+    behaviors.iter().enumerate().map(|(i, b)| {
+        let mut distances: Vec<usize> = behaviors.iter().enumerate()
+            .filter(|&(j, _)| j != i)
+            .map(|(_, other)| other)
+            .chain(archive.iter())
+            .map(|other| hamming_distance(b, other))
+            .collect();
+        distances.sort_unstable();
+        let nearest = &distances[..k.min(distances.len())];
+        if nearest.is_empty() {
+            0.0
+        } else {
+            nearest.iter().sum::<usize>() as f64 / nearest.len() as f64
+        }
+    }).collect()
+}
+
+/// Computes the total error of each of the specified genomes of the specified
+/// arena on the specified packed dataset, concurrently if the `parallel`
+/// feature is enabled.
+fn score(genomes: &NetworkArena, indices: &[usize], dataset: &BitDataset) -> Vec<usize> {
+    #[cfg(feature = "parallel")]
+    return crate::parallel::genome_errors_par(genomes, indices, dataset);
+    #[cfg(not(feature = "parallel"))]
+    return indices.iter().map(|&g| genomes.error(g, dataset)).collect();
+}
@@ -0,0 +1,133 @@
+//! Contains assertions over the behavior of `bnl` networks, for writing
+//! regression tests of trained models.
+//!
+//! `assert_implements` checks a network against part of the truth table it is
+//! expected to implement, while `assert_invariant` checks a property relating
+//! each input to its output over every input of the network if there are at
+//! most `MAX_SAMPLES` of them, and over `MAX_SAMPLES` random inputs otherwise
+//! (see `analysis::probe_inputs`). Each assertion has a `check_` counterpart
+//! returning the first `Counterexample` found instead of panicking.
+
+use crate::analysis::{probe_inputs, probe_inputs_with_rng};
+use crate::bits::format_bits;
+use crate::network::Network;
+use std::fmt;
+
+/// The maximum number of inputs over which invariants are checked.
+pub const MAX_SAMPLES: usize = 1 << 16;
+
+/// Represents an input for which a network failed an assertion.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct Counterexample {
+    /// The output expected by a truth table, or `None` if the network
+    /// violated an invariant.
+    pub expected: Option<Vec<bool>>,
+
+    /// The input at fault.
+    pub input: Vec<bool>,
+
+    /// The output of the network for the input.
+    pub output: Vec<bool>
+}
+
+/// Describes counterexamples in the style of the panic messages of the
+/// assertions.
+impl fmt::Display for Counterexample {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.expected {
+            Some(e) => write!(
+                f, "Network maps {} to {} but {} was expected",
+                format_bits(&self.input), format_bits(&self.output), format_bits(e)
+            ),
+            None => write!(
+                f, "Network maps {} to {}, violating the invariant",
+                format_bits(&self.input), format_bits(&self.output)
+            )
+        }
+    }
+}
+
+/// Allows counterexamples to be used with `?` in functions returning boxed
+/// errors.
+impl std::error::Error for Counterexample {}
+
+/// Asserts that the specified network produces the expected output of each of
+/// the specified `(input, expected)` rows of its truth table.
+///
+/// Panics with the first counterexample found otherwise.
+pub fn assert_implements(network: &Network, table: &[(Vec<bool>, Vec<bool>)]) {
+    if let Err(c) = check_implements(network, table) {
+        panic!("{}!", c);
+    }
+}
+
+/// Asserts that the specified predicate holds for the inputs and outputs of
+/// the specified network over every input, or over `MAX_SAMPLES` random inputs
+/// if there are more.
+///
+/// Panics with the first counterexample found otherwise.
+pub fn assert_invariant<F>(network: &Network, predicate: F)
+where
+    F: Fn(&[bool], &[bool]) -> bool
+{
+    if let Err(c) = check_invariant(network, predicate) {
+        panic!("{}!", c);
+    }
+}
+
+/// Checks that the specified network produces the expected output of each of
+/// the specified `(input, expected)` rows of its truth table, returning the
+/// first counterexample found otherwise.
+pub fn check_implements(network: &Network, table: &[(Vec<bool>, Vec<bool>)]) -> Result<(), Counterexample> {
+    for (input, expected) in table {
+        let output = network.apply(input);
+        if output != *expected {
+            return Err(Counterexample {
+                expected: Some(expected.clone()),
+                input: input.clone(),
+                output
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the specified predicate holds for the inputs and outputs of the
+/// specified network over every input, or over `MAX_SAMPLES` random inputs if
+/// there are more, returning the first counterexample found otherwise.
+pub fn check_invariant<F>(network: &Network, predicate: F) -> Result<(), Counterexample>
+where
+    F: Fn(&[bool], &[bool]) -> bool
+{
+    check_invariant_on(network, &probe_inputs(network.input_width(), MAX_SAMPLES), predicate)
+}
+
+/// Checks that the specified predicate holds for the inputs and outputs of the
+/// specified network over each of the specified inputs, returning the first
+/// counterexample found otherwise.
+pub fn check_invariant_on<F>(network: &Network, inputs: &[Vec<bool>], predicate: F) -> Result<(), Counterexample>
+where
+    F: Fn(&[bool], &[bool]) -> bool
+{
+    for input in inputs {
+        let output = network.apply(input);
+        if !predicate(input, &output) {
+            return Err(Counterexample {
+                expected: None,
+                input: input.clone(),
+                output
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks an invariant as with `check_invariant`, drawing any random inputs
+/// from the specified generator.
+pub fn check_invariant_with_rng<F, R>(network: &Network, predicate: F, rng: &mut R) -> Result<(), Counterexample>
+where
+    F: Fn(&[bool], &[bool]) -> bool,
+    R: rand::Rng + ?Sized
+{
+    check_invariant_on(network, &probe_inputs_with_rng(network.input_width(), MAX_SAMPLES, rng), predicate)
+}
@@ -0,0 +1,146 @@
+//! Contains a lightweight profiler, built with the `profiling` feature, which
+//! times the phases training spends its time in: loading datasets, evaluating
+//! candidate networks, mutating them and serializing networks.
+//!
+//! The routines of each phase start a `Timer` (see `timer`), which adds the
+//! time until it is dropped to the totals of its phase when it is the
+//! outermost timer of that phase on its thread, so that nested routines
+//! (such as `Dataset::from_csv` calling `Dataset::parse_csv`) are not counted
+//! twice. The totals are shared by every thread, so time spent evaluating on
+//! several threads at once (see the `parallel` module) is summed across them
+//! and may exceed the elapsed time. `take` returns the totals gathered so far
+//! and resets them, as `bnl train --profile` does around each round.
+
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// The number of calls of each phase timed so far.
+static CALLS: [AtomicU64; 4] = [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)];
+
+/// The number of nanoseconds spent in each phase so far.
+static NANOS: [AtomicU64; 4] = [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)];
+
+std::thread_local! {
+    /// The phases with a running timer on this thread, as a bit mask indexed
+    /// by `Phase::index`.
+    static ACTIVE: Cell<u8> = const { Cell::new(0) };
+}
+
+/// Represents the phases of training which are timed.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Phase {
+    /// Computing the error of networks on datasets.
+    Evaluate,
+
+    /// Loading and parsing datasets.
+    Load,
+
+    /// Mutating networks.
+    Mutate,
+
+    /// Serializing and deserializing networks.
+    Serialize
+}
+
+/// Implements custom methods for phases.
+impl Phase {
+    /// Every phase, in the order in which they are reported.
+    pub const ALL: [Phase; 4] = [Phase::Load, Phase::Evaluate, Phase::Mutate, Phase::Serialize];
+
+    /// Returns the index of this phase into the totals of the profiler.
+    fn index(self) -> usize {
+        match self {
+            Phase::Evaluate => 0,
+            Phase::Load => 1,
+            Phase::Mutate => 2,
+            Phase::Serialize => 3
+        }
+    }
+
+    /// Returns the lowercase name of this phase.
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Evaluate => "evaluate",
+            Phase::Load => "load",
+            Phase::Mutate => "mutate",
+            Phase::Serialize => "serialize"
+        }
+    }
+}
+
+/// Represents the time spent in each phase over some stretch of a run.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct Profile {
+    /// The number of timed calls of each phase, in the order of `Phase::ALL`.
+    pub calls: [u64; 4],
+
+    /// The total time spent in each phase, in the order of `Phase::ALL`.
+    pub totals: [Duration; 4]
+}
+
+/// Formats profiles as a table with one row per phase.
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<10} {:>10} {:>10} {:>12}", "phase", "calls", "seconds", "mean (us)")?;
+        for (i, phase) in Phase::ALL.iter().enumerate() {
+            let seconds = self.totals[i].as_secs_f64();
+            let mean = if self.calls[i] == 0 { 0.0 } else { 1e6 * seconds / self.calls[i] as f64 };
+            writeln!(f, "{:<10} {:>10} {:>10.3} {:>12.1}", phase.name(), self.calls[i], seconds, mean)?;
+        }
+        Ok(())
+    }
+}
+
+/// Represents a running timer of a phase, which records the time elapsed
+/// since it started when dropped.
+#[derive(Debug)]
+pub struct Timer {
+    /// The phase being timed.
+    phase: Phase,
+
+    /// The time at which this timer started, or `None` if another timer of
+    /// its phase was already running on this thread.
+    start: Option<Instant>
+}
+
+/// Records the time elapsed since a timer started.
+impl Drop for Timer {
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            let i = self.phase.index();
+            NANOS[i].fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+            CALLS[i].fetch_add(1, Ordering::Relaxed);
+            ACTIVE.with(|a| a.set(a.get() & !(1 << i)));
+        }
+    }
+}
+
+/// Returns the time spent in each phase since the profiler was last reset,
+/// and resets it.
+pub fn take() -> Profile {
+    let mut profile = Profile {
+        calls: [0; 4],
+        totals: [Duration::ZERO; 4]
+    };
+    for (i, phase) in Phase::ALL.iter().enumerate() {
+        profile.calls[i] = CALLS[phase.index()].swap(0, Ordering::Relaxed);
+        profile.totals[i] = Duration::from_nanos(NANOS[phase.index()].swap(0, Ordering::Relaxed));
+    }
+    profile
+}
+
+/// Starts timing the specified phase until the returned timer is dropped.
+pub fn timer(phase: Phase) -> Timer {
+    let i = phase.index();
+    let outermost = ACTIVE.with(|a| {
+        let active = a.get();
+        a.set(active | (1 << i));
+        active & (1 << i) == 0
+    });
+    Timer {
+        phase,
+        start: if outermost { Some(Instant::now()) } else { None }
+    }
+}
@@ -0,0 +1,351 @@
+//! Contains routines for exchanging `bnl` networks with implementations in
+//! other languages, in the Protocol Buffers wire format of the interchange
+//! schema `schema/bnl.proto` at the root of the repository.
+//!
+//! Any Protocol Buffers implementation may generate readers and writers for
+//! the schema, so that trained networks can be consumed without
+//! reimplementing the serializer of the `persist` module. This module encodes
+//! and decodes the wire format itself rather than depending on one: fields
+//! holding their default value are omitted when encoding, as generated code
+//! does, while unknown fields are skipped and repeated numeric fields are
+//! accepted both packed and unpacked when decoding.
+//!
+//! Every network decoded is checked with `Network::validate`. Malformed bytes
+//! and invalid networks fail with an I/O error of kind `InvalidData` wrapping
+//! an `Error::CorruptModel`, as with the loaders of the `persist` module,
+//! which gives the byte offset at which decoding failed (if it did).
+
+use crate::combinator::Combinator;
+use crate::error::Error;
+use crate::network::{Layer, Network, Neuron, Reduction};
+use std::convert::{TryFrom, TryInto};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The wire type of variable-length integer fields.
+const VARINT: u8 = 0;
+
+/// The wire type of 64-bit fixed-width fields.
+const FIXED64: u8 = 1;
+
+/// The wire type of length-delimited fields.
+const LEN: u8 = 2;
+
+/// The wire type of 32-bit fixed-width fields.
+const FIXED32: u8 = 5;
+
+/// Represents the value of a single field of an encoded message.
+enum Value<'a> {
+    /// A value of a fixed-width field of either width.
+    Fixed(u64),
+
+    /// The contents of a length-delimited field, along with the offset at
+    /// which they start.
+    Len(&'a [u8], usize),
+
+    /// The value of a variable-length integer field.
+    Varint(u64)
+}
+
+/// Represents a cursor over the fields of an encoded message.
+struct Reader<'a> {
+    /// The bytes of the message.
+    bytes: &'a [u8],
+
+    /// The offset of the start of the message within the bytes being
+    /// decoded.
+    offset: usize,
+
+    /// The position of the cursor within the message.
+    position: usize
+}
+
+/// Implements custom methods for message readers.
+impl<'a> Reader<'a> {
+    /// Creates a new reader over the message with the specified bytes, which
+    /// start at the specified offset.
+    fn new(bytes: &'a [u8], offset: usize) -> Self {
+        Reader {
+            bytes,
+            offset,
+            position: 0
+        }
+    }
+
+    /// Reads the next field of the message, returning its number, its value and
+    /// the offset at which it starts, or `None` at the end of the message.
+    fn next_field(&mut self) -> io::Result<Option<(u64, Value<'a>, usize)>> {
+        if self.position == self.bytes.len() {
+            return Ok(None);
+        }
+        let start = self.offset + self.position;
+        let key = self.varint()?;
+        let value = match (key & 7) as u8 {
+            VARINT => Value::Varint(self.varint()?),
+            FIXED64 => Value::Fixed(u64::from_le_bytes(self.take(8)?.try_into().unwrap())),
+            LEN => {
+                let len = self.varint()?;
+                let offset = self.offset + self.position;
+                let len = usize::try_from(len).map_err(|_| corrupt_model(Some(offset), "field is too long"))?;
+                Value::Len(self.take(len)?, offset)
+            },
+            FIXED32 => Value::Fixed(u32::from_le_bytes(self.take(4)?.try_into().unwrap()) as u64),
+            wire => return Err(corrupt_model(Some(start), format!("unsupported wire type {}", wire)))
+        };
+        Ok(Some((key >> 3, value, start)))
+    }
+
+    /// Reads the specified number of bytes.
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        let start = self.position;
+        match start.checked_add(len).and_then(|end| self.bytes.get(start..end)) {
+            Some(res) => {
+                self.position += len;
+                Ok(res)
+            },
+            None => Err(corrupt_model(Some(self.offset + start), "message is truncated"))
+        }
+    }
+
+    /// Reads a variable-length integer.
+    fn varint(&mut self) -> io::Result<u64> {
+        let start = self.offset + self.position;
+        let mut res = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1).map_err(|_| corrupt_model(Some(start), "varint is truncated"))?[0];
+            res |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(res);
+            }
+        }
+        Err(corrupt_model(Some(start), "varint is too long"))
+    }
+}
+
+/// Decodes a network and the hash of the dataset it was trained on (if saved)
+/// from the specified bytes, checking that the result is a valid network.
+pub fn decode(bytes: &[u8]) -> io::Result<(Network, Option<u64>)> {
+    let mut layers: Vec<Layer> = Vec::new();
+    let mut dataset_hash = None;
+    let mut reader = Reader::new(bytes, 0);
+    while let Some((number, value, at)) = reader.next_field()? {
+        match (number, value) {
+            (1, Value::Len(b, offset)) => layers.push(decode_layer(b, offset)?),
+            (2, Value::Len(b, offset)) => dataset_hash = decode_metadata(b, offset)?,
+            (1, _) | (2, _) => return Err(wrong_type("Network", number, at)),
+            _ => {}
+        }
+    }
+    let network = Network {
+        layers
+    };
+    network.validate().map_err(|e| corrupt_model(None, e))?;
+    Ok((network, dataset_hash))
+}
+
+/// Encodes the specified network and the hash of the dataset it was trained on
+/// (if known) as a `Network` message of the interchange schema.
+pub fn encode(network: &Network, dataset_hash: Option<u64>) -> Vec<u8> {
+    let mut res: Vec<u8> = Vec::new();
+    for layer in &network.layers {
+        let mut message: Vec<u8> = Vec::new();
+        for n in &layer.neurons {
+            put_len(&mut message, 1, &encode_neuron(n));
+        }
+        put_len(&mut res, 1, &message);
+    }
+    if let Some(hash) = dataset_hash {
+        let mut message: Vec<u8> = Vec::new();
+        put_key(&mut message, 1, FIXED64);
+        message.extend_from_slice(&hash.to_le_bytes());
+        put_len(&mut res, 2, &message);
+    }
+    res
+}
+
+/// Loads a network and the hash of the dataset it was trained on (if saved)
+/// from the specified file, as with `decode`.
+pub fn load<P: AsRef<Path>>(path: P) -> io::Result<(Network, Option<u64>)> {
+    decode(&fs::read(path)?)
+}
+
+/// Saves the specified network and the hash of the dataset it was trained on
+/// (if known) to the specified file, as with `encode`.
+pub fn save<P: AsRef<Path>>(network: &Network, dataset_hash: Option<u64>, path: P) -> io::Result<()> {
+    fs::write(path, encode(network, dataset_hash))
+}
+
+/// Creates an I/O error of kind `InvalidData` wrapping an
+/// `Error::CorruptModel`.
+fn corrupt_model<E: ToString>(offset: Option<usize>, e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, Error::CorruptModel { offset, reason: e.to_string() })
+}
+
+/// Decodes the combinator with the specified value of an enum field.
+fn decode_combinator(value: u64, offset: usize) -> io::Result<Combinator> {
+    u8::try_from(value).ok()
+        .and_then(|v| Combinator::try_from(v).ok())
+        .ok_or_else(|| corrupt_model(Some(offset), format!("unknown combinator {}", value)))
+}
+
+/// Decodes a `Layer` message from the specified bytes, which start at the
+/// specified offset.
+fn decode_layer(bytes: &[u8], offset: usize) -> io::Result<Layer> {
+    let mut neurons: Vec<Neuron> = Vec::new();
+    let mut reader = Reader::new(bytes, offset);
+    while let Some((number, value, at)) = reader.next_field()? {
+        match (number, value) {
+            (1, Value::Len(b, offset)) => neurons.push(decode_neuron(b, offset)?),
+            (1, _) => return Err(wrong_type("Layer", number, at)),
+            _ => {}
+        }
+    }
+    Ok(Layer {
+        neurons
+    })
+}
+
+/// Decodes a `Metadata` message from the specified bytes, which start at the
+/// specified offset, returning its dataset hash.
+fn decode_metadata(bytes: &[u8], offset: usize) -> io::Result<Option<u64>> {
+    let mut res = None;
+    let mut reader = Reader::new(bytes, offset);
+    while let Some((number, value, at)) = reader.next_field()? {
+        match (number, value) {
+            (1, Value::Fixed(hash)) => res = Some(hash),
+            (1, _) => return Err(wrong_type("Metadata", number, at)),
+            _ => {}
+        }
+    }
+    Ok(res)
+}
+
+/// Decodes a `Neuron` message from the specified bytes, which start at the
+/// specified offset.
+fn decode_neuron(bytes: &[u8], offset: usize) -> io::Result<Neuron> {
+    let mut res = Neuron {
+        arity: 0,
+        bias: false,
+        input_combinators: Vec::new(),
+        inputs: None,
+        locked: false,
+        reduction: Reduction::RightFold,
+        result_combinator: Combinator::False
+    };
+    let (mut inputs, mut sparse) = (Vec::new(), false);
+    let mut reader = Reader::new(bytes, offset);
+    while let Some((number, value, at)) = reader.next_field()? {
+        match (number, value) {
+            (1, Value::Varint(v)) => res.arity = usize::try_from(v).map_err(|_| corrupt_model(Some(at), "arity is too large"))?,
+            (2, Value::Varint(v)) => res.bias = v != 0,
+            (3, value) => for v in repeated("Neuron", number, value, at)? {
+                res.input_combinators.push(decode_combinator(v, at)?);
+            },
+            (4, value) => for v in repeated("Neuron", number, value, at)? {
+                inputs.push(usize::try_from(v).map_err(|_| corrupt_model(Some(at), "input index is too large"))?);
+            },
+            (5, Value::Varint(v)) => sparse = v != 0,
+            (6, Value::Varint(v)) => res.locked = v != 0,
+            (7, Value::Varint(v)) => res.reduction = match v {
+                0 => Reduction::RightFold,
+                1 => Reduction::LeftFold,
+                2 => Reduction::Tree,
+                _ => return Err(corrupt_model(Some(at), format!("unknown reduction {}", v)))
+            },
+            (8, Value::Varint(v)) => res.result_combinator = decode_combinator(v, at)?,
+            (1..=8, _) => return Err(wrong_type("Neuron", number, at)),
+            _ => {}
+        }
+    }
+    if sparse {
+        res.inputs = Some(inputs);
+    } else if !inputs.is_empty() {
+        return Err(corrupt_model(Some(offset), "dense neuron lists inputs"));
+    }
+    Ok(res)
+}
+
+/// Encodes the specified neuron as a `Neuron` message.
+fn encode_neuron(neuron: &Neuron) -> Vec<u8> {
+    let mut res: Vec<u8> = Vec::new();
+    put_varint_field(&mut res, 1, neuron.arity as u64);
+    put_varint_field(&mut res, 2, neuron.bias as u64);
+    put_packed(&mut res, 3, neuron.input_combinators.iter().map(|&c| u8::from(c) as u64));
+    if let Some(inputs) = &neuron.inputs {
+        put_packed(&mut res, 4, inputs.iter().map(|&i| i as u64));
+    }
+    put_varint_field(&mut res, 5, neuron.inputs.is_some() as u64);
+    put_varint_field(&mut res, 6, neuron.locked as u64);
+    put_varint_field(&mut res, 7, match neuron.reduction {
+        Reduction::RightFold => 0,
+        Reduction::LeftFold => 1,
+        Reduction::Tree => 2
+    });
+    put_varint_field(&mut res, 8, u8::from(neuron.result_combinator) as u64);
+    res
+}
+
+/// Appends the key of a field with the specified number and wire type.
+fn put_key(out: &mut Vec<u8>, number: u64, wire: u8) {
+    put_varint(out, (number << 3) | wire as u64);
+}
+
+/// Appends a length-delimited field with the specified number and contents.
+fn put_len(out: &mut Vec<u8>, number: u64, contents: &[u8]) {
+    put_key(out, number, LEN);
+    put_varint(out, contents.len() as u64);
+    out.extend_from_slice(contents);
+}
+
+/// Appends a packed repeated field with the specified number and values, or
+/// nothing if there are no values.
+fn put_packed<I: Iterator<Item = u64>>(out: &mut Vec<u8>, number: u64, values: I) {
+    let mut contents: Vec<u8> = Vec::new();
+    values.for_each(|v| put_varint(&mut contents, v));
+    if !contents.is_empty() {
+        put_len(out, number, &contents);
+    }
+}
+
+/// Appends a variable-length integer.
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Appends a variable-length integer field with the specified number and
+/// value, or nothing if the value is zero (its default).
+fn put_varint_field(out: &mut Vec<u8>, number: u64, value: u64) {
+    if value != 0 {
+        put_key(out, number, VARINT);
+        put_varint(out, value);
+    }
+}
+
+/// Returns the values of a repeated numeric field with the specified number
+/// of the specified message, starting at the specified offset, whether packed
+/// or not.
+fn repeated(message: &str, number: u64, value: Value, at: usize) -> io::Result<Vec<u64>> {
+    match value {
+        Value::Varint(v) => Ok(vec![v]),
+        Value::Len(bytes, offset) => {
+            let mut reader = Reader::new(bytes, offset);
+            let mut res: Vec<u64> = Vec::new();
+            while reader.position < bytes.len() {
+                res.push(reader.varint()?);
+            }
+            Ok(res)
+        },
+        Value::Fixed(_) => Err(wrong_type(message, number, at))
+    }
+}
+
+/// Creates an error for a field with the specified number of the specified
+/// message, starting at the specified offset, which has the wrong wire type.
+fn wrong_type(message: &str, number: u64, offset: usize) -> io::Error {
+    corrupt_model(Some(offset), format!("field {} of {} has the wrong wire type", number, message))
+}
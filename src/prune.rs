@@ -0,0 +1,120 @@
+//! Contains `Network::prune`, which shrinks a trained network by removing the
+//! hidden neurons it can do without, guided by the sensitivity of its error
+//! to each of them.
+//!
+//! The sensitivity of a neuron is the change in the error of the network over
+//! a dataset when the output of that neuron alone is flipped for every
+//! sample. Neurons of low sensitivity contribute little to the outputs, so
+//! pruning tries to remove them first, dropping their signal from the neurons
+//! of the following layer (see `mutate::remove_neurons`), and keeps each
+//! removal only if the error of the network stays within the given
+//! tolerance.
+
+use crate::batch::{apply_layer, BitBatch, BitDataset};
+use crate::mutate::remove_signal;
+use crate::network::Network;
+use rand::Rng;
+
+/// Represents the outcome of a call to `Network::prune`.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct PruneReport {
+    /// The error of the network after pruning.
+    pub final_error: usize,
+
+    /// The error of the network before pruning.
+    pub initial_error: usize,
+
+    /// The number of neurons removed from each layer.
+    pub removed: Vec<usize>
+}
+
+/// Implements sensitivity-guided pruning on `bnl` networks.
+impl Network {
+    /// Removes hidden neurons from this network in order of increasing
+    /// sensitivity (see `sensitivity`) for as long as its total error on the
+    /// specified dataset stays within `tolerance` bits of its error before
+    /// pruning, recomputing the sensitivities after each removal.
+    ///
+    /// Output neurons, locked neurons, neurons feeding locked neurons and the
+    /// last neuron of a layer are never removed.
+    pub fn prune(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], tolerance: usize) -> PruneReport {
+        self.prune_with_rng(dataset, tolerance, &mut crate::random::rng())
+    }
+
+    /// Prunes this network as with `Network::prune`, drawing the signal to
+    /// which a sparsely wired neuron left wired to no input is rewired from
+    /// the specified generator.
+    pub fn prune_with_rng<R: Rng + ?Sized>(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], tolerance: usize, rng: &mut R) -> PruneReport {
+        let packed = BitDataset::from_samples(dataset);
+        let initial = packed.error(self);
+        let mut report = PruneReport {
+            final_error: initial,
+            initial_error: initial,
+            removed: vec![0; self.layers.len()]
+        };
+        'prune: loop {
+            let sensitivities = packed_sensitivity(self, &packed);
+            let mut candidates: Vec<(isize, usize, usize)> = Vec::new();
+            for (i, (layer, next)) in self.layers.iter().zip(self.layers.iter().skip(1)).enumerate() {
+                if layer.neurons.len() < 2 || next.neurons.iter().any(|n| n.locked) {
+                    continue;
+                }
+                for (j, _) in layer.neurons.iter().enumerate().filter(|(_, n)| !n.locked) {
+                    candidates.push((sensitivities[i][j], i, j));
+                }
+            }
+            candidates.sort_unstable();
+            for (_, i, j) in candidates {
+                let mut candidate = self.clone();
+                candidate.layers[i].neurons.remove(j);
+                for n in candidate.layers[i + 1].neurons.iter_mut() {
+                    remove_signal(n, j, rng);
+                }
+                let e = packed.error(&candidate);
+                if e <= initial + tolerance {
+                    *self = candidate;
+                    report.final_error = e;
+                    report.removed[i] += 1;
+                    continue 'prune;
+                }
+            }
+            break;
+        }
+        report
+    }
+}
+
+/// Computes the sensitivity of packed dataset errors to each neuron of the
+/// specified network.
+fn packed_sensitivity(network: &Network, packed: &BitDataset) -> Vec<Vec<isize>> {
+    let len = packed.inputs.len;
+    if len == 0 {
+        return network.layers.iter().map(|l| vec![0; l.neurons.len()]).collect();
+    }
+    let error = |signals: Vec<Vec<u64>>| BitBatch { len, signals }.hamming_distance(&packed.expected) as isize;
+    let mut activations: Vec<Vec<Vec<u64>>> = vec![packed.inputs.signals.clone()];
+    for layer in &network.layers {
+        let next = apply_layer(layer, activations.last().unwrap());
+        activations.push(next);
+    }
+    let base = error(activations.last().unwrap().clone());
+    network.layers.iter().enumerate().map(|(i, layer)| {
+        (0..layer.neurons.len()).map(|j| {
+            let mut signals = activations[i + 1].clone();
+            signals[j].iter_mut().for_each(|w| *w = !*w);
+            for later in &network.layers[(i + 1)..] {
+                signals = apply_layer(later, &signals);
+            }
+            error(signals) - base
+        }).collect()
+    }).collect()
+}
+
+/// Computes the sensitivity of the error of the specified network on the
+/// specified dataset to each of its neurons (by layer, then by position
+/// within its layer): the change in its total error when the output of that
+/// neuron alone is flipped for every sample, which is negative if flipping it
+/// reduces the error.
+pub fn sensitivity(network: &Network, dataset: &[(Vec<bool>, Vec<bool>)]) -> Vec<Vec<isize>> {
+    packed_sensitivity(network, &BitDataset::from_samples(dataset))
+}
@@ -0,0 +1,186 @@
+//! Contains the source of randomness used by every randomized routine of
+//! `bnl` unless a generator is passed explicitly to its `_with_rng` variant
+//! (such as `Network::new_with_rng` or `Network::train_with_rng`), which makes
+//! a run reproducible given the seed of that generator.
+//!
+//! By default this is the thread-local generator of `rand::thread_rng`, which
+//! is seeded from the operating system. With the `strict-determinism` feature
+//! it is instead a thread-local `Xoshiro256` seeded via `reseed`, so that a
+//! program produces the same networks, mutations and training runs every time
+//! it is executed. That feature also rules out the other sources of
+//! nondeterminism of the crate:
+//!
+//! - it cannot be combined with the `parallel` feature, whose work is
+//!   scheduled across threads, or the `tensorboard` feature, whose event files
+//!   record the time at which they were written,
+//! - in debug builds, `rng` panics on a thread whose generator was never
+//!   seeded via `reseed` (release builds fall back to `DEFAULT_SEED`), so that
+//!   every randomized routine called without an explicit generator is seeded
+//!   deliberately, and
+//! - the public maps of the crate (such as `population::Lineage::individuals`)
+//!   are `Map`s, which iterate in the order of their keys rather than in the
+//!   randomized order of a `HashMap`.
+//!
+//! Neither of these generators can be saved and restored, so this module also
+//! provides `Xoshiro256`, a small generator whose whole state is a plain
+//! value, for runs which must be resumable (see the `checkpoint` module).
+
+use rand::{RngCore, SeedableRng};
+use std::convert::TryInto;
+#[cfg(feature = "strict-determinism")]
+use std::cell::RefCell;
+
+/// The seed of the generator of each thread when strict determinism is
+/// enabled in a release build, until `reseed` is called.
+#[cfg(feature = "strict-determinism")]
+pub const DEFAULT_SEED: u64 = 0;
+
+#[cfg(feature = "strict-determinism")]
+thread_local! {
+    /// The deterministic generator of the current thread, or `None` until it
+    /// is first seeded.
+    static GENERATOR: RefCell<Option<Xoshiro256>> = const { RefCell::new(None) };
+}
+
+/// Represents the maps exposed by the public types of the crate, which are
+/// ordered by key under strict determinism.
+#[cfg(not(feature = "strict-determinism"))]
+pub type Map<K, V> = std::collections::HashMap<K, V>;
+
+/// Represents the maps exposed by the public types of the crate, which are
+/// ordered by key under strict determinism.
+#[cfg(feature = "strict-determinism")]
+pub type Map<K, V> = std::collections::BTreeMap<K, V>;
+
+/// Represents a handle to the random number generator of the current thread.
+#[cfg(not(feature = "strict-determinism"))]
+pub type Source = rand::rngs::ThreadRng;
+
+/// Represents a handle to the deterministic random number generator of the
+/// current thread.
+#[cfg(feature = "strict-determinism")]
+#[derive(Clone,Debug)]
+pub struct Source {
+    /// Prevents handles from being created outside of `rng`.
+    _private: ()
+}
+
+/// Draws random values from the deterministic generator of the current thread.
+#[cfg(feature = "strict-determinism")]
+impl RngCore for Source {
+    fn next_u32(&mut self) -> u32 {
+        with_generator(|g| g.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        with_generator(|g| g.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        with_generator(|g| g.fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        with_generator(|g| g.try_fill_bytes(dest))
+    }
+}
+
+/// Resets the deterministic generator of the current thread to the state
+/// given by the specified seed.
+#[cfg(feature = "strict-determinism")]
+pub fn reseed(seed: u64) {
+    GENERATOR.with(|g| *g.borrow_mut() = Some(Xoshiro256::seed_from_u64(seed)));
+}
+
+/// Represents a xoshiro256** generator, whose state may be cloned, compared
+/// and (with the `serde` feature) serialized, so that a run drawing from it
+/// can be checkpointed and continued exactly.
+///
+/// It is not cryptographically secure, and should be created via
+/// `SeedableRng::seed_from_u64` (or `SeedableRng::from_seed`).
+#[derive(Clone,Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Xoshiro256 {
+    /// The state of the generator, which is never all zero.
+    state: [u64; 4]
+}
+
+/// Draws random values from the state of xoshiro256** generators.
+impl RngCore for Xoshiro256 {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let res = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        res
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Allows xoshiro256** generators to be seeded, replacing the invalid
+/// all-zero seed with a fixed valid one.
+impl SeedableRng for Xoshiro256 {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut state = [0u64; 4];
+        for (s, bytes) in state.iter_mut().zip(seed.chunks(8)) {
+            *s = u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+        if state == [0; 4] {
+            state = [0x9e37_79b9_7f4a_7c15, 0xbf58_476d_1ce4_e5b9, 0x94d0_49bb_1331_11eb, 1];
+        }
+        Xoshiro256 {
+            state
+        }
+    }
+}
+
+/// Returns a handle to the random number generator of the current thread.
+#[cfg(not(feature = "strict-determinism"))]
+pub fn rng() -> Source {
+    rand::thread_rng()
+}
+
+/// Returns a handle to the deterministic random number generator of the
+/// current thread.
+///
+/// Panics in debug builds if the generator of the current thread was never
+/// seeded via `reseed`.
+#[cfg(feature = "strict-determinism")]
+pub fn rng() -> Source {
+    debug_assert!(
+        GENERATOR.with(|g| g.borrow().is_some()),
+        "Cannot draw from the unseeded generator of this thread under strict determinism, call `random::reseed` first!"
+    );
+    Source {
+        _private: ()
+    }
+}
+
+/// Calls the specified function on the deterministic generator of the current
+/// thread, seeding it with `DEFAULT_SEED` if it was never seeded.
+#[cfg(feature = "strict-determinism")]
+fn with_generator<T, F: FnOnce(&mut Xoshiro256) -> T>(f: F) -> T {
+    GENERATOR.with(|g| f(g.borrow_mut().get_or_insert_with(|| Xoshiro256::seed_from_u64(DEFAULT_SEED))))
+}
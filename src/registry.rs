@@ -0,0 +1,152 @@
+//! Contains the `Registry` type, through which fitness functions, mutation
+//! operators and selection strategies may be looked up by name, so that
+//! experiment configs and the command-line interface may refer to components
+//! registered by downstream code as well as to the built-in ones.
+//!
+//! `Registry::builtin` holds the following components, to which others may be
+//! added under new names (or in place of built-in ones) with the
+//! `Registry::register_*` methods:
+//!
+//! * The fitness functions `hamming-loss` (see `metrics::HammingLoss`),
+//!   `exact-error` (the fraction of samples not reproduced exactly) and
+//!   `f1-loss` (one minus the F1 score over every output bit).
+//! * The mutation operators of the `mutate` module, named after them with
+//!   dashes, such as `flip-biases` for `mutate::flip_biases`.
+//! * The selection strategies `tournament` (tournaments of three networks,
+//!   as `Population::evolve` uses by default) and `truncation` (uniform among
+//!   the fittest half).
+
+use crate::metrics::{accuracy, confusion_matrix, Fitness, HammingLoss};
+use crate::mutate::{self, Mutation};
+use crate::network::Network;
+use crate::population::{Selection, Tournament, Truncation};
+use rand::RngCore;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Represents a collection of named fitness functions, mutation operators and
+/// selection strategies.
+#[derive(Clone)]
+pub struct Registry {
+    /// The fitness functions, keyed by name.
+    fitness: BTreeMap<String, Arc<dyn Fitness + Send + Sync>>,
+
+    /// The mutation operators, keyed by name.
+    mutations: BTreeMap<String, Arc<dyn Mutation>>,
+
+    /// The selection strategies, keyed by name.
+    selections: BTreeMap<String, Arc<dyn Selection>>
+}
+
+/// Implements custom methods for registries.
+impl Registry {
+    /// Creates a new registry holding the built-in components.
+    pub fn builtin() -> Self {
+        let mut res = Registry::new();
+        res.register_fitness("exact-error", |n: &Network, d: &[(Vec<bool>, Vec<bool>)]| 1.0 - accuracy(n, d));
+        res.register_fitness("f1-loss", |n: &Network, d: &[(Vec<bool>, Vec<bool>)]| 1.0 - confusion_matrix(n, d).f1());
+        res.register_fitness("hamming-loss", HammingLoss);
+        res.register_mutation("add-neurons", |n: &mut Network, rate: f64, rng: &mut dyn RngCore| mutate::add_neurons(n, rate, rng));
+        res.register_mutation("delete-layers", |n: &mut Network, rate: f64, rng: &mut dyn RngCore| mutate::delete_layers(n, rate, rng));
+        res.register_mutation("flip-biases", |n: &mut Network, rate: f64, rng: &mut dyn RngCore| mutate::flip_biases(n, rate, rng));
+        res.register_mutation("insert-layers", |n: &mut Network, rate: f64, rng: &mut dyn RngCore| mutate::insert_layers(n, rate, rng));
+        res.register_mutation("remove-neurons", |n: &mut Network, rate: f64, rng: &mut dyn RngCore| mutate::remove_neurons(n, rate, rng));
+        res.register_mutation("replace-combinators", |n: &mut Network, rate: f64, rng: &mut dyn RngCore| mutate::replace_combinators(n, rate, rng));
+        res.register_mutation("replace-parameters", |n: &mut Network, rate: f64, rng: &mut dyn RngCore| {
+            mutate::replace_parameters(n, rate, rng);
+        });
+        res.register_mutation("rerandomize-neurons", |n: &mut Network, rate: f64, rng: &mut dyn RngCore| mutate::rerandomize_neurons(n, rate, rng));
+        res.register_mutation("rewire-inputs", |n: &mut Network, rate: f64, rng: &mut dyn RngCore| mutate::rewire_inputs(n, rate, rng));
+        res.register_selection("tournament", Tournament(3));
+        res.register_selection("truncation", Truncation(0.5));
+        res
+    }
+
+    /// Looks up the fitness function with the specified name.
+    pub fn fitness(&self, name: &str) -> Result<Arc<dyn Fitness + Send + Sync>, String> {
+        lookup(&self.fitness, "fitness function", name)
+    }
+
+    /// Returns the names of the fitness functions of this registry, in
+    /// alphabetical order.
+    pub fn fitness_names(&self) -> Vec<&str> {
+        self.fitness.keys().map(String::as_str).collect()
+    }
+
+    /// Looks up the mutation operator with the specified name.
+    pub fn mutation(&self, name: &str) -> Result<Arc<dyn Mutation>, String> {
+        lookup(&self.mutations, "mutation operator", name)
+    }
+
+    /// Returns the names of the mutation operators of this registry, in
+    /// alphabetical order.
+    pub fn mutation_names(&self) -> Vec<&str> {
+        self.mutations.keys().map(String::as_str).collect()
+    }
+
+    /// Creates a new empty registry.
+    pub fn new() -> Self {
+        Registry {
+            fitness: BTreeMap::new(),
+            mutations: BTreeMap::new(),
+            selections: BTreeMap::new()
+        }
+    }
+
+    /// Registers the specified fitness function under the specified name,
+    /// replacing any already registered under it.
+    pub fn register_fitness<F: Fitness + Send + Sync + 'static>(&mut self, name: &str, fitness: F) {
+        self.fitness.insert(String::from(name), Arc::new(fitness));
+    }
+
+    /// Registers the specified mutation operator under the specified name,
+    /// replacing any already registered under it.
+    pub fn register_mutation<M: Mutation + 'static>(&mut self, name: &str, mutation: M) {
+        self.mutations.insert(String::from(name), Arc::new(mutation));
+    }
+
+    /// Registers the specified selection strategy under the specified name,
+    /// replacing any already registered under it.
+    pub fn register_selection<S: Selection + 'static>(&mut self, name: &str, selection: S) {
+        self.selections.insert(String::from(name), Arc::new(selection));
+    }
+
+    /// Looks up the selection strategy with the specified name.
+    pub fn selection(&self, name: &str) -> Result<Arc<dyn Selection>, String> {
+        lookup(&self.selections, "selection strategy", name)
+    }
+
+    /// Returns the names of the selection strategies of this registry, in
+    /// alphabetical order.
+    pub fn selection_names(&self) -> Vec<&str> {
+        self.selections.keys().map(String::as_str).collect()
+    }
+}
+
+/// Provides an empty registry.
+impl Default for Registry {
+    fn default() -> Self {
+        Registry::new()
+    }
+}
+
+/// Lists the names of the components of a registry.
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Registry")
+            .field("fitness", &self.fitness_names())
+            .field("mutations", &self.mutation_names())
+            .field("selections", &self.selection_names())
+            .finish()
+    }
+}
+
+/// Looks up the component with the specified name among the specified
+/// components of the specified kind, or describes the components available.
+fn lookup<T: ?Sized>(components: &BTreeMap<String, Arc<T>>, kind: &str, name: &str) -> Result<Arc<T>, String> {
+    components.get(name).cloned().ok_or_else(|| {
+        let names: Vec<&str> = components.keys().map(String::as_str).collect();
+        format!("unknown {} {} (expected one of {})", kind, name, names.join(", "))
+    })
+}
@@ -0,0 +1,325 @@
+//! Contains random number generator wrappers for recording the draws of a
+//! training run and replaying them later, such as on another platform, to find
+//! where two runs of the same seed stop agreeing.
+//!
+//! Every decision the training routines of `bnl` make (which mutation to try,
+//! which parents to breed, whether to accept a worse candidate) is a function
+//! of the dataset and the values drawn from their generator, so the draws of a
+//! run, recorded in order by a `Recorder`, determine it completely. A
+//! `Replayer` then feeds those draws back to a second run in order, so that it
+//! retraces the first step by step, while comparing them with the draws of a
+//! generator of its own: the first draw on which they differ (or on which the
+//! second run asks for a different kind of draw, having taken a different
+//! path) is reported as a `Divergence`.
+//!
+//! Logs are saved in a compact binary format consisting of the bytes `BNLR`
+//! followed by each draw in turn, as a tag byte (`0` for a `u32`, `1` for a
+//! `u64` and `2` for a byte string) and its little-endian value, with byte
+//! strings prefixed by their length as a little-endian `u32`.
+
+use crate::dataset::invalid_data;
+use rand::RngCore;
+use std::convert::TryInto;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The bytes at the start of every saved draw log.
+const LOG_MAGIC: &[u8; 4] = b"BNLR";
+
+/// Represents the point at which a replayed run stopped agreeing with the run
+/// it replays.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct Divergence {
+    /// The recorded draw, or `None` if the replayed run drew more values than
+    /// were recorded.
+    pub expected: Option<Draw>,
+
+    /// The draw of the replayed run, or `None` if it finished without drawing
+    /// every recorded value.
+    pub found: Option<Draw>,
+
+    /// The index of the draw at which the runs diverged.
+    pub index: usize
+}
+
+/// Describes divergences in terms of the draws involved.
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.expected, &self.found) {
+            (Some(e), Some(d)) => write!(f, "Draw {} was recorded as {} but replayed as {}", self.index, e, d),
+            (Some(e), None) => write!(f, "Draw {} was recorded as {} but never replayed", self.index, e),
+            (None, Some(d)) => write!(f, "Draw {} was replayed as {} but never recorded", self.index, d),
+            (None, None) => write!(f, "Draw {} diverged", self.index)
+        }
+    }
+}
+
+/// Represents a single value drawn from a random number generator.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub enum Draw {
+    /// A byte string, drawn via `RngCore::fill_bytes`.
+    Bytes(Vec<u8>),
+
+    /// A value drawn via `RngCore::next_u32`.
+    U32(u32),
+
+    /// A value drawn via `RngCore::next_u64`.
+    U64(u64)
+}
+
+/// Describes draws by their kind and value.
+impl fmt::Display for Draw {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Draw::Bytes(bytes) => {
+                write!(f, "bytes ")?;
+                bytes.iter().try_for_each(|b| write!(f, "{:02x}", b))
+            },
+            Draw::U32(value) => write!(f, "u32 {:#010x}", value),
+            Draw::U64(value) => write!(f, "u64 {:#018x}", value)
+        }
+    }
+}
+
+/// Represents a generator which draws from another generator, recording every
+/// value it draws.
+#[derive(Clone,Debug)]
+pub struct Recorder<R> {
+    /// The generator drawn from.
+    inner: R,
+
+    /// The values drawn so far, in order.
+    log: RngLog
+}
+
+/// Implements custom methods for recorders.
+impl<R: RngCore> Recorder<R> {
+    /// Consumes this recorder, returning the values drawn, in order.
+    pub fn into_log(self) -> RngLog {
+        self.log
+    }
+
+    /// Returns the values drawn so far, in order.
+    pub fn log(&self) -> &RngLog {
+        &self.log
+    }
+
+    /// Creates a new recorder drawing from the specified generator.
+    pub fn new(inner: R) -> Self {
+        Recorder {
+            inner,
+            log: RngLog::default()
+        }
+    }
+}
+
+/// Draws values from the generator of a recorder, recording them.
+impl<R: RngCore> RngCore for Recorder<R> {
+    fn next_u32(&mut self) -> u32 {
+        let value = self.inner.next_u32();
+        self.log.draws.push(Draw::U32(value));
+        value
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let value = self.inner.next_u64();
+        self.log.draws.push(Draw::U64(value));
+        value
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.inner.fill_bytes(dest);
+        self.log.draws.push(Draw::Bytes(dest.to_vec()));
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Represents a generator which replays the values of a log in order, comparing
+/// each with a value drawn from another generator.
+#[derive(Clone,Debug)]
+pub struct Replayer<R> {
+    /// The first draw on which the generator disagreed with the log, if any.
+    divergence: Option<Divergence>,
+
+    /// The generator compared with the log.
+    inner: R,
+
+    /// The values to replay.
+    log: RngLog,
+
+    /// The index of the next value to replay.
+    position: usize
+}
+
+/// Implements custom methods for replayers.
+impl<R: RngCore> Replayer<R> {
+    /// Returns the first draw on which the generator of this replayer disagreed
+    /// with its log, if any.
+    pub fn divergence(&self) -> Option<&Divergence> {
+        self.divergence.as_ref()
+    }
+
+    /// Finishes replaying, returning the first draw on which the generator of
+    /// this replayer disagreed with its log, or the first recorded draw which
+    /// was not replayed, if any.
+    pub fn finish(self) -> Result<(), Divergence> {
+        if let Some(divergence) = self.divergence {
+            return Err(divergence);
+        }
+        match self.log.draws.get(self.position) {
+            Some(draw) => Err(Divergence {
+                expected: Some(draw.clone()),
+                found: None,
+                index: self.position
+            }),
+            None => Ok(())
+        }
+    }
+
+    /// Creates a new replayer of the specified log, comparing it with the
+    /// specified generator.
+    pub fn new(log: RngLog, inner: R) -> Self {
+        Replayer {
+            divergence: None,
+            inner,
+            log,
+            position: 0
+        }
+    }
+
+    /// Returns the number of values replayed so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Replays the next value of the log, given the value the generator of
+    /// this replayer drew in its place: the recorded value if it is of the
+    /// same kind, and the drawn one otherwise.
+    fn replay(&mut self, found: Draw) -> Draw {
+        let expected = self.log.draws.get(self.position).cloned();
+        self.position += 1;
+        if self.divergence.is_none() && expected.as_ref() != Some(&found) {
+            self.divergence = Some(Divergence {
+                expected: expected.clone(),
+                found: Some(found.clone()),
+                index: self.position - 1
+            });
+        }
+        match (expected, &found) {
+            (Some(Draw::Bytes(e)), Draw::Bytes(d)) if e.len() == d.len() => Draw::Bytes(e),
+            (Some(Draw::U32(e)), Draw::U32(_)) => Draw::U32(e),
+            (Some(Draw::U64(e)), Draw::U64(_)) => Draw::U64(e),
+            _ => found
+        }
+    }
+}
+
+/// Replays the values of the log of a replayer.
+impl<R: RngCore> RngCore for Replayer<R> {
+    fn next_u32(&mut self) -> u32 {
+        let found = Draw::U32(self.inner.next_u32());
+        match self.replay(found) {
+            Draw::U32(value) => value,
+            _ => unreachable!()
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let found = Draw::U64(self.inner.next_u64());
+        match self.replay(found) {
+            Draw::U64(value) => value,
+            _ => unreachable!()
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.inner.fill_bytes(dest);
+        if let Draw::Bytes(bytes) = self.replay(Draw::Bytes(dest.to_vec())) {
+            dest.copy_from_slice(&bytes);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Represents the values drawn from a generator over a run, in order.
+#[derive(Clone,Debug,Default,PartialEq,Eq)]
+pub struct RngLog {
+    /// The values drawn, in order.
+    pub draws: Vec<Draw>
+}
+
+/// Implements custom methods for draw logs.
+impl RngLog {
+    /// Loads a log from the specified file.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let bytes = fs::read(&path)?;
+        RngLog::parse(&bytes).map_err(|e| invalid_data(&path, e))
+    }
+
+    /// Parses a log from the specified bytes.
+    pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 4 || &bytes[..4] != LOG_MAGIC {
+            return Err(String::from("not a draw log"));
+        }
+        let mut draws: Vec<Draw> = Vec::new();
+        let mut offset = 4;
+        let take = |offset: &mut usize, len: usize| -> Result<&[u8], String> {
+            let res = bytes.get(*offset..(*offset + len)).ok_or_else(|| format!("truncated draw at byte {}", *offset))?;
+            *offset += len;
+            Ok(res)
+        };
+        while offset < bytes.len() {
+            let tag = take(&mut offset, 1)?[0];
+            draws.push(match tag {
+                0 => Draw::U32(u32::from_le_bytes(take(&mut offset, 4)?.try_into().unwrap())),
+                1 => Draw::U64(u64::from_le_bytes(take(&mut offset, 8)?.try_into().unwrap())),
+                2 => {
+                    let len = u32::from_le_bytes(take(&mut offset, 4)?.try_into().unwrap()) as usize;
+                    Draw::Bytes(take(&mut offset, len)?.to_vec())
+                },
+                _ => return Err(format!("unknown draw tag {} at byte {}", tag, offset - 1))
+            });
+        }
+        Ok(RngLog {
+            draws
+        })
+    }
+
+    /// Saves this log to the specified file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+
+    /// Serializes this log into bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut res: Vec<u8> = LOG_MAGIC.to_vec();
+        for draw in &self.draws {
+            match draw {
+                Draw::Bytes(bytes) => {
+                    res.push(2);
+                    res.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+                    res.extend_from_slice(bytes);
+                },
+                Draw::U32(value) => {
+                    res.push(0);
+                    res.extend_from_slice(&value.to_le_bytes());
+                },
+                Draw::U64(value) => {
+                    res.push(1);
+                    res.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+        }
+        res
+    }
+}
@@ -0,0 +1,88 @@
+//! Contains summaries of how `bnl` networks change over the course of
+//! training, such as the sequence of champions recorded by
+//! `Population::evolve` (see `EvolveOptions::champions`).
+
+use crate::network::Network;
+use std::fmt;
+
+/// The characters used to draw heatmap cells, from coldest to hottest.
+const RAMP: &[u8] = b" .:-=+*#%@";
+
+/// Represents how often each neuron of a network changed over a sequence of
+/// versions of it.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct ChurnHeatmap {
+    /// The number of transitions in which each neuron (by layer, then by
+    /// position within its layer) of the last version differed from the
+    /// neuron at the same position of the version before it, or did not
+    /// exist in it.
+    pub changes: Vec<Vec<usize>>,
+
+    /// The number of transitions between consecutive versions.
+    pub transitions: usize
+}
+
+/// Implements custom methods for churn heatmaps.
+impl ChurnHeatmap {
+    /// Returns the position of the neuron which changed most often, preferring
+    /// earlier neurons when tied, or `None` if there are no neurons.
+    pub fn hottest(&self) -> Option<(usize, usize)> {
+        self.changes.iter().enumerate()
+            .flat_map(|(i, l)| l.iter().enumerate().map(move |(j, &c)| (c, i, j)))
+            .max_by_key(|&(c, i, j)| (c, std::cmp::Reverse((i, j))))
+            .map(|(_, i, j)| (i, j))
+    }
+
+    /// Returns the fraction of transitions in which the specified neuron
+    /// changed, or zero if there were no transitions.
+    pub fn rate(&self, layer: usize, neuron: usize) -> f64 {
+        if self.transitions == 0 {
+            0.0
+        } else {
+            self.changes[layer][neuron] as f64 / self.transitions as f64
+        }
+    }
+}
+
+/// Draws churn heatmaps with one row of cells per layer, from ` ` (never
+/// changed) to `@` (changed as often as the hottest neuron).
+impl fmt::Display for ChurnHeatmap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let max = self.changes.iter().flatten().copied().max().unwrap_or(0).max(1);
+        for (i, layer) in self.changes.iter().enumerate() {
+            let cells: String = layer.iter()
+                .map(|&c| RAMP[(c * (RAMP.len() - 1)).div_ceil(max)] as char)
+                .collect();
+            writeln!(f, "layer {}: |{}|", i, cells)?;
+        }
+        Ok(())
+    }
+}
+
+/// Counts how often each neuron changed over the specified sequence of
+/// versions of a network (such as the champion of each generation), comparing
+/// neurons by their position within their layer.
+///
+/// The heatmap has the shape of the last version, and is empty if the
+/// sequence is.
+pub fn churn_heatmap(history: &[Network]) -> ChurnHeatmap {
+    let last = match history.last() {
+        Some(n) => n,
+        None => return ChurnHeatmap { changes: Vec::new(), transitions: 0 }
+    };
+    let mut changes: Vec<Vec<usize>> = last.layers.iter().map(|l| vec![0; l.neurons.len()]).collect();
+    for pair in history.windows(2) {
+        let (before, after) = (&pair[0], &pair[1]);
+        for (i, layer) in after.layers.iter().enumerate().take(changes.len()) {
+            for (j, n) in layer.neurons.iter().enumerate().take(changes[i].len()) {
+                if before.layers.get(i).and_then(|l| l.neurons.get(j)) != Some(n) {
+                    changes[i][j] += 1;
+                }
+            }
+        }
+    }
+    ChurnHeatmap {
+        changes,
+        transitions: history.len() - 1
+    }
+}
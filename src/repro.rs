@@ -0,0 +1,159 @@
+//! Contains a reproducibility check, which runs a fixed seeded workload and
+//! summarizes each of its stages as a stable fingerprint, so that results on
+//! different operating systems and architectures can be compared against a
+//! stored golden file.
+//!
+//! Every stage draws from a `random::Xoshiro256` generator seeded with `SEED`
+//! (rather than `StdRng`, whose algorithm may change between versions of
+//! `rand`) and is fingerprinted with the stable hashes of the `cache` module,
+//! so two machines agree on a fingerprint exactly when they computed the same
+//! networks or outputs.
+//!
+//! Golden files hold one `name value` line per stage, with the value in
+//! hexadecimal, as written by the `Display` implementation of `Fingerprints`.
+
+use crate::cache::{dataset_hash, network_hash};
+use crate::network::Network;
+use crate::population::{EvolveOptions, Population};
+use crate::random::Xoshiro256;
+use crate::train::TrainOptions;
+use rand::SeedableRng;
+use std::fmt;
+
+/// The number of generations evolved by the evolution stage.
+pub const GENERATIONS: usize = 5;
+
+/// The width of the inputs of the networks of the workload.
+pub const INPUT_WIDTH: usize = 6;
+
+/// The number of iterations of hill climbing run by the training stage.
+pub const ITERATIONS: usize = 500;
+
+/// The seed of the generator of every stage of the workload.
+pub const SEED: u64 = 0x626e_6c00;
+
+/// Represents the fingerprints of each stage of the reproducibility workload.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct Fingerprints {
+    /// The hash of the best network after evolving a small population for
+    /// `GENERATIONS` generations.
+    pub evolution: u64,
+
+    /// The hash of a freshly generated network.
+    pub generation: u64,
+
+    /// The hash of the outputs of the trained network for every input.
+    pub inference: u64,
+
+    /// The hash of the generated network after `ITERATIONS` iterations of
+    /// hill climbing.
+    pub training: u64
+}
+
+/// Implements custom methods for fingerprints.
+impl Fingerprints {
+    /// Runs the reproducibility workload, returning the fingerprint of each of
+    /// its stages.
+    pub fn compute() -> Self {
+        let dataset = dataset();
+        let mut rng = Xoshiro256::seed_from_u64(SEED);
+        let mut network = Network::new_with_rng(INPUT_WIDTH, vec![8, 3], &mut rng);
+        let generation = network_hash(&network);
+        let opts = TrainOptions {
+            iterations: ITERATIONS,
+            ..TrainOptions::default()
+        };
+        network.train_with_rng(&dataset, opts, &mut rng);
+        let training = network_hash(&network);
+        let outputs: Vec<(Vec<bool>, Vec<bool>)> = dataset.iter()
+            .map(|(input, _)| (input.clone(), network.apply(input)))
+            .collect();
+        let inference = dataset_hash(&outputs);
+        let mut population = Population::new_with_rng(16, INPUT_WIDTH, vec![8, 3], EvolveOptions::default(), &mut rng);
+        let report = population.evolve_with_rng(&dataset, GENERATIONS, &mut rng);
+        Fingerprints {
+            evolution: network_hash(&report.best),
+            generation,
+            inference,
+            training
+        }
+    }
+
+    /// Returns the names of the stages whose fingerprints differ between this
+    /// and another set of fingerprints.
+    pub fn mismatches(&self, other: &Fingerprints) -> Vec<&'static str> {
+        self.stages().iter()
+            .zip(other.stages().iter())
+            .filter(|((_, a), (_, b))| a != b)
+            .map(|((name, _), _)| *name)
+            .collect()
+    }
+
+    /// Parses fingerprints from the contents of a golden file, ignoring blank
+    /// lines and lines starting with `#`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut res = Fingerprints {
+            evolution: 0,
+            generation: 0,
+            inference: 0,
+            training: 0
+        };
+        let mut seen: Vec<&str> = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, value) = line.split_once(char::is_whitespace)
+                .ok_or_else(|| format!("line {}: expected a stage name and a fingerprint", i + 1))?;
+            let value = u64::from_str_radix(value.trim(), 16)
+                .map_err(|e| format!("line {}: invalid fingerprint: {}", i + 1, e))?;
+            let field = match name {
+                "evolution" => &mut res.evolution,
+                "generation" => &mut res.generation,
+                "inference" => &mut res.inference,
+                "training" => &mut res.training,
+                _ => return Err(format!("line {}: unknown stage {}", i + 1, name))
+            };
+            *field = value;
+            seen.push(name);
+        }
+        if let Some((name, _)) = res.stages().iter().find(|(name, _)| !seen.contains(name)) {
+            return Err(format!("missing the fingerprint of stage {}", name));
+        }
+        Ok(res)
+    }
+
+    /// Returns the name and fingerprint of each stage, in the order in which
+    /// they are run.
+    fn stages(&self) -> [(&'static str, u64); 4] {
+        [
+            ("generation", self.generation),
+            ("training", self.training),
+            ("inference", self.inference),
+            ("evolution", self.evolution)
+        ]
+    }
+}
+
+/// Formats fingerprints as the contents of a golden file.
+impl fmt::Display for Fingerprints {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, value) in self.stages().iter() {
+            writeln!(f, "{} {:016x}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the dataset of the workload: every input of `INPUT_WIDTH` bits,
+/// mapped to its majority, parity and whether its halves are equal.
+fn dataset() -> Vec<(Vec<bool>, Vec<bool>)> {
+    let half = INPUT_WIDTH / 2;
+    (0..(1u32 << INPUT_WIDTH)).map(|x| {
+        let input: Vec<bool> = (0..INPUT_WIDTH).map(|b| (x >> b) & 1 == 1).collect();
+        let ones = x.count_ones() as usize;
+        let expected = vec![2 * ones > INPUT_WIDTH, ones % 2 == 1, input[..half] == input[half..]];
+        (input, expected)
+    }).collect()
+}
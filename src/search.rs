@@ -0,0 +1,257 @@
+//! Contains a hyperparameter search driver, which evolves a network for each
+//! of a set of candidate architectures and evolution settings on a dataset
+//! and ranks the results.
+//!
+//! Each trial draws from its own `random::Xoshiro256` generator, seeded from
+//! the generator passed to `search_with_rng` and recorded in its `Trial`, so
+//! that a search is reproducible given the seed of that generator and any
+//! single trial can be rerun on its own via `Candidate::run`.
+//!
+//! Candidates are normalized before they are run (see `Candidate::normalize`),
+//! and every candidate whose normalized settings repeat those of an earlier
+//! one is skipped, so that a large sweep spends its budget only on distinct
+//! settings and its report holds one row per setting.
+
+use crate::network::Network;
+use crate::population::{EvolveOptions, Population};
+use crate::random::Xoshiro256;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+
+/// Represents a single point of a search space.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Candidate {
+    /// The lengths of the hidden layers of the network, which is followed by
+    /// an output layer as wide as the outputs of the dataset.
+    pub hidden: Vec<usize>,
+
+    /// The mutation rate of the population (see `EvolveOptions`).
+    pub mutation_rate: f64,
+
+    /// The number of networks in the population.
+    pub population_size: usize
+}
+
+/// Implements custom methods for search candidates.
+impl Candidate {
+    /// Returns the settings this candidate is actually run with: its
+    /// mutation rate clamped between zero and one, its population size raised
+    /// to at least one and its empty hidden layers dropped.
+    pub fn normalize(&self) -> Candidate {
+        Candidate {
+            hidden: self.hidden.iter().copied().filter(|&l| l > 0).collect(),
+            mutation_rate: self.mutation_rate.clamp(0.0, 1.0),
+            population_size: self.population_size.max(1)
+        }
+    }
+
+    /// Evolves a population with the settings of this candidate for the
+    /// specified number of generations, drawing from a generator seeded with
+    /// the specified seed, and returns the resulting trial.
+    ///
+    /// The candidate is normalized first (see `Candidate::normalize`).
+    ///
+    /// Panics if the dataset is empty.
+    pub fn run(&self, dataset: &[(Vec<bool>, Vec<bool>)], generations: usize, options: &EvolveOptions, seed: u64) -> Trial {
+        let (input, expected) = dataset.first().expect("Cannot search on an empty dataset!");
+        let candidate = self.normalize();
+        let mut layers = candidate.hidden.clone();
+        layers.push(expected.len());
+        let options = EvolveOptions {
+            mutation_rate: candidate.mutation_rate,
+            ..options.clone()
+        };
+        let mut rng = Xoshiro256::seed_from_u64(seed);
+        let mut population = Population::new_with_rng(candidate.population_size, input.len(), layers, options, &mut rng);
+        let report = population.evolve_with_rng(dataset, generations, &mut rng);
+        Trial {
+            candidate,
+            error: report.best_error,
+            evaluations: report.evaluations,
+            network: report.best,
+            seed
+        }
+    }
+}
+
+/// Implements the formatting of candidates as a compact description of their
+/// settings.
+impl fmt::Display for Candidate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hidden: Vec<String> = self.hidden.iter().map(|l| l.to_string()).collect();
+        write!(f, "hidden=[{}] rate={} population={}", hidden.join(","), self.mutation_rate, self.population_size)
+    }
+}
+
+/// Represents the outcome of a call to `search`, whose trials are ranked from
+/// best to worst.
+#[derive(Clone,Debug)]
+pub struct SearchReport {
+    /// The number of candidates skipped because their normalized settings
+    /// repeated those of an earlier candidate.
+    pub skipped: usize,
+
+    /// The trials of the search, ordered by their error, then by the number
+    /// of neurons of their networks, then by the order in which they were
+    /// run.
+    pub trials: Vec<Trial>
+}
+
+/// Implements custom methods for search reports.
+impl SearchReport {
+    /// Returns the best trial of the search, or `None` if no trials were run.
+    pub fn best(&self) -> Option<&Trial> {
+        self.trials.first()
+    }
+}
+
+/// Implements the formatting of search reports as a ranked summary, with one
+/// line per trial, followed by the number of duplicate candidates skipped (if
+/// any).
+impl fmt::Display for SearchReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:>4} {:>8} {:>8} {:>12}  settings", "rank", "error", "neurons", "evaluations")?;
+        for (i, t) in self.trials.iter().enumerate() {
+            writeln!(f, "{:>4} {:>8} {:>8} {:>12}  {}", i + 1, t.error, neuron_count(&t.network), t.evaluations, t.candidate)?;
+        }
+        if self.skipped > 0 {
+            writeln!(f, "skipped {} duplicate candidates", self.skipped)?;
+        }
+        Ok(())
+    }
+}
+
+/// Represents the space of candidates explored by `search`.
+#[derive(Clone,Debug)]
+pub struct SearchSpace {
+    /// The number of generations evolved by each trial.
+    pub generations: usize,
+
+    /// The candidate lengths of the hidden layers of the network, each of
+    /// which gives both its depth and its widths.
+    pub hidden: Vec<Vec<usize>>,
+
+    /// The candidate mutation rates.
+    pub mutation_rates: Vec<f64>,
+
+    /// The remaining evolution options shared by every trial.
+    pub options: EvolveOptions,
+
+    /// The candidate population sizes.
+    pub population_sizes: Vec<usize>
+}
+
+/// Implements custom methods for search spaces.
+impl SearchSpace {
+    /// Returns every candidate of this space, varying the population size
+    /// fastest and the hidden layers slowest.
+    pub fn grid(&self) -> Vec<Candidate> {
+        let mut res: Vec<Candidate> = Vec::new();
+        for hidden in &self.hidden {
+            for &mutation_rate in &self.mutation_rates {
+                for &population_size in &self.population_sizes {
+                    res.push(Candidate {
+                        hidden: hidden.clone(),
+                        mutation_rate,
+                        population_size
+                    });
+                }
+            }
+        }
+        res
+    }
+
+    /// Returns the specified number of candidates, each of whose settings is
+    /// drawn uniformly at random from those of this space.
+    ///
+    /// Panics if any setting of this space has no candidate values.
+    pub fn sample_with_rng<R: Rng + ?Sized>(&self, count: usize, rng: &mut R) -> Vec<Candidate> {
+        if self.hidden.is_empty() || self.mutation_rates.is_empty() || self.population_sizes.is_empty() {
+            panic!("Cannot sample from a search space with no candidate values!");
+        }
+        (0..count).map(|_| Candidate {
+            hidden: self.hidden[rng.gen_range(0, self.hidden.len())].clone(),
+            mutation_rate: self.mutation_rates[rng.gen_range(0, self.mutation_rates.len())],
+            population_size: self.population_sizes[rng.gen_range(0, self.population_sizes.len())]
+        }).collect()
+    }
+}
+
+/// Provides a reasonable default search space of a few shallow architectures.
+impl Default for SearchSpace {
+    fn default() -> Self {
+        SearchSpace {
+            generations: 50,
+            hidden: vec![vec![8], vec![16], vec![8, 8]],
+            mutation_rates: vec![0.01, 0.02, 0.05],
+            options: EvolveOptions::default(),
+            population_sizes: vec![16, 32]
+        }
+    }
+}
+
+/// Represents how the candidates of a search space are chosen.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Strategy {
+    /// Tries every candidate of the space (see `SearchSpace::grid`).
+    Grid,
+
+    /// Tries the specified number of candidates drawn at random (see
+    /// `SearchSpace::sample_with_rng`).
+    Random(usize)
+}
+
+/// Represents the outcome of evolving a single candidate.
+#[derive(Clone,Debug)]
+pub struct Trial {
+    /// The normalized settings of the trial.
+    pub candidate: Candidate,
+
+    /// The error of the best network found.
+    pub error: usize,
+
+    /// The number of networks evaluated on the dataset.
+    pub evaluations: usize,
+
+    /// The best network found.
+    pub network: Network,
+
+    /// The seed of the generator the trial drew from.
+    pub seed: u64
+}
+
+/// Returns the number of neurons of the specified network.
+fn neuron_count(network: &Network) -> usize {
+    network.layers.iter().map(|l| l.neurons.len()).sum()
+}
+
+/// Evolves a network for each candidate of the specified space chosen by the
+/// specified strategy on the specified dataset, and ranks the results.
+///
+/// Panics if the dataset is empty.
+pub fn search(dataset: &[(Vec<bool>, Vec<bool>)], space: &SearchSpace, strategy: Strategy) -> SearchReport {
+    search_with_rng(dataset, space, strategy, &mut crate::random::rng())
+}
+
+/// Runs a search as with `search`, drawing the candidates of a random search
+/// and the seed of each trial from the specified generator.
+pub fn search_with_rng<R: Rng + ?Sized>(dataset: &[(Vec<bool>, Vec<bool>)], space: &SearchSpace, strategy: Strategy, rng: &mut R) -> SearchReport {
+    let candidates = match strategy {
+        Strategy::Grid => space.grid(),
+        Strategy::Random(count) => space.sample_with_rng(count, rng)
+    };
+    let mut distinct: Vec<Candidate> = Vec::new();
+    for c in candidates.iter().map(Candidate::normalize) {
+        if !distinct.contains(&c) {
+            distinct.push(c);
+        }
+    }
+    let mut trials: Vec<Trial> = distinct.iter()
+        .map(|c| c.run(dataset, space.generations, &space.options, rng.gen()))
+        .collect();
+    trials.sort_by_key(|t| (t.error, neuron_count(&t.network)));
+    SearchReport {
+        skipped: candidates.len() - distinct.len(),
+        trials
+    }
+}
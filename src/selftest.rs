@@ -0,0 +1,191 @@
+//! Contains a quick self-test of a `bnl` build, which checks that every
+//! evaluation backend compiled into it computes the reference semantics and
+//! that training still converges, so that platform-specific problems (such
+//! as with the bit-parallel words of `Network::apply_batch`, threading or the
+//! JIT compiler) can be caught on the machine where `bnl` is installed.
+//!
+//! The self-test checks the combinators and the golden network of the
+//! `conformance` module, then evaluates hand-built reference circuits (see
+//! `circuits`) exhaustively and random networks on every input with each
+//! backend, and finally trains a small network on three-input parity from a
+//! fixed seed, which always finds an exact solution.
+
+use crate::batch::BitBatch;
+use crate::benchmark::Task;
+use crate::bits::format_bits;
+use crate::builder::NetworkBuilder;
+use crate::combinator::Combinator::*;
+use crate::conformance::{check_combinators, check_network};
+use crate::network::{compute_boolean, Network};
+use crate::random::Xoshiro256;
+use crate::train::TrainOptions;
+use rand::SeedableRng;
+use std::fmt;
+
+/// Represents an evaluation backend, computing the outputs of a network for
+/// many inputs.
+pub type Backend = fn(&Network, &[Vec<bool>]) -> Result<Vec<Vec<bool>>, String>;
+
+/// The number of iterations of hill climbing run by the training check.
+pub const ITERATIONS: usize = 2000;
+
+/// The seed of the generator of the random networks and of training.
+pub const SEED: u64 = 0x626e_6c01;
+
+/// Represents the outcome of a single check of the self-test.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct Check {
+    /// The name of the check.
+    pub name: String,
+
+    /// The outcome of the check, with a description of the first problem
+    /// found if it failed.
+    pub result: Result<(), String>
+}
+
+/// Represents the outcomes of every check of the self-test.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct SelfTestReport {
+    /// The checks run, in order.
+    pub checks: Vec<Check>
+}
+
+/// Implements custom methods for self-test reports.
+impl SelfTestReport {
+    /// Determines whether every check passed.
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.result.is_ok())
+    }
+}
+
+/// Formats self-test reports with one line per check.
+impl fmt::Display for SelfTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for c in &self.checks {
+            match &c.result {
+                Ok(()) => writeln!(f, "ok     {}", c.name)?,
+                Err(e) => writeln!(f, "FAILED {}: {}", c.name, e)?
+            }
+        }
+        let passed = self.checks.iter().filter(|c| c.result.is_ok()).count();
+        writeln!(f, "{}/{} checks passed", passed, self.checks.len())
+    }
+}
+
+/// Returns the evaluation backends compiled into this build, by name.
+pub fn backends() -> Vec<(&'static str, Backend)> {
+    #[cfg_attr(not(any(feature = "parallel", feature = "jit")), allow(unused_mut))]
+    let mut res: Vec<(&'static str, Backend)> = vec![
+        ("apply", |n, inputs| Ok(inputs.iter().map(|x| n.apply(x)).collect())),
+        ("apply_batch", |n, inputs| Ok(n.apply_batch(&BitBatch::from_samples(inputs)).to_samples()))
+    ];
+    #[cfg(feature = "parallel")]
+    res.push(("apply_many_par", |n, inputs| Ok(n.apply_many_par(inputs))));
+    #[cfg(feature = "jit")]
+    res.push(("jit", |n, inputs| Ok(n.compile()?.apply_batch(&BitBatch::from_samples(inputs)).to_samples())));
+    res
+}
+
+/// Returns hand-built reference circuits computing eight-input parity, the
+/// three-input multiplexer and three-input majority, each along with the
+/// task whose every sample it gets right.
+pub fn circuits() -> Vec<(Task, Network)> {
+    let parity = NetworkBuilder::new(8)
+        .layer(|l| l.neuron(&[Xor; 7], false, Left))
+        .build();
+    // For inputs `s`, `d0` and `d1`, the neurons compute `s & d1` and `!s & d0`.
+    let multiplexer = NetworkBuilder::new(3)
+        .layer(|l| l.neuron(&[And, Right], false, Left).neuron(&[AndNotLeft, Left], false, Left))
+        .layer(|l| l.neuron(&[Or], false, Left))
+        .build();
+    // For inputs `a`, `b` and `c`, the neurons compute `a & b`, `b & c` and
+    // `a & c`.
+    let majority = NetworkBuilder::new(3)
+        .layer(|l| l.neuron(&[And, Left], false, Left).neuron(&[Right, And], false, Left).neuron(&[And, Right], false, Left))
+        .layer(|l| l.neuron(&[Or, Or], false, Left))
+        .build();
+    vec![
+        (Task::Parity(8), parity.unwrap()),
+        (Task::Multiplexer(1), multiplexer.unwrap()),
+        (Task::Majority(3), majority.unwrap())
+    ]
+}
+
+/// Runs every check of the self-test.
+pub fn run() -> SelfTestReport {
+    #[cfg(feature = "strict-determinism")]
+    crate::random::reseed(SEED);
+    let mut checks = vec![
+        Check {
+            name: String::from("combinators"),
+            result: check_combinators(compute_boolean)
+        },
+        Check {
+            name: String::from("conformance"),
+            result: check_network(|n, x| n.apply(x))
+        }
+    ];
+    for (name, apply) in backends() {
+        checks.push(Check {
+            name: format!("backend {}", name),
+            result: check_backend(apply)
+        });
+    }
+    checks.push(Check {
+        name: String::from("training"),
+        result: check_training()
+    });
+    SelfTestReport {
+        checks
+    }
+}
+
+/// Checks that the specified backend gets every sample of every reference
+/// circuit right, and agrees with `Network::apply` on every input of random
+/// networks.
+fn check_backend(apply: Backend) -> Result<(), String> {
+    for (task, network) in circuits() {
+        let dataset = task.dataset();
+        let inputs: Vec<Vec<bool>> = dataset.iter().map(|(i, _)| i.clone()).collect();
+        let outputs = apply(&network, &inputs)?;
+        if let Some(((input, expected), actual)) = dataset.iter().zip(outputs.iter()).find(|((_, e), a)| e != *a) {
+            return Err(format!(
+                "{} circuit on {} produced {} but expected {}",
+                task, format_bits(input), format_bits(actual), format_bits(expected)
+            ));
+        }
+    }
+    let mut rng = Xoshiro256::seed_from_u64(SEED);
+    let inputs: Vec<Vec<bool>> = Task::Parity(8).dataset().into_iter().map(|(i, _)| i).collect();
+    for _ in 0..8 {
+        let network = Network::new_with_rng(8, vec![8, 8, 4], &mut rng);
+        let outputs = apply(&network, &inputs)?;
+        if let Some((input, actual)) = inputs.iter().zip(outputs.iter()).find(|(i, a)| network.apply(i) != **a) {
+            return Err(format!(
+                "random network on {} produced {} but expected {}",
+                format_bits(input), format_bits(actual), format_bits(&network.apply(input))
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that hill climbing from `SEED` solves three-input parity within
+/// `ITERATIONS` iterations.
+fn check_training() -> Result<(), String> {
+    let dataset = Task::Parity(3).dataset();
+    let mut rng = Xoshiro256::seed_from_u64(SEED);
+    let mut network = Network::new_with_rng(3, vec![3, 1], &mut rng);
+    let opts = TrainOptions {
+        iterations: ITERATIONS,
+        ..TrainOptions::default()
+    };
+    let report = network.train_with_rng(&dataset, opts, &mut rng);
+    if report.final_error != 0 {
+        return Err(format!(
+            "training on parity-3 left {} of {} output bits wrong after {} iterations",
+            report.final_error, dataset.len(), report.iterations
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,211 @@
+//! Contains `Network::simplify`, which shrinks a `bnl` network without
+//! changing the function it computes.
+//!
+//! Simplification repeatedly applies three rewrites until none of them
+//! applies any longer:
+//!
+//! - hidden layers which merely pass each of their inputs through unchanged
+//!   are collapsed,
+//! - hidden neurons which no neuron of the following layer depends on are
+//!   removed, and
+//! - hidden neurons whose output is constant are removed, their value being
+//!   folded into the input chains of the neurons of the following layer.
+//!
+//! Whether a neuron depends on one of its inputs is read off its combinators
+//! (see `Neuron::support`), so no inputs are ever enumerated and networks of
+//! any width can be simplified.
+//!
+//! As with pruning, locked neurons are left untouched: they are never
+//! removed, no layer containing them is collapsed and no signal they read is
+//! removed. Neither is a signal read by a neuron reducing its inputs as a
+//! tree (see `Reduction::Tree`), whose combinators cannot be adjusted around
+//! a missing input.
+
+use crate::combinator::Combinator;
+use crate::network::{compute_boolean, Network, Neuron, Reduction};
+
+/// Represents the outcome of a call to `Network::simplify`, where layers are
+/// numbered as in the original network.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct SimplifyReport {
+    /// The indices of the identity layers which were collapsed, in ascending
+    /// order.
+    pub collapsed: Vec<usize>,
+
+    /// The number of constant neurons folded into the following layer, for
+    /// each layer.
+    pub folded: Vec<usize>,
+
+    /// The number of unused neurons removed from each layer.
+    pub removed: Vec<usize>
+}
+
+/// Implements custom methods for simplification reports.
+impl SimplifyReport {
+    /// Determines whether simplification left the network unchanged.
+    pub fn is_empty(&self) -> bool {
+        self.collapsed.is_empty() && self.folded.iter().chain(self.removed.iter()).all(|&n| n == 0)
+    }
+}
+
+/// Implements simplification on `bnl` networks.
+impl Network {
+    /// Returns a copy of this network computing the same function with its
+    /// constant hidden neurons folded into the following layer, its unused
+    /// hidden neurons removed and its identity layers collapsed, along with a
+    /// report of what was removed.
+    ///
+    /// The output layer, locked neurons, neurons feeding locked neurons or
+    /// neurons reducing their inputs as a tree and the last neuron of a layer
+    /// are never removed.
+    pub fn simplify(&self) -> (Network, SimplifyReport) {
+        let mut network = self.clone();
+        let mut origin: Vec<usize> = (0..self.layers.len()).collect();
+        let mut report = SimplifyReport {
+            collapsed: Vec::new(),
+            folded: vec![0; self.layers.len()],
+            removed: vec![0; self.layers.len()]
+        };
+        loop {
+            if network.layers.len() > 1 {
+                if let Some(i) = (0..network.layers.len()).find(|&i| is_identity(&network, i)) {
+                    network.layers.remove(i);
+                    report.collapsed.push(origin.remove(i));
+                    continue;
+                }
+            }
+            let candidate = (0..network.layers.len().saturating_sub(1))
+                .filter(|&i| network.layers[i].neurons.len() > 1)
+                .find_map(|i| {
+                    let next = &network.layers[i + 1].neurons;
+                    network.layers[i].neurons.iter().enumerate().find_map(|(j, n)| {
+                        if n.locked || !next.iter().all(|c| can_remove(c, j)) {
+                            None
+                        } else if next.iter().all(|c| !c.support().contains(&j)) {
+                            Some((i, j, None))
+                        } else if n.support().is_empty() {
+                            Some((i, j, Some(n.apply(&vec![false; n.arity]))))
+                        } else {
+                            None
+                        }
+                    })
+                });
+            let (i, j, constant) = match candidate {
+                Some(c) => c,
+                None => break
+            };
+            network.layers[i].neurons.remove(j);
+            for n in network.layers[i + 1].neurons.iter_mut() {
+                remove_signal(n, j, constant.unwrap_or(false));
+            }
+            match constant {
+                Some(_) => report.folded[origin[i]] += 1,
+                None => report.removed[origin[i]] += 1
+            }
+        }
+        report.collapsed.sort_unstable();
+        (network, report)
+    }
+}
+
+/// Determines whether the signal at the specified index can be removed from
+/// the input of the specified neuron.
+fn can_remove(neuron: &Neuron, index: usize) -> bool {
+    !neuron.locked && (neuron.reduction != Reduction::Tree || neuron.inputs.as_ref().is_some_and(|v| !v.contains(&index)))
+}
+
+/// Finds the combinator computing the specified function of two values.
+fn combinator_of<F: Fn(bool, bool) -> bool>(f: F) -> Combinator {
+    *Combinator::ALL.iter()
+        .find(|&&c| [(false, false), (false, true), (true, false), (true, true)].iter()
+            .all(|&(l, r)| compute_boolean(l, r, c) == f(l, r)))
+        .expect("Cannot find a combinator for a function of two values!")
+}
+
+/// Determines whether the layer at the specified index of the specified
+/// network outputs exactly its input vector.
+fn is_identity(network: &Network, layer: usize) -> bool {
+    let width = if layer == 0 {
+        network.input_width()
+    } else {
+        network.layers[layer - 1].neurons.len()
+    };
+    let neurons = &network.layers[layer].neurons;
+    neurons.len() == width && neurons.iter().enumerate().all(|(j, n)| {
+        if n.locked {
+            return false;
+        }
+        let mut input = vec![false; width];
+        let low = n.apply(&input);
+        input[j] = true;
+        n.support() == [j] && !low && n.apply(&input)
+    })
+}
+
+/// Removes the input at the specified position of the chain of the specified
+/// neuron, which must read at least two inputs, adjusting the combinators
+/// around it so that the neuron computes the same function as before for
+/// inputs in which the removed input has the specified value.
+///
+/// The combinator reading the removed input turns the signal it passes on
+/// into a function of the remaining one, which is merged into the
+/// combinator consuming that signal: the next combinator of the reduction,
+/// or the result combinator if there is none.
+fn remove_position(neuron: &mut Neuron, position: usize, value: bool) {
+    let left_fold = neuron.reduction == Reduction::LeftFold;
+    let (index, reads_left) = match (left_fold, position == 0, position + 1 == neuron.fan_in()) {
+        (true, true, _) => (0, true),
+        (true, false, _) => (position - 1, false),
+        (false, _, true) => (position - 1, false),
+        (false, _, false) => (position, true)
+    };
+    let removed = neuron.input_combinators.remove(index);
+    let pass = move |x: bool| if reads_left {
+        compute_boolean(value, x, removed)
+    } else {
+        compute_boolean(x, value, removed)
+    };
+    let consumer = if left_fold { Some(index).filter(|&i| i < neuron.input_combinators.len()) } else { index.checked_sub(1) };
+    match consumer {
+        Some(i) if left_fold => {
+            let c = neuron.input_combinators[i];
+            neuron.input_combinators[i] = combinator_of(|l, r| compute_boolean(pass(l), r, c));
+        },
+        Some(i) => {
+            let c = neuron.input_combinators[i];
+            neuron.input_combinators[i] = combinator_of(|l, r| compute_boolean(l, pass(r), c));
+        },
+        None => {
+            let result = neuron.result_combinator;
+            neuron.result_combinator = combinator_of(|l, r| compute_boolean(pass(l), r, result));
+        }
+    }
+}
+
+/// Removes the signal at the specified index from the input of the specified
+/// neuron, so that it computes the same function as before for inputs in
+/// which that signal has the specified value.
+///
+/// A sparsely wired neuron left reading only that signal is rewired to the
+/// first signal, which its result combinator then ignores.
+fn remove_signal(neuron: &mut Neuron, index: usize, value: bool) {
+    if neuron.inputs.is_none() {
+        remove_position(neuron, index, value);
+        neuron.arity -= 1;
+        return;
+    }
+    while let Some(k) = neuron.inputs.as_ref().and_then(|v| v.iter().position(|&i| i == index)) {
+        if neuron.fan_in() == 1 {
+            let result = neuron.result_combinator;
+            neuron.result_combinator = combinator_of(|_, r| compute_boolean(value, r, result));
+            neuron.inputs = Some(vec![0]);
+            break;
+        }
+        remove_position(neuron, k, value);
+        neuron.inputs.as_mut().unwrap().remove(k);
+    }
+    neuron.arity -= 1;
+    for i in neuron.inputs.as_mut().unwrap().iter_mut().filter(|i| **i > index) {
+        *i -= 1;
+    }
+}
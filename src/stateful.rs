@@ -0,0 +1,174 @@
+//! Contains the `StatefulNetwork` type, for evaluating `bnl` networks as
+//! recurrent circuits which model sequential processes such as counters and
+//! simple state machines.
+//!
+//! A stateful network wraps a network whose input is the external input of
+//! each step followed by the state, which consists of the values of a chosen
+//! subset of its outputs on the previous step (all `false` before the first
+//! step, or after a reset):
+//!
+//! ```
+//! use bnl::builder::NetworkBuilder;
+//! use bnl::stateful::StatefulNetwork;
+//! use bnl::Combinator::*;
+//!
+//! // A two-bit counter of the steps on which its input is true, reading the
+//! // input `e` followed by the state `s0, s1`.
+//! let network = NetworkBuilder::new(3)
+//!     .layer(|l| l.neuron(&[And, Left], false, Left).selector(2).neuron(&[Xor, Left], false, Left))
+//!     .layer(|l| l.selector(2).neuron(&[Xor, Left], false, Left))
+//!     .build()
+//!     .unwrap();
+//! let mut counter = StatefulNetwork::new(network, vec![0, 1]);
+//! counter.run(&[vec![true], vec![false], vec![true], vec![true]]);
+//! assert_eq!(counter.state(), &[true, true]);
+//! ```
+//!
+//! A stateful network may also be run as a cyclic circuit settling on a fixed
+//! point, by holding its external input while stepping it until its state
+//! stops changing. `StatefulNetwork::apply_with_deadline` does so within a
+//! time budget for real-time callers, returning the latest output along with
+//! whether it converged, and `StatefulNetwork::apply_until_stable` within a
+//! number of steps, deterministically.
+
+use crate::network::Network;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// Represents the outcome of settling a `StatefulNetwork` on a held input.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct Settled {
+    /// Whether the state reached a fixed point, in which case stepping the
+    /// network again would not change its output.
+    pub converged: bool,
+
+    /// The output of the last step.
+    pub output: Vec<bool>,
+
+    /// The number of steps taken.
+    pub steps: usize
+}
+
+/// Represents a network some of whose outputs are fed back as inputs on the
+/// next step.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct StatefulNetwork {
+    /// The indices of the outputs fed back as the state, in the order in
+    /// which they are appended to the external input.
+    feedback: Vec<usize>,
+
+    /// The wrapped network.
+    network: Network,
+
+    /// The current state.
+    state: Vec<bool>
+}
+
+/// Implements custom methods for stateful networks.
+impl StatefulNetwork {
+    /// Steps this network on the specified external input, held fixed, as
+    /// with `StatefulNetwork::apply_with_deadline`, but for at most the
+    /// specified number of steps (and at least one) rather than for a time
+    /// budget.
+    pub fn apply_until_stable(&mut self, input: &[bool], max_steps: usize) -> Settled {
+        self.settle(input, |steps| steps < max_steps)
+    }
+
+    /// Steps this network on the specified external input, held fixed, until
+    /// its state stops changing, the state revisits an earlier one (so that
+    /// the circuit oscillates and will never converge), or the specified time
+    /// budget runs out after at least one step, whichever comes first. The
+    /// state is left as of the last step.
+    pub fn apply_with_deadline(&mut self, input: &[bool], budget: Duration) -> Settled {
+        let deadline = Instant::now() + budget;
+        self.settle(input, |_| Instant::now() < deadline)
+    }
+
+    /// Returns the indices of the outputs fed back as the state.
+    pub fn feedback(&self) -> &[usize] {
+        &self.feedback
+    }
+
+    /// Returns the width of the external input read on each step.
+    pub fn input_width(&self) -> usize {
+        self.network.input_width() - self.feedback.len()
+    }
+
+    /// Returns the wrapped network.
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+
+    /// Creates a new stateful network wrapping the specified network, feeding
+    /// back the outputs at the specified indices, starting from the all-`false`
+    /// state.
+    ///
+    /// Panics if an index is not an output of the network, or if the network's
+    /// input is narrower than the state.
+    pub fn new(network: Network, feedback: Vec<usize>) -> Self {
+        if let Some(i) = feedback.iter().find(|&&i| i >= network.output_width()) {
+            panic!("Cannot feed back output {} of a network with {} outputs!", i, network.output_width());
+        }
+        if network.input_width() < feedback.len() {
+            panic!(
+                "Cannot feed back {} outputs into a network with {} inputs!",
+                feedback.len(), network.input_width()
+            );
+        }
+        StatefulNetwork {
+            state: vec![false; feedback.len()],
+            feedback,
+            network
+        }
+    }
+
+    /// Resets the state of this network to all `false`.
+    pub fn reset(&mut self) {
+        self.state.iter_mut().for_each(|s| *s = false);
+    }
+
+    /// Steps this network through each of the specified external inputs in
+    /// turn, continuing from its current state, returning its outputs on
+    /// each step.
+    pub fn run(&mut self, inputs: &[Vec<bool>]) -> Vec<Vec<bool>> {
+        inputs.iter().map(|i| self.step(i)).collect()
+    }
+
+    /// Implements `StatefulNetwork::apply_with_deadline` and
+    /// `StatefulNetwork::apply_until_stable`, stepping until the specified
+    /// function of the number of steps taken so far returns `false`.
+    fn settle<F: FnMut(usize) -> bool>(&mut self, input: &[bool], mut keep_going: F) -> Settled {
+        let mut seen: HashSet<Vec<bool>> = HashSet::new();
+        let mut steps = 0;
+        loop {
+            let before = self.state.clone();
+            let output = self.step(input);
+            steps += 1;
+            let converged = self.state == before;
+            seen.insert(before);
+            if converged || seen.contains(&self.state) || !keep_going(steps) {
+                return Settled {
+                    converged,
+                    output,
+                    steps
+                };
+            }
+        }
+    }
+
+    /// Returns the current state of this network.
+    pub fn state(&self) -> &[bool] {
+        &self.state
+    }
+
+    /// Applies this network to the specified external input and its current
+    /// state, returning every output and updating the state from the fed
+    /// back ones.
+    pub fn step(&mut self, input: &[bool]) -> Vec<bool> {
+        let mut full = input.to_vec();
+        full.extend_from_slice(&self.state);
+        let output = self.network.apply(&full);
+        self.state = self.feedback.iter().map(|&i| output[i]).collect();
+        output
+    }
+}
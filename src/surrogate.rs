@@ -0,0 +1,115 @@
+//! Contains a surrogate model for cheaply estimating the error of `bnl`
+//! networks without evaluating them on a dataset.
+//!
+//! The surrogate describes each network by its genome (its bias and
+//! combinator parameters, in order) and predicts its error as the mean error
+//! of the `k` most similar genomes seen so far, where similarity is the number
+//! of parameters in which two genomes differ. It is only meaningful for
+//! networks of identical shape, such as the members of a `Population`.
+
+use crate::network::Network;
+use std::collections::VecDeque;
+
+/// Represents the options controlling the surrogate model used by
+/// `Population::evolve`.
+#[derive(Clone,Debug)]
+pub struct SurrogateOptions {
+    /// The maximum number of evaluated genomes remembered by the surrogate,
+    /// after which the oldest are forgotten.
+    pub capacity: usize,
+
+    /// The number of nearest genomes over which predictions are averaged.
+    pub k: usize,
+
+    /// The factor by which offspring are oversampled before pre-screening,
+    /// so that only the most promising `1 / oversample` of them is evaluated.
+    pub oversample: usize
+}
+
+/// Provides reasonable default surrogate options.
+impl Default for SurrogateOptions {
+    fn default() -> Self {
+        SurrogateOptions {
+            capacity: 1000,
+            k: 5,
+            oversample: 4
+        }
+    }
+}
+
+/// Represents a nearest-neighbor surrogate model of network error.
+#[derive(Clone,Debug)]
+pub struct Surrogate {
+    /// The remembered genomes along with their true errors, oldest first.
+    pub archive: VecDeque<(Vec<u8>, usize)>,
+
+    /// The maximum number of remembered genomes.
+    pub capacity: usize,
+
+    /// The number of nearest genomes over which predictions are averaged.
+    pub k: usize
+}
+
+/// Implements custom methods for surrogate models.
+impl Surrogate {
+    /// Determines whether the genome of the specified network has already been
+    /// observed, in which case evaluating it would yield no new information.
+    pub fn has_observed(&self, network: &Network) -> bool {
+        let g = genome(network);
+        self.archive.iter().any(|(other, _)| *other == g)
+    }
+
+    /// Creates a new surrogate model which has not observed any networks.
+    pub fn new(capacity: usize, k: usize) -> Self {
+        Surrogate {
+            archive: VecDeque::new(),
+            capacity,
+            k
+        }
+    }
+
+    /// Records the true error of the specified network.
+    pub fn observe(&mut self, network: &Network, error: usize) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.archive.len() == self.capacity {
+            self.archive.pop_front();
+        }
+        self.archive.push_back((genome(network), error));
+    }
+
+    /// Predicts the error of the specified network, or returns `None` if no
+    /// networks have been observed yet.
+    pub fn predict(&self, network: &Network) -> Option<f64> {
+        if self.archive.is_empty() {
+            return None;
+        }
+        let g = genome(network);
+        let mut distances: Vec<(usize, usize)> = self.archive.iter()
+            .map(|(other, e)| (genome_distance(&g, other), *e))
+            .collect();
+        distances.sort_unstable();
+        let nearest = &distances[..self.k.clamp(1, distances.len())];
+        Some(nearest.iter().map(|&(_, e)| e as f64).sum::<f64>() / nearest.len() as f64)
+    }
+}
+
+/// Computes the genome of the specified network: the bias, input combinators
+/// and result combinator of each neuron in order.
+pub fn genome(network: &Network) -> Vec<u8> {
+    let mut res: Vec<u8> = Vec::new();
+    for n in network.layers.iter().flat_map(|l| l.neurons.iter()) {
+        res.push(n.bias as u8);
+        res.extend(n.input_combinators.iter().map(|&c| u8::from(c)));
+        res.push(u8::from(n.result_combinator));
+    }
+    res
+}
+
+/// Computes the number of positions at which two genomes differ, counting any
+/// difference in length as differing positions.
+pub fn genome_distance(a: &[u8], b: &[u8]) -> usize {
+    let diff = a.iter().zip(b.iter()).filter(|(x, y)| x != y).count();
+    diff + (a.len() as isize - b.len() as isize).unsigned_abs()
+}
@@ -0,0 +1,154 @@
+//! Contains the `EventWriter` type, for recording training scalars (such as
+//! the `history` of a `TrainReport` or `EvolveReport`) as TensorBoard event
+//! files, so that `bnl` runs can be monitored alongside other experiments.
+//!
+//! An event file is a sequence of TFRecord records, each holding an `Event`
+//! protocol buffer, which are encoded here directly rather than through a
+//! protobuf library:
+//!
+//! ```no_run
+//! use bnl::tensorboard::EventWriter;
+//!
+//! let mut network = bnl::Network::new(2, vec![1]);
+//! let dataset = vec![(vec![true, true], vec![true])];
+//! let report = network.train(&dataset, Default::default());
+//! let mut writer = EventWriter::create("runs/and").unwrap();
+//! writer.add_history("train/error", report.history.iter().map(|&e| e as f64)).unwrap();
+//! ```
+
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The version string TensorBoard expects in the first event of each file.
+const FILE_VERSION: &str = "brain.Event:2";
+
+/// Represents a writer of TensorBoard events to an underlying stream.
+#[derive(Debug)]
+pub struct EventWriter<W: Write> {
+    /// The stream events are written to.
+    writer: W
+}
+
+/// Implements custom methods for event writers to files.
+impl EventWriter<BufWriter<fs::File>> {
+    /// Creates a new event file within the specified log directory (creating
+    /// the directory if necessary), named as TensorBoard expects.
+    pub fn create<P: AsRef<Path>>(logdir: P) -> io::Result<Self> {
+        fs::create_dir_all(&logdir)?;
+        let name = format!("events.out.tfevents.{}.bnl", wall_time() as u64);
+        Self::new(BufWriter::new(fs::File::create(logdir.as_ref().join(name))?))
+    }
+}
+
+/// Implements custom methods for event writers.
+impl<W: Write> EventWriter<W> {
+    /// Records each value of the specified history as a scalar of the
+    /// specified tag, at steps counting up from zero.
+    pub fn add_history<I: IntoIterator<Item = f64>>(&mut self, tag: &str, history: I) -> io::Result<()> {
+        for (step, value) in history.into_iter().enumerate() {
+            self.add_scalar(tag, step as u64, value)?;
+        }
+        self.flush()
+    }
+
+    /// Records the specified value as a scalar of the specified tag at the
+    /// specified step.
+    pub fn add_scalar(&mut self, tag: &str, step: u64, value: f64) -> io::Result<()> {
+        let mut summary_value = Vec::new();
+        put_bytes(&mut summary_value, 1, tag.as_bytes());
+        put_key(&mut summary_value, 2, 5);
+        summary_value.extend_from_slice(&(value as f32).to_le_bytes());
+        let mut summary = Vec::new();
+        put_bytes(&mut summary, 1, &summary_value);
+        let mut event = event_header(step);
+        put_bytes(&mut event, 5, &summary);
+        self.write_record(&event)
+    }
+
+    /// Flushes the underlying stream.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Returns the underlying stream.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Creates a new event writer to the specified stream, writing the
+    /// file version event which starts every event file.
+    pub fn new(writer: W) -> io::Result<Self> {
+        let mut res = EventWriter { writer };
+        let mut event = event_header(0);
+        put_bytes(&mut event, 3, FILE_VERSION.as_bytes());
+        res.write_record(&event)?;
+        Ok(res)
+    }
+
+    /// Writes the specified data as a single TFRecord record.
+    fn write_record(&mut self, data: &[u8]) -> io::Result<()> {
+        let len = (data.len() as u64).to_le_bytes();
+        self.writer.write_all(&len)?;
+        self.writer.write_all(&masked_crc32c(&len).to_le_bytes())?;
+        self.writer.write_all(data)?;
+        self.writer.write_all(&masked_crc32c(data).to_le_bytes())
+    }
+}
+
+/// Computes the CRC-32C (Castagnoli) checksum of the specified data.
+fn crc32c(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in data {
+        crc ^= b as u32;
+        for _i in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0x82f6_3b78 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Encodes the wall time and specified step shared by every event.
+fn event_header(step: u64) -> Vec<u8> {
+    let mut res = Vec::new();
+    put_key(&mut res, 1, 1);
+    res.extend_from_slice(&wall_time().to_le_bytes());
+    put_key(&mut res, 2, 0);
+    put_varint(&mut res, step);
+    res
+}
+
+/// Computes the masked CRC-32C checksum TFRecord stores alongside its record
+/// lengths and data.
+fn masked_crc32c(data: &[u8]) -> u32 {
+    let crc = crc32c(data);
+    crc.rotate_right(15).wrapping_add(0xa282_ead8)
+}
+
+/// Appends a length-delimited protobuf field to the specified buffer.
+fn put_bytes(buf: &mut Vec<u8>, field: u32, data: &[u8]) {
+    put_key(buf, field, 2);
+    put_varint(buf, data.len() as u64);
+    buf.extend_from_slice(data);
+}
+
+/// Appends the key of a protobuf field of the specified wire type to the
+/// specified buffer.
+fn put_key(buf: &mut Vec<u8>, field: u32, wire_type: u32) {
+    put_varint(buf, ((field << 3) | wire_type) as u64);
+}
+
+/// Appends the specified value to the specified buffer as a protobuf varint.
+fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+/// Returns the current time in seconds since the Unix epoch.
+fn wall_time() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0.0, |d| d.as_secs_f64())
+}
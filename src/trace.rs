@@ -0,0 +1,83 @@
+//! Contains `Network::apply_traced`, which records the bits flowing through
+//! every layer of a `bnl` network while it is applied, for debugging why a
+//! network produces a particular output:
+//!
+//! ```
+//! use bnl::builder::NetworkBuilder;
+//! use bnl::Combinator::*;
+//!
+//! let network = NetworkBuilder::new(2)
+//!     .layer(|l| l.neuron(&[And], false, Left).neuron(&[Or], true, Xor))
+//!     .build()
+//!     .unwrap();
+//! let trace = network.apply_traced(&[true, false]);
+//! assert_eq!(trace.to_string(), "input:   10\nlayer 0: 00\n");
+//! assert_eq!(format!("{:#}", trace), "input:   10\nlayer 0: 00\n    pre: 01\n");
+//! ```
+
+use crate::bits::format_bits;
+use crate::network::Network;
+use std::fmt;
+
+/// Represents the intermediate values computed while applying a network to a
+/// single input.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct Trace {
+    /// The input the network was applied to.
+    pub input: Vec<bool>,
+
+    /// The output of each layer, the last of which is the output of the
+    /// network.
+    pub layers: Vec<Vec<bool>>,
+
+    /// The value of each neuron of each layer before its result combinator
+    /// combined it with its bias (see `Neuron::apply_input`).
+    pub pre_bias: Vec<Vec<bool>>
+}
+
+/// Implements custom methods for traces.
+impl Trace {
+    /// Returns the output of the traced network.
+    pub fn output(&self) -> &[bool] {
+        self.layers.last().map_or(&self.input, |l| l)
+    }
+}
+
+/// Draws traces with one row of bits per layer, below the input. The alternate
+/// form (`{:#}`) also draws the pre-bias value of each layer's neurons below
+/// their outputs.
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "input:   {}", format_bits(&self.input))?;
+        for (i, (layer, pre)) in self.layers.iter().zip(self.pre_bias.iter()).enumerate() {
+            let label = format!("layer {}:", i);
+            writeln!(f, "{:<8} {}", label, format_bits(layer))?;
+            if f.alternate() {
+                writeln!(f, "{:>8} {}", "pre:", format_bits(pre))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Implements tracing on `bnl` networks.
+impl Network {
+    /// "Applies" this network on the specified input vector of boolean values
+    /// as with `Network::apply`, recording the output of every layer and the
+    /// pre-bias value of every neuron.
+    pub fn apply_traced(&self, input: &[bool]) -> Trace {
+        let mut layers: Vec<Vec<bool>> = Vec::new();
+        let mut pre_bias: Vec<Vec<bool>> = Vec::new();
+        for layer in &self.layers {
+            let previous = layers.last().map_or(input, |l| l);
+            let pre: Vec<bool> = layer.neurons.iter().map(|n| n.apply_input(previous)).collect();
+            layers.push(layer.neurons.iter().zip(pre.iter()).map(|(n, &p)| n.apply_result(p)).collect());
+            pre_bias.push(pre);
+        }
+        Trace {
+            input: input.to_vec(),
+            layers,
+            pre_bias
+        }
+    }
+}
@@ -0,0 +1,797 @@
+//! Contains routines for training `bnl` networks on datasets of
+//! `(input, expected)` samples.
+//!
+//! Training works by stochastic local search: the parameters of randomly
+//! selected neurons (biases and combinators) are mutated, and each mutation is
+//! kept only if it does not increase the total Hamming distance between the
+//! network's outputs and the expected outputs. Alternatively, simulated
+//! annealing (see `Network::anneal`) occasionally keeps mutations which
+//! increase the error, allowing it to escape local optima. Every trainer
+//! evaluates candidates bit-parallel over a packed copy of the dataset (see
+//! `batch::BitDataset`), which the `_packed` variants accept ready-made from
+//! `Dataset::pack` so that repeated training runs pack it only once. Custom objectives may be minimized instead via
+//! `Network::train_with` and the `metrics::Fitness` trait, and progress may
+//! be reported to an observer via `Network::train_observed` (see the
+//! `observe` module). Training may also run on a background thread via
+//! `spawn`, which returns a `TrainingHandle` for monitoring, pausing and
+//! cancelling it without blocking the calling thread.
+//!
+//! On imbalanced datasets, where a constant network matching the majority
+//! class is hard to climb away from, candidates may instead be compared on
+//! class-balanced mini-batches (see `TrainOptions::balanced_batch` and
+//! `data::BalancedSampler`).
+
+use crate::analysis::hamming_distance;
+use crate::batch::BitDataset;
+use crate::combinator::Combinator;
+use crate::data::BalancedSampler;
+use crate::metrics::Fitness;
+use crate::network::{BiasMode, InitProfile, Network, Neuron};
+use crate::observe::{Control, GenerationStats, TrainObserver};
+use rand::Rng;
+use std::fmt;
+use std::panic;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+/// Represents the options controlling `Network::anneal`.
+#[derive(Clone,Debug)]
+pub struct AnnealOptions {
+    /// Which parts of the bias stage of each neuron may be mutated.
+    pub bias_mode: BiasMode,
+
+    /// The maximum number of candidate mutations to evaluate.
+    pub iterations: usize,
+
+    /// Whether mutations may only introduce monotone combinators (see
+    /// `TrainOptions::monotone`).
+    pub monotone: bool,
+
+    /// The number of parameters changed by each candidate mutation.
+    pub mutations_per_step: usize,
+
+    /// The temperature schedule to follow.
+    pub schedule: Schedule
+}
+
+/// Provides reasonable default annealing options.
+impl Default for AnnealOptions {
+    fn default() -> Self {
+        AnnealOptions {
+            bias_mode: BiasMode::Learned,
+            iterations: 10000,
+            monotone: false,
+            mutations_per_step: 1,
+            schedule: Schedule::Exponential {
+                start: 2.0,
+                decay: 0.9995
+            }
+        }
+    }
+}
+
+/// Represents a source of the class-balanced mini-batches on which training
+/// compares candidates (see `TrainOptions::balanced_batch`).
+pub(crate) struct Batches {
+    /// The sampler drawing the indices of the samples of each mini-batch.
+    sampler: BalancedSampler,
+
+    /// The samples of the dataset.
+    samples: Vec<(Vec<bool>, Vec<bool>)>,
+
+    /// The number of samples of each mini-batch.
+    size: usize
+}
+
+/// Implements custom methods for mini-batch sources.
+impl Batches {
+    /// Draws the samples of a new mini-batch.
+    pub(crate) fn draw<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<(Vec<bool>, Vec<bool>)> {
+        self.sampler.sample_with_rng(self.size, rng).into_iter().map(|i| self.samples[i].clone()).collect()
+    }
+
+    /// Creates a new source of mini-batches of the specified size over the
+    /// specified samples, or `None` if no size is specified.
+    pub(crate) fn new(samples: Vec<(Vec<bool>, Vec<bool>)>, size: Option<usize>) -> Option<Self> {
+        size.map(|size| Batches {
+            sampler: BalancedSampler::new(&samples),
+            samples,
+            size
+        })
+    }
+
+    /// Creates a new source of mini-batches as with `Batches::new`, over the
+    /// samples of the specified packed dataset.
+    pub(crate) fn unpacked(packed: &BitDataset, size: Option<usize>) -> Option<Self> {
+        size?;
+        Batches::new(packed.inputs.to_samples().into_iter().zip(packed.expected.to_samples()).collect(), size)
+    }
+}
+
+/// Represents the state of a training job shared between its thread and its
+/// handle.
+struct JobState {
+    /// Whether the job has been cancelled.
+    cancelled: bool,
+
+    /// Whether the job has been paused.
+    paused: bool,
+
+    /// The latest progress of the job.
+    progress: TrainProgress
+}
+
+/// Represents the options controlling `progressive`.
+#[derive(Clone,Debug)]
+pub struct ProgressiveOptions {
+    /// The maximum number of hidden layers which may be added.
+    pub max_hidden_layers: usize,
+
+    /// The maximum number of training stages to run.
+    pub max_stages: usize,
+
+    /// The maximum width to which a hidden layer may be widened, after which
+    /// a new hidden layer is added instead.
+    pub max_width: usize,
+
+    /// The options used to train the network during each stage.
+    pub stage: TrainOptions,
+
+    /// The number of neurons added each time a hidden layer is widened.
+    pub widen_step: usize
+}
+
+/// Provides reasonable default progressive training options.
+impl Default for ProgressiveOptions {
+    fn default() -> Self {
+        ProgressiveOptions {
+            max_hidden_layers: 3,
+            max_stages: 50,
+            max_width: 32,
+            stage: TrainOptions {
+                iterations: 2000,
+                ..TrainOptions::default()
+            },
+            widen_step: 2
+        }
+    }
+}
+
+/// Represents the outcome of a call to `progressive`.
+#[derive(Clone,Debug)]
+pub struct ProgressiveReport {
+    /// The error of the network after each stage.
+    pub history: Vec<usize>,
+
+    /// The trained network.
+    pub network: Network,
+
+    /// The layer lengths of the network during each stage.
+    pub topologies: Vec<Vec<usize>>
+}
+
+/// Represents a temperature schedule for simulated annealing, mapping the
+/// current iteration to a temperature. A mutation increasing the error by
+/// `d` is kept with probability `exp(-d / temperature)`, so higher
+/// temperatures accept worse networks more readily.
+#[derive(Clone)]
+pub enum Schedule {
+    /// A temperature given by a custom function of the current iteration and
+    /// the total number of iterations.
+    Custom(Arc<dyn Fn(usize, usize) -> f64 + Send + Sync>),
+
+    /// A temperature starting at `start` and multiplied by `decay` after
+    /// every iteration.
+    Exponential {
+        /// The initial temperature.
+        start: f64,
+
+        /// The factor applied to the temperature after every iteration.
+        decay: f64
+    },
+
+    /// A temperature decreasing linearly from `start` at the first iteration
+    /// to `end` at the last.
+    Linear {
+        /// The initial temperature.
+        start: f64,
+
+        /// The final temperature.
+        end: f64
+    }
+}
+
+/// Implements custom methods for temperature schedules.
+impl Schedule {
+    /// Computes the temperature at the specified iteration (counting from
+    /// zero) of an annealing run of the specified total length.
+    pub fn temperature(&self, iteration: usize, total: usize) -> f64 {
+        match self {
+            Schedule::Custom(f) => f(iteration, total),
+            Schedule::Exponential { start, decay } => start * decay.powi(iteration as i32),
+            Schedule::Linear { start, end } => {
+                let t = if total > 1 { iteration as f64 / (total - 1) as f64 } else { 1.0 };
+                start + (end - start) * t
+            }
+        }
+    }
+}
+
+/// Allows temperature schedules to be debug-printed, despite custom
+/// schedules holding an opaque function.
+impl fmt::Debug for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Schedule::Custom(_) => write!(f, "Custom(..)"),
+            Schedule::Exponential { start, decay } => f.debug_struct("Exponential")
+                .field("start", start)
+                .field("decay", decay)
+                .finish(),
+            Schedule::Linear { start, end } => f.debug_struct("Linear")
+                .field("start", start)
+                .field("end", end)
+                .finish()
+        }
+    }
+}
+
+/// Represents the configuration of a training job started by `spawn`.
+#[derive(Clone,Debug)]
+pub struct TrainConfig {
+    /// The dataset to train on.
+    pub dataset: Vec<(Vec<bool>, Vec<bool>)>,
+
+    /// The network to train.
+    pub network: Network,
+
+    /// The options controlling training.
+    pub options: TrainOptions,
+
+    /// The seed of the generator the job draws from, or `None` to draw from
+    /// the generator of its thread (see the `random` module).
+    pub seed: Option<u64>
+}
+
+/// Represents the options controlling `Network::train`.
+#[derive(Clone,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrainOptions {
+    /// Whether to keep mutations which leave the error unchanged. Boolean
+    /// networks have large plateaus of equal error, so accepting such
+    /// "neutral" moves lets the search drift across them.
+    pub accept_neutral: bool,
+
+    /// If specified, each candidate mutation is compared with the current
+    /// network on a fresh mini-batch of this many samples, drawn with equal
+    /// numbers of samples of each expected output pattern (see
+    /// `data::BalancedSampler`), rather than on the whole dataset. The
+    /// reported errors remain those on the whole dataset, which may then rise.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub balanced_batch: Option<usize>,
+
+    /// Which parts of the bias stage of each neuron may be mutated. With
+    /// `BiasMode::Fixed` or `BiasMode::Disabled`, the network should have been
+    /// initialized accordingly (see `InitProfile::bias_mode`).
+    pub bias_mode: BiasMode,
+
+    /// The maximum number of candidate mutations to evaluate.
+    pub iterations: usize,
+
+    /// Whether mutations may only introduce monotone combinators (see
+    /// `Combinator::MONOTONE`), so that a monotone network stays monotone.
+    /// The network should have been initialized accordingly (see
+    /// `Network::new_monotone`).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub monotone: bool,
+
+    /// The number of parameters changed by each candidate mutation.
+    pub mutations_per_step: usize
+}
+
+/// Provides reasonable default training options.
+impl Default for TrainOptions {
+    fn default() -> Self {
+        TrainOptions {
+            accept_neutral: true,
+            balanced_batch: None,
+            bias_mode: BiasMode::Learned,
+            iterations: 10000,
+            monotone: false,
+            mutations_per_step: 1
+        }
+    }
+}
+
+/// Represents the progress of a training job started by `spawn`.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct TrainProgress {
+    /// The lowest error reached so far.
+    pub best_error: usize,
+
+    /// Whether the job has stopped, because it finished, was cancelled or
+    /// panicked.
+    pub finished: bool,
+
+    /// The number of candidate mutations evaluated so far.
+    pub iterations: usize,
+
+    /// The maximum number of candidate mutations the job will evaluate.
+    pub total_iterations: usize
+}
+
+/// Represents the outcome of a call to `Network::train`, with errors measured
+/// as numbers of differing bits, or of a call to `Network::train_with`, with
+/// errors measured by the given fitness function.
+#[derive(Clone,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrainReport<E = usize> {
+    /// The error of the network after training.
+    pub final_error: E,
+
+    /// The error of the network after each improvement, starting with its
+    /// error before training.
+    pub history: Vec<E>,
+
+    /// The error of the network before training.
+    pub initial_error: E,
+
+    /// The number of candidate mutations evaluated.
+    pub iterations: usize
+}
+
+/// Represents a handle to a training job running on a background thread,
+/// through which it may be monitored and controlled.
+///
+/// Every method except `TrainingHandle::join` returns immediately, so the
+/// handle may be polled from a GUI event loop or an asynchronous task (which
+/// should call `join` through its runtime's facility for blocking work, such
+/// as `tokio::task::spawn_blocking`, or only once `progress` reports the job
+/// finished).
+pub struct TrainingHandle {
+    /// The state shared with the thread of the job.
+    shared: Arc<(Mutex<JobState>, Condvar)>,
+
+    /// The thread of the job.
+    thread: thread::JoinHandle<(Network, TrainReport)>
+}
+
+/// Implements custom methods for training handles.
+impl TrainingHandle {
+    /// Stops the job after the candidate mutation it is evaluating, even if
+    /// it is paused. The network trained so far remains available via
+    /// `TrainingHandle::join`.
+    pub fn cancel(&self) {
+        self.state().cancelled = true;
+        self.shared.1.notify_all();
+    }
+
+    /// Waits for the job to stop and returns the trained network along with
+    /// its training report.
+    ///
+    /// If the job panicked, the panic is resumed on the calling thread.
+    pub fn join(self) -> (Network, TrainReport) {
+        self.thread.join().unwrap_or_else(|e| panic::resume_unwind(e))
+    }
+
+    /// Pauses the job after the candidate mutation it is evaluating, until
+    /// `TrainingHandle::resume` or `TrainingHandle::cancel` is called.
+    pub fn pause(&self) {
+        self.state().paused = true;
+    }
+
+    /// Returns the latest progress of the job.
+    pub fn progress(&self) -> TrainProgress {
+        let mut progress = self.state().progress;
+        progress.finished |= self.thread.is_finished();
+        progress
+    }
+
+    /// Resumes the job if it is paused.
+    pub fn resume(&self) {
+        self.state().paused = false;
+        self.shared.1.notify_all();
+    }
+
+    /// Locks the state shared with the thread of the job, which no panic can
+    /// leave inconsistent.
+    fn state(&self) -> std::sync::MutexGuard<'_, JobState> {
+        self.shared.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Implements training on `bnl` networks.
+impl Network {
+    /// Trains this network in place on the specified dataset via simulated
+    /// annealing, stopping early if the network reproduces every sample.
+    ///
+    /// Every candidate mutation which does not increase the error is kept,
+    /// and one which does is kept with a probability determined by the
+    /// temperature schedule. Since the search may wander away from good
+    /// networks, this network is left as the best one encountered, and the
+    /// returned report describes it.
+    pub fn anneal(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], opts: AnnealOptions) -> TrainReport {
+        self.anneal_with_rng(dataset, opts, &mut crate::random::rng())
+    }
+
+    /// Trains this network in place via simulated annealing as with
+    /// `Network::anneal_with_rng`, but on an already packed dataset (see
+    /// `Dataset::pack`), so that it is packed only once across calls.
+    pub fn anneal_packed_with_rng<R: Rng + ?Sized>(&mut self, packed: &BitDataset, opts: AnnealOptions, rng: &mut R) -> TrainReport {
+        let initial = packed.error(self);
+        let mut report = TrainReport {
+            final_error: initial,
+            history: vec![initial],
+            initial_error: initial,
+            iterations: 0
+        };
+        let mut current = self.clone();
+        let mut current_error = initial;
+        while report.iterations < opts.iterations && report.final_error > 0 {
+            let temperature = opts.schedule.temperature(report.iterations, opts.iterations);
+            report.iterations += 1;
+            let mut candidate = current.clone();
+            for _i in 0..opts.mutations_per_step {
+                mutate(&mut candidate, opts.bias_mode, combinator_pool(opts.monotone), rng);
+            }
+            let e = packed.error(&candidate);
+            let accept = e <= current_error || (temperature > 0.0
+                && rng.gen::<f64>() < (-((e - current_error) as f64) / temperature).exp());
+            if accept {
+                current = candidate;
+                current_error = e;
+                if e < report.final_error {
+                    report.history.push(e);
+                    report.final_error = e;
+                    *self = current.clone();
+                }
+            }
+        }
+        report
+    }
+
+    /// Trains this network in place via simulated annealing as with
+    /// `Network::anneal`, drawing random choices from the specified generator.
+    pub fn anneal_with_rng<R: Rng + ?Sized>(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], opts: AnnealOptions, rng: &mut R) -> TrainReport {
+        self.anneal_packed_with_rng(&BitDataset::from_samples(dataset), opts, rng)
+    }
+
+    /// Trains this network in place on the specified dataset via hill
+    /// climbing, stopping early if the network reproduces every sample.
+    pub fn train(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], opts: TrainOptions) -> TrainReport {
+        self.train_with_rng(dataset, opts, &mut crate::random::rng())
+    }
+
+    /// Trains this network in place via hill climbing as with
+    /// `Network::train`, but passes the statistics of each candidate mutation
+    /// to the specified observer, stopping early if it returns `Control::Stop`.
+    pub fn train_observed(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], opts: TrainOptions, observer: &mut dyn TrainObserver) -> TrainReport {
+        self.train_observed_with_rng(dataset, opts, observer, &mut crate::random::rng())
+    }
+
+    /// Trains this network in place via hill climbing as with
+    /// `Network::train_observed_with_rng`, but on an already packed dataset
+    /// (see `Dataset::pack`).
+    pub fn train_observed_packed_with_rng<R: Rng + ?Sized>(&mut self, packed: &BitDataset, opts: TrainOptions, observer: &mut dyn TrainObserver, rng: &mut R) -> TrainReport {
+        let initial = packed.error(self);
+        let mut report = TrainReport {
+            final_error: initial,
+            history: vec![initial],
+            initial_error: initial,
+            iterations: 0
+        };
+        let batches = Batches::unpacked(packed, opts.balanced_batch);
+        while report.iterations < opts.iterations && report.final_error > 0 {
+            let e = climb(self, packed, batches.as_ref(), &opts, &mut report, rng);
+            let stats = GenerationStats {
+                best_error: report.final_error,
+                evaluations: report.iterations + 1,
+                generation: report.iterations,
+                mean_error: e as f64,
+                mutations: opts.mutations_per_step
+            };
+            if observer.observe(&stats) == Control::Stop {
+                break;
+            }
+        }
+        report
+    }
+
+    /// Trains this network in place via hill climbing as with
+    /// `Network::train_observed`, drawing random choices from the specified
+    /// generator.
+    pub fn train_observed_with_rng<R: Rng + ?Sized>(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], opts: TrainOptions, observer: &mut dyn TrainObserver, rng: &mut R) -> TrainReport {
+        self.train_observed_packed_with_rng(&BitDataset::from_samples(dataset), opts, observer, rng)
+    }
+
+    /// Trains this network in place via hill climbing as with
+    /// `Network::train_with_rng`, but on an already packed dataset (see
+    /// `Dataset::pack`), so that it is packed only once across calls.
+    pub fn train_packed_with_rng<R: Rng + ?Sized>(&mut self, packed: &BitDataset, opts: TrainOptions, rng: &mut R) -> TrainReport {
+        self.train_observed_packed_with_rng(packed, opts, &mut |_: &GenerationStats| Control::Continue, rng)
+    }
+
+    /// Trains this network in place on the specified dataset via hill
+    /// climbing as with `Network::train`, but minimizing the error given by
+    /// the specified fitness function, and stopping early if it reaches zero.
+    ///
+    /// The dataset is packed once and passed to `Fitness::error_packed`
+    /// along with the samples, so that fitness functions which can (such as
+    /// `metrics::HammingLoss`) evaluate candidates bit-parallel like
+    /// `Network::train`, while others evaluate them sample by sample.
+    pub fn train_with<F: Fitness + ?Sized>(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], opts: TrainOptions, fitness: &F) -> TrainReport<f64> {
+        self.train_with_fitness_rng(dataset, opts, fitness, &mut crate::random::rng())
+    }
+
+    /// Trains this network in place via hill climbing as with
+    /// `Network::train_with`, drawing random choices from the specified
+    /// generator.
+    pub fn train_with_fitness_rng<F: Fitness + ?Sized, R: Rng + ?Sized>(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], opts: TrainOptions, fitness: &F, rng: &mut R) -> TrainReport<f64> {
+        let packed = BitDataset::from_samples(dataset);
+        let initial = fitness.error_packed(self, dataset, &packed);
+        let mut report = TrainReport {
+            final_error: initial,
+            history: vec![initial],
+            initial_error: initial,
+            iterations: 0
+        };
+        let batches = Batches::new(dataset.to_vec(), opts.balanced_batch);
+        while report.iterations < opts.iterations && report.final_error > 0.0 {
+            report.iterations += 1;
+            let mut candidate = self.clone();
+            for _i in 0..opts.mutations_per_step {
+                mutate(&mut candidate, opts.bias_mode, combinator_pool(opts.monotone), rng);
+            }
+            if let Some(b) = &batches {
+                let batch = b.draw(rng);
+                let packed_batch = BitDataset::from_samples(&batch);
+                let (current, e) = (fitness.error_packed(self, &batch, &packed_batch), fitness.error_packed(&candidate, &batch, &packed_batch));
+                if e < current || (opts.accept_neutral && e == current) {
+                    let e = fitness.error_packed(&candidate, dataset, &packed);
+                    if e < report.final_error {
+                        report.history.push(e);
+                    }
+                    report.final_error = e;
+                    *self = candidate;
+                }
+                continue;
+            }
+            let e = fitness.error_packed(&candidate, dataset, &packed);
+            if e < report.final_error || (opts.accept_neutral && e == report.final_error) {
+                if e < report.final_error {
+                    report.history.push(e);
+                }
+                report.final_error = e;
+                *self = candidate;
+            }
+        }
+        report
+    }
+
+    /// Trains this network in place via hill climbing as with
+    /// `Network::train`, drawing random choices from the specified generator.
+    pub fn train_with_rng<R: Rng + ?Sized>(&mut self, dataset: &[(Vec<bool>, Vec<bool>)], opts: TrainOptions, rng: &mut R) -> TrainReport {
+        self.train_observed_with_rng(dataset, opts, &mut |_: &GenerationStats| Control::Continue, rng)
+    }
+}
+
+/// Performs a single iteration of `Network::train` on the specified network
+/// and packed dataset, recording its outcome in the specified report and
+/// returning the error of the candidate (on the mini-batch it was compared
+/// on, if mini-batches are specified).
+pub(crate) fn climb<R: Rng + ?Sized>(network: &mut Network, packed: &BitDataset, batches: Option<&Batches>, opts: &TrainOptions, report: &mut TrainReport, rng: &mut R) -> usize {
+    report.iterations += 1;
+    let mut candidate = network.clone();
+    for _i in 0..opts.mutations_per_step {
+        mutate(&mut candidate, opts.bias_mode, combinator_pool(opts.monotone), rng);
+    }
+    if let Some(b) = batches {
+        let batch = BitDataset::from_samples(&b.draw(rng));
+        let (current, e) = (batch.error(network), batch.error(&candidate));
+        if e < current || (opts.accept_neutral && e == current) {
+            let full = packed.error(&candidate);
+            if full < report.final_error {
+                report.history.push(full);
+            }
+            report.final_error = full;
+            *network = candidate;
+        }
+        return e;
+    }
+    let e = packed.error(&candidate);
+    if e < report.final_error || (opts.accept_neutral && e == report.final_error) {
+        if e < report.final_error {
+            report.history.push(e);
+        }
+        report.final_error = e;
+        *network = candidate;
+    }
+    e
+}
+
+/// Returns the pool of combinators mutations draw from, restricted to the
+/// monotone combinators if specified.
+pub(crate) fn combinator_pool(monotone: bool) -> &'static [Combinator] {
+    if monotone { &Combinator::MONOTONE } else { &Combinator::ALL }
+}
+
+/// Computes the total error of the specified network on the specified dataset,
+/// as the sum of the Hamming distances between its outputs and the expected
+/// outputs.
+pub fn error(network: &Network, dataset: &[(Vec<bool>, Vec<bool>)]) -> usize {
+    #[cfg(feature = "profiling")]
+    let _timer = crate::profile::timer(crate::profile::Phase::Evaluate);
+    dataset.iter()
+        .map(|(input, expected)| hamming_distance(&network.apply(input), expected))
+        .sum()
+}
+
+/// Randomly changes a single parameter of a single randomly selected unlocked
+/// neuron of the specified network (see `mutate_neuron`), drawn from the specified
+/// generator.
+pub(crate) fn mutate<R: Rng + ?Sized>(network: &mut Network, bias_mode: BiasMode, combinators: &[Combinator], rng: &mut R) {
+    #[cfg(feature = "profiling")]
+    let _timer = crate::profile::timer(crate::profile::Phase::Mutate);
+    let total = network.layers.iter().flat_map(|l| l.neurons.iter()).filter(|n| !n.locked).count();
+    if total == 0 {
+        return;
+    }
+    let index = rng.gen_range(0, total);
+    let neuron = network.layers.iter_mut()
+        .flat_map(|l| l.neurons.iter_mut())
+        .filter(|n| !n.locked)
+        .nth(index)
+        .unwrap();
+    mutate_neuron(neuron, bias_mode, combinators, rng);
+}
+
+/// Randomly changes a single parameter (the bias, one input combinator, the
+/// result combinator, or, if the neuron is sparsely wired, the index of one of
+/// its inputs, as far as the specified bias mode allows) of the specified
+/// neuron, drawn from the specified generator. Changed combinators are drawn
+/// uniformly from the specified pool.
+pub(crate) fn mutate_neuron<R: Rng + ?Sized>(neuron: &mut Neuron, bias_mode: BiasMode, combinators: &[Combinator], rng: &mut R) {
+    let fixed = match bias_mode {
+        BiasMode::Disabled => 2,
+        BiasMode::Fixed(_) => 1,
+        BiasMode::Learned => 0
+    };
+    let chain = neuron.input_combinators.len();
+    let len = chain + 2 + neuron.inputs.as_ref().map_or(0, |v| v.len());
+    if len == fixed {
+        return;
+    }
+    let choice = rng.gen_range(fixed, len);
+    if choice == 0 {
+        neuron.bias = !neuron.bias;
+    } else if choice == 1 {
+        neuron.result_combinator = combinators[rng.gen_range(0, combinators.len())];
+    } else if choice < chain + 2 {
+        neuron.input_combinators[choice - 2] = combinators[rng.gen_range(0, combinators.len())];
+    } else {
+        let arity = neuron.arity;
+        neuron.inputs.as_mut().unwrap()[choice - chain - 2] = rng.gen_range(0, arity);
+    }
+}
+
+/// Trains a network on the specified dataset starting from the smallest viable
+/// topology (a single layer of output neurons), growing it whenever training
+/// plateaus.
+///
+/// Each stage trains the current network with `Network::train`. If a stage
+/// fails to reduce the error, the last hidden layer is widened by
+/// `widen_step` neurons, or (if there is no hidden layer, or it has already
+/// reached `max_width`) a new hidden layer is inserted before the output
+/// layer. Both operations preserve the function computed by the network (see
+/// `Network::widen` and `Network::deepen`), so every stage warm-starts from
+/// the network learned by the stage before it. Stops once the network
+/// reproduces every sample, after `max_stages` stages, or when the topology
+/// can no longer grow.
+///
+/// If the stage options are monotone (see `TrainOptions::monotone`), the
+/// network starts out monotone (see `Network::new_monotone`) and grows only by
+/// monotone neurons, so that it stays monotone after every stage.
+///
+/// Panics if the dataset is empty.
+pub fn progressive(dataset: &[(Vec<bool>, Vec<bool>)], opts: ProgressiveOptions) -> ProgressiveReport {
+    progressive_with_rng(dataset, opts, &mut crate::random::rng())
+}
+
+/// Trains a network progressively as with `progressive`, drawing random
+/// choices from the specified generator.
+pub fn progressive_with_rng<R: Rng + ?Sized>(dataset: &[(Vec<bool>, Vec<bool>)], opts: ProgressiveOptions, rng: &mut R) -> ProgressiveReport {
+    let (input, expected) = dataset.first().expect("Cannot train on an empty dataset!");
+    let monotone = opts.stage.monotone;
+    let mut network = if monotone {
+        Network::new_monotone_with_rng(input.len(), vec![expected.len()], rng)
+    } else {
+        Network::new_with_rng(input.len(), vec![expected.len()], rng)
+    };
+    let mut history: Vec<usize> = Vec::new();
+    let mut topologies: Vec<Vec<usize>> = Vec::new();
+    let packed = BitDataset::from_samples(dataset);
+    let mut last = packed.error(&network);
+    for _stage in 0..opts.max_stages {
+        topologies.push(network.layers.iter().map(|l| l.neurons.len()).collect());
+        let e = network.train_packed_with_rng(&packed, opts.stage.clone(), rng).final_error;
+        if monotone {
+            if let Err(e) = network.validate_monotone() {
+                panic!("Cannot keep a network monotone during progressive training: {}!", e);
+            }
+        }
+        history.push(e);
+        if e == 0 {
+            break;
+        }
+        if e >= last {
+            let hidden = network.layers.len() - 1;
+            if hidden > 0 && network.layers[hidden - 1].neurons.len() < opts.max_width {
+                let count = opts.widen_step.min(opts.max_width - network.layers[hidden - 1].neurons.len());
+                if monotone {
+                    network.widen_with_profile_with_rng(hidden - 1, count, &InitProfile::monotone(), rng);
+                } else {
+                    network.widen_with_rng(hidden - 1, count, rng);
+                }
+            } else if hidden < opts.max_hidden_layers {
+                network.deepen(hidden);
+            } else {
+                break;
+            }
+        }
+        last = e;
+    }
+    ProgressiveReport {
+        history,
+        network,
+        topologies
+    }
+}
+
+/// Starts training a network via hill climbing as with `Network::train` on a
+/// new thread, returning a handle through which the job may be monitored,
+/// paused, resumed or cancelled.
+pub fn spawn(config: TrainConfig) -> TrainingHandle {
+    let packed = BitDataset::from_samples(&config.dataset);
+    let progress = TrainProgress {
+        best_error: packed.error(&config.network),
+        finished: false,
+        iterations: 0,
+        total_iterations: config.options.iterations
+    };
+    let shared = Arc::new((Mutex::new(JobState {
+        cancelled: false,
+        paused: false,
+        progress
+    }), Condvar::new()));
+    let job = Arc::clone(&shared);
+    let thread = thread::spawn(move || {
+        let TrainConfig { mut network, options, seed, .. } = config;
+        let (lock, resumed) = &*job;
+        let mut observer = |stats: &GenerationStats| {
+            let mut state = lock.lock().unwrap_or_else(|e| e.into_inner());
+            state.progress.best_error = stats.best_error;
+            state.progress.iterations = stats.generation;
+            while state.paused && !state.cancelled {
+                state = resumed.wait(state).unwrap_or_else(|e| e.into_inner());
+            }
+            if state.cancelled { Control::Stop } else { Control::Continue }
+        };
+        let report = match seed {
+            Some(seed) => {
+                use rand::SeedableRng;
+                let mut rng = crate::random::Xoshiro256::seed_from_u64(seed);
+                network.train_observed_packed_with_rng(&packed, options, &mut observer, &mut rng)
+            },
+            None => network.train_observed_packed_with_rng(&packed, options, &mut observer, &mut crate::random::rng())
+        };
+        let mut state = lock.lock().unwrap_or_else(|e| e.into_inner());
+        state.progress.best_error = report.final_error;
+        state.progress.finished = true;
+        state.progress.iterations = report.iterations;
+        drop(state);
+        (network, report)
+    });
+    TrainingHandle {
+        shared,
+        thread
+    }
+}
@@ -0,0 +1,142 @@
+//! Contains routines for enumerating the complete behavior of small `bnl`
+//! networks, and for checking exhaustively whether two networks compute the
+//! same function.
+//!
+//! Input `i` of the row at index `x` of a truth table is bit `i` of `x`, as
+//! with `analysis::probe_inputs`. Rows are evaluated bit-parallel (see the
+//! `batch` module) in chunks of `CHUNK_LEN`, so memory use is bounded when
+//! checking equivalence.
+
+use crate::batch::BitBatch;
+use crate::network::Network;
+
+/// The number of rows evaluated together as a single batch.
+pub const CHUNK_LEN: usize = 1 << 16;
+
+/// The largest input width for which `Network::equivalent_to` will enumerate
+/// every input.
+pub const MAX_EQUIVALENCE_WIDTH: usize = 32;
+
+/// The largest input width for which `Network::truth_table` will enumerate
+/// every input.
+pub const MAX_TABLE_WIDTH: usize = 20;
+
+/// Implements exhaustive enumeration on `bnl` networks.
+impl Network {
+    /// Determines whether this network computes the same function as another
+    /// network, by comparing their outputs on every input.
+    ///
+    /// Networks of different input or output widths are never equivalent.
+    ///
+    /// Panics if the input width exceeds `MAX_EQUIVALENCE_WIDTH`.
+    pub fn equivalent_to(&self, other: &Network) -> bool {
+        self.same_widths(other) && self.first_difference(other).is_none()
+    }
+
+    /// Finds the first input (in truth table order) on which this network and
+    /// another network of the same input and output widths disagree, or
+    /// `None` if they are equivalent.
+    ///
+    /// Panics if the networks' widths differ, or if the input width exceeds
+    /// `MAX_EQUIVALENCE_WIDTH`.
+    pub fn first_difference(&self, other: &Network) -> Option<Vec<bool>> {
+        if !self.same_widths(other) {
+            panic!(
+                "Cannot compare networks of widths {} -> {} and {} -> {}!",
+                self.input_width(), self.output_width(), other.input_width(), other.output_width()
+            );
+        }
+        let width = self.input_width();
+        if width > MAX_EQUIVALENCE_WIDTH {
+            panic!("Cannot enumerate every input of a network with {} inputs!", width);
+        }
+        let rows = 1u64 << width;
+        let mut start = 0;
+        while start < rows {
+            let len = (rows - start).min(CHUNK_LEN as u64) as usize;
+            let batch = exhaustive_batch(width, start, len);
+            let (a, b) = (self.apply_batch(&batch), other.apply_batch(&batch));
+            if a.hamming_distance(&b) > 0 {
+                let index = a.to_samples().iter().zip(b.to_samples().iter()).position(|(x, y)| x != y).unwrap();
+                let x = start + index as u64;
+                return Some((0..width).map(|i| (x >> i) & 1 == 1).collect());
+            }
+            start += len as u64;
+        }
+        None
+    }
+
+    /// Determines whether this network has the same input and output widths as
+    /// another network.
+    fn same_widths(&self, other: &Network) -> bool {
+        self.input_width() == other.input_width() && self.output_width() == other.output_width()
+    }
+
+    /// Computes the output of this network for every possible input, in truth
+    /// table order.
+    ///
+    /// Panics if the input width exceeds `MAX_TABLE_WIDTH`.
+    pub fn truth_table(&self) -> Vec<Vec<bool>> {
+        let width = self.input_width();
+        if width > MAX_TABLE_WIDTH {
+            panic!("Cannot enumerate the truth table of a network with {} inputs!", width);
+        }
+        let rows = 1usize << width;
+        (0..rows).step_by(CHUNK_LEN)
+            .flat_map(|start| {
+                let batch = exhaustive_batch(width, start as u64, (rows - start).min(CHUNK_LEN));
+                self.apply_batch(&batch).to_samples()
+            })
+            .collect()
+    }
+
+    /// Computes the output of this network for every possible input as a
+    /// packed batch (see the `batch` module), whose sample at index `x` is the
+    /// output for the row at index `x` of the truth table, taking one bit per
+    /// row of each output rather than a whole vector. Bits beyond the last row
+    /// are zero.
+    ///
+    /// Returns an error if the input width exceeds the specified maximum (or
+    /// `MAX_EQUIVALENCE_WIDTH`), which bounds the memory used at `2^max_bits`
+    /// bits per output, i.e. two megabytes for 24 inputs.
+    pub fn truth_table_packed(&self, max_bits: usize) -> Result<BitBatch, String> {
+        let width = self.input_width();
+        let limit = max_bits.min(MAX_EQUIVALENCE_WIDTH);
+        if width > limit {
+            return Err(format!("network has {} inputs (at most {} allowed)", width, limit));
+        }
+        let rows = 1usize << width;
+        let mut signals: Vec<Vec<u64>> = vec![Vec::with_capacity(rows.div_ceil(64)); self.output_width()];
+        for start in (0..rows).step_by(CHUNK_LEN) {
+            let batch = exhaustive_batch(width, start as u64, (rows - start).min(CHUNK_LEN));
+            for (s, chunk) in signals.iter_mut().zip(self.apply_batch(&batch).signals) {
+                s.extend(chunk);
+            }
+        }
+        if rows < 64 {
+            signals.iter_mut().for_each(|s| s[0] &= (1 << rows) - 1);
+        }
+        Ok(BitBatch {
+            len: rows,
+            signals
+        })
+    }
+}
+
+/// Packs the specified number of consecutive rows of the truth table of the
+/// specified input width, starting at the specified row, into a batch.
+fn exhaustive_batch(width: usize, start: u64, len: usize) -> BitBatch {
+    let words = len.div_ceil(64);
+    let signals = (0..width).map(|i| {
+        (0..words).map(|w| {
+            (0..64).fold(0u64, |acc, j| {
+                let x = start + (64 * w + j) as u64;
+                acc | (((x >> i) & 1) << j)
+            })
+        }).collect()
+    }).collect();
+    BitBatch {
+        len,
+        signals
+    }
+}
@@ -0,0 +1,75 @@
+//! Contains `Network::apply_mc`, a crude estimate of the confidence of a
+//! `bnl` network in its outputs via gate-level dropout at inference time.
+//!
+//! Each stochastic forward pass disables every gate (every input combinator
+//! and result combinator of every neuron) independently with the given rate,
+//! forcing its output to `false`, as dropout zeroes activations. Outputs
+//! which most passes agree on are robust to the loss of a few gates, whereas
+//! outputs which flip between passes hinge on a handful of them.
+
+use crate::network::{compute_boolean, Network};
+use rand::Rng;
+
+/// Represents the outcome of a call to `Network::apply_mc`.
+#[derive(Clone,Debug,PartialEq)]
+pub struct McEstimate {
+    /// The fraction of passes producing each output of `output`, between one
+    /// half and one.
+    pub agreement: Vec<f64>,
+
+    /// The value of each output produced by most passes, with ties going to
+    /// the output of the network without dropout.
+    pub output: Vec<bool>
+}
+
+/// Implements Monte Carlo dropout on `bnl` networks.
+impl Network {
+    /// "Applies" this network to the specified input over the specified
+    /// number of stochastic passes, each of which disables every gate with
+    /// the specified probability, and reports how many of them agree on each
+    /// output.
+    ///
+    /// Panics if the number of passes is zero.
+    pub fn apply_mc(&self, input: &[bool], samples: usize, rate: f64) -> McEstimate {
+        self.apply_mc_with_rng(input, samples, rate, &mut crate::random::rng())
+    }
+
+    /// Applies this network as with `Network::apply_mc`, drawing the disabled
+    /// gates from the specified generator.
+    pub fn apply_mc_with_rng<R: Rng + ?Sized>(&self, input: &[bool], samples: usize, rate: f64, rng: &mut R) -> McEstimate {
+        if samples == 0 {
+            panic!("Cannot estimate agreement over zero passes!");
+        }
+        let mut ones: Vec<usize> = vec![0; self.output_width()];
+        for _i in 0..samples {
+            for (o, b) in ones.iter_mut().zip(dropout_pass(self, input, rate, rng)) {
+                *o += b as usize;
+            }
+        }
+        let deterministic = self.apply(input);
+        let (output, agreement) = ones.iter().zip(deterministic).map(|(&n, d)| {
+            let value = if 2 * n == samples { d } else { 2 * n > samples };
+            let agreeing = if value { n } else { samples - n };
+            (value, agreeing as f64 / samples as f64)
+        }).unzip();
+        McEstimate {
+            agreement,
+            output
+        }
+    }
+}
+
+/// Applies the specified network to the specified input once, disabling each
+/// gate with the specified probability.
+fn dropout_pass<R: Rng + ?Sized>(network: &Network, input: &[bool], rate: f64, rng: &mut R) -> Vec<bool> {
+    let mut gate = |l: bool, r: bool, c| !rng.gen_bool(rate) && compute_boolean(l, r, c);
+    let mut signals = input.to_vec();
+    for layer in &network.layers {
+        signals = layer.neurons.iter().map(|n| {
+            let selected: Vec<bool> = (0..n.fan_in()).map(|k| signals[n.input_index(k)]).collect();
+            let acc = n.reduction.reduce(selected, &n.input_combinators, &mut gate);
+            gate(acc, n.bias, n.result_combinator)
+        }).collect();
+    }
+    signals
+}
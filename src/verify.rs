@@ -0,0 +1,37 @@
+//! Contains routines for building circuits which check properties of `bnl`
+//! networks, so that those properties may be decided by a solver or other
+//! exhaustive backend rather than by sampling.
+//!
+//! The central construction is the miter of two networks, a network with a
+//! single output which is `true` exactly on the inputs where the networks
+//! disagree. The networks are equivalent if and only if the miter is
+//! unsatisfiable, i.e. always outputs `false`.
+
+use crate::combinator::Combinator;
+use crate::network::{Layer, Network, Neuron, Reduction};
+
+/// Creates the miter of the specified networks, a network on the same input
+/// with a single output which is `true` if and only if the networks differ in
+/// at least one output bit.
+///
+/// The miter XORs each pair of corresponding outputs (see `Network::xor`) and
+/// ORs the results together in one further neuron.
+///
+/// Panics if the networks differ in input or output width, or have no
+/// layers.
+pub fn miter(a: &Network, b: &Network) -> Network {
+    let mut res = a.xor(b);
+    let width = res.output_width();
+    res.layers.push(Layer {
+        neurons: vec![Neuron {
+            arity: width,
+            bias: false,
+            input_combinators: vec![Combinator::Or; width - 1],
+            inputs: None,
+            locked: false,
+            reduction: Reduction::RightFold,
+            result_combinator: Combinator::Left
+        }]
+    });
+    res
+}
@@ -0,0 +1,94 @@
+//! Contains the `Word` trait abstracting over the machine words into which
+//! samples are packed for bit-parallel evaluation (see the `batch` module).
+//!
+//! Each bit of a word holds the value of a signal for a different sample, so
+//! that a bitwise operation on a word applies a combinator to as many samples
+//! as the word has bits. The kernels of `batch` and `linear` are written
+//! against this trait, so the same code evaluates 64 samples at once with
+//! `u64` (as `BitBatch` does), 128 with `u128`, or more with any wider type,
+//! such as a SIMD vector, which implements it.
+
+use core::fmt;
+use core::ops;
+
+/// Represents a machine word of bits, each of which holds a signal of a
+/// different sample.
+pub trait Word: Copy + Eq + fmt::Debug
+    + ops::BitAnd<Output = Self> + ops::BitOr<Output = Self> + ops::BitXor<Output = Self> + ops::Not<Output = Self> {
+    /// The number of bits of this word, and so of samples it holds.
+    const BITS: usize;
+
+    /// The word whose bits are all `false`.
+    const ZERO: Self;
+
+    /// Determines whether the bit at the specified position is set.
+    fn bit(self, index: usize) -> bool;
+
+    /// Counts the bits of this word which are set.
+    fn count_ones(self) -> u32;
+
+    /// Returns the word whose bits all equal the specified value.
+    fn splat(value: bool) -> Self {
+        if value { !Self::ZERO } else { Self::ZERO }
+    }
+
+    /// Returns this word with the bit at the specified position set.
+    fn with_bit(self, index: usize) -> Self;
+}
+
+/// Implements `Word` for primitive unsigned integers.
+macro_rules! impl_word {
+    ($($t:ty),*) => {
+        $(
+            /// Packs samples into the bits of unsigned integers, with sample
+            /// `j` at the bit of value `2^j`.
+            impl Word for $t {
+                const BITS: usize = <$t>::BITS as usize;
+
+                const ZERO: Self = 0;
+
+                fn bit(self, index: usize) -> bool {
+                    (self >> index) & 1 == 1
+                }
+
+                fn count_ones(self) -> u32 {
+                    <$t>::count_ones(self)
+                }
+
+                fn with_bit(self, index: usize) -> Self {
+                    self | (1 << index)
+                }
+            }
+        )*
+    };
+}
+
+impl_word!(u8, u16, u32, u64, u128);
+
+/// Packs the specified samples, which must all be of the specified width,
+/// into the words of each of their signals, where bit `j` of word `w` of
+/// signal `i` is signal `i` of sample `W::BITS * w + j`. Bits beyond the last
+/// sample are `false`.
+///
+/// Panics if any sample is of a different width.
+pub fn pack<W: Word>(samples: &[Vec<bool>], width: usize) -> Vec<Vec<W>> {
+    let words = samples.len().div_ceil(W::BITS);
+    let mut signals: Vec<Vec<W>> = vec![vec![W::ZERO; words]; width];
+    for (j, sample) in samples.iter().enumerate() {
+        if sample.len() != width {
+            panic!("Cannot pack samples of width {} and {} into one batch!", width, sample.len());
+        }
+        for (signal, _) in signals.iter_mut().zip(sample.iter()).filter(|(_, &b)| b) {
+            signal[j / W::BITS] = signal[j / W::BITS].with_bit(j % W::BITS);
+        }
+    }
+    signals
+}
+
+/// Unpacks the specified number of samples from the words of each of their
+/// signals, as packed by `pack`.
+pub fn unpack<W: Word>(signals: &[Vec<W>], len: usize) -> Vec<Vec<bool>> {
+    (0..len)
+        .map(|j| signals.iter().map(|s| s[j / W::BITS].bit(j % W::BITS)).collect())
+        .collect()
+}
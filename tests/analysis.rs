@@ -0,0 +1,69 @@
+use bnl::analysis::{duplicate_neurons, merge_duplicate_neurons, op_count, probe_inputs_with_rng};
+use bnl::random::Xoshiro256;
+use bnl::{Combinator, InitProfile, Layer, Network, Neuron, Reduction};
+use rand::SeedableRng;
+
+fn neuron(input_combinators: Vec<Combinator>, reduction: Reduction) -> Network {
+    let mut neuron = Neuron::constant(4, false);
+    neuron.input_combinators = input_combinators;
+    neuron.reduction = reduction;
+    neuron.result_combinator = Combinator::Left;
+    Network {
+        layers: vec![Layer {
+            neurons: vec![neuron]
+        }]
+    }
+}
+
+#[test]
+fn counts_live_gates_under_every_reduction() {
+    use Combinator::*;
+    let cases = [
+        (vec![Left, Or, Or], Reduction::RightFold, 2),
+        (vec![Left, Or, Or], Reduction::LeftFold, 4),
+        (vec![Or, Or, Right], Reduction::LeftFold, 2),
+        (vec![Left, Or, Or], Reduction::Tree, 4),
+        (vec![Or, Right, Left], Reduction::Tree, 3)
+    ];
+    for (combinators, reduction, live) in cases.iter().cloned() {
+        let count = op_count(&neuron(combinators, reduction));
+        assert_eq!((count.gates, count.live_gates, count.neurons), (4, live, 1), "{:?}", reduction);
+    }
+    let mut constant = neuron(vec![Or, Or, Or], Reduction::LeftFold);
+    constant.layers[0].neurons[0].result_combinator = Right;
+    assert_eq!(op_count(&constant).live_gates, 0);
+}
+
+#[test]
+fn merges_duplicate_neurons_without_changing_outputs() {
+    let mut rng = Xoshiro256::seed_from_u64(1);
+    let inputs = probe_inputs_with_rng(6, 64, &mut rng);
+    let sparse = InitProfile {
+        fan_in: Some(3),
+        ..InitProfile::default()
+    };
+    let tree = InitProfile {
+        reduction: Reduction::Tree,
+        ..InitProfile::default()
+    };
+    for profile in &[InitProfile::default(), sparse, tree] {
+        let mut network = Network::new_with_profile_with_rng(6, vec![5, 4, 2], profile, &mut rng);
+        network.layers[0].neurons[3] = network.layers[0].neurons[1].clone();
+        network.layers[0].neurons[4] = network.layers[0].neurons[1].clone();
+        network.layers[1].neurons[2] = network.layers[1].neurons[0].clone();
+        let found = duplicate_neurons(&network, &inputs);
+        assert!(found.contains(&(0, 1, 3)) && found.contains(&(0, 1, 4)) && found.contains(&(1, 0, 2)));
+        let (merged, removed) = merge_duplicate_neurons(&network, &inputs);
+        merged.validate().unwrap();
+        assert!(removed >= 3);
+        assert_eq!(network.layers.iter().map(|l| l.neurons.len()).sum::<usize>() - removed, merged.layers.iter().map(|l| l.neurons.len()).sum::<usize>());
+        assert!(duplicate_neurons(&merged, &inputs).iter().all(|&(l, _, _)| l + 1 == merged.layers.len()));
+        for input in &inputs {
+            assert_eq!(merged.apply(input), network.apply(input));
+        }
+        let mut locked = network.clone();
+        locked.layers[1].neurons[1].locked = true;
+        let (merged, _) = merge_duplicate_neurons(&locked, &inputs);
+        assert_eq!(merged.layers[0].neurons.len(), 5);
+    }
+}
@@ -0,0 +1,72 @@
+use bnl::arena::NetworkArena;
+use bnl::batch::BitDataset;
+use bnl::crossover::{layer_crossover_with_rng, uniform_crossover_with_rng};
+use bnl::mutate::replace_parameters_from;
+use bnl::random::Xoshiro256;
+use bnl::{Combinator, InitProfile, Network};
+use rand::{Rng, SeedableRng};
+
+fn networks(rng: &mut Xoshiro256) -> Vec<Network> {
+    let sparse = InitProfile {
+        fan_in: Some(3),
+        ..InitProfile::default()
+    };
+    (0..6).map(|i| if i % 2 == 0 {
+        Network::new_with_rng(7, vec![6, 4, 2], rng)
+    } else {
+        Network::new_with_profile_with_rng(7, vec![6, 4, 2], &sparse, rng)
+    }).collect()
+}
+
+#[test]
+fn stores_and_evaluates_networks_exactly() {
+    let mut rng = Xoshiro256::seed_from_u64(1);
+    let networks = networks(&mut rng);
+    let mut arena = NetworkArena::new();
+    for n in &networks {
+        arena.push(n);
+    }
+    let samples: Vec<(Vec<bool>, Vec<bool>)> = (0..100)
+        .map(|_| ((0..7).map(|_| rng.gen()).collect(), vec![rng.gen(), rng.gen()]))
+        .collect();
+    let packed = BitDataset::from_samples(&samples);
+    assert_eq!(arena.len(), networks.len());
+    assert_eq!(arena.to_networks(), networks);
+    for (g, n) in networks.iter().enumerate() {
+        assert_eq!(arena.shape(g), vec![6, 4, 2]);
+        assert_eq!(arena.apply_batch(g, &packed.inputs), n.apply_batch(&packed.inputs));
+        assert_eq!(arena.error(g, &packed), packed.error(n));
+    }
+    let mut copy = NetworkArena::new();
+    copy.push_from(&arena, 3);
+    assert_eq!(copy.get(0), networks[3]);
+    copy.clear();
+    assert!(copy.is_empty());
+    copy.push_from(&arena, 4);
+    assert_eq!(copy.to_networks(), vec![networks[4].clone()]);
+}
+
+#[test]
+fn breeds_like_nested_networks() {
+    let mut rng = Xoshiro256::seed_from_u64(2);
+    let networks = networks(&mut rng);
+    let mut parents = NetworkArena::new();
+    for n in &networks {
+        parents.push(n);
+    }
+    let mut children = NetworkArena::new();
+    for seed in 0..8 {
+        let (a, b) = (2 * (seed % 3), 2 * (seed % 3) + 1 - (seed % 2));
+        let (mut x, mut y) = (Xoshiro256::seed_from_u64(seed as u64), Xoshiro256::seed_from_u64(seed as u64));
+        let g = children.push_uniform_crossover_with_rng(&parents, a, b, &mut x);
+        assert_eq!(children.get(g), uniform_crossover_with_rng(&networks[a], &networks[b], &mut y));
+        let g = children.push_layer_crossover_with_rng(&parents, a, b, &mut x);
+        assert_eq!(children.get(g), layer_crossover_with_rng(&networks[a], &networks[b], &mut y));
+        let mut mutated = children.get(g);
+        mutated.layers[0].neurons[1].locked = true;
+        let g = children.push(&mutated);
+        let count = children.replace_parameters_from(g, 0.2, &Combinator::MONOTONE, &mut x);
+        assert_eq!(count, replace_parameters_from(&mut mutated, 0.2, &Combinator::MONOTONE, &mut y));
+        assert_eq!(children.get(g), mutated);
+    }
+}
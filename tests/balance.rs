@@ -0,0 +1,60 @@
+use bnl::balance::ASSOCIATIVE;
+use bnl::random::Xoshiro256;
+use bnl::{Combinator, Network, Reduction};
+use rand::{Rng, SeedableRng};
+
+fn inputs(width: usize) -> impl Iterator<Item = Vec<bool>> {
+    (0..(1usize << width)).map(move |x| (0..width).map(|b| (x >> b) & 1 == 1).collect())
+}
+
+#[test]
+fn balances_associative_chains_without_changing_outputs() {
+    let mut rng = Xoshiro256::seed_from_u64(1);
+    for round in 0..16 {
+        let mut network = Network::new_with_rng(8, vec![8, 6, 3], &mut rng);
+        for n in network.layers.iter_mut().flat_map(|l| l.neurons.iter_mut()) {
+            n.reduction = if rng.gen() { Reduction::LeftFold } else { Reduction::RightFold };
+            let uniform = ASSOCIATIVE[rng.gen_range(0, ASSOCIATIVE.len())];
+            for c in n.input_combinators.iter_mut() {
+                *c = match round % 2 {
+                    0 => uniform,
+                    _ => if rng.gen() { Combinator::Xor } else { Combinator::Xnor }
+                };
+            }
+        }
+        let (balanced, report) = network.balance();
+        assert!(report.depth_after < report.depth_before);
+        assert_eq!(report.depth_after, balanced.depth());
+        assert_eq!(report.balanced.iter().sum::<usize>(), 17);
+        for input in inputs(8) {
+            assert_eq!(balanced.apply(&input), network.apply(&input));
+        }
+    }
+}
+
+#[test]
+fn respects_critical_paths_and_locked_neurons() {
+    let mut network = Network::new_with_rng(8, vec![4, 2], &mut Xoshiro256::seed_from_u64(2));
+    for (j, n) in network.layers[0].neurons.iter_mut().enumerate() {
+        if j > 0 {
+            n.inputs = Some(vec![j]);
+            n.input_combinators.clear();
+        }
+        n.locked = true;
+    }
+    for n in network.layers[1].neurons.iter_mut() {
+        n.inputs = Some(vec![1, 2, 3, 0]);
+        n.input_combinators = vec![Combinator::Or; 3];
+        n.reduction = Reduction::LeftFold;
+    }
+    network.layers[1].neurons[1].inputs = Some(vec![0, 1, 2, 3]);
+    network.validate().unwrap();
+    let (balanced, report) = network.balance();
+    assert_eq!(report.balanced, vec![0, 1]);
+    assert_eq!(balanced.layers[1].neurons[0].reduction, Reduction::LeftFold);
+    assert_eq!(balanced.layers[1].neurons[1].reduction, Reduction::Tree);
+    assert_eq!((report.depth_before, report.depth_after), (12, 11));
+    for input in inputs(8) {
+        assert_eq!(balanced.apply(&input), network.apply(&input));
+    }
+}
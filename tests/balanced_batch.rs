@@ -0,0 +1,82 @@
+use bnl::observe::{Control, GenerationStats};
+use bnl::population::{EvolveOptions, Population, Selection};
+use bnl::random::Xoshiro256;
+use bnl::train::TrainOptions;
+use bnl::{Layer, Network, Neuron};
+use rand::{RngCore, SeedableRng};
+use std::sync::{Arc, Mutex};
+
+/// Records the scores it selects parents by, always choosing the first.
+#[derive(Debug,Default)]
+struct Scores(Mutex<Vec<f64>>);
+
+impl Selection for Scores {
+    fn select(&self, scores: &[f64], _rng: &mut dyn RngCore) -> usize {
+        self.0.lock().unwrap().extend_from_slice(scores);
+        0
+    }
+}
+
+/// Returns the samples of the conjunction of six inputs, of which only one
+/// is positive.
+fn conjunction() -> Vec<(Vec<bool>, Vec<bool>)> {
+    (0..64usize).map(|x| {
+        let input: Vec<bool> = (0..6).map(|b| (x >> b) & 1 == 1).collect();
+        (input, vec![x == 63])
+    }).collect()
+}
+
+/// Returns a network which always outputs false and is never mutated.
+fn constant_false() -> Network {
+    let mut neuron = Neuron::constant(6, false);
+    neuron.locked = true;
+    Network {
+        layers: vec![Layer {
+            neurons: vec![neuron]
+        }]
+    }
+}
+
+#[test]
+fn training_compares_candidates_on_balanced_batches() {
+    let dataset = conjunction();
+    let train = |balanced_batch| {
+        let opts = TrainOptions {
+            balanced_batch,
+            iterations: 20,
+            ..TrainOptions::default()
+        };
+        let mut errors: Vec<f64> = Vec::new();
+        let mut observer = |stats: &GenerationStats| {
+            errors.push(stats.mean_error);
+            Control::Continue
+        };
+        let report = constant_false().train_observed_with_rng(&dataset, opts, &mut observer, &mut Xoshiro256::seed_from_u64(1));
+        assert_eq!(report.final_error, 1);
+        errors
+    };
+    assert_eq!(train(None), vec![1.0; 20]);
+    assert_eq!(train(Some(8)), vec![4.0; 20]);
+}
+
+#[test]
+fn evolution_selects_parents_on_balanced_batches() {
+    let dataset = conjunction();
+    let evolve = |balanced_batch| {
+        let scores = Arc::new(Scores::default());
+        let options = EvolveOptions {
+            balanced_batch,
+            elitism: 0,
+            selection: Some(scores.clone()),
+            ..EvolveOptions::default()
+        };
+        let mut population = Population::from_networks(&vec![constant_false(); 4], options);
+        let report = population.evolve_with_rng(&dataset, 3, &mut Xoshiro256::seed_from_u64(2));
+        assert_eq!(report.history, vec![1, 1, 1]);
+        let recorded = scores.0.lock().unwrap().clone();
+        assert!(!recorded.is_empty());
+        recorded
+    };
+    assert!(evolve(None).iter().all(|&s| s == 1.0));
+    assert!(evolve(Some(8)).iter().all(|&s| s == 4.0));
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "serde")]
+
+use bnl::cache::dataset_hash;
+use bnl::checkpoint::Trainer;
+use bnl::random::Xoshiro256;
+use bnl::train::TrainOptions;
+use bnl::{Error, Network};
+use rand::{Rng, SeedableRng};
+use std::env;
+use std::fs;
+use std::io;
+
+fn dataset() -> Vec<(Vec<bool>, Vec<bool>)> {
+    let mut rng = Xoshiro256::seed_from_u64(1);
+    (0..64u32).map(|x| {
+        let input: Vec<bool> = (0..6).map(|b| (x >> b) & 1 == 1).collect();
+        let expected: Vec<bool> = (0..3).map(|_| rng.gen()).collect();
+        (input, expected)
+    }).collect()
+}
+
+fn options() -> TrainOptions {
+    TrainOptions {
+        iterations: 400,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn matches_train_with_rng() {
+    let dataset = dataset();
+    let network = Network::new_seeded(3, 6, vec![8, 3]);
+    let mut expected = network.clone();
+    let report = expected.train_with_rng(&dataset, options(), &mut Xoshiro256::seed_from_u64(9));
+    let mut trainer = Trainer::new(network, options(), 9);
+    assert_eq!(trainer.run(&dataset).unwrap(), report);
+    assert_eq!(trainer.network(), &expected);
+}
+
+#[test]
+fn resume_is_deterministic() {
+    let dataset = dataset();
+    let network = Network::new_seeded(5, 6, vec![8, 3]);
+    let mut uninterrupted = Trainer::new(network.clone(), options(), 11);
+    let expected = uninterrupted.run(&dataset).unwrap();
+    assert!(expected.iterations > 150);
+    for name in &["bnl-checkpoint.json", "bnl-checkpoint.bin"] {
+        let path = env::temp_dir().join(format!("{}-{}", std::process::id(), name));
+        let mut interrupted = Trainer::new(network.clone(), options(), 11).with_checkpoints(&path, 50);
+        assert!(!interrupted.run_for(&dataset, 120).unwrap());
+        drop(interrupted);
+        let mut resumed = Trainer::resume(&path).unwrap();
+        assert_eq!(resumed.report().unwrap().iterations, 100);
+        let report = resumed.run(&dataset).unwrap();
+        assert!(resumed.is_finished());
+        let reloaded = Trainer::resume(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(report, expected);
+        assert_eq!(resumed.network(), uninterrupted.network());
+        assert_eq!(reloaded.report(), Some(&expected));
+    }
+}
+
+#[test]
+fn resume_rejects_other_datasets() {
+    let mut dataset = dataset();
+    let mut trainer = Trainer::new(Network::new_seeded(7, 6, vec![3]), options(), 13);
+    trainer.run_for(&dataset, 10).unwrap();
+    let before = (trainer.network().clone(), trainer.report().cloned());
+    dataset[0].1[0] = !dataset[0].1[0];
+    let e = trainer.run_for(&dataset, 10).unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+    let expected = trainer.dataset_hash().unwrap();
+    assert_eq!(e.get_ref().unwrap().downcast_ref::<Error>(), Some(&Error::DatasetMismatch { expected, found: dataset_hash(&dataset) }));
+    assert_eq!((trainer.network().clone(), trainer.report().cloned()), before);
+}
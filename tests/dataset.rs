@@ -0,0 +1,87 @@
+use bnl::data::{resolve_conflicts, ConflictStrategy};
+use bnl::dataset::Dataset;
+use std::ptr;
+
+fn dataset(len: usize) -> Dataset {
+    Dataset::from((0..len).map(|i| ((0..4).map(|b| (i >> b) & 1 == 1).collect(), vec![i % 3 == 0])).collect::<Vec<_>>())
+}
+
+#[test]
+fn splits_and_batches_borrow_samples() {
+    let dataset = dataset(10);
+    let (train, test) = dataset.split(0.75);
+    assert_eq!((train.len(), test.len()), (7, 3));
+    assert!(ptr::eq(&train.as_slice()[0], &dataset.samples[0]));
+    assert!(ptr::eq(&test.as_slice()[0], &dataset.samples[7]));
+    let batches: Vec<usize> = dataset.batches(4).map(|b| b.len()).collect();
+    assert_eq!(batches, vec![4, 4, 2]);
+    assert!(dataset.batches(4).zip(dataset.samples.chunks(4)).all(|(b, c)| ptr::eq(b.as_slice(), c)));
+    assert_eq!(test.to_dataset().samples, dataset.samples[7..].to_vec());
+}
+
+#[test]
+fn folds_cover_every_sample_once() {
+    let mut dataset = dataset(11);
+    let original = dataset.clone();
+    let mut tested: Vec<(Vec<bool>, Vec<bool>)> = Vec::new();
+    let mut lens: Vec<usize> = Vec::new();
+    dataset.for_each_fold(3, |train, test| {
+        assert_eq!(train.len() + test.len(), 11);
+        assert!(test.as_slice().iter().all(|s| !train.as_slice().contains(s)));
+        lens.push(test.len());
+        tested.extend(test.as_slice().iter().cloned());
+    });
+    assert_eq!(lens, vec![4, 4, 3]);
+    assert_eq!(tested, original.samples);
+    assert_eq!(dataset, original);
+}
+
+#[test]
+fn hash_tells_datasets_apart() {
+    let mut dataset = dataset(16);
+    let hash = dataset.hash();
+    assert_eq!(dataset.clone().hash(), hash);
+    assert_eq!(hash, 0x05e5_ff48_55c3_475b);
+    dataset.samples[3].1[0] = !dataset.samples[3].1[0];
+    assert_ne!(dataset.hash(), hash);
+    dataset.samples[3].1[0] = !dataset.samples[3].1[0];
+    dataset.samples.swap(0, 1);
+    assert_ne!(dataset.hash(), hash);
+}
+
+#[test]
+fn shuffles_deterministically_for_a_seed() {
+    let original = dataset(8);
+    let mut shuffled = original.clone();
+    shuffled.shuffle(3);
+    let mut again = original.clone();
+    again.shuffle(3);
+    assert_eq!(shuffled, again);
+    let order: Vec<usize> = shuffled.samples.iter().map(|s| original.samples.iter().position(|o| o == s).unwrap()).collect();
+    assert_eq!(order, vec![7, 0, 3, 1, 6, 2, 4, 5]);
+}
+
+#[test]
+fn resolves_conflicts_in_place() {
+    let sample = |input: &str, expected: bool| (input.chars().map(|c| c == '1').collect::<Vec<bool>>(), vec![expected]);
+    let original = Dataset::from(vec![
+        sample("00", false),
+        sample("01", true),
+        sample("01", false),
+        sample("10", true),
+        sample("01", false),
+        sample("10", true)
+    ]);
+    let cases = [
+        (ConflictStrategy::Drop, vec![sample("00", false), sample("10", true), sample("10", true)]),
+        (ConflictStrategy::KeepFirst, vec![sample("00", false), sample("01", true), sample("10", true), sample("10", true)]),
+        (ConflictStrategy::Majority, vec![sample("00", false), sample("01", false), sample("10", true), sample("10", true)])
+    ];
+    for (strategy, expected) in cases.iter().cloned() {
+        let mut dataset = original.clone();
+        dataset.resolve_conflicts(strategy);
+        assert_eq!(dataset.samples, expected, "{:?}", strategy);
+        dataset.validate().unwrap();
+        assert_eq!(resolve_conflicts(&original.samples, strategy), expected);
+    }
+}
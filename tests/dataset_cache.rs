@@ -0,0 +1,28 @@
+use bnl::data::cache::{source_key, DatasetCache};
+use bnl::dataset::Dataset;
+use std::fs;
+
+#[test]
+fn reloads_unchanged_sources_from_the_cache() {
+    let dir = std::env::temp_dir().join(format!("bnl-dataset-cache-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let source = dir.join("xor.csv");
+    fs::write(&source, "a,b,y\n0,0,0\n0,1,1\n1,0,1\n1,1,0\n").unwrap();
+    let mut cache = DatasetCache::open(dir.join("cache")).unwrap();
+    let parsed = Dataset::from_csv(&source, 1).unwrap();
+    assert_eq!(cache.load(&source, 1).unwrap(), parsed);
+    assert_eq!(cache.load(&source, 1).unwrap(), parsed);
+    assert_eq!((cache.hits, cache.misses), (1, 1));
+    assert_ne!(cache.load(&source, 2).unwrap(), parsed);
+    assert_eq!(cache.misses, 2);
+    fs::write(&source, "a,b,y\n0,0,1\n").unwrap();
+    assert_eq!(cache.load(&source, 1).unwrap().len(), 1);
+    assert_eq!(cache.misses, 3);
+    let key = source_key(&fs::read(&source).unwrap(), Some(1));
+    fs::write(cache.path_for(key), b"garbage").unwrap();
+    assert_eq!(cache.load(&source, 1).unwrap().len(), 1);
+    assert_eq!(cache.load(&source, 1).unwrap().len(), 1);
+    assert_eq!((cache.hits, cache.misses), (2, 4));
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,61 @@
+use bnl::encode::{one_hot_decode, one_hot_encode, thermometer_decode, thermometer_encode, Coding};
+
+#[test]
+fn gray_codes_differ_in_one_bit() {
+    for value in 0..255u8 {
+        let a = Coding::Gray.encode_u8(value);
+        let b = Coding::Gray.encode_u8(value + 1);
+        assert_eq!(a.iter().zip(b.iter()).filter(|(x, y)| x != y).count(), 1);
+    }
+}
+
+#[test]
+fn integer_round_trip() {
+    for &coding in &[Coding::Binary, Coding::Gray] {
+        for value in 0..=255u8 {
+            assert_eq!(coding.decode_u8(&coding.encode_u8(value)), value);
+        }
+        for &value in &[0, 1, 0xdead_beef, u32::MAX] {
+            let bits = coding.encode_u32(value);
+            assert_eq!(bits.len(), 32);
+            assert_eq!(coding.decode_u32(&bits), value);
+        }
+    }
+    assert_eq!(Coding::Binary.encode_u8(6), vec![false, true, true, false, false, false, false, false]);
+}
+
+#[test]
+fn one_hot_round_trip() {
+    for label in 0..5 {
+        let bits = one_hot_encode(label, 5);
+        assert_eq!(bits.iter().filter(|&&b| b).count(), 1);
+        assert_eq!(one_hot_decode(&bits, 5), label);
+    }
+}
+
+#[test]
+fn one_hot_decodes_votes() {
+    let bits = [true, false, false, true, true, false, true, true, false];
+    assert_eq!(one_hot_decode(&bits, 3), 1);
+    assert_eq!(one_hot_decode(&[false; 4], 2), 0);
+    assert_eq!(one_hot_decode(&[false, true, true, false], 4), 1);
+}
+
+#[test]
+#[should_panic]
+fn one_hot_rejects_uneven_votes() {
+    one_hot_decode(&[true; 5], 2);
+}
+
+#[test]
+fn thermometer_round_trip() {
+    assert_eq!(thermometer_encode(0.5, 0.0, 1.0, 4), vec![true, true, false, false]);
+    assert_eq!(thermometer_encode(-3.0, 0.0, 1.0, 3), vec![false; 3]);
+    assert_eq!(thermometer_encode(7.0, 0.0, 1.0, 3), vec![true; 3]);
+    for level in 0..=8 {
+        let value = 10.0 + 2.0 * level as f64;
+        let bits = thermometer_encode(value, 10.0, 26.0, 8);
+        assert!((thermometer_decode(&bits, 10.0, 26.0) - value).abs() < 1e-9);
+    }
+    assert_eq!(thermometer_decode(&[true, false, true, false], 0.0, 1.0), 0.5);
+}
@@ -0,0 +1,127 @@
+use bnl::analysis::probe_inputs_with_rng;
+use bnl::export::{test_vectors_with_rng, CellLibrary};
+use bnl::random::Xoshiro256;
+use bnl::{InitProfile, Layer, Network, Neuron, Reduction};
+use rand::SeedableRng;
+use std::collections::{HashMap, HashSet};
+
+/// Evaluates a BLIF netlist on the specified input, returning its outputs and
+/// the on-sets of its tables of two inputs.
+fn simulate_blif(blif: &str, input: &[bool]) -> (Vec<bool>, HashSet<Vec<String>>) {
+    let mut nets: HashMap<String, bool> = input.iter().enumerate().map(|(i, &b)| (format!("x{}", i), b)).collect();
+    let mut outputs: Vec<String> = Vec::new();
+    let mut tables: HashSet<Vec<String>> = HashSet::new();
+    let lines: Vec<&str> = blif.lines().collect();
+    let mut i = 0;
+    while i < lines.len() {
+        let words: Vec<&str> = lines[i].split_whitespace().collect();
+        i += 1;
+        match words.first() {
+            Some(&".outputs") => outputs = words[1..].iter().map(|w| w.to_string()).collect(),
+            Some(&".names") => {
+                let (ins, out) = (&words[1..(words.len() - 1)], words[words.len() - 1]);
+                let mut rows: Vec<String> = Vec::new();
+                while i < lines.len() && !lines[i].starts_with('.') {
+                    rows.push(lines[i].split_whitespace().next().unwrap().to_string());
+                    i += 1;
+                }
+                let row: String = ins.iter().map(|n| if nets[*n] { '1' } else { '0' }).collect();
+                let value = if ins.is_empty() { !rows.is_empty() } else { rows.contains(&row) };
+                if ins.len() == 2 {
+                    tables.insert(rows);
+                }
+                nets.insert(out.to_string(), value);
+            },
+            _ => {}
+        }
+    }
+    (outputs.iter().map(|o| nets[o]).collect(), tables)
+}
+
+#[test]
+fn test_vectors_follow_the_evaluator() {
+    let mut rng = Xoshiro256::seed_from_u64(1);
+    let network = Network::new_with_rng(4, vec![3, 2], &mut rng);
+    let exhaustive = test_vectors_with_rng(&network, 16, &mut rng);
+    assert_eq!(exhaustive.len(), 16);
+    let sampled = test_vectors_with_rng(&network, 10, &mut rng);
+    assert_eq!(sampled.len(), 10);
+    for (input, expected) in exhaustive.as_slice().iter().chain(sampled.as_slice()) {
+        assert_eq!(network.apply(input), *expected);
+    }
+}
+
+#[test]
+fn testbench_checks_every_vector_most_significant_bit_first() {
+    let network = Network {
+        layers: vec![Layer {
+            neurons: vec![Neuron::selector(3, 0), Neuron::selector(3, 2)]
+        }]
+    };
+    let vectors = test_vectors_with_rng(&network, 8, &mut Xoshiro256::seed_from_u64(0));
+    let testbench = network.to_verilog_testbench("sel", &vectors);
+    assert!(testbench.contains("module sel_tb;"));
+    assert!(testbench.contains("    sel dut (.x(x), .out(out));"));
+    assert!(testbench.contains("    reg [2:0] x;\n    wire [1:0] out;"));
+    assert_eq!(testbench.matches("        check(").count(), 8);
+    assert!(testbench.contains("        check(3'b001, 2'b01);"));
+    assert!(testbench.contains("        check(3'b100, 2'b10);"));
+    assert!(network.to_verilog("sel").starts_with("module sel ("));
+}
+
+#[test]
+fn maps_netlists_onto_cell_libraries() {
+    let mut rng = Xoshiro256::seed_from_u64(2);
+    let rows = |rows: &[&str]| rows.iter().map(|r| r.to_string()).collect::<Vec<String>>();
+    let allowed = [
+        (CellLibrary::AndOrInv, vec![rows(&["11"]), rows(&["01", "10", "11"])]),
+        (CellLibrary::NandInv, vec![rows(&["00", "01", "10"])]),
+        (CellLibrary::NorInv, vec![rows(&["00"])])
+    ];
+    let profiles = [
+        InitProfile::default(),
+        InitProfile {
+            fan_in: Some(3),
+            reduction: Reduction::Tree,
+            ..InitProfile::default()
+        },
+        InitProfile {
+            reduction: Reduction::LeftFold,
+            ..InitProfile::default()
+        }
+    ];
+    for profile in &profiles {
+        let network = Network::new_with_profile_with_rng(5, vec![6, 4, 2], profile, &mut rng);
+        for (library, cells) in &allowed {
+            let blif = network.to_blif_mapped("m", *library);
+            let verilog = network.to_verilog_mapped("m", *library);
+            for input in probe_inputs_with_rng(5, 32, &mut rng) {
+                let (output, tables) = simulate_blif(&blif, &input);
+                assert_eq!(output, network.apply(&input), "{}", library);
+                assert!(tables.iter().all(|t| cells.contains(t)), "{}: {:?}", library, tables);
+            }
+            let forms: &[&str] = match library {
+                CellLibrary::AndOrInv => &["n & n", "n | n"],
+                CellLibrary::NandInv => &["~(n & n)"],
+                _ => &["~(n | n)"]
+            };
+            for line in verilog.lines().filter(|l| l.starts_with("    wire l")) {
+                let expr = line.split(" = ").nth(1).unwrap().trim_end_matches(';');
+                let mut form = String::new();
+                for c in expr.chars() {
+                    if !(c.is_alphanumeric() || "_[]".contains(c)) {
+                        form.push(c);
+                    } else if !form.ends_with('n') {
+                        form.push('n');
+                    }
+                }
+                assert!(forms.contains(&form.as_str()) || form == "~n" || form == "n", "{}: {}", library, line);
+            }
+        }
+        let blif = network.to_blif("m");
+        for input in probe_inputs_with_rng(5, 32, &mut rng) {
+            assert_eq!(simulate_blif(&blif, &input).0, network.apply(&input));
+        }
+        assert_eq!(network.to_verilog_mapped("m", CellLibrary::Any), network.to_verilog("m"));
+    }
+}
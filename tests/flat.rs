@@ -0,0 +1,31 @@
+use bnl::flat::FlatNetwork;
+use bnl::random::Xoshiro256;
+use bnl::Network;
+use rand::SeedableRng;
+
+#[test]
+fn round_trip_preserves_wiring() {
+    let mut network = Network::new_with_rng(4, vec![3, 2], &mut Xoshiro256::seed_from_u64(1));
+    network.layers[0].neurons[0].inputs = Some(vec![0, 1, 2, 3]);
+    network.layers[0].neurons[1].inputs = Some(vec![2]);
+    network.layers[0].neurons[1].input_combinators.clear();
+    network.layers[1].neurons[0].locked = true;
+    network.validate().unwrap();
+    let flat = FlatNetwork::new(&network);
+    assert_eq!(flat.to_network(), network);
+    for x in 0..16u32 {
+        let input: Vec<bool> = (0..4).map(|b| (x >> b) & 1 == 1).collect();
+        assert_eq!(flat.apply(&input), network.apply(&input));
+    }
+}
+
+#[test]
+fn write_to_copies_parameters() {
+    let network = Network::new_with_rng(5, vec![4, 3], &mut Xoshiro256::seed_from_u64(2));
+    let mut flat = FlatNetwork::new(&network);
+    flat.biases.iter_mut().for_each(|b| *b = !*b);
+    let mut target = network.clone();
+    flat.write_to(&mut target);
+    assert_eq!(target, flat.to_network());
+    assert!(target.layers.iter().flat_map(|l| &l.neurons).zip(network.layers.iter().flat_map(|l| &l.neurons)).all(|(a, b)| a.bias != b.bias));
+}
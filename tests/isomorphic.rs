@@ -0,0 +1,61 @@
+use bnl::analysis::probe_inputs_with_rng;
+use bnl::builder::NetworkBuilder;
+use bnl::random::Xoshiro256;
+use bnl::{Combinator, InitProfile, Network};
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+/// Reorders the neurons of the specified hidden layer so that position `k`
+/// holds the neuron formerly at `order[k]`, rewiring the next layer to match.
+fn permuted(network: &Network, layer: usize, order: &[usize]) -> Network {
+    let mut res = network.clone();
+    res.layers[layer].neurons = order.iter().map(|&j| network.layers[layer].neurons[j].clone()).collect();
+    let mut position = vec![0; order.len()];
+    for (k, &j) in order.iter().enumerate() {
+        position[j] = k;
+    }
+    for n in res.layers[layer + 1].neurons.iter_mut() {
+        n.inputs = Some((0..n.fan_in()).map(|k| position[n.input_index(k)]).collect());
+    }
+    res
+}
+
+#[test]
+fn detects_permuted_twins() {
+    let mut rng = Xoshiro256::seed_from_u64(1);
+    let sparse = InitProfile {
+        fan_in: Some(3),
+        ..InitProfile::default()
+    };
+    for profile in &[InitProfile::default(), sparse] {
+        let network = Network::new_with_profile_with_rng(6, vec![5, 4, 2], profile, &mut rng);
+        let mut first: Vec<usize> = (0..5).collect();
+        let mut second: Vec<usize> = (0..4).collect();
+        first.shuffle(&mut rng);
+        second.shuffle(&mut rng);
+        let twin = permuted(&permuted(&network, 0, &first), 1, &second);
+        twin.validate().unwrap();
+        assert!(network.isomorphic_to(&twin));
+        assert!(twin.isomorphic_to(&network));
+        for input in probe_inputs_with_rng(6, 64, &mut rng) {
+            assert_eq!(twin.apply(&input), network.apply(&input));
+        }
+        let mut locked = twin.clone();
+        locked.layers[1].neurons[0].locked = true;
+        assert!(!network.isomorphic_to(&locked));
+    }
+}
+
+#[test]
+fn rejects_reordering_without_rewiring() {
+    let build = |first, second| NetworkBuilder::new(2)
+        .layer(|l| l.neuron(&[first], false, Combinator::Left).neuron(&[second], false, Combinator::Left))
+        .layer(|l| l.neuron(&[Combinator::AndNotRight], false, Combinator::Left))
+        .build()
+        .unwrap();
+    let network = build(Combinator::And, Combinator::Or);
+    let swapped = build(Combinator::Or, Combinator::And);
+    assert_ne!(network.apply(&[true, false]), swapped.apply(&[true, false]));
+    assert!(!network.isomorphic_to(&swapped));
+    assert!(!network.isomorphic_to(&permuted(&swapped, 0, &[1, 0])));
+}
@@ -0,0 +1,89 @@
+use bnl::mutate::{add_neurons_with_profile, apply_batch_from, replace_combinators_from, rerandomize_neurons, rerandomize_neurons_with_profile, BatchOps};
+use bnl::train::{progressive_with_rng, ProgressiveOptions, TrainOptions};
+use bnl::random::Xoshiro256;
+use bnl::{Combinator, InitProfile, Network, Reduction};
+use rand::SeedableRng;
+
+#[test]
+fn batch_mutation_keeps_monotone_networks_monotone() {
+    let mut rng = Xoshiro256::seed_from_u64(1);
+    let mut population: Vec<Network> = (0..8).map(|_| Network::new_monotone_with_rng(6, vec![5, 3], &mut rng)).collect();
+    let ops = BatchOps {
+        bias_rate: 0.5,
+        combinator_rate: 0.5,
+        wiring_rate: 0.5
+    };
+    apply_batch_from(&mut population, ops, &Combinator::MONOTONE, &mut rng);
+    for network in &population {
+        network.validate().unwrap();
+        network.validate_monotone().unwrap();
+    }
+}
+
+#[test]
+fn profiled_mutation_keeps_monotone_networks_monotone() {
+    let mut rng = Xoshiro256::seed_from_u64(2);
+    let profile = InitProfile::monotone();
+    let mut network = Network::new_monotone_with_rng(6, vec![5, 4, 3], &mut rng);
+    network.layers[0].neurons[0].inputs = Some(vec![1, 4]);
+    network.layers[0].neurons[0].input_combinators.truncate(1);
+    for _ in 0..20 {
+        add_neurons_with_profile(&mut network, 0.5, &profile, &mut rng);
+        replace_combinators_from(&mut network, 0.5, &Combinator::MONOTONE, &mut rng);
+        rerandomize_neurons_with_profile(&mut network, 0.5, &profile, &mut rng);
+        network.validate().unwrap();
+        network.validate_monotone().unwrap();
+    }
+    assert_eq!(network.layers[2].neurons.len(), 3);
+}
+
+#[test]
+fn monotone_progressive_training_stays_monotone() {
+    let dataset: Vec<(Vec<bool>, Vec<bool>)> = (0..16u32).map(|x| {
+        let input: Vec<bool> = (0..4).map(|b| (x >> b) & 1 == 1).collect();
+        let ones = input.iter().filter(|&&b| b).count();
+        (input, vec![ones >= 2, ones >= 3])
+    }).collect();
+    let opts = ProgressiveOptions {
+        max_stages: 6,
+        stage: TrainOptions {
+            iterations: 200,
+            monotone: true,
+            ..TrainOptions::default()
+        },
+        ..ProgressiveOptions::default()
+    };
+    let report = progressive_with_rng(&dataset, opts, &mut Xoshiro256::seed_from_u64(3));
+    report.network.validate_monotone().unwrap();
+}
+
+#[test]
+fn rerandomized_neurons_keep_their_reduction_and_lock() {
+    let mut rng = Xoshiro256::seed_from_u64(4);
+    let reductions = [Reduction::LeftFold, Reduction::RightFold, Reduction::Tree];
+    let mut network = Network::new_with_rng(6, vec![6, 3], &mut rng);
+    for (k, n) in network.layers.iter_mut().flat_map(|l| l.neurons.iter_mut()).enumerate() {
+        n.reduction = reductions[k % 3];
+        n.locked = k % 4 == 0;
+    }
+    let profile = InitProfile {
+        reduction: Reduction::RightFold,
+        ..InitProfile::default()
+    };
+    for with_profile in &[false, true] {
+        let mut mutated = network.clone();
+        if *with_profile {
+            rerandomize_neurons_with_profile(&mut mutated, 1.0, &profile, &mut rng);
+        } else {
+            rerandomize_neurons(&mut mutated, 1.0, &mut rng);
+        }
+        mutated.validate().unwrap();
+        assert_ne!(mutated, network);
+        for (before, after) in network.layers.iter().flat_map(|l| l.neurons.iter()).zip(mutated.layers.iter().flat_map(|l| l.neurons.iter())) {
+            assert_eq!((after.reduction, after.locked), (before.reduction, before.locked));
+            if before.locked {
+                assert_eq!(after, before);
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use bnl::analysis::hamming_distance;
+use bnl::dataset::Dataset;
+use bnl::metrics::{hamming_loss, Fitness, HammingLoss};
+use bnl::random::Xoshiro256;
+use bnl::train::TrainOptions;
+use bnl::Network;
+use rand::{Rng, SeedableRng};
+
+fn random_dataset<R: Rng>(len: usize, rng: &mut R) -> Dataset {
+    Dataset::from((0..len).map(|_| {
+        let input: Vec<bool> = (0..6).map(|_| rng.gen()).collect();
+        let expected = vec![input[0] ^ input[3], input[1] && !input[5]];
+        (input, expected)
+    }).collect::<Vec<_>>())
+}
+
+#[test]
+fn evaluates_samples_bit_parallel() {
+    let mut rng = Xoshiro256::seed_from_u64(1);
+    let dataset = random_dataset(131, &mut rng);
+    let packed = dataset.pack();
+    assert_eq!(packed.len(), 131);
+    for _ in 0..8 {
+        let network = Network::new_with_rng(6, vec![5, 2], &mut rng);
+        let errors: Vec<usize> = dataset.as_slice().iter()
+            .map(|(input, expected)| hamming_distance(&network.apply(input), expected))
+            .collect();
+        assert_eq!(packed.sample_errors(&network), errors);
+        assert_eq!(packed.error(&network), errors.iter().sum::<usize>());
+        assert_eq!(
+            HammingLoss.error_packed(&network, dataset.as_slice(), &packed),
+            hamming_loss(&network, dataset.as_slice())
+        );
+    }
+}
+
+#[test]
+fn trains_identically_on_packed_datasets() {
+    let mut rng = Xoshiro256::seed_from_u64(2);
+    let dataset = random_dataset(100, &mut rng);
+    let network = Network::new_with_rng(6, vec![4, 2], &mut rng);
+    let opts = TrainOptions {
+        iterations: 500,
+        ..TrainOptions::default()
+    };
+    let (mut unpacked, mut packed) = (network.clone(), network.clone());
+    let expected = unpacked.train_with_rng(dataset.as_slice(), opts.clone(), &mut Xoshiro256::seed_from_u64(3));
+    let report = packed.train_packed_with_rng(&dataset.pack(), opts.clone(), &mut Xoshiro256::seed_from_u64(3));
+    assert_eq!(report, expected);
+    assert_eq!(packed, unpacked);
+    let per_sample = |n: &Network, d: &[(Vec<bool>, Vec<bool>)]| hamming_loss(n, d);
+    let (mut slow, mut fast) = (network.clone(), network);
+    let expected = slow.train_with_fitness_rng(dataset.as_slice(), opts.clone(), &per_sample, &mut Xoshiro256::seed_from_u64(4));
+    let report = fast.train_with_fitness_rng(dataset.as_slice(), opts, &HammingLoss, &mut Xoshiro256::seed_from_u64(4));
+    assert_eq!(report, expected);
+    assert_eq!(fast, slow);
+}
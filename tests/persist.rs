@@ -0,0 +1,132 @@
+#![cfg(feature = "serde")]
+
+use bnl::analysis::probe_inputs_with_rng;
+use bnl::dataset::Dataset;
+use bnl::persist::{Format, Metadata};
+use bnl::random::Xoshiro256;
+use bnl::{Combinator, Error, Network};
+use rand::SeedableRng;
+use std::env;
+use std::fs;
+use std::io;
+
+fn corruption(e: io::Error) -> (Option<usize>, String) {
+    match e.get_ref().and_then(|e| e.downcast_ref::<Error>()) {
+        Some(Error::CorruptModel { offset, reason }) => (*offset, reason.clone()),
+        _ => panic!("expected a corrupt model error but found {}", e)
+    }
+}
+
+fn assert_same_outputs(a: &Network, b: &Network) {
+    for input in probe_inputs_with_rng(a.input_width(), 256, &mut Xoshiro256::seed_from_u64(0)) {
+        assert_eq!(a.apply(&input), b.apply(&input));
+    }
+}
+
+#[test]
+fn bytes_round_trip() {
+    let network = Network::new_with_rng(6, vec![6, 7, 6], &mut Xoshiro256::seed_from_u64(1));
+    for &format in &[Format::Bincode, Format::Json] {
+        let bytes = network.to_bytes(format).unwrap();
+        let loaded = Network::from_bytes(&bytes, format).unwrap();
+        assert_eq!(network, loaded);
+        assert_same_outputs(&network, &loaded);
+    }
+}
+
+#[test]
+fn file_round_trip() {
+    let network = Network::new_with_rng(8, vec![5, 3], &mut Xoshiro256::seed_from_u64(2));
+    for name in &["bnl-round-trip.json", "bnl-round-trip.bin"] {
+        let path = env::temp_dir().join(format!("{}-{}", std::process::id(), name));
+        network.save(&path).unwrap();
+        let loaded = Network::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(network, loaded);
+        assert_same_outputs(&network, &loaded);
+    }
+}
+
+#[test]
+fn round_trips_metadata() {
+    let network = Network::new_seeded(3, 5, vec![4, 2]);
+    let dataset = Dataset::from(vec![(vec![true; 5], vec![false, true])]);
+    let metadata = Metadata {
+        dataset_hash: Some(dataset.hash())
+    };
+    for &format in &[Format::Bincode, Format::Json] {
+        let bytes = network.to_bytes_with_metadata(&metadata, format).unwrap();
+        assert_eq!(Network::from_bytes_with_metadata(&bytes, format).unwrap(), (network.clone(), metadata.clone()));
+        assert_eq!(Network::from_bytes(&bytes, format).unwrap(), network);
+        let plain = network.to_bytes(format).unwrap();
+        assert_eq!(Network::from_bytes_with_metadata(&plain, format).unwrap(), (network.clone(), Metadata::default()));
+    }
+}
+
+#[test]
+fn format_from_extension() {
+    assert_eq!(Format::from_path("net.json"), Format::Json);
+    assert_eq!(Format::from_path("net.JSON"), Format::Json);
+    assert_eq!(Format::from_path("net.bin"), Format::Bincode);
+    assert_eq!(Format::from_path("net"), Format::Bincode);
+}
+
+#[test]
+fn rejects_invalid_networks() {
+    let mut network = Network::new_with_rng(4, vec![3, 2], &mut Xoshiro256::seed_from_u64(3));
+    network.layers[1].neurons[0].input_combinators.pop();
+    let bytes = network.to_bytes(Format::Json).unwrap();
+    assert!(Network::from_bytes(&bytes, Format::Json).is_err());
+    assert!(Network::from_bytes(b"not a network", Format::Json).is_err());
+    let oversized = br#"{"layers":[{"neurons":[{"arity":2,"bias":false,"input_combinators":[16],"result_combinator":3}]}]}"#;
+    assert!(Network::from_bytes(oversized, Format::Json).is_err());
+}
+
+#[test]
+fn normalizes_oversized_combinators_leniently() {
+    let oversized = br#"{"layers":[{"neurons":[{"arity":2,"bias":false,"input_combinators":[17],"result_combinator":3}]}]}"#;
+    let (network, warnings) = Network::from_bytes_lenient(oversized, Format::Json).unwrap();
+    assert_eq!(network.layers[0].neurons[0].input_combinators[0], Combinator::from_u8_wrapping(1));
+    assert_eq!(warnings.len(), 1);
+    assert!(Network::from_bytes(oversized, Format::Json).is_err());
+}
+
+#[test]
+fn strict_loads_stay_strict_after_lenient_loads() {
+    let oversized = br#"{"layers":[{"neurons":[{"arity":2,"bias":false,"input_combinators":[16],"result_combinator":3}]}]}"#;
+    let truncated = br#"{"layers":[{"neurons":[{"arity":2,"bias":false,"input_combinators":[16],"#;
+    assert!(Network::from_bytes_lenient(oversized, Format::Json).is_ok());
+    assert!(Network::from_bytes(oversized, Format::Json).is_err());
+    assert!(Network::from_bytes_lenient(truncated, Format::Json).is_err());
+    assert!(Network::from_bytes(oversized, Format::Json).is_err());
+}
+
+#[test]
+fn reports_corrupt_models() {
+    let empty = br#"{"layers":[{"neurons":[{"arity":1,"bias":false,"input_combinators":[],"result_combinator":3}]},{"neurons":[]}]}"#;
+    let (offset, reason) = corruption(Network::from_bytes(empty, Format::Json).unwrap_err());
+    assert_eq!(offset, None);
+    assert!(reason.starts_with("layer 1:"), "{}", reason);
+
+    let mut network = Network::new_with_rng(4, vec![3, 2], &mut Xoshiro256::seed_from_u64(4));
+    network.layers[0].neurons.pop();
+    for &format in &[Format::Bincode, Format::Json] {
+        let (offset, reason) = corruption(Network::from_bytes(&network.to_bytes(format).unwrap(), format).unwrap_err());
+        assert_eq!(offset, None);
+        assert!(reason.starts_with("layer 1: neuron 0:"), "{}", reason);
+    }
+
+    let network = Network::new_with_rng(4, vec![3, 2], &mut Xoshiro256::seed_from_u64(5));
+    let bytes = network.to_bytes(Format::Bincode).unwrap();
+    let (offset, _) = corruption(Network::from_bytes(&bytes[..bytes.len() - 3], Format::Bincode).unwrap_err());
+    assert_eq!(offset, Some(bytes.len() - 3));
+
+    let mut other = network.clone();
+    other.layers[1].neurons[1].result_combinator = Combinator::from_u8_wrapping(network.layers[1].neurons[1].result_combinator as u8 + 1);
+    let at = bytes.iter().zip(other.to_bytes(Format::Bincode).unwrap()).position(|(a, b)| *a != b).unwrap();
+    let mut oversized = bytes.clone();
+    oversized[at] = 16;
+    let (offset, reason) = corruption(Network::from_bytes(&oversized, Format::Bincode).unwrap_err());
+    assert_eq!(offset, Some(at + 1));
+    assert!(reason.contains("16"), "{}", reason);
+}
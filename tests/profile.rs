@@ -0,0 +1,27 @@
+#![cfg(feature = "profiling")]
+
+use bnl::benchmark::Task;
+use bnl::profile::{self, Phase};
+use bnl::random::Xoshiro256;
+use bnl::train::TrainOptions;
+use bnl::Network;
+use rand::SeedableRng;
+
+#[test]
+fn counts_every_phase_of_training() {
+    let dataset = Task::Parity(3).dataset();
+    let mut rng = Xoshiro256::seed_from_u64(0);
+    let mut network = Network::new_with_rng(3, vec![3, 1], &mut rng);
+    profile::take();
+    let opts = TrainOptions {
+        iterations: 50,
+        ..TrainOptions::default()
+    };
+    let report = network.train_with_rng(&dataset, opts, &mut rng);
+    let profile = profile::take();
+    let calls = |phase: Phase| profile.calls[Phase::ALL.iter().position(|&p| p == phase).unwrap()];
+    assert_eq!(calls(Phase::Mutate), report.iterations as u64);
+    assert!(calls(Phase::Evaluate) >= report.iterations as u64);
+    assert_eq!(calls(Phase::Load), 0);
+    assert_eq!(profile::take().calls, [0; 4]);
+}
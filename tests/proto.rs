@@ -0,0 +1,57 @@
+use bnl::builder::NetworkBuilder;
+use bnl::error::Error;
+use bnl::proto::{decode, encode};
+use bnl::random::Xoshiro256;
+use bnl::{Combinator, InitProfile, Network, Reduction};
+use rand::{Rng, SeedableRng};
+
+#[test]
+fn round_trips_networks_and_metadata() {
+    let mut rng = Xoshiro256::seed_from_u64(1);
+    let sparse = InitProfile {
+        fan_in: Some(3),
+        reduction: Reduction::Tree,
+        ..InitProfile::default()
+    };
+    for profile in &[InitProfile::default(), sparse] {
+        let mut network = Network::new_with_profile_with_rng(6, vec![5, 4, 2], profile, &mut rng);
+        for n in network.layers.iter_mut().flat_map(|l| l.neurons.iter_mut()) {
+            n.locked = rng.gen();
+        }
+        assert_eq!(decode(&encode(&network, None)).unwrap(), (network.clone(), None));
+        assert_eq!(decode(&encode(&network, Some(0))).unwrap(), (network.clone(), Some(0)));
+        assert_eq!(decode(&encode(&network, Some(u64::MAX))).unwrap(), (network, Some(u64::MAX)));
+    }
+}
+
+#[test]
+fn matches_the_wire_format_of_the_schema() {
+    let network = NetworkBuilder::new(2)
+        .layer(|l| l.neuron(&[Combinator::And], true, Combinator::Xor))
+        .build()
+        .unwrap();
+    // Network.layers[0].neurons[0] = { arity: 2, bias: true, input_combinators: [AND], result_combinator: XOR }.
+    let expected = [0x0a, 0x0b, 0x0a, 0x09, 0x08, 0x02, 0x10, 0x01, 0x1a, 0x01, 0x01, 0x40, 0x06];
+    assert_eq!(encode(&network, None), expected);
+    // The same neuron with its fields reordered, an unpacked combinator and an
+    // unknown field, as other encoders may write it.
+    let reordered = [0x0a, 0x0c, 0x0a, 0x0a, 0x40, 0x06, 0x18, 0x01, 0x78, 0x2a, 0x10, 0x01, 0x08, 0x02];
+    assert_eq!(decode(&reordered).unwrap().0, network);
+}
+
+#[test]
+fn rejects_malformed_messages() {
+    let network = NetworkBuilder::new(2)
+        .layer(|l| l.neuron(&[Combinator::And], true, Combinator::Xor))
+        .build()
+        .unwrap();
+    let bytes = encode(&network, Some(7));
+    for len in 1..13 {
+        let e = decode(&bytes[..len]).unwrap_err();
+        assert!(matches!(e.get_ref().and_then(|e| e.downcast_ref::<Error>()), Some(Error::CorruptModel { .. })));
+    }
+    let mut bad = encode(&network, None);
+    *bad.last_mut().unwrap() = 0x10;
+    let e = decode(&bad).unwrap_err();
+    assert!(e.to_string().contains("unknown combinator 16"), "{}", e);
+}
@@ -0,0 +1,58 @@
+use bnl::metrics::{Fitness, HammingLoss};
+use bnl::population::{EvolveOptions, Population, Selection, Tournament};
+use bnl::random::Xoshiro256;
+use bnl::registry::Registry;
+use bnl::Network;
+use rand::{RngCore, SeedableRng};
+use std::sync::Arc;
+
+/// Selects the parent with the lowest score, always.
+#[derive(Debug)]
+struct Fittest;
+
+impl Selection for Fittest {
+    fn select(&self, scores: &[f64], _rng: &mut dyn RngCore) -> usize {
+        (0..scores.len()).min_by(|&a, &b| scores[a].partial_cmp(&scores[b]).unwrap()).unwrap()
+    }
+}
+
+fn xor() -> Vec<(Vec<bool>, Vec<bool>)> {
+    (0..4).map(|i| (vec![i & 1 == 1, i & 2 == 2], vec![(i & 1 == 1) != (i & 2 == 2)])).collect()
+}
+
+#[test]
+fn looks_up_builtin_and_registered_components() {
+    let mut registry = Registry::builtin();
+    let mut rng = Xoshiro256::seed_from_u64(1);
+    let network = Network::new_with_rng(2, vec![3, 1], &mut rng);
+    let hamming = registry.fitness("hamming-loss").unwrap();
+    assert_eq!(hamming.error(&network, &xor()), HammingLoss.error(&network, &xor()));
+    let unknown = registry.fitness("nonsense").err().unwrap();
+    assert!(unknown.contains("exact-error, f1-loss, hamming-loss"), "{}", unknown);
+    registry.register_fitness("always-zero", |_: &Network, _: &[(Vec<bool>, Vec<bool>)]| 0.0);
+    assert_eq!(registry.fitness("always-zero").unwrap().error(&network, &xor()), 0.0);
+    let mut flipped = network.clone();
+    registry.mutation("flip-biases").unwrap().mutate(&mut flipped, 1.0, &mut rng);
+    assert!(flipped.layers.iter().flat_map(|l| &l.neurons).zip(network.layers.iter().flat_map(|l| &l.neurons)).all(|(a, b)| a.bias != b.bias));
+    assert!(registry.mutation_names().contains(&"rewire-inputs"));
+    registry.register_selection("fittest", Fittest);
+    assert_eq!(registry.selection("fittest").unwrap().select(&[3.0, 1.0, 2.0], &mut rng), 1);
+    assert_eq!(registry.selection_names(), vec!["fittest", "tournament", "truncation"]);
+}
+
+#[test]
+fn evolves_with_a_chosen_selection_strategy() {
+    let evolve = |selection: Option<Arc<dyn Selection>>| {
+        let options = EvolveOptions {
+            selection,
+            ..EvolveOptions::default()
+        };
+        let mut rng = Xoshiro256::seed_from_u64(2);
+        let mut population = Population::new_with_rng(16, 2, vec![3, 1], options, &mut rng);
+        population.evolve_with_rng(&xor(), 5, &mut rng).history
+    };
+    assert_eq!(evolve(None), evolve(Some(Arc::new(Tournament(3)))));
+    let selection = Registry::builtin().selection("truncation").unwrap();
+    let history = evolve(Some(selection));
+    assert!(!history.is_empty() && history.windows(2).all(|w| w[1] <= w[0]));
+}
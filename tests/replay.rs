@@ -0,0 +1,46 @@
+use bnl::random::Xoshiro256;
+use bnl::replay::{Draw, Recorder, Replayer, RngLog};
+use bnl::train::TrainOptions;
+use bnl::Network;
+use rand::{Rng, RngCore, SeedableRng};
+
+fn xor() -> Vec<(Vec<bool>, Vec<bool>)> {
+    (0..4).map(|i| (vec![i & 1 == 1, i & 2 == 2], vec![(i & 1 == 1) != (i & 2 == 2)])).collect()
+}
+
+fn train<R: RngCore>(rng: &mut R) -> Network {
+    let mut network = Network::new_with_rng(2, vec![3, 1], rng);
+    let opts = TrainOptions {
+        iterations: 200,
+        ..TrainOptions::default()
+    };
+    network.train_with_rng(&xor(), opts, rng);
+    network
+}
+
+#[test]
+fn replays_recorded_training_runs() {
+    let mut recorder = Recorder::new(Xoshiro256::seed_from_u64(1));
+    let network = train(&mut recorder);
+    let mut bytes = [0u8; 5];
+    recorder.fill_bytes(&mut bytes);
+    let log = recorder.into_log();
+    assert!(!log.draws.is_empty());
+    assert_eq!(log.draws.last(), Some(&Draw::Bytes(bytes.to_vec())));
+    let log = RngLog::parse(&log.to_bytes()).unwrap();
+    let mut replayer = Replayer::new(log.clone(), Xoshiro256::seed_from_u64(1));
+    assert_eq!(train(&mut replayer), network);
+    let mut replayed = [0u8; 5];
+    replayer.fill_bytes(&mut replayed);
+    assert_eq!(replayed, bytes);
+    assert_eq!(replayer.position(), log.draws.len());
+    replayer.finish().unwrap();
+    let mut replayer = Replayer::new(log.clone(), Xoshiro256::seed_from_u64(2));
+    assert_eq!(train(&mut replayer), network);
+    assert_eq!(replayer.divergence().map(|d| d.index), Some(0));
+    let mut replayer = Replayer::new(log, Xoshiro256::seed_from_u64(1));
+    let _: u64 = replayer.gen();
+    let divergence = replayer.finish().unwrap_err();
+    assert_eq!((divergence.index, divergence.found), (1, None));
+    assert!(RngLog::parse(b"BNLR\x01\x00").is_err());
+}
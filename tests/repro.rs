@@ -0,0 +1,14 @@
+use bnl::repro::Fingerprints;
+
+#[test]
+fn matches_golden_fingerprints() {
+    let golden = Fingerprints::parse(include_str!("golden/repro.txt")).unwrap();
+    assert_eq!(Fingerprints::compute().mismatches(&golden), Vec::<&str>::new());
+}
+
+#[test]
+fn round_trips_through_golden_files() {
+    let fingerprints = Fingerprints::compute();
+    assert_eq!(Fingerprints::parse(&fingerprints.to_string()).unwrap(), fingerprints);
+    assert!(Fingerprints::parse("generation 0\n").is_err());
+}
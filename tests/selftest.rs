@@ -0,0 +1,14 @@
+use bnl::selftest::{self, circuits};
+
+#[test]
+fn passes_every_check() {
+    let report = selftest::run();
+    assert!(report.passed(), "{}", report);
+}
+
+#[test]
+fn circuits_solve_their_tasks() {
+    for (task, network) in circuits() {
+        assert!(task.dataset().iter().all(|(input, expected)| network.apply(input) == *expected), "{}", task);
+    }
+}
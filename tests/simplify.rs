@@ -0,0 +1,54 @@
+use bnl::analysis::probe_inputs_with_rng;
+use bnl::random::Xoshiro256;
+use bnl::{InitProfile, Network, Neuron, Reduction};
+use rand::{Rng, SeedableRng};
+
+fn assert_simplifies_exactly(profile: &InitProfile, lock: bool, seed: u64) -> usize {
+    let mut rng = Xoshiro256::seed_from_u64(seed);
+    let mut changed = 0;
+    for _ in 0..32 {
+        let mut network = Network::new_with_profile_with_rng(10, vec![8, 8, 3], profile, &mut rng);
+        for l in 0..2 {
+            let j = rng.gen_range(0, 8);
+            if profile.fan_in.is_none() && rng.gen() {
+                network.layers[l].neurons[j] = Neuron::constant(network.layers[l].neurons[j].arity, rng.gen());
+            }
+        }
+        if lock {
+            for n in network.layers.iter_mut().flat_map(|l| l.neurons.iter_mut()) {
+                n.locked = rng.gen_bool(0.3);
+            }
+        }
+        let (simplified, report) = network.simplify();
+        simplified.validate().unwrap();
+        for input in probe_inputs_with_rng(10, 512, &mut rng) {
+            assert_eq!(simplified.apply(&input), network.apply(&input));
+        }
+        changed += !report.is_empty() as usize;
+    }
+    changed
+}
+
+#[test]
+fn preserves_outputs_of_random_networks() {
+    let dense = InitProfile {
+        sparsity: 0.5,
+        ..InitProfile::default()
+    };
+    let sparse = InitProfile {
+        fan_in: Some(3),
+        ..InitProfile::default()
+    };
+    let left_fold = InitProfile {
+        reduction: Reduction::LeftFold,
+        sparsity: 0.5,
+        ..InitProfile::default()
+    };
+    let changed = [
+        assert_simplifies_exactly(&dense, false, 1),
+        assert_simplifies_exactly(&sparse, false, 2),
+        assert_simplifies_exactly(&left_fold, false, 3),
+        assert_simplifies_exactly(&dense, true, 4)
+    ];
+    assert!(changed.iter().all(|&c| c > 0), "{:?}", changed);
+}
@@ -0,0 +1,41 @@
+use bnl::builder::NetworkBuilder;
+use bnl::stateful::StatefulNetwork;
+use bnl::Combinator::{self, *};
+use std::time::Duration;
+
+/// Builds a stateful network of one output, fed back as the state, computing
+/// the specified combinator of its external input and the state.
+fn feedback(combinator: Combinator) -> StatefulNetwork {
+    let network = NetworkBuilder::new(2)
+        .layer(|l| l.neuron(&[combinator], false, Left))
+        .build()
+        .unwrap();
+    StatefulNetwork::new(network, vec![0])
+}
+
+#[test]
+fn settles_on_fixed_points() {
+    let mut latch = feedback(Or);
+    let settled = latch.apply_until_stable(&[true], 10);
+    assert!(settled.converged);
+    assert_eq!((settled.output, settled.steps), (vec![true], 2));
+    let settled = latch.apply_with_deadline(&[false], Duration::from_secs(10));
+    assert!(settled.converged);
+    assert_eq!((settled.output, settled.steps), (vec![true], 1));
+    assert_eq!(latch.state(), &[true]);
+}
+
+#[test]
+fn gives_up_on_oscillation_and_deadlines() {
+    let mut oscillator = feedback(NotRight);
+    let settled = oscillator.apply_with_deadline(&[false], Duration::from_secs(10));
+    assert!(!settled.converged);
+    assert_eq!((settled.output, settled.steps), (vec![false], 2));
+    let mut latch = feedback(Or);
+    let settled = latch.apply_with_deadline(&[true], Duration::ZERO);
+    assert!(!settled.converged);
+    assert_eq!((settled.output, settled.steps), (vec![true], 1));
+    let settled = latch.apply_until_stable(&[true], 0);
+    assert!(settled.converged);
+    assert_eq!(settled.steps, 1);
+}
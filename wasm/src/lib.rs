@@ -0,0 +1,110 @@
+//! Exposes inference on saved `bnl` networks to JavaScript via `wasm-bindgen`,
+//! through the `WasmNetwork` type, for running them client-side.
+//!
+//! This crate is kept apart from `bnl` itself so that only it is built as a
+//! `cdylib`. Build it for `wasm32-unknown-unknown` (for instance with
+//! `wasm-pack build wasm`), then load and apply a network from JavaScript,
+//! with inputs and outputs as `Uint8Array`s:
+//!
+//! ```text
+//! import { WasmNetwork } from "./pkg/bnl_wasm.js";
+//!
+//! const network = WasmNetwork.fromJson(await (await fetch("model.json")).text());
+//! const output = network.apply(new Uint8Array([1, 0, 1, 1, 0, 1]));
+//! ```
+
+use bnl::network::Network;
+use bnl::persist::Format;
+use wasm_bindgen::prelude::*;
+
+/// Represents a network loaded for inference from JavaScript.
+#[wasm_bindgen]
+#[derive(Clone,Debug)]
+pub struct WasmNetwork {
+    /// The wrapped network.
+    network: Network
+}
+
+/// Implements the methods of networks exported to JavaScript.
+#[wasm_bindgen]
+impl WasmNetwork {
+    /// "Applies" the network to an input given as one byte per value, where
+    /// any non-zero byte is `true`, returning its output in the same form
+    /// (as `0` or `1`).
+    ///
+    /// Fails if the width of the input does not match the network.
+    pub fn apply(&self, input: &[u8]) -> Result<Vec<u8>, JsValue> {
+        let input: Vec<bool> = input.iter().map(|&b| b != 0).collect();
+        let output = self.network.try_apply(&input).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(output.into_iter().map(|b| b as u8).collect())
+    }
+
+    /// "Applies" the network to an input packed eight values per byte, least
+    /// significant bit first, returning its output packed in the same way
+    /// (with any unused bits of the last byte cleared).
+    ///
+    /// Fails if the input holds fewer values than the network reads, or more
+    /// bytes than it needs.
+    #[wasm_bindgen(js_name = applyPacked)]
+    pub fn apply_packed(&self, input: &[u8]) -> Result<Vec<u8>, JsValue> {
+        let width = self.network.input_width();
+        if input.len() != width.div_ceil(8) {
+            return Err(JsValue::from_str(&format!(
+                "expected {} packed bytes for {} inputs but found {}", width.div_ceil(8), width, input.len()
+            )));
+        }
+        let input: Vec<bool> = (0..width).map(|i| (input[i / 8] >> (i % 8)) & 1 == 1).collect();
+        let output = self.network.apply(&input);
+        let mut res = vec![0u8; output.len().div_ceil(8)];
+        for (i, &b) in output.iter().enumerate() {
+            res[i / 8] |= (b as u8) << (i % 8);
+        }
+        Ok(res)
+    }
+
+    /// Loads a network saved as bincode (see `Network::from_bytes`).
+    #[wasm_bindgen(js_name = fromBincode)]
+    pub fn from_bincode(bytes: &[u8]) -> Result<WasmNetwork, JsValue> {
+        Network::from_bytes(bytes, Format::Bincode)
+            .map(WasmNetwork::from)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Loads a network saved as JSON (see `Network::from_bytes`).
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<WasmNetwork, JsValue> {
+        Network::from_bytes(json.as_bytes(), Format::Json)
+            .map(WasmNetwork::from)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Returns the width of the inputs accepted by the network.
+    #[wasm_bindgen(getter, js_name = inputWidth)]
+    pub fn input_width(&self) -> usize {
+        self.network.input_width()
+    }
+
+    /// Returns the width of the outputs produced by the network.
+    #[wasm_bindgen(getter, js_name = outputWidth)]
+    pub fn output_width(&self) -> usize {
+        self.network.output_width()
+    }
+}
+
+/// Implements custom methods for networks loaded for JavaScript which are not
+/// exported to it.
+impl WasmNetwork {
+    /// Returns the wrapped network.
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+}
+
+/// Allows networks to be wrapped for JavaScript.
+impl From<Network> for WasmNetwork {
+    fn from(network: Network) -> Self {
+        WasmNetwork {
+            network
+        }
+    }
+}